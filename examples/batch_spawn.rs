@@ -0,0 +1,169 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use std::time::Instant;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+const GRID_SIZE: usize = 100;
+const BOX_HALF_SIZE: f32 = 0.2;
+
+#[derive(Resource, Default)]
+struct SpawnTimer {
+    started: Option<(&'static str, Instant)>,
+    report: String,
+}
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .init_resource::<SpawnTimer>()
+        .add_systems(Startup, (setup_camera, setup_instructions))
+        .add_systems(Startup, setup_ground)
+        .add_systems(
+            Update,
+            (check_keys, add_debug_draw_to_batch, update_instructions),
+        )
+        .add_systems(Last, report_spawn_time)
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.1,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 40., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_instructions(mut commands: Commands) {
+    commands.spawn(
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            left: Val::Px(15.0),
+            ..default()
+        }),
+    );
+}
+
+fn setup_ground(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+    let shape = b2Shape::EdgeTwoSided {
+        v1: Vec2::new(-60., 0.),
+        v2: Vec2::new(60., 0.),
+    };
+    commands.spawn((
+        b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)),
+        DebugDrawFixtures::default_static(),
+    ));
+}
+
+fn grid() -> impl Iterator<Item = (b2BodyDef, b2FixtureDef)> {
+    (0..GRID_SIZE * GRID_SIZE).map(|i| {
+        let (x, y) = ((i % GRID_SIZE) as f32, (i / GRID_SIZE) as f32);
+        let body_def = b2BodyDef {
+            body_type: b2BodyType::Dynamic,
+            position: Vec2::new(x - GRID_SIZE as f32 / 2., y + 1.) * BOX_HALF_SIZE * 4.,
+            allow_sleep: true,
+            ..default()
+        };
+        let fixture_def = b2FixtureDef::new(b2Shape::create_box(BOX_HALF_SIZE, BOX_HALF_SIZE), 1.);
+        (body_def, fixture_def)
+    })
+}
+
+fn check_keys(
+    input: Res<Input<KeyCode>>,
+    bodies: Query<(Entity, &b2Body)>,
+    mut spawn_timer: ResMut<SpawnTimer>,
+    mut commands: Commands,
+) {
+    let batch = input.just_pressed(KeyCode::B);
+    if !batch && !input.just_pressed(KeyCode::I) {
+        return;
+    }
+
+    // Start over with an empty grid, keeping the ground
+    for (entity, body) in bodies.iter() {
+        if matches!(body.body_type, b2BodyType::Dynamic) {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    if batch {
+        spawn_timer.started = Some(("batch", Instant::now()));
+        commands.spawn_bodies_batch(grid().collect::<Vec<_>>());
+    } else {
+        spawn_timer.started = Some(("individual", Instant::now()));
+        for (body_def, fixture_def) in grid() {
+            let body_entity = commands.spawn(b2BodyBundle::new(&body_def)).id();
+            commands.spawn((
+                b2Fixture::new(body_entity, &fixture_def),
+                DebugDrawFixtures::default_dynamic(),
+            ));
+        }
+    }
+}
+
+fn add_debug_draw_to_batch(
+    added: Query<Entity, (Added<b2Fixtures>, Without<DebugDrawFixtures>)>,
+    mut commands: Commands,
+) {
+    for entity in added.iter() {
+        commands
+            .entity(entity)
+            .insert(DebugDrawFixtures::default_dynamic());
+    }
+}
+
+/// Runs after the bodies and fixtures have been created in the physics world and stepped once.
+fn report_spawn_time(mut spawn_timer: ResMut<SpawnTimer>) {
+    let Some((mode, started)) = spawn_timer.started.take() else {
+        return;
+    };
+    let elapsed = started.elapsed();
+    spawn_timer.report = format!(
+        "Spawned {} bodies ({}) in {:.1} ms",
+        GRID_SIZE * GRID_SIZE,
+        mode,
+        elapsed.as_secs_f64() * 1000.
+    );
+    info!("{}", spawn_timer.report);
+}
+
+fn update_instructions(spawn_timer: Res<SpawnTimer>, mut text: Query<&mut Text>) {
+    let instruction_text = format!(
+        "'B' Spawn grid in a batch\n'I' Spawn grid one body at a time\n{}",
+        spawn_timer.report
+    );
+
+    let mut text = text.single_mut();
+    *text = Text::from_section(
+        instruction_text,
+        TextStyle {
+            font_size: 20.0,
+            color: Color::WHITE,
+            ..default()
+        },
+    );
+}
@@ -0,0 +1,96 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .add_systems(Startup, setup_camera)
+        .add_systems(Startup, (setup_pool, setup_water, setup_box))
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.01,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 2., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_pool(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+    let walls = [
+        b2Shape::create_box_with_offset(3., 0.1, Vec2::new(0., -0.1)),
+        b2Shape::create_box_with_offset(0.1, 2., Vec2::new(-3.1, 1.9)),
+        b2Shape::create_box_with_offset(0.1, 2., Vec2::new(3.1, 1.9)),
+    ];
+    for shape in walls {
+        commands.spawn((
+            b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)),
+            DebugDrawFixtures::default_static(),
+        ));
+    }
+}
+
+fn setup_water(mut commands: Commands) {
+    let particle_system_def = b2ParticleSystemDef {
+        radius: 0.04,
+        damping_strength: 0.2,
+        max_count: 10000,
+        ..default()
+    };
+    let particle_system_entity = commands
+        .spawn((
+            b2ParticleSystem::new(&particle_system_def),
+            b2ParticleSystemContacts::default(),
+            DebugDrawParticleSystem {},
+        ))
+        .id();
+
+    let particle_group_def = b2ParticleGroupDef {
+        flags: b2ParticleFlags::WaterParticle,
+        shape: b2Shape::create_box_with_offset(2.95, 0.8, Vec2::new(0., 0.8)),
+        color: Color::rgb(0.2, 0.5, 1.).into(),
+        lifetime: 0.,
+    };
+    commands.spawn(b2ParticleGroup::new(
+        particle_system_entity,
+        &particle_group_def,
+    ));
+}
+
+fn setup_box(mut commands: Commands) {
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position: Vec2::new(0.5, 3.),
+        angle: 0.4,
+        ..default()
+    };
+    let box_entity = commands
+        .spawn((
+            b2BodyBundle::new(&body_def),
+            Buoyancy {
+                density_multiplier: 3.,
+                ..default()
+            },
+        ))
+        .id();
+    let fixture_def = b2FixtureDef::new(b2Shape::create_box(0.5, 0.25), 0.5);
+    commands.spawn((
+        b2Fixture::new(box_entity, &fixture_def),
+        DebugDrawFixtures::default_dynamic(),
+    ));
+}
@@ -0,0 +1,254 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+const MOTOR_SPEED: f32 = 50.;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .add_systems(Startup, (setup_camera, setup_instructions))
+        .add_systems(
+            Startup,
+            (setup_physics_world, setup_ground, setup_car).chain(),
+        )
+        .add_systems(
+            Update,
+            (
+                check_keys,
+                check_physics_time_keys,
+                check_debug_draw_keys,
+                update_instructions,
+            ),
+        )
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.05,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(20., 5., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_instructions(mut commands: Commands) {
+    commands.spawn(
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            left: Val::Px(15.0),
+            ..default()
+        }),
+    );
+}
+
+fn setup_physics_world(world: &mut World) {
+    let gravity = Vec2::new(0., -9.81);
+    let b2_world = b2World::new(gravity);
+    world.insert_non_send_resource(b2_world);
+}
+
+fn setup_ground(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+
+    let heights = [
+        0., 0., 0.25, 1., 4., 0., 0., -1., -2., -2., -1.25, 0., 0., 0., 1., 2., 1., 0., 0.,
+    ];
+    let step = 5.;
+    let mut x = -20.;
+    let mut y = 0.;
+    for height in heights {
+        let next = Vec2::new(x + step, height);
+        let shape = b2Shape::EdgeTwoSided {
+            v1: Vec2::new(x, y),
+            v2: next,
+        };
+        let fixture_def = b2FixtureDef {
+            shape,
+            density: 0.,
+            friction: 0.6,
+            ..default()
+        };
+        commands.spawn((
+            b2Fixture::new(ground_entity, &fixture_def),
+            DebugDrawFixtures::default_static(),
+        ));
+        x = next.x;
+        y = next.y;
+    }
+}
+
+fn setup_car(mut commands: Commands) {
+    let chassis_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position: Vec2::new(0., 1.),
+        ..default()
+    };
+    let chassis_entity = commands.spawn(b2BodyBundle::new(&chassis_def)).id();
+    let chassis_shape = b2Shape::Polygon {
+        vertices: vec![
+            Vec2::new(-1.5, -0.5),
+            Vec2::new(1.5, -0.5),
+            Vec2::new(1.5, 0.),
+            Vec2::new(0., 0.9),
+            Vec2::new(-1.15, 0.9),
+            Vec2::new(-1.5, 0.2),
+        ],
+    };
+    commands.spawn((
+        b2Fixture::new(chassis_entity, &b2FixtureDef::new(chassis_shape, 1.)),
+        DebugDrawFixtures::default_dynamic(),
+    ));
+
+    let wheel_offsets = [Vec2::new(-1., -0.65), Vec2::new(1., -0.65)];
+    for (i, offset) in wheel_offsets.into_iter().enumerate() {
+        let wheel_def = b2BodyDef {
+            body_type: b2BodyType::Dynamic,
+            position: chassis_def.position + offset,
+            ..default()
+        };
+        let wheel_entity = commands.spawn(b2BodyBundle::new(&wheel_def)).id();
+        let wheel_shape = b2Shape::Circle {
+            radius: 0.4,
+            position: Vec2::ZERO,
+        };
+        let fixture_def = b2FixtureDef {
+            shape: wheel_shape,
+            density: 1.,
+            friction: 0.9,
+            ..default()
+        };
+        commands.spawn((
+            b2Fixture::new(wheel_entity, &fixture_def),
+            DebugDrawFixtures::default_dynamic(),
+        ));
+
+        // Only the rear wheel is driven
+        let is_rear_wheel = i == 0;
+        let joint_def = b2WheelJointDef {
+            local_anchor_a: offset,
+            local_axis_a: Vec2::Y,
+            enable_motor: is_rear_wheel,
+            max_motor_torque: if is_rear_wheel { 20. } else { 10. },
+            lower_translation: -0.25,
+            upper_translation: 0.25,
+            enable_limit: true,
+            stiffness: 25.,
+            damping: 2.5,
+            ..default()
+        };
+        commands.spawn_empty().add(CreateWheelJoint::new(
+            chassis_entity,
+            wheel_entity,
+            false,
+            &joint_def,
+        ));
+    }
+}
+
+fn check_keys(input: Res<Input<KeyCode>>, mut joints: Query<&mut b2WheelJoint>) {
+    let motor_speed = if input.pressed(KeyCode::A) {
+        MOTOR_SPEED
+    } else if input.pressed(KeyCode::D) {
+        -MOTOR_SPEED
+    } else {
+        0.
+    };
+
+    for mut joint in joints.iter_mut() {
+        if joint.motor_speed != motor_speed {
+            joint.motor_speed = motor_speed;
+        }
+    }
+}
+
+fn check_physics_time_keys(input: Res<Input<KeyCode>>, mut physics_time: ResMut<PhysicsTime>) {
+    if input.just_pressed(KeyCode::P) {
+        if physics_time.is_paused() {
+            physics_time.resume();
+        } else {
+            physics_time.pause();
+        }
+    }
+    if input.just_pressed(KeyCode::N) {
+        physics_time.step_once();
+    }
+    if input.just_pressed(KeyCode::T) {
+        physics_time.time_scale = if physics_time.time_scale == 1. {
+            0.25
+        } else {
+            1.
+        };
+    }
+}
+
+fn check_debug_draw_keys(input: Res<Input<KeyCode>>, mut config: ResMut<DebugDrawConfig>) {
+    if input.just_pressed(KeyCode::Key1) {
+        config.draw_joints = !config.draw_joints;
+    }
+    if input.just_pressed(KeyCode::Key2) {
+        config.draw_contacts = !config.draw_contacts;
+    }
+    if input.just_pressed(KeyCode::Key3) {
+        config.draw_aabbs = !config.draw_aabbs;
+    }
+    if input.just_pressed(KeyCode::Key4) {
+        config.draw_velocities = !config.draw_velocities;
+    }
+    if input.just_pressed(KeyCode::Key5) {
+        config.draw_centers_of_mass = !config.draw_centers_of_mass;
+    }
+}
+
+fn update_instructions(
+    joints: Query<&b2WheelJoint>,
+    physics_time: Res<PhysicsTime>,
+    mut text: Query<&mut Text>,
+) {
+    let mut instruction_text = String::from(
+        "'A' Drive left\n'D' Drive right\n'P' Pause/resume\n'N' Single step\n'T' Slow motion\n\
+         '1'-'5' Toggle joints, contacts, AABBs, velocities, centers of mass\n",
+    );
+    if physics_time.is_paused() {
+        instruction_text += "Paused\n";
+    }
+    for (i, joint) in joints.iter().enumerate() {
+        instruction_text += &format!(
+            "Wheel {}: suspension {:.3}, wheel speed {:.1}\n",
+            i,
+            joint.joint_translation(),
+            joint.joint_angular_speed()
+        );
+    }
+
+    let mut text = text.single_mut();
+    *text = Text::from_section(
+        instruction_text,
+        TextStyle {
+            font_size: 20.0,
+            color: Color::WHITE,
+            ..default()
+        },
+    );
+}
@@ -0,0 +1,156 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+const CHARACTER_RADIUS: f32 = 0.5;
+const CHARACTER_SPEED: f32 = 6.;
+/// How many times the movement is redirected along the walls it hits in a single frame.
+const MAX_SLIDES: usize = 3;
+
+#[derive(Component)]
+struct Character;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default().with_gravity(Vec2::ZERO),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .add_systems(Startup, (setup_camera, setup_instructions))
+        .add_systems(Startup, (setup_walls, setup_character))
+        .add_systems(Update, move_character)
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.05,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_instructions(mut commands: Commands) {
+    commands.spawn(
+        TextBundle::from_section(
+            "Arrow keys to move",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            left: Val::Px(15.0),
+            ..default()
+        }),
+    );
+}
+
+fn setup_walls(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+    let walls = [
+        b2Shape::create_box_with_offset(15., 0.5, Vec2::new(0., -10.)),
+        b2Shape::create_box_with_offset(15., 0.5, Vec2::new(0., 10.)),
+        b2Shape::create_box_with_offset(0.5, 10., Vec2::new(-15., 0.)),
+        b2Shape::create_box_with_offset(0.5, 10., Vec2::new(15., 0.)),
+        b2Shape::create_box_with_offset(3., 3., Vec2::new(-6., 2.)),
+        b2Shape::Polygon {
+            vertices: vec![
+                Vec2::new(3., -6.),
+                Vec2::new(10., -2.),
+                Vec2::new(9., -1.),
+                Vec2::new(2., -5.),
+            ],
+        },
+        b2Shape::Circle {
+            radius: 2.,
+            position: Vec2::new(6., 5.),
+        },
+    ];
+    for shape in walls {
+        commands.spawn((
+            b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)),
+            DebugDrawFixtures::default_static(),
+        ));
+    }
+}
+
+fn setup_character(mut commands: Commands) {
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Kinematic,
+        ..default()
+    };
+    let character_entity = commands
+        .spawn((
+            b2BodyBundle::new(&body_def),
+            KinematicTarget::new(Vec2::ZERO, 0.),
+            Character,
+        ))
+        .id();
+    commands.spawn((
+        b2Fixture::new(character_entity, &b2FixtureDef::new(character_shape(), 1.)),
+        DebugDrawFixtures::default_dynamic(),
+    ));
+}
+
+fn character_shape() -> b2Shape {
+    b2Shape::Circle {
+        radius: CHARACTER_RADIUS,
+        position: Vec2::ZERO,
+    }
+}
+
+/// Moves the character as far as it can towards the input direction, and slides the rest of the
+/// way along the walls it hits.
+fn move_character(
+    input: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    ray_caster: PhysicsRayCaster,
+    mut characters: Query<(Entity, &mut KinematicTarget), With<Character>>,
+) {
+    let mut direction = Vec2::ZERO;
+    if input.pressed(KeyCode::Left) {
+        direction.x -= 1.;
+    }
+    if input.pressed(KeyCode::Right) {
+        direction.x += 1.;
+    }
+    if input.pressed(KeyCode::Down) {
+        direction.y -= 1.;
+    }
+    if input.pressed(KeyCode::Up) {
+        direction.y += 1.;
+    }
+
+    let (character_entity, mut target) = characters.single_mut();
+    let shape = character_shape();
+    let filter = b2RayCastFilter::excluding_bodies([character_entity]);
+
+    // The target is where the character was last sent, which it reaches on the next step
+    let mut position = target.position;
+    let mut remaining = direction.normalize_or_zero() * CHARACTER_SPEED * time.delta_seconds();
+    for _ in 0..MAX_SLIDES {
+        if remaining == Vec2::ZERO {
+            break;
+        }
+        let Some(hit) = ray_caster.shape_cast(&shape, (position, 0.), remaining, &filter) else {
+            position += remaining;
+            break;
+        };
+        position += remaining * hit.fraction;
+        remaining *= 1. - hit.fraction;
+        remaining -= hit.normal * remaining.dot(hit.normal);
+    }
+    target.position = position;
+}
@@ -3,13 +3,7 @@ extern crate bevy_liquidfun;
 
 use bevy::prelude::*;
 
-use bevy_liquidfun::dynamics::{b2BodyBundle, b2Fixture, b2FixtureDef};
-use bevy_liquidfun::plugins::{LiquidFunDebugDrawPlugin, LiquidFunPlugin};
-use bevy_liquidfun::utils::DebugDrawFixtures;
-use bevy_liquidfun::{
-    collision::b2Shape,
-    dynamics::{b2BodyDef, b2BodyType::Dynamic, b2World},
-};
+use bevy_liquidfun::prelude::*;
 fn main() {
     App::new()
         .add_plugins((
@@ -69,7 +63,7 @@ fn setup_physics_bodies(mut commands: Commands) {
     let fixture_def = b2FixtureDef::new(circle_shape, 1.);
     for i in 0..10 {
         let body_def = b2BodyDef {
-            body_type: Dynamic,
+            body_type: b2BodyType::Dynamic,
             position: Vec2::new(0., 4. + 3. * i as f32),
             ..default()
         };
@@ -0,0 +1,132 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+extern crate rand;
+
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+const BREAKING_IMPULSE: f32 = 8.;
+
+#[derive(Component)]
+struct Breakable;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .add_systems(Startup, (setup_camera, setup_instructions))
+        .add_systems(
+            Startup,
+            (setup_physics_world, setup_ground.after(setup_physics_world)),
+        )
+        .add_systems(
+            Update,
+            (check_spawn_key, break_on_hard_impacts, draw_contact_points),
+        )
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.05,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 10., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_instructions(mut commands: Commands) {
+    commands.spawn(
+        TextBundle::from_section(
+            "'Space' Drop a box, hard impacts break it",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            left: Val::Px(15.0),
+            ..default()
+        }),
+    );
+}
+
+fn setup_physics_world(world: &mut World) {
+    let gravity = Vec2::new(0., -9.81);
+    let b2_world = b2World::new(gravity);
+    world.insert_non_send_resource(b2_world);
+}
+
+fn setup_ground(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+
+    let shape = b2Shape::EdgeTwoSided {
+        v1: Vec2::new(-40., 0.),
+        v2: Vec2::new(40., 0.),
+    };
+    let fixture_def = b2FixtureDef::new(shape, 0.);
+    commands.spawn((
+        b2Fixture::new(ground_entity, &fixture_def),
+        DebugDrawFixtures::default_static(),
+    ));
+}
+
+fn check_spawn_key(key_input: Res<Input<KeyCode>>, mut commands: Commands) {
+    if !key_input.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    let mut rng = thread_rng();
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position: Vec2::new(rng.gen_range(-5.0..=5.0), rng.gen_range(5.0..=25.0)),
+        ..default()
+    };
+    let body_entity = commands
+        .spawn((b2BodyBundle::new(&body_def), Breakable))
+        .id();
+
+    let fixture_def = b2FixtureDef::new(b2Shape::create_box(0.5, 0.5), 1.);
+    commands.spawn((
+        b2Fixture::new(body_entity, &fixture_def),
+        DebugDrawFixtures::default_dynamic(),
+    ));
+}
+
+fn break_on_hard_impacts(
+    mut post_solve_events: EventReader<b2PostSolveEvent>,
+    breakables: Query<(), With<Breakable>>,
+    mut commands: Commands,
+) {
+    for event in post_solve_events.read() {
+        if event.impulse.max_normal_impulse() < BREAKING_IMPULSE {
+            continue;
+        }
+
+        for body in [event.contact.body_a, event.contact.body_b] {
+            if breakables.contains(body) {
+                commands.entity(body).despawn_recursive();
+            }
+        }
+    }
+}
+
+fn draw_contact_points(contacts: Res<b2Contacts>, mut gizmos: Gizmos) {
+    for contact in contacts.contacts() {
+        for point in contact.manifold.points() {
+            gizmos.circle_2d(*point, 0.1, Color::ORANGE_RED);
+        }
+    }
+}
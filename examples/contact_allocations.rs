@@ -0,0 +1,139 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use bevy_liquidfun::prelude::*;
+
+const BODY_COUNT: usize = 2_000;
+const FRAME_COUNT: usize = 300;
+
+/// Counts the allocations made through the global allocator.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Drops boxes onto a bumpy floor so thousands of contacts begin and end every frame, and prints
+/// how many allocations and how much time a frame takes with contact events enabled. The contact
+/// events shouldn't add to the allocations once their buffers have grown to fit the pile.
+///
+/// Pass `--marked` to only emit the contact events of a single box with [`EmitContactEvents`],
+/// which skips recording the contacts of all the others.
+fn main() {
+    let marked = std::env::args().any(|arg| arg == "--marked");
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, LiquidFunPlugin::default()))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            1. / 60.,
+        )))
+        .insert_resource(ContactEventFilter {
+            require_marker: marked,
+            stay_events: true,
+            ..default()
+        })
+        .add_systems(Startup, setup_physics_bodies)
+        .add_systems(Update, read_contact_events);
+
+    // Lets the buffers grow to their working size before counting
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let mut counts: Vec<usize> = Vec::with_capacity(FRAME_COUNT);
+    let start = Instant::now();
+    for _ in 0..FRAME_COUNT {
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        app.update();
+        counts.push(ALLOCATIONS.load(Ordering::Relaxed) - before);
+    }
+    let frame_time = start.elapsed() / FRAME_COUNT as u32;
+    counts.sort_unstable();
+    println!(
+        "{} bodies{}: {:.3} ms and {} allocations per frame (median), {} at most",
+        BODY_COUNT,
+        if marked { ", one marked" } else { "" },
+        frame_time.as_secs_f64() * 1000.,
+        counts[counts.len() / 2],
+        counts[counts.len() - 1]
+    );
+}
+
+fn setup_physics_bodies(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+    for i in 0..40 {
+        let shape = b2Shape::Circle {
+            radius: 1.,
+            position: Vec2::new(i as f32 * 2. - 40., 0.),
+        };
+        commands.spawn(b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)));
+    }
+
+    let columns = 50;
+    let fixture_def = b2FixtureDef {
+        shape: b2Shape::create_box(0.4, 0.4),
+        density: 1.,
+        friction: 0.3,
+        restitution: 0.3,
+        ..default()
+    };
+    commands.spawn((
+        b2BodyBundle::new(&b2BodyDef {
+            body_type: b2BodyType::Dynamic,
+            position: Vec2::new(0., 80.),
+            allow_sleep: false,
+            ..default()
+        }),
+        b2Fixtures::new([fixture_def.clone()]),
+        EmitContactEvents,
+    ));
+    let bodies: Vec<(b2BodyDef, b2FixtureDef)> = (0..BODY_COUNT)
+        .map(|i| {
+            let body_def = b2BodyDef {
+                body_type: b2BodyType::Dynamic,
+                position: Vec2::new(
+                    (i % columns) as f32 * 1.2 - 30.,
+                    (i / columns) as f32 * 1.2 + 2.,
+                ),
+                allow_sleep: false,
+                ..default()
+            };
+            (body_def, fixture_def.clone())
+        })
+        .collect();
+    commands.spawn_bodies_batch(bodies);
+}
+
+fn read_contact_events(
+    mut begin_contact_events: EventReader<b2BeginContactEvent>,
+    mut end_contact_events: EventReader<b2EndContactEvent>,
+    mut stay_contact_events: EventReader<b2ContactStayEvent>,
+) {
+    begin_contact_events.read().count();
+    end_contact_events.read().count();
+    stay_contact_events.read().count();
+}
@@ -3,16 +3,7 @@ extern crate bevy_liquidfun;
 
 use bevy::prelude::*;
 
-use bevy_liquidfun::dynamics::{b2BodyBundle, b2Fixture, b2FixtureDef};
-use bevy_liquidfun::particles::{
-    b2ParticleFlags, b2ParticleGroup, b2ParticleGroupDef, b2ParticleSystem, b2ParticleSystemDef,
-};
-use bevy_liquidfun::plugins::{LiquidFunDebugDrawPlugin, LiquidFunPlugin};
-use bevy_liquidfun::utils::{DebugDrawFixtures, DebugDrawParticleSystem};
-use bevy_liquidfun::{
-    collision::b2Shape,
-    dynamics::{b2BodyDef, b2BodyType::Dynamic, b2World},
-};
+use bevy_liquidfun::prelude::*;
 
 fn main() {
     App::new()
@@ -31,6 +22,7 @@ fn main() {
                 setup_particles.after(setup_circle),
             ),
         )
+        .add_systems(Update, draw_particles_by_speed)
         .run();
 }
 
@@ -110,7 +102,7 @@ fn setup_ground(mut commands: Commands) {
 
 fn setup_circle(mut commands: Commands) {
     let body_def = b2BodyDef {
-        body_type: Dynamic,
+        body_type: b2BodyType::Dynamic,
         position: Vec2::new(0., 8.),
         ..default()
     };
@@ -134,9 +126,7 @@ fn setup_particles(mut commands: Commands) {
         ..default()
     };
     let particle_system = b2ParticleSystem::new(&particle_system_def);
-    let particle_system_entity = commands
-        .spawn((particle_system, DebugDrawParticleSystem {}))
-        .id();
+    let particle_system_entity = commands.spawn(particle_system).id();
 
     let shape = b2Shape::Circle {
         radius: 2.,
@@ -145,7 +135,23 @@ fn setup_particles(mut commands: Commands) {
     let particle_group_def = b2ParticleGroupDef {
         flags: b2ParticleFlags::ElasticParticle,
         shape,
+        color: b2ParticleColor::ZERO,
+        lifetime: 0.,
     };
     let particle_group = b2ParticleGroup::new(particle_system_entity, &particle_group_def);
     commands.spawn(particle_group);
 }
+
+fn draw_particles_by_speed(particle_systems: Query<&b2ParticleSystem>, mut gizmos: Gizmos) {
+    const MAX_SPEED: f32 = 5.;
+    for particle_system in particle_systems.iter() {
+        let radius = particle_system.get_definition().radius;
+        let positions = particle_system.get_positions();
+        let velocities = particle_system.get_velocities();
+        for (position, velocity) in positions.iter().zip(velocities) {
+            let t = (velocity.length() / MAX_SPEED).min(1.);
+            let color = Color::rgb(t, 0.3, 1. - t);
+            gizmos.circle_2d(*position, radius, color);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+const LOWER_FLOOR: f32 = 0.;
+const UPPER_FLOOR: f32 = 8.;
+const ELEVATOR_SPEED: f32 = 2.;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .add_systems(Startup, (setup_camera, setup_instructions))
+        .add_systems(Startup, setup_physics_bodies)
+        .add_systems(Update, (drive_elevator, update_instructions))
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.03,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 5., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_instructions(mut commands: Commands) {
+    commands.spawn(
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            left: Val::Px(15.0),
+            ..default()
+        }),
+    );
+}
+
+fn setup_physics_bodies(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+    let shape = b2Shape::EdgeTwoSided {
+        v1: Vec2::new(-10., -1.),
+        v2: Vec2::new(10., -1.),
+    };
+    commands.spawn((
+        b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)),
+        DebugDrawFixtures::default_static(),
+    ));
+
+    let cabin_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position: Vec2::new(0., LOWER_FLOOR),
+        fixed_rotation: true,
+        allow_sleep: false,
+        ..default()
+    };
+    let cabin_entity = commands.spawn(b2BodyBundle::new(&cabin_def)).id();
+    let fixture_def = b2FixtureDef::new(b2Shape::create_box(2., 0.2), 1.);
+    commands.spawn((
+        b2Fixture::new(cabin_entity, &fixture_def),
+        DebugDrawFixtures::default_dynamic(),
+    ));
+
+    let joint_def = b2PrismaticJointDef {
+        local_anchor_a: Vec2::new(0., LOWER_FLOOR),
+        local_axis_a: Vec2::Y,
+        enable_limit: true,
+        lower_translation: 0.,
+        upper_translation: UPPER_FLOOR - LOWER_FLOOR,
+        enable_motor: true,
+        max_motor_force: 1000.,
+        motor_speed: ELEVATOR_SPEED,
+        ..default()
+    };
+    commands.spawn_empty().add(CreatePrismaticJoint::new(
+        ground_entity,
+        cabin_entity,
+        false,
+        &joint_def,
+    ));
+
+    // A passenger riding the cabin
+    let passenger_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position: Vec2::new(0., LOWER_FLOOR + 0.7),
+        ..default()
+    };
+    let passenger_entity = commands.spawn(b2BodyBundle::new(&passenger_def)).id();
+    let fixture_def = b2FixtureDef {
+        shape: b2Shape::create_box(0.5, 0.5),
+        density: 1.,
+        friction: 0.6,
+        ..default()
+    };
+    commands.spawn((
+        b2Fixture::new(passenger_entity, &fixture_def),
+        DebugDrawFixtures::default_dynamic(),
+    ));
+}
+
+fn drive_elevator(mut joints: Query<&mut b2PrismaticJoint>) {
+    let mut joint = joints.single_mut();
+    // Turn around when a limit is reached, based on the translation of the last step
+    let translation = joint.joint_translation();
+    if translation >= joint.upper_translation - 0.01 && joint.motor_speed > 0. {
+        joint.motor_speed = -ELEVATOR_SPEED;
+    } else if translation <= joint.lower_translation + 0.01 && joint.motor_speed < 0. {
+        joint.motor_speed = ELEVATOR_SPEED;
+    }
+}
+
+fn update_instructions(joints: Query<&b2PrismaticJoint>, mut text: Query<&mut Text>) {
+    let joint = joints.single();
+    let instruction_text = format!(
+        "Translation: {:.2} m\nSpeed: {:.2} m/s\nMotor force: {:.1} N",
+        joint.joint_translation(),
+        joint.joint_speed(),
+        joint.motor_force()
+    );
+
+    let mut text = text.single_mut();
+    *text = Text::from_section(
+        instruction_text,
+        TextStyle {
+            font_size: 20.0,
+            color: Color::WHITE,
+            ..default()
+        },
+    );
+}
@@ -0,0 +1,149 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+const BOMB_POSITION: Vec2 = Vec2::new(0., 0.5);
+const BLAST_RADIUS: f32 = 8.;
+const CRATE_HALF_SIZE: f32 = 0.4;
+
+#[derive(Resource, Default)]
+struct Occlusion(bool);
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .init_resource::<Occlusion>()
+        .add_systems(Startup, (setup_camera, setup_instructions))
+        .add_systems(Startup, (setup_ground, setup_crates))
+        .add_systems(Update, (check_keys, draw_bomb, update_instructions))
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.04,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 8., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_instructions(mut commands: Commands) {
+    commands.spawn(
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            left: Val::Px(15.0),
+            ..default()
+        }),
+    );
+}
+
+fn setup_ground(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+    let shapes = [
+        b2Shape::EdgeTwoSided {
+            v1: Vec2::new(-20., 0.),
+            v2: Vec2::new(20., 0.),
+        },
+        // Shields the pile on the left when occlusion is on
+        b2Shape::create_box_with_offset(0.25, 2., Vec2::new(-2., 2.)),
+    ];
+    for shape in shapes {
+        commands.spawn((
+            b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)),
+            DebugDrawFixtures::default_static(),
+        ));
+    }
+}
+
+fn setup_crates(mut commands: Commands) {
+    for pile_x in [-7., 3.] {
+        for row in 0..4 {
+            for column in 0..(4 - row) {
+                let position = Vec2::new(
+                    pile_x + (column as f32 + row as f32 * 0.5) * CRATE_HALF_SIZE * 2.,
+                    CRATE_HALF_SIZE + row as f32 * CRATE_HALF_SIZE * 2.,
+                );
+                spawn_crate(&mut commands, position);
+            }
+        }
+    }
+}
+
+fn spawn_crate(commands: &mut Commands, position: Vec2) {
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position,
+        allow_sleep: true,
+        ..default()
+    };
+    let crate_entity = commands.spawn(b2BodyBundle::new(&body_def)).id();
+    let fixture_def = b2FixtureDef {
+        shape: b2Shape::create_box(CRATE_HALF_SIZE, CRATE_HALF_SIZE),
+        density: 1.,
+        friction: 0.6,
+        ..default()
+    };
+    commands.spawn((
+        b2Fixture::new(crate_entity, &fixture_def),
+        DebugDrawFixtures::default_dynamic(),
+    ));
+}
+
+fn check_keys(
+    input: Res<Input<KeyCode>>,
+    mut occlusion: ResMut<Occlusion>,
+    mut explosions: EventWriter<ExplosionEvent>,
+) {
+    if input.just_pressed(KeyCode::O) {
+        occlusion.0 = !occlusion.0;
+    }
+    if input.just_pressed(KeyCode::Space) {
+        let mut explosion =
+            Explosion::new(BOMB_POSITION, BLAST_RADIUS, 15.).with_falloff(Falloff::Linear);
+        explosion.occlusion = occlusion.0;
+        explosions.send(ExplosionEvent(explosion));
+    }
+}
+
+fn draw_bomb(mut gizmos: Gizmos) {
+    gizmos.circle_2d(BOMB_POSITION, 0.3, Color::ORANGE_RED);
+    gizmos.circle_2d(BOMB_POSITION, BLAST_RADIUS, Color::rgba(1., 0.3, 0.1, 0.3));
+}
+
+fn update_instructions(occlusion: Res<Occlusion>, mut text: Query<&mut Text>) {
+    let instruction_text = format!(
+        "'Space' Detonate the bomb\n'O' Turn occlusion {}",
+        if occlusion.0 { "off" } else { "on" }
+    );
+
+    let mut text = text.single_mut();
+    *text = Text::from_section(
+        instruction_text,
+        TextStyle {
+            font_size: 20.0,
+            color: Color::WHITE,
+            ..default()
+        },
+    );
+}
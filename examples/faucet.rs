@@ -0,0 +1,149 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+            LiquidFunParticleRenderPlugin,
+        ))
+        .add_systems(Startup, (setup_camera, setup_instructions))
+        .add_systems(
+            Startup,
+            (setup_physics_world, setup_container, setup_faucet).chain(),
+        )
+        .add_systems(Update, (check_keys, update_instructions))
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.01,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 2., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_instructions(mut commands: Commands) {
+    commands.spawn(
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            left: Val::Px(15.0),
+            ..default()
+        }),
+    );
+}
+
+fn setup_physics_world(world: &mut World) {
+    let gravity = Vec2::new(0., -9.81);
+    let b2_world = b2World::new(gravity);
+    world.insert_non_send_resource(b2_world);
+}
+
+fn setup_container(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+    let walls = [
+        b2Shape::create_box_with_offset(2., 0.1, Vec2::new(0., -0.1)),
+        b2Shape::create_box_with_offset(0.1, 1.5, Vec2::new(-2.1, 1.4)),
+        b2Shape::create_box_with_offset(0.1, 1.5, Vec2::new(2.1, 1.4)),
+    ];
+    for shape in walls {
+        commands.spawn((
+            b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)),
+            DebugDrawFixtures::default_static(),
+        ));
+    }
+}
+
+fn setup_faucet(mut commands: Commands) {
+    let particle_system_def = b2ParticleSystemDef {
+        radius: 0.035,
+        damping_strength: 0.2,
+        max_count: 10000,
+        ..default()
+    };
+    let particle_system_entity = commands
+        .spawn((
+            b2ParticleSystem::new(&particle_system_def),
+            RenderParticleSystem {
+                size_scale: 1.5,
+                ..default()
+            },
+        ))
+        .id();
+
+    let mut emitter = b2ParticleEmitter::new(particle_system_entity, Vec2::new(-1.5, 4.5), 400.);
+    emitter.shape = b2EmitterShape::Circle { radius: 0.1 };
+    emitter.velocity = Vec2::new(1., 0.);
+    emitter.velocity_spread = 0.2;
+    emitter.color = Color::rgb(0.2, 0.5, 1.).into();
+    commands.spawn(emitter);
+}
+
+fn check_keys(
+    input: Res<Input<KeyCode>>,
+    mut emitters: Query<&mut b2ParticleEmitter>,
+    mut particle_systems: Query<&mut b2ParticleSystem>,
+) {
+    let mut emitter = emitters.single_mut();
+    if input.just_pressed(KeyCode::Space) {
+        emitter.enabled = !emitter.enabled;
+    }
+    if input.just_pressed(KeyCode::B) {
+        emitter.emit_now(500);
+    }
+    if input.just_pressed(KeyCode::P) {
+        let mut particle_system = particle_systems.single_mut();
+        let paused = particle_system.is_paused();
+        particle_system.set_paused(!paused);
+    }
+}
+
+fn update_instructions(
+    emitters: Query<&b2ParticleEmitter>,
+    particle_systems: Query<&b2ParticleSystem>,
+    mut text: Query<&mut Text>,
+) {
+    let emitter = emitters.single();
+    let particle_system = particle_systems.single();
+    let instruction_text = format!(
+        "'Space' Turn the faucet {}\n'B' Burst\n'P' {} the water\nParticles: {}",
+        if emitter.enabled { "off" } else { "on" },
+        if particle_system.is_paused() {
+            "Unfreeze"
+        } else {
+            "Freeze"
+        },
+        particle_system.particle_count()
+    );
+
+    let mut text = text.single_mut();
+    *text = Text::from_section(
+        instruction_text,
+        TextStyle {
+            font_size: 20.0,
+            color: Color::WHITE,
+            ..default()
+        },
+    );
+}
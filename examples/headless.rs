@@ -0,0 +1,39 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use bevy_liquidfun::prelude::*;
+
+/// Simulates a falling box without a window or renderer, advancing the time by hand.
+fn main() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, LiquidFunPlugin::default().headless()))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            1. / 60.,
+        )))
+        .add_systems(Startup, setup_box);
+
+    for frame in 0..120 {
+        app.update();
+        if frame % 20 == 0 {
+            let mut bodies = app.world.query::<&b2Body>();
+            let body = bodies.single(&app.world);
+            println!("Frame {}: box at {:.3}", frame, body.position.y);
+        }
+    }
+}
+
+fn setup_box(mut commands: Commands) {
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position: Vec2::new(0., 10.),
+        ..default()
+    };
+    let body_entity = commands.spawn(b2BodyBundle::new(&body_def)).id();
+    let fixture_def = b2FixtureDef::new(b2Shape::create_box(0.5, 0.5), 1.);
+    commands.spawn(b2Fixture::new(body_entity, &fixture_def));
+}
@@ -0,0 +1,114 @@
+extern crate bevy;
+extern crate bevy_inspector_egui;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+use bevy_inspector_egui::quick::WorldInspectorPlugin;
+
+use bevy_liquidfun::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+            WorldInspectorPlugin::new(),
+        ))
+        .add_systems(Startup, setup_camera)
+        .add_systems(Startup, (setup_ground, setup_windmill, setup_boxes))
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.03,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 6., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_ground(mut commands: Commands) {
+    let ground_entity = commands
+        .spawn((b2BodyBundle::default(), Name::new("Ground")))
+        .id();
+    let shapes = [
+        b2Shape::EdgeTwoSided {
+            v1: Vec2::new(-10., 0.),
+            v2: Vec2::new(10., 0.),
+        },
+        b2Shape::create_box_with_offset(0.25, 6., Vec2::new(-10., 6.)),
+        b2Shape::create_box_with_offset(0.25, 6., Vec2::new(10., 6.)),
+    ];
+    for shape in shapes {
+        commands.spawn((
+            b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)),
+            DebugDrawFixtures::default_static(),
+        ));
+    }
+}
+
+/// Select the "Windmill joint" entity in the inspector to tune the motor while it runs.
+fn setup_windmill(mut commands: Commands) {
+    let anchor = Vec2::new(0., 5.);
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position: anchor,
+        allow_sleep: false,
+        ..default()
+    };
+    let windmill_entity = commands
+        .spawn((b2BodyBundle::new(&body_def), Name::new("Windmill")))
+        .id();
+    for shape in [b2Shape::create_box(4., 0.2), b2Shape::create_box(0.2, 4.)] {
+        commands.spawn((
+            b2Fixture::new(windmill_entity, &b2FixtureDef::new(shape, 1.)),
+            DebugDrawFixtures::default_dynamic(),
+        ));
+    }
+
+    let joint_def = b2RevoluteJointDef {
+        local_anchor_a: anchor,
+        enable_motor: true,
+        motor_speed: 1.,
+        max_motor_torque: 500.,
+        ..default()
+    };
+    commands
+        .spawn(Name::new("Windmill joint"))
+        .add(CreateRevoluteJoint::new(
+            ground_entity,
+            windmill_entity,
+            false,
+            &joint_def,
+        ));
+}
+
+fn setup_boxes(mut commands: Commands) {
+    for i in 0..8 {
+        let body_def = b2BodyDef {
+            body_type: b2BodyType::Dynamic,
+            position: Vec2::new(-8. + i as f32 * 0.6, 10.),
+            ..default()
+        };
+        let fixture_def = b2FixtureDef {
+            shape: b2Shape::create_box(0.25, 0.25),
+            density: 1.,
+            friction: 0.5,
+            ..default()
+        };
+        commands.spawn((
+            b2BodyBundle::new(&body_def),
+            b2Fixtures::new([fixture_def]),
+            DebugDrawFixtures::default_dynamic(),
+            Name::new(format!("Box {}", i)),
+        ));
+    }
+}
@@ -0,0 +1,110 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+const PLATFORM_AMPLITUDE: Vec2 = Vec2::new(6., 2.);
+const PLATFORM_CENTER: Vec2 = Vec2::new(0., 4.);
+
+#[derive(Component)]
+struct Platform;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .add_systems(Startup, setup_camera)
+        .add_systems(
+            Startup,
+            (setup_physics_world, setup_ground, setup_platform).chain(),
+        )
+        .add_systems(Update, move_platform)
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.05,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 8., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_physics_world(world: &mut World) {
+    let gravity = Vec2::new(0., -9.81);
+    let b2_world = b2World::new(gravity);
+    world.insert_non_send_resource(b2_world);
+}
+
+fn setup_ground(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+    let shape = b2Shape::EdgeTwoSided {
+        v1: Vec2::new(-20., 0.),
+        v2: Vec2::new(20., 0.),
+    };
+    commands.spawn((
+        b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)),
+        DebugDrawFixtures::default_static(),
+    ));
+}
+
+fn setup_platform(mut commands: Commands) {
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Kinematic,
+        position: PLATFORM_CENTER,
+        ..default()
+    };
+    let platform_entity = commands
+        .spawn((
+            b2BodyBundle::new(&body_def),
+            KinematicTarget::new(PLATFORM_CENTER, 0.),
+            Platform,
+        ))
+        .id();
+    let fixture_def = b2FixtureDef {
+        shape: b2Shape::create_box(3., 0.25),
+        friction: 0.8,
+        ..default()
+    };
+    commands.spawn((
+        b2Fixture::new(platform_entity, &fixture_def),
+        DebugDrawFixtures::default_static(),
+    ));
+
+    // A stack of boxes carried along by the platform
+    for i in 0..5 {
+        let body_def = b2BodyDef {
+            body_type: b2BodyType::Dynamic,
+            position: PLATFORM_CENTER + Vec2::new(0., 0.75 + i as f32 * 1.),
+            allow_sleep: true,
+            ..default()
+        };
+        let box_entity = commands.spawn(b2BodyBundle::new(&body_def)).id();
+        let fixture_def = b2FixtureDef {
+            shape: b2Shape::create_box(0.5, 0.5),
+            density: 1.,
+            friction: 0.8,
+            ..default()
+        };
+        commands.spawn((
+            b2Fixture::new(box_entity, &fixture_def),
+            DebugDrawFixtures::default_dynamic(),
+        ));
+    }
+}
+
+fn move_platform(time: Res<Time>, mut platforms: Query<&mut KinematicTarget, With<Platform>>) {
+    let t = time.elapsed_seconds() * 0.5;
+    let mut target = platforms.single_mut();
+    target.position = PLATFORM_CENTER + Vec2::new(t.sin(), (2. * t).sin()) * PLATFORM_AMPLITUDE;
+}
@@ -0,0 +1,97 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use bevy_liquidfun::prelude::*;
+
+const BODY_COUNT: usize = 20_000;
+const FRAME_COUNT: u32 = 300;
+
+/// Measures the frame time of a stack of circles, like the circle_stack example, blown up to
+/// 20k bodies, once with the bodies synced on a single thread and once spread over several. Pass
+/// a different body count as the first argument to see how the syncing of the bodies scales.
+fn main() {
+    let body_count = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(BODY_COUNT);
+
+    let sequential = frame_time(body_count, usize::MAX);
+    let parallel = frame_time(body_count, 0);
+    println!(
+        "{} bodies: {:.3} ms per frame synced on one thread, {:.3} ms on several ({:.2}x)",
+        body_count,
+        sequential.as_secs_f64() * 1000.,
+        parallel.as_secs_f64() * 1000.,
+        sequential.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
+
+fn frame_time(body_count: usize, parallel_sync_threshold: usize) -> Duration {
+    let settings = b2WorldSettings {
+        parallel_sync_threshold,
+        ..default()
+    };
+    let mut app = App::new();
+    // Keeps the transform sync, unlike the headless example, as it is part of what is measured
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        LiquidFunPlugin::new(settings),
+    ))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+        1. / 60.,
+    )))
+    .add_systems(Startup, move |commands: Commands| {
+        setup_physics_bodies(commands, body_count)
+    });
+
+    // Lets the circles settle into a pile before measuring
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let start = Instant::now();
+    for _ in 0..FRAME_COUNT {
+        app.update();
+    }
+    start.elapsed() / FRAME_COUNT
+}
+
+fn setup_physics_bodies(mut commands: Commands, body_count: usize) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+    let shape = b2Shape::EdgeTwoSided {
+        v1: Vec2::new(-200., 0.),
+        v2: Vec2::new(200., 0.),
+    };
+    commands.spawn(b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)));
+
+    let columns = 200;
+    let fixture_def = b2FixtureDef {
+        shape: b2Shape::Circle {
+            radius: 0.5,
+            position: Vec2::ZERO,
+        },
+        density: 1.,
+        friction: 0.3,
+        ..default()
+    };
+    let bodies: Vec<(b2BodyDef, b2FixtureDef)> = (0..body_count)
+        .map(|i| {
+            let body_def = b2BodyDef {
+                body_type: b2BodyType::Dynamic,
+                position: Vec2::new(
+                    (i % columns) as f32 * 1.5 - 150.,
+                    (i / columns) as f32 * 1.2 + 1.,
+                ),
+                ..default()
+            };
+            (body_def, fixture_def.clone())
+        })
+        .collect();
+    commands.spawn_bodies_batch(bodies);
+}
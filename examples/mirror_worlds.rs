@@ -0,0 +1,103 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+const MIRROR_WORLD: PhysicsWorldId = PhysicsWorldId(1);
+
+/// Two independent physics worlds side by side: boxes fall down in the default world on the left
+/// and up in the mirror world on the right. A ray across both halves only hits the boxes of the
+/// world it is cast in.
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .add_systems(Startup, setup_camera)
+        .add_systems(
+            Startup,
+            (
+                setup_physics_worlds,
+                setup_physics_bodies.after(setup_physics_worlds),
+            ),
+        )
+        .add_systems(Update, cast_rays)
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.05,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_physics_worlds(world: &mut World) {
+    world.insert_non_send_resource(b2World::new(Vec2::new(0., -9.81)));
+    world
+        .non_send_resource_mut::<b2Worlds>()
+        .insert(MIRROR_WORLD, b2World::new(Vec2::new(0., 9.81)));
+}
+
+fn setup_physics_bodies(mut commands: Commands) {
+    // The grounds of both worlds span the whole width, each world only sees its own
+    spawn_ground(&mut commands, -15., PhysicsWorldId::DEFAULT);
+    spawn_ground(&mut commands, 15., MIRROR_WORLD);
+
+    let fixture_def = b2FixtureDef::new(b2Shape::create_box(0.5, 0.5), 1.);
+    for i in 0..10 {
+        for (x, world_id) in [(-10., PhysicsWorldId::DEFAULT), (10., MIRROR_WORLD)] {
+            let body_def = b2BodyDef {
+                body_type: b2BodyType::Dynamic,
+                position: Vec2::new(x + 0.2 * (i % 3) as f32, 1.2 * i as f32 - 5.),
+                ..default()
+            };
+            let body_entity = commands
+                .spawn((b2BodyBundle::new(&body_def), world_id))
+                .id();
+            commands.spawn((
+                b2Fixture::new(body_entity, &fixture_def),
+                DebugDrawFixtures::default_dynamic(),
+            ));
+        }
+    }
+}
+
+fn spawn_ground(commands: &mut Commands, y: f32, world_id: PhysicsWorldId) {
+    let ground_entity = commands.spawn((b2BodyBundle::default(), world_id)).id();
+    let shape = b2Shape::EdgeTwoSided {
+        v1: Vec2::new(-20., y),
+        v2: Vec2::new(20., y),
+    };
+    commands.spawn((
+        b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)),
+        DebugDrawFixtures::default_static(),
+    ));
+}
+
+fn cast_rays(ray_caster: PhysicsRayCaster, mut gizmos: Gizmos) {
+    for (y, world_id, color) in [
+        (-12., PhysicsWorldId::DEFAULT, Color::YELLOW),
+        (12., MIRROR_WORLD, Color::CYAN),
+    ] {
+        let Some(world) = ray_caster.in_world(world_id) else {
+            continue;
+        };
+        let start = Vec2::new(-20., y);
+        let end = Vec2::new(20., y);
+        let hits = world.cast_all(start, end, &b2RayCastFilter::default());
+        gizmos.line_2d(start, end, color);
+        for hit in hits {
+            gizmos.circle_2d(hit.point, 0.3, color);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+const MOVE_SPEED: f32 = 6.;
+const JUMP_SPEED: f32 = 12.;
+
+#[derive(Component)]
+struct Player;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .add_systems(Startup, (setup_camera, setup_instructions))
+        .add_systems(
+            Startup,
+            (setup_physics_world, setup_level, setup_player).chain(),
+        )
+        .add_systems(Update, move_player)
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.05,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 10., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_instructions(mut commands: Commands) {
+    commands.spawn(
+        TextBundle::from_section(
+            "'A' 'D' Move\n'Space' Jump up through the platforms",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            left: Val::Px(15.0),
+            ..default()
+        }),
+    );
+}
+
+fn setup_physics_world(world: &mut World) {
+    let gravity = Vec2::new(0., -9.81);
+    let b2_world = b2World::new(gravity);
+    world.insert_non_send_resource(b2_world);
+}
+
+fn setup_level(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+
+    let ground_shape = b2Shape::EdgeTwoSided {
+        v1: Vec2::new(-20., 0.),
+        v2: Vec2::new(20., 0.),
+    };
+    commands.spawn((
+        b2Fixture::new(ground_entity, &b2FixtureDef::new(ground_shape, 0.)),
+        DebugDrawFixtures::default_static(),
+    ));
+
+    let platform_offsets = [Vec2::new(-4., 4.), Vec2::new(3., 8.), Vec2::new(-2., 12.)];
+    for offset in platform_offsets {
+        let platform_shape = b2Shape::create_box_with_offset(3., 0.25, offset);
+        commands.spawn((
+            b2Fixture::new(ground_entity, &b2FixtureDef::new(platform_shape, 0.)),
+            OneWayPlatform::default(),
+            DebugDrawFixtures::default_static(),
+        ));
+    }
+}
+
+fn setup_player(mut commands: Commands) {
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position: Vec2::new(0., 1.),
+        fixed_rotation: true,
+        ..default()
+    };
+    let player_entity = commands.spawn((b2BodyBundle::new(&body_def), Player)).id();
+
+    let fixture_def = b2FixtureDef {
+        shape: b2Shape::create_box(0.4, 0.8),
+        density: 1.,
+        friction: 0.,
+        ..default()
+    };
+    commands.spawn((
+        b2Fixture::new(player_entity, &fixture_def),
+        DebugDrawFixtures::default_dynamic(),
+    ));
+}
+
+fn move_player(input: Res<Input<KeyCode>>, mut players: Query<&mut b2Body, With<Player>>) {
+    let mut body = players.single_mut();
+
+    let mut velocity = body.linear_velocity;
+    velocity.x = if input.pressed(KeyCode::A) {
+        -MOVE_SPEED
+    } else if input.pressed(KeyCode::D) {
+        MOVE_SPEED
+    } else {
+        0.
+    };
+    if input.just_pressed(KeyCode::Space) {
+        velocity.y = JUMP_SPEED;
+    }
+
+    if velocity != body.linear_velocity {
+        body.linear_velocity = velocity;
+    }
+}
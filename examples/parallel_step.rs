@@ -0,0 +1,126 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use bevy_liquidfun::prelude::*;
+
+const WORLD_COUNT: u32 = 1;
+const BODY_COUNT: usize = 5_000;
+const FRAME_COUNT: usize = 300;
+/// Stands in for the extraction and rendering of a frame, which the steps overlap with when they
+/// run on a task.
+const RENDER_TIME: Duration = Duration::from_millis(4);
+
+/// Simulates a pile of boxes and two blocks of water in each world and prints how long a frame
+/// takes, including some busy work in place of the rendering. Run it with and without
+/// `--features parallel_step` to compare stepping on the main thread with stepping alongside the
+/// rendering.
+fn main() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, LiquidFunPlugin::default().headless()))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            1. / 60.,
+        )))
+        .add_systems(
+            Startup,
+            (setup_physics_worlds, setup_physics_bodies).chain(),
+        );
+
+    // Lets the piles settle into their working size before measuring
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let mut frame_times: Vec<Duration> = Vec::with_capacity(FRAME_COUNT);
+    for _ in 0..FRAME_COUNT {
+        let start = Instant::now();
+        app.update();
+        render();
+        frame_times.push(start.elapsed());
+    }
+    frame_times.sort_unstable();
+    println!(
+        "{} worlds of {} bodies and two blocks of water, stepped {}: {:.3} ms per frame (median), {:.3} ms at most",
+        WORLD_COUNT,
+        BODY_COUNT,
+        if cfg!(feature = "parallel_step") {
+            "alongside the rendering"
+        } else {
+            "on the main thread"
+        },
+        frame_times[frame_times.len() / 2].as_secs_f64() * 1000.,
+        frame_times[frame_times.len() - 1].as_secs_f64() * 1000.,
+    );
+}
+
+fn render() {
+    let start = Instant::now();
+    while start.elapsed() < RENDER_TIME {
+        std::hint::spin_loop();
+    }
+}
+
+fn setup_physics_worlds(world: &mut World) {
+    let mut extra_worlds = world.non_send_resource_mut::<b2Worlds>();
+    for id in 1..WORLD_COUNT {
+        extra_worlds.insert(PhysicsWorldId(id), b2World::new(Vec2::new(0., -9.81)));
+    }
+}
+
+fn setup_physics_bodies(mut commands: Commands) {
+    for id in 0..WORLD_COUNT {
+        spawn_scene(&mut commands, PhysicsWorldId(id));
+    }
+}
+
+fn spawn_scene(commands: &mut Commands, world_id: PhysicsWorldId) {
+    let ground_entity = commands.spawn((b2BodyBundle::default(), world_id)).id();
+    let shape = b2Shape::EdgeTwoSided {
+        v1: Vec2::new(-40., 0.),
+        v2: Vec2::new(40., 0.),
+    };
+    commands.spawn(b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)));
+
+    let columns = 40;
+    let fixture_def = b2FixtureDef::new(b2Shape::create_box(0.4, 0.4), 1.);
+    for i in 0..BODY_COUNT {
+        let body_def = b2BodyDef {
+            body_type: b2BodyType::Dynamic,
+            position: Vec2::new(
+                (i % columns) as f32 * 1.2 - 24.,
+                (i / columns) as f32 * 1.2 + 1.,
+            ),
+            allow_sleep: false,
+            ..default()
+        };
+        commands.spawn((
+            b2BodyBundle::new(&body_def),
+            b2Fixtures::new([fixture_def.clone()]),
+            world_id,
+        ));
+    }
+
+    for x in [-32., 32.] {
+        let particle_system_def = b2ParticleSystemDef {
+            radius: 0.15,
+            ..default()
+        };
+        let particle_system_entity = commands
+            .spawn((b2ParticleSystem::new(&particle_system_def), world_id))
+            .id();
+        let particle_group_def = b2ParticleGroupDef {
+            flags: b2ParticleFlags::WaterParticle,
+            shape: b2Shape::create_box_with_offset(4., 4., Vec2::new(x, 5.)),
+            color: b2ParticleColor::ZERO,
+            lifetime: 0.,
+        };
+        commands.spawn(b2ParticleGroup::new(
+            particle_system_entity,
+            &particle_group_def,
+        ));
+    }
+}
@@ -3,16 +3,7 @@ extern crate bevy_liquidfun;
 
 use bevy::prelude::*;
 
-use bevy_liquidfun::dynamics::{b2BodyBundle, b2Fixture, b2FixtureDef};
-use bevy_liquidfun::particles::{
-    b2ParticleFlags, b2ParticleGroup, b2ParticleGroupDef, b2ParticleSystem, b2ParticleSystemDef,
-};
-use bevy_liquidfun::plugins::{LiquidFunDebugDrawPlugin, LiquidFunPlugin};
-use bevy_liquidfun::utils::{DebugDrawFixtures, DebugDrawParticleSystem};
-use bevy_liquidfun::{
-    collision::b2Shape,
-    dynamics::{b2BodyDef, b2BodyType::Dynamic, b2World},
-};
+use bevy_liquidfun::prelude::*;
 
 fn main() {
     App::new()
@@ -54,63 +45,44 @@ fn setup_physics_world(world: &mut World) {
 }
 
 fn setup_ground(mut commands: Commands) {
-    {
-        let ground_entity = commands.spawn(b2BodyBundle::default()).id();
-
-        {
-            let shape = b2Shape::Polygon {
-                vertices: vec![
-                    Vec2::new(-4., -1.),
-                    Vec2::new(4., -1.),
-                    Vec2::new(4., 0.),
-                    Vec2::new(-4., 0.),
-                ],
-            };
-
-            let fixture_def = b2FixtureDef::new(shape, 0.);
-            commands.spawn((
-                b2Fixture::new(ground_entity, &fixture_def),
-                DebugDrawFixtures::default_static(),
-            ));
-        }
-
-        {
-            let shape = b2Shape::Polygon {
-                vertices: vec![
-                    Vec2::new(-4., -0.1),
-                    Vec2::new(-2., -0.1),
-                    Vec2::new(-2., 2.),
-                    Vec2::new(-4., 3.),
-                ],
-            };
-            let fixture_def = b2FixtureDef::new(shape, 0.);
-            commands.spawn((
-                b2Fixture::new(ground_entity, &fixture_def),
-                DebugDrawFixtures::default_static(),
-            ));
-        }
-
-        {
-            let shape = b2Shape::Polygon {
-                vertices: vec![
-                    Vec2::new(2., -0.1),
-                    Vec2::new(4., -0.1),
-                    Vec2::new(4., 3.),
-                    Vec2::new(2., 2.),
-                ],
-            };
-            let fixture_def = b2FixtureDef::new(shape, 0.);
-            commands.spawn((
-                b2Fixture::new(ground_entity, &fixture_def),
-                DebugDrawFixtures::default_static(),
-            ));
-        }
-    }
+    let shapes = [
+        b2Shape::Polygon {
+            vertices: vec![
+                Vec2::new(-4., -1.),
+                Vec2::new(4., -1.),
+                Vec2::new(4., 0.),
+                Vec2::new(-4., 0.),
+            ],
+        },
+        b2Shape::Polygon {
+            vertices: vec![
+                Vec2::new(-4., -0.1),
+                Vec2::new(-2., -0.1),
+                Vec2::new(-2., 2.),
+                Vec2::new(-4., 3.),
+            ],
+        },
+        b2Shape::Polygon {
+            vertices: vec![
+                Vec2::new(2., -0.1),
+                Vec2::new(4., -0.1),
+                Vec2::new(4., 3.),
+                Vec2::new(2., 2.),
+            ],
+        },
+    ];
+    commands.spawn_multi_fixture_body(
+        &b2BodyDef::default(),
+        shapes.map(|shape| b2FixtureDef::new(shape, 0.)),
+        |_, fixture| {
+            fixture.insert(DebugDrawFixtures::default_static());
+        },
+    );
 }
 
 fn setup_circle(mut commands: Commands) {
     let body_def = b2BodyDef {
-        body_type: Dynamic,
+        body_type: b2BodyType::Dynamic,
         position: Vec2::new(0., 8.),
         ..default()
     };
@@ -145,6 +117,8 @@ fn setup_particles(mut commands: Commands) {
     let particle_group_def = b2ParticleGroupDef {
         flags: b2ParticleFlags::WaterParticle,
         shape,
+        color: Color::rgb(0.2, 0.5, 1.).into(),
+        lifetime: 0.,
     };
     let particle_group = b2ParticleGroup::new(particle_system_entity, &particle_group_def);
     commands.spawn(particle_group);
@@ -0,0 +1,208 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+const RUN_SPEED: f32 = 5.;
+const PLATFORM_START: Vec2 = Vec2::new(14., 1.);
+const PLATFORM_TRAVEL: f32 = 6.;
+
+#[derive(Component)]
+struct Player;
+
+#[derive(Component)]
+struct Platform;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunCharacterControllerPlugin,
+            LiquidFunDebugDrawPlugin,
+        ))
+        .add_systems(Startup, (setup_camera, setup_instructions))
+        .add_systems(Startup, (setup_level, setup_platform, setup_player))
+        .add_systems(Update, (control_player, move_platform, update_instructions))
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.04,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(4., 6., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_instructions(mut commands: Commands) {
+    commands.spawn(
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            left: Val::Px(15.0),
+            ..default()
+        }),
+    );
+}
+
+fn setup_level(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+    let shapes = [
+        // Floor
+        b2Shape::EdgeTwoSided {
+            v1: Vec2::new(-12., 0.),
+            v2: Vec2::new(24., 0.),
+        },
+        // A walkable slope
+        b2Shape::Polygon {
+            vertices: vec![Vec2::new(-10., 0.), Vec2::new(-4., 0.), Vec2::new(-4., 3.)],
+        },
+        // A slope too steep to stand on
+        b2Shape::Polygon {
+            vertices: vec![
+                Vec2::new(-12., 0.),
+                Vec2::new(-10.5, 0.),
+                Vec2::new(-12., 6.),
+            ],
+        },
+        // Steps
+        b2Shape::create_box_with_offset(1., 0.1, Vec2::new(1., 0.1)),
+        b2Shape::create_box_with_offset(1., 0.2, Vec2::new(3., 0.2)),
+        b2Shape::create_box_with_offset(1., 0.3, Vec2::new(5., 0.3)),
+        // A wall to slide down
+        b2Shape::create_box_with_offset(0.25, 4., Vec2::new(9., 4.)),
+    ];
+    for shape in shapes {
+        let fixture_def = b2FixtureDef {
+            shape,
+            friction: 0.8,
+            ..default()
+        };
+        commands.spawn((
+            b2Fixture::new(ground_entity, &fixture_def),
+            DebugDrawFixtures::default_static(),
+        ));
+    }
+}
+
+fn setup_platform(mut commands: Commands) {
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Kinematic,
+        position: PLATFORM_START,
+        ..default()
+    };
+    let platform_entity = commands
+        .spawn((
+            b2BodyBundle::new(&body_def),
+            KinematicTarget::new(PLATFORM_START, 0.),
+            Platform,
+        ))
+        .id();
+    let fixture_def = b2FixtureDef {
+        shape: b2Shape::create_box(2., 0.25),
+        friction: 0.8,
+        ..default()
+    };
+    commands.spawn((
+        b2Fixture::new(platform_entity, &fixture_def),
+        DebugDrawFixtures::default_static(),
+    ));
+}
+
+fn setup_player(mut commands: Commands) {
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position: Vec2::new(0., 2.),
+        fixed_rotation: true,
+        allow_sleep: false,
+        ..default()
+    };
+    let player_entity = commands
+        .spawn((
+            b2BodyBundle::new(&body_def),
+            CharacterController::default(),
+            Player,
+        ))
+        .id();
+    let fixture_def = b2FixtureDef {
+        shape: b2Shape::Circle {
+            radius: 0.5,
+            position: Vec2::ZERO,
+        },
+        density: 1.,
+        friction: 0.8,
+        ..default()
+    };
+    commands.spawn((
+        b2Fixture::new(player_entity, &fixture_def),
+        DebugDrawFixtures::default_dynamic(),
+    ));
+}
+
+fn control_player(
+    input: Res<Input<KeyCode>>,
+    mut players: Query<&mut MovementIntent, With<Player>>,
+) {
+    let Ok(mut intent) = players.get_single_mut() else {
+        return;
+    };
+    let mut direction = 0.;
+    if input.pressed(KeyCode::Left) {
+        direction -= 1.;
+    }
+    if input.pressed(KeyCode::Right) {
+        direction += 1.;
+    }
+    intent.desired_velocity = Vec2::new(direction * RUN_SPEED, 0.);
+    if input.just_pressed(KeyCode::Space) {
+        intent.jump();
+    }
+}
+
+fn move_platform(time: Res<Time>, mut platforms: Query<&mut KinematicTarget, With<Platform>>) {
+    let t = time.elapsed_seconds() * 0.5;
+    let mut target = platforms.single_mut();
+    target.position = PLATFORM_START + Vec2::new(0., (t.sin() + 1.) * 0.5 * PLATFORM_TRAVEL);
+}
+
+fn update_instructions(players: Query<&Grounded, With<Player>>, mut text: Query<&mut Text>) {
+    let Ok(grounded) = players.get_single() else {
+        return;
+    };
+    let instruction_text = format!(
+        "'Left' 'Right' Run\n'Space' Jump\n{}",
+        if grounded.is_grounded {
+            "Grounded"
+        } else if grounded.wall_normal.is_some() {
+            "Against a wall"
+        } else {
+            "In the air"
+        }
+    );
+
+    let mut text = text.single_mut();
+    *text = Text::from_section(
+        instruction_text,
+        TextStyle {
+            font_size: 20.0,
+            color: Color::WHITE,
+            ..default()
+        },
+    );
+}
@@ -8,44 +8,48 @@ use bevy::input::prelude::*;
 use bevy::prelude::*;
 use rand::prelude::*;
 
-use bevy_liquidfun::dynamics::{b2BodyBundle, b2Fixture, b2FixtureDef};
-use bevy_liquidfun::plugins::{LiquidFunDebugDrawPlugin, LiquidFunPlugin};
-use bevy_liquidfun::utils::DebugDrawFixtures;
-use bevy_liquidfun::{
-    collision::b2Shape,
-    dynamics::{b2BodyDef, b2BodyType::Dynamic, b2World},
-};
+use bevy_liquidfun::prelude::*;
 
 #[derive(Resource)]
 struct ShapeCollection {
-    pub shapes: Vec<b2Shape>,
+    pub shapes: Vec<Vec<b2Shape>>,
 }
 
 #[derive(Component)]
 struct AllowDestroy;
 
 fn main() {
+    let star_outline: Vec<Vec2> = (0..10)
+        .map(|i| {
+            let angle = i as f32 * PI / 5.;
+            let radius = if i % 2 == 0 { 1.2 } else { 0.5 };
+            Vec2::new(radius * angle.cos(), radius * angle.sin())
+        })
+        .collect();
+
     let available_shapes = vec![
-        b2Shape::Polygon {
+        vec![b2Shape::Polygon {
             vertices: vec![
                 Vec2::new(-0.5, 0.0),
                 Vec2::new(0.5, 0.0),
                 Vec2::new(0.0, 1.5),
             ],
-        },
-        b2Shape::Polygon {
+        }],
+        vec![b2Shape::Polygon {
             vertices: vec![
                 Vec2::new(-0.1, 0.0),
                 Vec2::new(0.1, 0.0),
                 Vec2::new(0.0, 1.5),
             ],
-        },
-        b2Shape::create_regular_polygon(8, 1., 0.),
-        b2Shape::create_box(0.5, 0.5),
-        b2Shape::Circle {
+        }],
+        vec![b2Shape::create_regular_polygon(8, 1., 0.)],
+        vec![b2Shape::create_box(0.5, 0.5)],
+        vec![b2Shape::Circle {
             radius: 0.5,
             position: Vec2::ZERO,
-        },
+        }],
+        b2Shape::decompose_polygon(&star_outline).unwrap(),
+        b2Shape::from_mesh(&Mesh::from(shape::RegularPolygon::new(1., 6))).unwrap(),
     ];
 
     App::new()
@@ -82,7 +86,7 @@ fn setup_camera(mut commands: Commands) {
 fn setup_instructions(mut commands: Commands) {
     commands.spawn(
         TextBundle::from_section(
-            "'1-5' Spawn a new body\n'd' Delete a body",
+            "'1-7' Spawn a new body\n'd' Delete a body",
             TextStyle {
                 font_size: 20.0,
                 color: Color::WHITE,
@@ -136,35 +140,37 @@ fn check_create_body_keys(
         shape_index = Some(3);
     } else if key_input.just_pressed(KeyCode::Key5) {
         shape_index = Some(4);
+    } else if key_input.just_pressed(KeyCode::Key6) {
+        shape_index = Some(5);
+    } else if key_input.just_pressed(KeyCode::Key7) {
+        shape_index = Some(6);
     }
 
     if let Some(i) = shape_index {
-        let shape = &shape_collection.shapes[i];
-        create_body(shape, commands);
+        let shapes = &shape_collection.shapes[i];
+        create_body(shapes, commands);
     }
 }
 
-fn create_body(shape: &b2Shape, mut commands: Commands) {
+fn create_body(shapes: &[b2Shape], mut commands: Commands) {
     let mut rng = thread_rng();
     let body_def = b2BodyDef {
-        body_type: Dynamic,
+        body_type: b2BodyType::Dynamic,
         position: Vec2::new(rng.gen_range(-2.0..=2.0), 10.),
         angle: rng.gen_range(-PI..=PI),
         ..default()
     };
-    let body_entity = commands
-        .spawn((b2BodyBundle::new(&body_def), AllowDestroy))
-        .id();
-
-    let fixture_def = b2FixtureDef {
+    let fixture_defs = shapes.iter().map(|shape| b2FixtureDef {
         shape: shape.clone(),
         density: 1.0,
         friction: 0.3,
         ..default()
-    };
+    });
     commands.spawn((
-        b2Fixture::new(body_entity, &fixture_def),
+        b2BodyBundle::new(&body_def),
+        b2Fixtures::new(fixture_defs),
         DebugDrawFixtures::default_dynamic(),
+        AllowDestroy,
     ));
 }
 
@@ -5,16 +5,7 @@ use std::f32::consts::PI;
 
 use bevy::prelude::*;
 
-use bevy_liquidfun::dynamics::{
-    b2BodyBundle, b2Fixture, b2FixtureDef, b2PrismaticJoint, b2PrismaticJointDef,
-    CreatePrismaticJoint,
-};
-use bevy_liquidfun::plugins::{LiquidFunDebugDrawPlugin, LiquidFunPlugin};
-use bevy_liquidfun::utils::DebugDrawFixtures;
-use bevy_liquidfun::{
-    collision::b2Shape,
-    dynamics::{b2BodyDef, b2BodyType::Dynamic, b2World},
-};
+use bevy_liquidfun::prelude::*;
 
 fn main() {
     App::new()
@@ -31,7 +22,7 @@ fn main() {
                 setup_physics_bodies.after(setup_physics_world),
             ),
         )
-        .add_systems(Update, check_keys)
+        .add_systems(Update, (check_keys, log_limit_events))
         .run();
 }
 
@@ -89,12 +80,14 @@ fn setup_physics_bodies(mut commands: Commands) {
         ..default()
     };
 
-    commands.spawn_empty().add(CreatePrismaticJoint::new(
-        ground_entity,
-        box_entity,
-        true,
-        &joint_def,
-    ));
+    commands
+        .spawn(EmitJointLimitEvents::default())
+        .add(CreatePrismaticJoint::new(
+            ground_entity,
+            box_entity,
+            true,
+            &joint_def,
+        ));
 }
 
 fn create_ground(commands: &mut Commands) -> Entity {
@@ -115,7 +108,7 @@ fn create_ground(commands: &mut Commands) -> Entity {
 
 fn create_box(commands: &mut Commands) -> Entity {
     let body_def = b2BodyDef {
-        body_type: Dynamic,
+        body_type: b2BodyType::Dynamic,
         position: Vec2::new(0., 10.),
         angle: 0.5 * PI,
         allow_sleep: false,
@@ -149,3 +142,10 @@ fn check_keys(input: Res<Input<KeyCode>>, mut joints: Query<&mut b2PrismaticJoin
         joint.motor_speed = -joint.motor_speed;
     }
 }
+
+fn log_limit_events(mut joint_limit_events: EventReader<b2JointLimitEvent>) {
+    for event in joint_limit_events.read() {
+        let action = if event.entering { "Reached" } else { "Left" };
+        info!("{} the {:?} limit", action, event.limit);
+    }
+}
@@ -0,0 +1,150 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .add_systems(Startup, (setup_camera, setup_instructions))
+        .add_systems(
+            Startup,
+            (
+                setup_physics_world,
+                setup_physics_bodies.after(setup_physics_world),
+            ),
+        )
+        .add_systems(Update, (draw_ropes, update_instructions))
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.05,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 10., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_instructions(mut commands: Commands) {
+    commands.spawn(
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            left: Val::Px(15.0),
+            ..default()
+        }),
+    );
+}
+
+fn setup_physics_world(world: &mut World) {
+    let gravity = Vec2::new(0., -9.81);
+    let b2_world = b2World::new(gravity);
+    world.insert_non_send_resource(b2_world);
+}
+
+fn setup_physics_bodies(mut commands: Commands) {
+    let ground_anchor_a = Vec2::new(-5., 20.);
+    let ground_anchor_b = Vec2::new(5., 20.);
+
+    let elevator_position = Vec2::new(-5., 8.);
+    let elevator_entity = create_box(&mut commands, elevator_position, 2., 0.5, 2.);
+
+    let counterweight_position = Vec2::new(5., 12.);
+    let counterweight_entity = create_box(&mut commands, counterweight_position, 1., 1., 1.);
+
+    let joint_def = b2PulleyJointDef::initialize(
+        elevator_position,
+        counterweight_position,
+        ground_anchor_a,
+        ground_anchor_b,
+        elevator_position,
+        counterweight_position,
+        1.,
+    );
+    commands.spawn_empty().add(CreatePulleyJoint::new(
+        elevator_entity,
+        counterweight_entity,
+        false,
+        &joint_def,
+    ));
+}
+
+fn create_box(
+    commands: &mut Commands,
+    position: Vec2,
+    half_width: f32,
+    half_height: f32,
+    density: f32,
+) -> Entity {
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position,
+        fixed_rotation: true,
+        ..default()
+    };
+    let box_entity = commands.spawn(b2BodyBundle::new(&body_def)).id();
+
+    let box_shape = b2Shape::create_box(half_width, half_height);
+    let fixture_def = b2FixtureDef::new(box_shape, density);
+    commands.spawn((
+        b2Fixture::new(box_entity, &fixture_def),
+        DebugDrawFixtures::default_dynamic(),
+    ));
+
+    return box_entity;
+}
+
+fn draw_ropes(joints: Query<&b2PulleyJoint>, mut gizmos: Gizmos) {
+    for joint in joints.iter() {
+        let a = joint.ground_anchor_a();
+        let b = joint.ground_anchor_b();
+        gizmos.line_2d(a, b, Color::GRAY);
+        gizmos.line_2d(
+            a,
+            a - Vec2::Y * joint.current_length_a(),
+            Color::ANTIQUE_WHITE,
+        );
+        gizmos.line_2d(
+            b,
+            b - Vec2::Y * joint.current_length_b(),
+            Color::ANTIQUE_WHITE,
+        );
+    }
+}
+
+fn update_instructions(joints: Query<&b2PulleyJoint>, mut text: Query<&mut Text>) {
+    let joint = joints.single();
+    let mut text = text.single_mut();
+    *text = Text::from_section(
+        format!(
+            "Elevator rope: {:.2}\nCounterweight rope: {:.2}",
+            joint.current_length_a(),
+            joint.current_length_b()
+        ),
+        TextStyle {
+            font_size: 20.0,
+            color: Color::WHITE,
+            ..default()
+        },
+    );
+}
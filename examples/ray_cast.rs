@@ -8,15 +8,7 @@ use bevy::input::prelude::*;
 use bevy::prelude::*;
 use rand::prelude::*;
 
-use bevy_liquidfun::dynamics::{
-    b2BodyBundle, b2Fixture, b2FixtureDef, b2RayCastAll, b2RayCastAny, b2RayCastClosest,
-};
-use bevy_liquidfun::plugins::{LiquidFunDebugDrawPlugin, LiquidFunPlugin};
-use bevy_liquidfun::utils::DebugDrawFixtures;
-use bevy_liquidfun::{
-    collision::b2Shape,
-    dynamics::{b2BodyDef, b2BodyType::Dynamic, b2World},
-};
+use bevy_liquidfun::prelude::*;
 
 #[derive(Resource)]
 struct ShapeCollection {
@@ -182,7 +174,7 @@ fn check_create_body_keys(
 fn create_body(shape: &b2Shape, mut commands: Commands) {
     let mut rng = thread_rng();
     let body_def = b2BodyDef {
-        body_type: Dynamic,
+        body_type: b2BodyType::Dynamic,
         position: Vec2::new(rng.gen_range(-10.0..=10.0), rng.gen_range(0.0..=20.0)),
         angle: rng.gen_range(-PI..=PI),
         ..default()
@@ -223,7 +215,7 @@ fn check_delete_body_key(
 fn cast_ray(
     mut gizmos: Gizmos,
     time: Res<Time>,
-    mut b2_world: NonSendMut<b2World>,
+    ray_caster: PhysicsRayCaster,
     mode: Res<RayCastMode>,
 ) {
     let ray_start = Vec2::new(0., 10.);
@@ -232,24 +224,22 @@ fn cast_ray(
     let ray_end = ray_start + Vec2::new(RAY_LENGTH * f32::cos(angle), RAY_LENGTH * f32::sin(angle));
     gizmos.line_2d(ray_start, ray_end, Color::WHITE);
 
+    let filter = b2RayCastFilter::default();
     match mode.as_ref() {
         RayCastMode::Closest => {
-            let callback = b2RayCastClosest::new();
-            let hit = b2_world.ray_cast(callback, &ray_start, &ray_end);
+            let hit = ray_caster.cast_closest(ray_start, ray_end, &filter);
             if let Some(hit) = hit {
                 gizmos.line_2d(hit.point, hit.point + hit.normal, Color::ORANGE_RED);
             }
         }
         RayCastMode::Any => {
-            let callback = b2RayCastAny::new();
-            let hit = b2_world.ray_cast(callback, &ray_start, &ray_end);
+            let hit = ray_caster.cast_any(ray_start, ray_end, &filter);
             if let Some(hit) = hit {
                 gizmos.line_2d(hit.point, hit.point + hit.normal, Color::ORANGE_RED);
             }
         }
         RayCastMode::All => {
-            let callback = b2RayCastAll::new();
-            let hits = b2_world.ray_cast(callback, &ray_start, &ray_end);
+            let hits = ray_caster.cast_all(ray_start, ray_end, &filter);
             for hit in hits {
                 gizmos.line_2d(hit.point, hit.point + hit.normal, Color::ORANGE_RED);
             }
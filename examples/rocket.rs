@@ -0,0 +1,126 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+const THRUST: f32 = 30.;
+const TURN_TORQUE: f32 = 4.;
+
+#[derive(Component)]
+struct Rocket;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .add_systems(Startup, (setup_camera, setup_instructions))
+        .add_systems(Startup, (setup_ground, setup_rocket))
+        .add_systems(Update, control_rocket)
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.04,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 10., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_instructions(mut commands: Commands) {
+    commands.spawn(
+        TextBundle::from_section(
+            "'Up' Thrust\n'Left' 'Right' Turn",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            left: Val::Px(15.0),
+            ..default()
+        }),
+    );
+}
+
+fn setup_ground(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+    let shapes = [
+        b2Shape::EdgeTwoSided {
+            v1: Vec2::new(-20., 0.),
+            v2: Vec2::new(20., 0.),
+        },
+        b2Shape::create_box_with_offset(0.25, 10., Vec2::new(-20., 10.)),
+        b2Shape::create_box_with_offset(0.25, 10., Vec2::new(20., 10.)),
+    ];
+    for shape in shapes {
+        commands.spawn((
+            b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)),
+            DebugDrawFixtures::default_static(),
+        ));
+    }
+}
+
+fn setup_rocket(mut commands: Commands) {
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position: Vec2::new(0., 1.),
+        allow_sleep: false,
+        ..default()
+    };
+    let rocket_entity = commands
+        .spawn((
+            b2BodyBundle::new(&body_def),
+            LocalExternalForce::default(),
+            Rocket,
+        ))
+        .id();
+    let fixture_def = b2FixtureDef {
+        shape: b2Shape::Polygon {
+            vertices: vec![
+                Vec2::new(-0.4, -1.),
+                Vec2::new(0.4, -1.),
+                Vec2::new(0.4, 0.5),
+                Vec2::new(0., 1.),
+                Vec2::new(-0.4, 0.5),
+            ],
+        },
+        density: 1.,
+        friction: 0.6,
+        ..default()
+    };
+    commands.spawn((
+        b2Fixture::new(rocket_entity, &fixture_def),
+        DebugDrawFixtures::default_dynamic(),
+    ));
+}
+
+fn control_rocket(
+    input: Res<Input<KeyCode>>,
+    mut rockets: Query<&mut LocalExternalForce, With<Rocket>>,
+) {
+    let mut force = rockets.single_mut();
+    if input.pressed(KeyCode::Up) {
+        // Pushes the rocket from its nozzle, along its nose
+        force.apply_force_at_point(Vec2::new(0., THRUST), Vec2::new(0., -1.));
+    }
+    if input.pressed(KeyCode::Left) {
+        force.apply_torque(TURN_TORQUE);
+    }
+    if input.pressed(KeyCode::Right) {
+        force.apply_torque(-TURN_TORQUE);
+    }
+}
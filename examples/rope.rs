@@ -0,0 +1,125 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+const ANCHOR_START: Vec2 = Vec2::new(-6., 12.);
+
+#[derive(Component)]
+struct Anchor;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .add_systems(Startup, setup_camera)
+        .add_systems(Startup, (setup_bridge, setup_hanging_rope))
+        .add_systems(Update, (draw_rope_segments, move_anchor))
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.04,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 6., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_bridge(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+    let cliffs = [
+        b2Shape::create_box_with_offset(2., 2., Vec2::new(-10., 2.)),
+        b2Shape::create_box_with_offset(2., 2., Vec2::new(10., 2.)),
+    ];
+    for shape in cliffs {
+        commands.spawn((
+            b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)),
+            DebugDrawFixtures::default_static(),
+        ));
+    }
+
+    let bridge_def = RopeDef {
+        segment_count: 16,
+        thickness: 0.25,
+        friction: 0.6,
+        ..default()
+    };
+    commands.spawn_empty().add(
+        CreateRope::new(Vec2::new(-8., 4.), Vec2::new(8., 4.), &bridge_def)
+            .with_start_body(ground_entity)
+            .with_end_body(ground_entity),
+    );
+
+    // Something to walk the bridge
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position: Vec2::new(-2., 6.),
+        ..default()
+    };
+    let box_entity = commands.spawn(b2BodyBundle::new(&body_def)).id();
+    let fixture_def = b2FixtureDef::new(b2Shape::create_box(0.5, 0.5), 1.);
+    commands.spawn((
+        b2Fixture::new(box_entity, &fixture_def),
+        DebugDrawFixtures::default_dynamic(),
+    ));
+}
+
+fn setup_hanging_rope(mut commands: Commands) {
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Kinematic,
+        position: ANCHOR_START,
+        ..default()
+    };
+    let anchor_entity = commands
+        .spawn((
+            b2BodyBundle::new(&body_def),
+            KinematicTarget::new(ANCHOR_START, 0.),
+            Anchor,
+        ))
+        .id();
+    let fixture_def = b2FixtureDef::new(b2Shape::create_box(0.3, 0.3), 0.);
+    commands.spawn((
+        b2Fixture::new(anchor_entity, &fixture_def),
+        DebugDrawFixtures::default_static(),
+    ));
+
+    let rope_def = RopeDef {
+        segment_count: 12,
+        joint_type: RopeJointType::Distance {
+            stiffness: 2000.,
+            damping: 10.,
+        },
+        ..default()
+    };
+    commands.spawn_empty().add(
+        CreateRope::new(ANCHOR_START, ANCHOR_START - Vec2::new(0., 6.), &rope_def)
+            .with_start_body(anchor_entity),
+    );
+}
+
+fn draw_rope_segments(mut commands: Commands, ropes: Query<&Rope, Added<Rope>>) {
+    for rope in ropes.iter() {
+        for segment in &rope.segments {
+            commands
+                .entity(*segment)
+                .insert(DebugDrawFixtures::default_dynamic());
+        }
+    }
+}
+
+fn move_anchor(time: Res<Time>, mut anchors: Query<&mut KinematicTarget, With<Anchor>>) {
+    let t = time.elapsed_seconds();
+    let mut target = anchors.single_mut();
+    target.position = ANCHOR_START + Vec2::new(t.sin() * 3., 0.);
+}
@@ -0,0 +1,132 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+const GRAVITY: Vec2 = Vec2::new(0., -9.81);
+const STEAM: u32 = 1;
+
+const HOT_PLATE_CENTER: Vec2 = Vec2::new(0.5, 0.1);
+const HOT_PLATE_HALF_EXTENTS: Vec2 = Vec2::new(0.8, 0.1);
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .add_systems(Startup, setup_camera)
+        .add_systems(
+            Startup,
+            (setup_physics_world, setup_container, setup_water).chain(),
+        )
+        .add_systems(Update, (boil_water, lift_steam))
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.01,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 2., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_physics_world(world: &mut World) {
+    let b2_world = b2World::new(GRAVITY);
+    world.insert_non_send_resource(b2_world);
+}
+
+fn setup_container(mut commands: Commands) {
+    let ground_entity = commands.spawn(b2BodyBundle::default()).id();
+    let walls = [
+        b2Shape::create_box_with_offset(2., 0.1, Vec2::new(0., -0.1)),
+        b2Shape::create_box_with_offset(0.1, 2.5, Vec2::new(-2.1, 2.4)),
+        b2Shape::create_box_with_offset(0.1, 2.5, Vec2::new(2.1, 2.4)),
+        b2Shape::create_box_with_offset(2.2, 0.1, Vec2::new(0., 5.)),
+    ];
+    for shape in walls {
+        commands.spawn((
+            b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)),
+            DebugDrawFixtures::default_static(),
+        ));
+    }
+
+    let hot_plate_shape = b2Shape::create_box_with_offset(
+        HOT_PLATE_HALF_EXTENTS.x,
+        HOT_PLATE_HALF_EXTENTS.y,
+        HOT_PLATE_CENTER,
+    );
+    let mut hot_plate_def = b2FixtureDef::new(hot_plate_shape, 0.);
+    hot_plate_def.is_sensor = true;
+    commands.spawn((
+        b2Fixture::new(ground_entity, &hot_plate_def),
+        DebugDrawFixtures {
+            awake_color: Color::RED,
+            asleep_color: Color::RED,
+            ..default()
+        },
+    ));
+}
+
+fn setup_water(mut commands: Commands) {
+    let particle_system_def = b2ParticleSystemDef {
+        radius: 0.035,
+        damping_strength: 0.2,
+        max_count: 5000,
+        ..default()
+    };
+    let particle_system_entity = commands
+        .spawn((
+            b2ParticleSystem::new(&particle_system_def).with_user_data(),
+            DebugDrawParticleSystem {},
+        ))
+        .id();
+
+    let mut emitter = b2ParticleEmitter::new(particle_system_entity, Vec2::new(-1.5, 3.), 200.);
+    emitter.shape = b2EmitterShape::Circle { radius: 0.1 };
+    emitter.velocity = Vec2::new(1., 0.);
+    emitter.color = Color::rgb(0.2, 0.5, 1.).into();
+    commands.spawn(emitter);
+}
+
+/// Turns the water particles touching the hot plate into steam.
+fn boil_water(mut particle_systems: Query<&mut b2ParticleSystem>) {
+    let mut particle_system = particle_systems.single_mut();
+    let radius = particle_system.get_definition().radius;
+    let hot_area = HOT_PLATE_HALF_EXTENTS + Vec2::splat(radius);
+    for index in 0..particle_system.particle_count() {
+        if particle_system.get_user_data(index) == STEAM {
+            continue;
+        }
+        let offset = particle_system.get_positions()[index] - HOT_PLATE_CENTER;
+        if offset.x.abs() > hot_area.x || offset.y.abs() > hot_area.y {
+            continue;
+        }
+        particle_system.set_particle_user_data(index, STEAM);
+        particle_system.set_particle_flags(index, b2ParticleFlags::PowderParticle);
+        particle_system.set_particle_color(index, Color::WHITE);
+    }
+}
+
+/// Box2D applies the same gravity to all the particles of a system, so the steam particles have
+/// it canceled out by hand, and drift up slowly.
+fn lift_steam(time: Res<Time>, mut particle_systems: Query<&mut b2ParticleSystem>) {
+    let mut particle_system = particle_systems.single_mut();
+    let lift = (-GRAVITY + Vec2::new(0., 0.5)) * time.delta_seconds();
+    for index in 0..particle_system.particle_count() {
+        if particle_system.get_user_data(index) != STEAM {
+            continue;
+        }
+        let velocity = particle_system.get_velocity(index);
+        particle_system.set_particle_velocity(index, velocity + lift);
+    }
+}
@@ -0,0 +1,118 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use bevy_liquidfun::prelude::*;
+
+const GRID_SIZES: [usize; 7] = [10, 20, 32, 40, 50, 71, 100];
+const BOX_HALF_SIZE: f32 = 0.2;
+const FRAME_COUNT: u32 = 120;
+
+/// Spawns grids of boxes up to 100x100 and prints how long it takes to create them one entity at a
+/// time and in a batch, and how long a frame takes with the bodies synced on one thread and on
+/// several. The smallest grid for which the parallel sync is faster is the body count to use for
+/// [`b2WorldSettings::parallel_sync_threshold`] on this machine.
+fn main() {
+    let mut parallel_from = None;
+    for grid_size in GRID_SIZES {
+        let body_count = grid_size * grid_size;
+        let (_, individual_spawn) = grid_app(grid_size, false, usize::MAX);
+        let (mut sequential_app, batch_spawn) = grid_app(grid_size, true, usize::MAX);
+        let (mut parallel_app, _) = grid_app(grid_size, true, 0);
+        let sequential_frame = frame_time(&mut sequential_app);
+        let parallel_frame = frame_time(&mut parallel_app);
+        println!(
+            "{:>5} bodies: spawned in {:.1} ms one at a time, {:.1} ms in a batch; {:.3} ms per frame synced on one thread, {:.3} ms on several",
+            body_count,
+            individual_spawn.as_secs_f64() * 1000.,
+            batch_spawn.as_secs_f64() * 1000.,
+            sequential_frame.as_secs_f64() * 1000.,
+            parallel_frame.as_secs_f64() * 1000.,
+        );
+        if parallel_from.is_none() && parallel_frame < sequential_frame {
+            parallel_from = Some(body_count);
+        }
+    }
+    match parallel_from {
+        Some(body_count) => println!(
+            "The parallel sync pays off from about {} bodies",
+            body_count
+        ),
+        None => println!("The parallel sync didn't pay off for any of the grids"),
+    }
+}
+
+/// An app with a grid of falling boxes, along with the time it took to create them in the
+/// physics world.
+fn grid_app(grid_size: usize, batch: bool, parallel_sync_threshold: usize) -> (App, Duration) {
+    let settings = b2WorldSettings {
+        parallel_sync_threshold,
+        ..default()
+    };
+    let mut app = App::new();
+    // Keeps the transform sync, as it is part of what is measured
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        LiquidFunPlugin::new(settings),
+    ))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+        1. / 60.,
+    )));
+    app.update();
+
+    let ground_entity = app.world.spawn(b2BodyBundle::default()).id();
+    let shape = b2Shape::EdgeTwoSided {
+        v1: Vec2::new(-60., 0.),
+        v2: Vec2::new(60., 0.),
+    };
+    app.world
+        .spawn(b2Fixture::new(ground_entity, &b2FixtureDef::new(shape, 0.)));
+
+    let start = Instant::now();
+    let fixture_def = b2FixtureDef::new(b2Shape::create_box(BOX_HALF_SIZE, BOX_HALF_SIZE), 1.);
+    let body_defs = (0..grid_size * grid_size).map(|i| {
+        let (x, y) = ((i % grid_size) as f32, (i / grid_size) as f32);
+        b2BodyDef {
+            body_type: b2BodyType::Dynamic,
+            position: Vec2::new(x - grid_size as f32 / 2., y + 1.) * BOX_HALF_SIZE * 4.,
+            allow_sleep: false,
+            ..default()
+        }
+    });
+    if batch {
+        let bodies: Vec<_> = body_defs
+            .map(|body_def| {
+                (
+                    b2BodyBundle::new(&body_def),
+                    b2Fixtures::new([fixture_def.clone()]),
+                )
+            })
+            .collect();
+        app.world.spawn_batch(bodies);
+    } else {
+        for body_def in body_defs {
+            let body_entity = app.world.spawn(b2BodyBundle::new(&body_def)).id();
+            app.world.spawn(b2Fixture::new(body_entity, &fixture_def));
+        }
+    }
+    // The bodies are created and stepped once during the update
+    app.update();
+    (app, start.elapsed())
+}
+
+fn frame_time(app: &mut App) -> Duration {
+    // Lets the grid fall into a pile before measuring
+    for _ in 0..60 {
+        app.update();
+    }
+    let start = Instant::now();
+    for _ in 0..FRAME_COUNT {
+        app.update();
+    }
+    start.elapsed() / FRAME_COUNT
+}
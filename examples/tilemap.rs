@@ -0,0 +1,118 @@
+extern crate bevy;
+extern crate bevy_liquidfun;
+extern crate rand;
+
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+const TILE_SIZE: f32 = 1.;
+
+// The first row is the top of the level
+const LEVEL: [&str; 12] = [
+    "#..................#",
+    "#..................#",
+    "#.....####.........#",
+    "#..................#",
+    "#.........#.....##.#",
+    "#.###....##........#",
+    "#.#.#...#.#........#",
+    "#.###..#..#....#...#",
+    "#......####...#.#..#",
+    "#.............#.#..#",
+    "##...........#...#.#",
+    "####################",
+];
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LiquidFunPlugin::default(),
+            LiquidFunDebugDrawPlugin,
+        ))
+        .add_systems(Startup, (setup_camera, setup_instructions))
+        .add_systems(Startup, setup_level)
+        .add_systems(Update, spawn_balls)
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.03,
+            far: 1000.,
+            near: -1000.,
+            ..OrthographicProjection::default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 0., 0.)),
+        ..Camera2dBundle::default()
+    });
+}
+
+fn setup_instructions(mut commands: Commands) {
+    commands.spawn(
+        TextBundle::from_section(
+            "'Space' Drop balls",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            left: Val::Px(15.0),
+            ..default()
+        }),
+    );
+}
+
+fn setup_level(mut commands: Commands) {
+    let size = UVec2::new(LEVEL[0].len() as u32, LEVEL.len() as u32);
+    let grid = FnGridView::new(size, |x, y| {
+        let row = LEVEL[(size.y - 1 - y) as usize];
+        row.as_bytes()[x as usize] == b'#'
+    });
+    let origin = -size.as_vec2() * TILE_SIZE / 2.;
+    let fixture_def = b2FixtureDef {
+        friction: 0.6,
+        ..default()
+    };
+    let level_entity = commands.spawn_tilemap_colliders(&grid, TILE_SIZE, origin, &fixture_def);
+    commands
+        .entity(level_entity)
+        .insert(DebugDrawFixtures::default_static());
+}
+
+fn spawn_balls(input: Res<Input<KeyCode>>, mut commands: Commands) {
+    if !input.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    let mut rng = thread_rng();
+    for _ in 0..10 {
+        let body_def = b2BodyDef {
+            body_type: b2BodyType::Dynamic,
+            position: Vec2::new(rng.gen_range(-8.0..=8.), 5.),
+            ..default()
+        };
+        let fixture_def = b2FixtureDef {
+            shape: b2Shape::Circle {
+                radius: 0.25,
+                position: Vec2::ZERO,
+            },
+            density: 1.,
+            friction: 0.3,
+            restitution: 0.2,
+            ..default()
+        };
+        commands.spawn((
+            b2BodyBundle::new(&body_def),
+            b2Fixtures::new([fixture_def]),
+            DebugDrawFixtures::default_dynamic(),
+        ));
+    }
+}
@@ -5,19 +5,7 @@ use std::f32::consts::PI;
 
 use bevy::prelude::*;
 
-use bevy_liquidfun::dynamics::{
-    b2Body, b2BodyBundle, b2Fixture, b2FixtureDef, b2Joint, b2RevoluteJoint, b2RevoluteJointDef,
-    CreateRevoluteJoint,
-};
-use bevy_liquidfun::particles::{
-    b2ParticleFlags, b2ParticleGroup, b2ParticleGroupDef, b2ParticleSystem, b2ParticleSystemDef,
-};
-use bevy_liquidfun::plugins::{LiquidFunDebugDrawPlugin, LiquidFunPlugin};
-use bevy_liquidfun::utils::{DebugDrawFixtures, DebugDrawParticleSystem};
-use bevy_liquidfun::{
-    collision::b2Shape,
-    dynamics::{b2BodyDef, b2BodyType::Dynamic, b2World},
-};
+use bevy_liquidfun::prelude::*;
 
 fn main() {
     App::new()
@@ -58,26 +46,25 @@ fn setup_box(mut commands: Commands) {
 
     let box_pos = Vec2::new(0., 1.);
     let box_def = b2BodyDef {
-        body_type: Dynamic,
+        body_type: b2BodyType::Dynamic,
         position: box_pos,
         ..default()
     };
-    let box_entity = commands.spawn(b2BodyBundle::new(&box_def)).id();
-
     let shapes = vec![
         b2Shape::create_box_with_offset(0.05, 1., Vec2::new(2.0, 0.0)),
         b2Shape::create_box_with_offset(0.05, 1., Vec2::new(-2.0, 0.0)),
         b2Shape::create_box_with_offset(2., 0.05, Vec2::new(0.0, 1.0)),
         b2Shape::create_box_with_offset(2., 0.05, Vec2::new(0.0, -1.0)),
     ];
+    let fixtures = b2Fixtures::new(shapes.into_iter().map(|shape| b2FixtureDef::new(shape, 5.)));
 
-    shapes.into_iter().for_each(|shape| {
-        let fixture_def = b2FixtureDef::new(shape, 5.);
-        commands.spawn((
-            b2Fixture::new(box_entity, &fixture_def),
+    let box_entity = commands
+        .spawn((
+            b2BodyBundle::new(&box_def),
+            fixtures,
             DebugDrawFixtures::default_static(),
-        ));
-    });
+        ))
+        .id();
 
     let joint_def = b2RevoluteJointDef {
         local_anchor_a: box_pos,
@@ -108,19 +95,17 @@ fn setup_particles(mut commands: Commands) {
     let particle_group_def = b2ParticleGroupDef {
         flags: b2ParticleFlags::WaterParticle,
         shape,
+        color: Color::rgb(0.2, 0.5, 1.).into(),
+        lifetime: 0.,
     };
     let particle_group = b2ParticleGroup::new(particle_system_entity, &particle_group_def);
     commands.spawn(particle_group);
 }
 
-fn set_motor_speed(mut joints: Query<(&b2Joint, &mut b2RevoluteJoint)>, bodies: Query<&b2Body>) {
-    let joint = joints.single();
-    let box_entity = joint.0.body_b();
-    let box_body = bodies.get(*box_entity).unwrap();
-
-    if let Some(speed) = calculate_new_speed(box_body.angle) {
-        let mut joint = joints.single_mut();
-        joint.1.motor_speed = speed;
+fn set_motor_speed(mut joints: Query<&mut b2RevoluteJoint>) {
+    let mut joint = joints.single_mut();
+    if let Some(speed) = calculate_new_speed(joint.joint_angle()) {
+        joint.motor_speed = speed;
     }
 }
 
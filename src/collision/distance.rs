@@ -0,0 +1,87 @@
+use autocxx::WithinBox;
+use bevy::math::Vec2;
+use libliquidfun_sys::box2d::ffi;
+use libliquidfun_sys::box2d::ffi::{int32, uint16};
+
+use crate::collision::{b2Shape, ffi_child_count};
+use crate::internal::*;
+
+/// The closest points between two shapes, and the distance between them.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone)]
+pub struct b2DistanceOutput {
+    /// The point on the first shape closest to the second one, in world coordinates.
+    pub point_a: Vec2,
+    /// The point on the second shape closest to the first one, in world coordinates.
+    pub point_b: Vec2,
+    /// The distance between the shapes, 0 if they overlap.
+    pub distance: f32,
+}
+
+/// The distance between two shapes placed at the given positions and angles. For chains, this
+/// is the distance to the closest edge.
+pub fn distance_between(
+    shape_a: &b2Shape,
+    transform_a: (Vec2, f32),
+    shape_b: &b2Shape,
+    transform_b: (Vec2, f32),
+) -> b2DistanceOutput {
+    let ffi_shape_a = shape_a.to_ffi();
+    let ffi_shape_b = shape_b.to_ffi();
+    let (shape_a, shape_b) = (ffi_shape_a.as_ffi(), ffi_shape_b.as_ffi());
+    (0..ffi_child_count(shape_a))
+        .flat_map(|child_a| {
+            (0..ffi_child_count(shape_b)).map(move |child_b| {
+                ffi_distance(
+                    (shape_a, child_a),
+                    transform_a,
+                    (shape_b, child_b),
+                    transform_b,
+                )
+            })
+        })
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))
+        .unwrap_or(b2DistanceOutput {
+            point_a: transform_a.0,
+            point_b: transform_b.0,
+            distance: f32::INFINITY,
+        })
+}
+
+/// The distance between one child of each shape, e.g. a single edge of a chain.
+pub(crate) fn ffi_distance(
+    (shape_a, child_a): (&ffi::b2Shape, i32),
+    transform_a: (Vec2, f32),
+    (shape_b, child_b): (&ffi::b2Shape, i32),
+    transform_b: (Vec2, f32),
+) -> b2DistanceOutput {
+    let mut input = ffi::b2DistanceInput::new().within_box();
+    input.proxyA.Set(shape_a, int32::from(child_a));
+    input.proxyB.Set(shape_b, int32::from(child_b));
+    input
+        .transformA
+        .Set(&to_b2Vec2(&transform_a.0), transform_a.1);
+    input
+        .transformB
+        .Set(&to_b2Vec2(&transform_b.0), transform_b.1);
+    input.useRadii = true;
+
+    // An empty cache, GJK starts from scratch
+    let mut cache = ffi::b2SimplexCache::new().within_box();
+    cache.count = uint16::from(0);
+
+    let mut output = ffi::b2DistanceOutput::new().within_box();
+    let _box2d_globals = lock_box2d_globals();
+    unsafe {
+        ffi::b2Distance(
+            output.as_mut().get_unchecked_mut(),
+            cache.as_mut().get_unchecked_mut(),
+            input.as_ref().get_ref(),
+        );
+    }
+    b2DistanceOutput {
+        point_a: to_Vec2(&output.pointA),
+        point_b: to_Vec2(&output.pointB),
+        distance: output.distance,
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use bevy::math::{IVec2, Vec2};
+
+/// The closed outlines around the solid cells of a grid, counter-clockwise around islands and
+/// clockwise around holes, with vertices on the cell corners. Cells outside of the grid are
+/// empty, and cells that only touch at a corner belong to separate islands.
+pub(crate) fn trace_outlines(
+    width: i32,
+    height: i32,
+    is_solid: impl Fn(i32, i32) -> bool,
+) -> Vec<Vec<Vec2>> {
+    // Every side of a solid cell that faces an empty one is an edge, directed so that the
+    // solid cell is on its left
+    let mut edges: HashMap<IVec2, Vec<IVec2>> = HashMap::new();
+    for y in 0..height {
+        for x in 0..width {
+            if !is_solid(x, y) {
+                continue;
+            }
+            let sides = [
+                (IVec2::new(0, -1), IVec2::new(x, y), IVec2::X),
+                (IVec2::new(1, 0), IVec2::new(x + 1, y), IVec2::Y),
+                (IVec2::new(0, 1), IVec2::new(x + 1, y + 1), IVec2::NEG_X),
+                (IVec2::new(-1, 0), IVec2::new(x, y + 1), IVec2::NEG_Y),
+            ];
+            for (neighbour, start, direction) in sides {
+                if !is_solid(x + neighbour.x, y + neighbour.y) {
+                    edges.entry(start).or_default().push(direction);
+                }
+            }
+        }
+    }
+
+    let mut start_corners: Vec<IVec2> = edges.keys().copied().collect();
+    start_corners.sort_by_key(|corner| (corner.y, corner.x));
+
+    let mut outlines = Vec::new();
+    for start in start_corners {
+        while let Some(&start_direction) =
+            edges.get(&start).and_then(|directions| directions.first())
+        {
+            let mut outline = vec![start.as_vec2()];
+            let mut corner = start + start_direction;
+            let mut direction = start_direction;
+            loop {
+                let Some(next_direction) = next_edge(&edges, corner, direction) else {
+                    break;
+                };
+                if corner == start && next_direction == start_direction {
+                    break;
+                }
+                remove_edge(&mut edges, corner, next_direction);
+                if next_direction != direction {
+                    outline.push(corner.as_vec2());
+                }
+                corner += next_direction;
+                direction = next_direction;
+            }
+            remove_edge(&mut edges, start, start_direction);
+
+            if direction == start_direction {
+                outline.remove(0);
+            }
+            if outline.len() >= 3 {
+                outlines.push(outline);
+            }
+        }
+    }
+    outlines
+}
+
+/// Where two islands only touch at a corner, turning left keeps their outlines apart.
+fn next_edge(edges: &HashMap<IVec2, Vec<IVec2>>, corner: IVec2, direction: IVec2) -> Option<IVec2> {
+    let directions = edges.get(&corner)?;
+    let left = direction.perp();
+    [left, direction, -left]
+        .into_iter()
+        .find(|candidate| directions.contains(candidate))
+}
+
+fn remove_edge(edges: &mut HashMap<IVec2, Vec<IVec2>>, corner: IVec2, direction: IVec2) {
+    if let Some(directions) = edges.get_mut(&corner) {
+        directions.retain(|d| *d != direction);
+        if directions.is_empty() {
+            edges.remove(&corner);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collision::signed_area;
+
+    fn outlines_of(rows: &[&str]) -> Vec<Vec<Vec2>> {
+        // The first row is the top one, so y points up like in the world
+        let height = rows.len() as i32;
+        let width = rows[0].len() as i32;
+        trace_outlines(width, height, |x, y| {
+            x >= 0
+                && y >= 0
+                && x < width
+                && y < height
+                && rows[(height - 1 - y) as usize].as_bytes()[x as usize] == b'#'
+        })
+    }
+
+    fn vertices(points: &[(f32, f32)]) -> Vec<Vec2> {
+        points.iter().map(|&(x, y)| Vec2::new(x, y)).collect()
+    }
+
+    #[test]
+    fn single_cell() {
+        assert_eq!(
+            outlines_of(&["#"]),
+            [vertices(&[(0., 0.), (1., 0.), (1., 1.), (0., 1.)])]
+        );
+    }
+
+    #[test]
+    fn straight_runs_only_keep_the_corners() {
+        let outlines = outlines_of(&["###", "#.."]);
+        assert_eq!(outlines.len(), 1);
+        assert_eq!(outlines[0].len(), 6);
+        assert_eq!(signed_area(&outlines[0]), 4.);
+    }
+
+    #[test]
+    fn holes_wind_the_other_way() {
+        let mut outlines = outlines_of(&["###", "#.#", "###"]);
+        assert_eq!(outlines.len(), 2);
+        outlines.sort_by(|a, b| signed_area(b).total_cmp(&signed_area(a)));
+        assert_eq!(signed_area(&outlines[0]), 9.);
+        assert_eq!(signed_area(&outlines[1]), -1.);
+        assert_eq!(outlines[1].len(), 4);
+    }
+
+    #[test]
+    fn cells_touching_at_a_corner_are_separate_islands() {
+        let outlines = outlines_of(&[".#", "#."]);
+        assert_eq!(outlines.len(), 2);
+        for outline in &outlines {
+            assert_eq!(outline.len(), 4);
+            assert_eq!(signed_area(outline), 1.);
+        }
+    }
+
+    #[test]
+    fn empty_grid() {
+        assert!(outlines_of(&["...", "..."]).is_empty());
+        assert!(trace_outlines(0, 0, |_, _| true).is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+use bevy::math::Vec2;
+use bevy::render::render_resource::TextureFormat;
+use bevy::render::texture::Image;
+
+use crate::collision::grid_outline::trace_outlines;
+use crate::collision::{b2Shape, signed_area, AssetShapeError};
+
+#[derive(Debug, Clone)]
+pub struct ImageShapeSettings {
+    /// Pixels with an alpha above this are solid.
+    pub alpha_threshold: u8,
+    /// The largest distance in pixels the simplified outline may stray from the pixel edges.
+    pub simplify_epsilon: f32,
+    /// Islands with a smaller area in pixels are dropped.
+    pub min_island_area: f32,
+    /// The size of the shapes, which are centered on the image like a `Sprite` with the default
+    /// anchor.
+    pub pixels_per_meter: f32,
+}
+
+impl Default for ImageShapeSettings {
+    fn default() -> Self {
+        Self {
+            alpha_threshold: 127,
+            simplify_epsilon: 1.,
+            min_island_area: 4.,
+            pixels_per_meter: 1.,
+        }
+    }
+}
+
+impl b2Shape {
+    /// Convex [`b2Shape::Polygon`]s covering the opaque parts of an image. The outlines of the
+    /// solid pixels are traced along the pixel edges, simplified with Douglas-Peucker and then
+    /// split with [`b2Shape::decompose_polygon`]. Every island of solid pixels, connected through
+    /// their sides rather than only their corners, is decomposed on its own.
+    ///
+    /// Holes in an island are filled, and islands inside those holes are dropped. A large
+    /// `simplify_epsilon` can make an outline cross itself, which fails the decomposition.
+    pub fn from_image_alpha(
+        image: &Image,
+        settings: &ImageShapeSettings,
+    ) -> Result<Vec<b2Shape>, AssetShapeError> {
+        let format = image.texture_descriptor.format;
+        if !matches!(
+            format,
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb
+        ) {
+            return Err(AssetShapeError::UnsupportedFormat(format));
+        }
+
+        let width = image.texture_descriptor.size.width as i32;
+        let height = image.texture_descriptor.size.height as i32;
+        // Rows are stored from the top, but the outlines are traced with y pointing up
+        let is_solid = |x: i32, y: i32| {
+            if x < 0 || y < 0 || x >= width || y >= height {
+                return false;
+            }
+            let pixel = ((height - 1 - y) * width + x) as usize;
+            image
+                .data
+                .get(pixel * 4 + 3)
+                .is_some_and(|alpha| *alpha > settings.alpha_threshold)
+        };
+
+        // Holes are traced clockwise, so their area is negative
+        let islands: Vec<Vec<Vec2>> = trace_outlines(width, height, is_solid)
+            .into_iter()
+            .filter(|outline| signed_area(outline) >= settings.min_island_area.max(f32::EPSILON))
+            .collect();
+
+        let offset = Vec2::new(width as f32, height as f32) / 2.;
+        let mut shapes = Vec::new();
+        for (index, outline) in islands.iter().enumerate() {
+            // The center of the pixel to the left of the first edge, which can't lie on the
+            // outline of another island
+            let edge_direction = (outline[1] - outline[0]).normalize();
+            let pixel_center = outline[0] + 0.5 * (edge_direction + edge_direction.perp());
+            let is_in_hole = islands.iter().enumerate().any(|(other, other_outline)| {
+                other != index && is_point_in_polygon(pixel_center, other_outline)
+            });
+            if is_in_hole {
+                continue;
+            }
+
+            let vertices: Vec<Vec2> = simplify_outline(outline, settings.simplify_epsilon)
+                .into_iter()
+                .map(|vertex| (vertex - offset) / settings.pixels_per_meter)
+                .collect();
+            shapes.extend(b2Shape::decompose_polygon(&vertices)?);
+        }
+        Ok(shapes)
+    }
+}
+
+/// Douglas-Peucker on a closed outline, split at its first vertex and the vertex farthest away
+/// from it.
+fn simplify_outline(outline: &[Vec2], epsilon: f32) -> Vec<Vec2> {
+    if epsilon <= 0. || outline.len() <= 3 {
+        return outline.to_vec();
+    }
+
+    let farthest = (1..outline.len())
+        .max_by(|&a, &b| {
+            outline[0]
+                .distance_squared(outline[a])
+                .total_cmp(&outline[0].distance_squared(outline[b]))
+        })
+        .unwrap();
+    let mut first_half = simplify_polyline(&outline[..=farthest], epsilon);
+    let mut second_half: Vec<Vec2> = outline[farthest..].to_vec();
+    second_half.push(outline[0]);
+    let second_half = simplify_polyline(&second_half, epsilon);
+
+    first_half.pop();
+    first_half.extend(&second_half[..second_half.len() - 1]);
+    if first_half.len() < 3 {
+        return outline.to_vec();
+    }
+    first_half
+}
+
+fn simplify_polyline(points: &[Vec2], epsilon: f32) -> Vec<Vec2> {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let farthest = (1..points.len() - 1)
+        .map(|i| (i, distance_to_segment(points[i], first, last)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    match farthest {
+        Some((index, distance)) if distance > epsilon => {
+            let mut simplified = simplify_polyline(&points[..=index], epsilon);
+            simplified.pop();
+            simplified.extend(simplify_polyline(&points[index..], epsilon));
+            simplified
+        }
+        _ => vec![first, last],
+    }
+}
+
+fn distance_to_segment(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let t = if ab.length_squared() > 0. {
+        ((point - a).dot(ab) / ab.length_squared()).clamp(0., 1.)
+    } else {
+        0.
+    };
+    point.distance(a + ab * t)
+}
+
+fn is_point_in_polygon(point: Vec2, polygon: &[Vec2]) -> bool {
+    let count = polygon.len();
+    let mut inside = false;
+    for i in 0..count {
+        let (a, b) = (polygon[i], polygon[(i + 1) % count]);
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use bevy::math::Vec2;
+use bevy::render::mesh::{Mesh, VertexAttributeValues};
+use bevy::render::render_resource::{PrimitiveTopology, TextureFormat};
+
+use crate::collision::{b2Shape, merge_convex_pieces, PolygonDecompositionError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssetShapeError {
+    /// The mesh has no `Mesh::ATTRIBUTE_POSITION`, or its positions aren't 2 or 3 floats.
+    MissingPositions,
+    /// Only triangle lists can be turned into shapes.
+    UnsupportedTopology(PrimitiveTopology),
+    /// Only 8-bit RGBA images can be turned into shapes.
+    UnsupportedFormat(TextureFormat),
+    /// The outline of an island couldn't be split into convex polygons.
+    Decomposition(PolygonDecompositionError),
+}
+
+impl fmt::Display for AssetShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingPositions => write!(f, "mesh has no usable vertex positions"),
+            Self::UnsupportedTopology(topology) => {
+                write!(f, "mesh topology {:?} is not a triangle list", topology)
+            }
+            Self::UnsupportedFormat(format) => {
+                write!(f, "image format {:?} is not 8-bit RGBA", format)
+            }
+            Self::Decomposition(error) => write!(f, "outline decomposition failed: {}", error),
+        }
+    }
+}
+
+impl Error for AssetShapeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Decomposition(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<PolygonDecompositionError> for AssetShapeError {
+    fn from(error: PolygonDecompositionError) -> Self {
+        Self::Decomposition(error)
+    }
+}
+
+impl b2Shape {
+    /// Convex [`b2Shape::Polygon`]s covering the triangles of a 2D mesh, using the x and y
+    /// coordinates of its vertex positions. Adjacent triangles are merged into larger polygons
+    /// where possible, and disconnected parts of the mesh end up in separate shapes.
+    ///
+    /// Meant for simple meshes such as the 2D shapes of Bevy, every triangle is at least one
+    /// polygon, so detailed meshes produce a lot of fixtures.
+    pub fn from_mesh(mesh: &Mesh) -> Result<Vec<b2Shape>, AssetShapeError> {
+        let topology = mesh.primitive_topology();
+        if topology != PrimitiveTopology::TriangleList {
+            return Err(AssetShapeError::UnsupportedTopology(topology));
+        }
+
+        let positions: Vec<Vec2> = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => {
+                positions.iter().map(|p| Vec2::new(p[0], p[1])).collect()
+            }
+            Some(VertexAttributeValues::Float32x2(positions)) => {
+                positions.iter().map(|p| Vec2::new(p[0], p[1])).collect()
+            }
+            _ => return Err(AssetShapeError::MissingPositions),
+        };
+        let indices: Vec<usize> = match mesh.indices() {
+            Some(indices) => indices.iter().collect(),
+            None => (0..positions.len()).collect(),
+        };
+
+        // Meshes often duplicate vertices for their normals or UVs, which would keep the
+        // triangles from sharing edges
+        let mut welded_indices: HashMap<(u32, u32), usize> = HashMap::new();
+        let mut vertices: Vec<Vec2> = Vec::new();
+        let mut weld = |position: Vec2| {
+            *welded_indices
+                .entry((position.x.to_bits(), position.y.to_bits()))
+                .or_insert_with(|| {
+                    vertices.push(position);
+                    vertices.len() - 1
+                })
+        };
+        let mut triangles: Vec<Vec<usize>> = Vec::with_capacity(indices.len() / 3);
+        for triangle in indices.chunks_exact(3) {
+            let Some(corners) = triangle
+                .iter()
+                .map(|&index| positions.get(index).copied())
+                .collect::<Option<Vec<Vec2>>>()
+            else {
+                continue;
+            };
+            let area = (corners[1] - corners[0]).perp_dot(corners[2] - corners[0]);
+            if !area.is_finite() || area.abs() <= f32::EPSILON {
+                continue;
+            }
+
+            let mut triangle: Vec<usize> = corners.into_iter().map(&mut weld).collect();
+            if area < 0. {
+                triangle.reverse();
+            }
+            if triangle[0] != triangle[1]
+                && triangle[1] != triangle[2]
+                && triangle[0] != triangle[2]
+            {
+                triangles.push(triangle);
+            }
+        }
+
+        Ok(merge_convex_pieces(&vertices, triangles)
+            .into_iter()
+            .map(|piece| b2Shape::Polygon {
+                vertices: piece.iter().map(|&i| vertices[i]).collect(),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,412 @@
+use std::error::Error;
+use std::fmt;
+
+use bevy::math::Vec2;
+
+use crate::collision::{b2Shape, LINEAR_SLOP, MAX_POLYGON_VERTICES};
+
+/// Vertices closer than this are welded together by Box2D, so we treat them as duplicates.
+const WELD_DISTANCE: f32 = 0.5 * LINEAR_SLOP;
+
+/// Sine of the smallest angle between two edges that is not considered collinear.
+const COLLINEAR_TOLERANCE: f32 = 1e-4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolygonDecompositionError {
+    /// A polygon needs at least three vertices.
+    TooFewVertices(usize),
+    /// The vertex at the given index has a NaN or infinite coordinate.
+    NonFiniteVertex(usize),
+    /// The vertices at the given indices are (nearly) at the same position.
+    DuplicateVertex { first: usize, second: usize },
+    /// All vertices lie on a single line, so the polygon has no area.
+    Collinear,
+    /// The edges starting at the given vertex indices cross or overlap each other.
+    SelfIntersecting {
+        first_edge: usize,
+        second_edge: usize,
+    },
+}
+
+impl fmt::Display for PolygonDecompositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooFewVertices(count) => {
+                write!(f, "polygon has {} vertices, at least 3 are required", count)
+            }
+            Self::NonFiniteVertex(index) => {
+                write!(f, "vertex {} has a NaN or infinite coordinate", index)
+            }
+            Self::DuplicateVertex { first, second } => {
+                write!(
+                    f,
+                    "vertices {} and {} are at the same position",
+                    first, second
+                )
+            }
+            Self::Collinear => write!(f, "all vertices are collinear"),
+            Self::SelfIntersecting {
+                first_edge,
+                second_edge,
+            } => write!(
+                f,
+                "edges starting at vertices {} and {} intersect",
+                first_edge, second_edge
+            ),
+        }
+    }
+}
+
+impl Error for PolygonDecompositionError {}
+
+impl b2Shape {
+    /// Splits a simple polygon, which may be concave and have any number of vertices, into
+    /// convex [`b2Shape::Polygon`] pieces that Box2D can handle. The outline is triangulated by
+    /// ear clipping and adjacent triangles are then merged as long as the result stays convex
+    /// and within [`MAX_POLYGON_VERTICES`]. Vertices may be given in either winding order.
+    ///
+    /// The returned shapes are usually spawned together on one body, e.g. through
+    /// [`crate::dynamics::b2Fixtures`].
+    pub fn decompose_polygon(vertices: &[Vec2]) -> Result<Vec<b2Shape>, PolygonDecompositionError> {
+        validate_simple_polygon(vertices)?;
+
+        let mut polygon = vertices.to_vec();
+        if signed_area(&polygon) < 0. {
+            polygon.reverse();
+        }
+        let mut indices: Vec<usize> = (0..polygon.len()).collect();
+        remove_collinear_vertices(&polygon, &mut indices);
+        let polygon: Vec<Vec2> = indices.iter().map(|&i| polygon[i]).collect();
+
+        if polygon.len() <= MAX_POLYGON_VERTICES && is_convex(&polygon, &indices_of(&polygon)) {
+            return Ok(vec![b2Shape::Polygon { vertices: polygon }]);
+        }
+
+        let triangles = triangulate(&polygon)?;
+        let pieces = merge_convex_pieces(&polygon, triangles);
+        Ok(pieces
+            .into_iter()
+            .map(|piece| b2Shape::Polygon {
+                vertices: piece.iter().map(|&i| polygon[i]).collect(),
+            })
+            .collect())
+    }
+}
+
+fn validate_simple_polygon(vertices: &[Vec2]) -> Result<(), PolygonDecompositionError> {
+    let count = vertices.len();
+    if count < 3 {
+        return Err(PolygonDecompositionError::TooFewVertices(count));
+    }
+
+    if let Some(index) = vertices.iter().position(|v| !v.is_finite()) {
+        return Err(PolygonDecompositionError::NonFiniteVertex(index));
+    }
+
+    for first in 0..count {
+        for second in (first + 1)..count {
+            if vertices[first].distance_squared(vertices[second]) < WELD_DISTANCE * WELD_DISTANCE {
+                return Err(PolygonDecompositionError::DuplicateVertex { first, second });
+            }
+        }
+    }
+
+    if signed_area(vertices).abs() <= f32::EPSILON {
+        return Err(PolygonDecompositionError::Collinear);
+    }
+
+    for first_edge in 0..count {
+        // Adjacent edges only share a vertex, unless the outline folds back onto itself
+        let prev = vertices[first_edge];
+        let curr = vertices[(first_edge + 1) % count];
+        let next = vertices[(first_edge + 2) % count];
+        if turn(prev, curr, next).abs() <= COLLINEAR_TOLERANCE
+            && (curr - prev).dot(next - curr) < 0.
+        {
+            return Err(PolygonDecompositionError::SelfIntersecting {
+                first_edge,
+                second_edge: (first_edge + 1) % count,
+            });
+        }
+
+        for second_edge in (first_edge + 2)..count {
+            if first_edge == 0 && second_edge == count - 1 {
+                continue;
+            }
+            if segments_intersect(
+                vertices[first_edge],
+                vertices[first_edge + 1],
+                vertices[second_edge],
+                vertices[(second_edge + 1) % count],
+            ) {
+                return Err(PolygonDecompositionError::SelfIntersecting {
+                    first_edge,
+                    second_edge,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn triangulate(polygon: &[Vec2]) -> Result<Vec<Vec<usize>>, PolygonDecompositionError> {
+    let mut remaining = indices_of(polygon);
+    let mut triangles = Vec::with_capacity(polygon.len() - 2);
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let neighbours = |i: usize| {
+            (
+                remaining[(i + count - 1) % count],
+                remaining[i],
+                remaining[(i + 1) % count],
+            )
+        };
+
+        if let Some(i) = (0..count).find(|&i| {
+            let (prev, curr, next) = neighbours(i);
+            is_ear(polygon, &remaining, prev, curr, next)
+        }) {
+            let (prev, curr, next) = neighbours(i);
+            triangles.push(vec![prev, curr, next]);
+            remaining.remove(i);
+            continue;
+        }
+
+        // Clipping can leave zero-area spikes behind, which can be dropped without losing area
+        let Some(i) = (0..count).find(|&i| {
+            let (prev, curr, next) = neighbours(i);
+            turn(polygon[prev], polygon[curr], polygon[next]).abs() <= COLLINEAR_TOLERANCE
+        }) else {
+            return Err(PolygonDecompositionError::SelfIntersecting {
+                first_edge: remaining[0],
+                second_edge: remaining[count - 1],
+            });
+        };
+        remaining.remove(i);
+    }
+
+    if remaining.len() == 3
+        && turn(
+            polygon[remaining[0]],
+            polygon[remaining[1]],
+            polygon[remaining[2]],
+        ) > COLLINEAR_TOLERANCE
+    {
+        triangles.push(remaining);
+    }
+    Ok(triangles)
+}
+
+fn is_ear(polygon: &[Vec2], remaining: &[usize], prev: usize, curr: usize, next: usize) -> bool {
+    let (a, b, c) = (polygon[prev], polygon[curr], polygon[next]);
+    if turn(a, b, c) <= COLLINEAR_TOLERANCE {
+        return false;
+    }
+    !remaining
+        .iter()
+        .filter(|&&i| i != prev && i != curr && i != next)
+        .any(|&i| is_point_in_triangle(polygon[i], a, b, c))
+}
+
+/// Hertel-Mehlhorn style merging: repeatedly joins two pieces across their shared diagonal if
+/// the result is still a valid Box2D polygon.
+pub(crate) fn merge_convex_pieces(
+    polygon: &[Vec2],
+    mut pieces: Vec<Vec<usize>>,
+) -> Vec<Vec<usize>> {
+    let mut merged_any = true;
+    while merged_any {
+        merged_any = false;
+        'search: for i in 0..pieces.len() {
+            for j in (i + 1)..pieces.len() {
+                if let Some(merged) = try_merge(polygon, &pieces[i], &pieces[j]) {
+                    pieces[i] = merged;
+                    pieces.swap_remove(j);
+                    merged_any = true;
+                    break 'search;
+                }
+            }
+        }
+    }
+    pieces
+}
+
+fn try_merge(polygon: &[Vec2], a: &[usize], b: &[usize]) -> Option<Vec<usize>> {
+    for ai in 0..a.len() {
+        let start = a[ai];
+        let end = a[(ai + 1) % a.len()];
+
+        // Both pieces are counter-clockwise, so b walks the shared edge in the other direction
+        let Some(bi) = (0..b.len()).find(|&bi| b[bi] == end && b[(bi + 1) % b.len()] == start)
+        else {
+            continue;
+        };
+
+        let mut merged = Vec::with_capacity(a.len() + b.len() - 2);
+        merged.extend((0..a.len()).map(|k| a[(ai + 1 + k) % a.len()]));
+        merged.extend((1..b.len() - 1).map(|k| b[(bi + 1 + k) % b.len()]));
+        remove_collinear_vertices(polygon, &mut merged);
+
+        if merged.len() <= MAX_POLYGON_VERTICES && is_convex(polygon, &merged) {
+            return Some(merged);
+        }
+        return None;
+    }
+    None
+}
+
+fn remove_collinear_vertices(polygon: &[Vec2], indices: &mut Vec<usize>) {
+    while indices.len() > 3 {
+        let count = indices.len();
+        let collinear = (0..count).find(|&i| {
+            let prev = polygon[indices[(i + count - 1) % count]];
+            let curr = polygon[indices[i]];
+            let next = polygon[indices[(i + 1) % count]];
+            turn(prev, curr, next).abs() <= COLLINEAR_TOLERANCE
+        });
+        match collinear {
+            Some(i) => {
+                indices.remove(i);
+            }
+            None => break,
+        }
+    }
+}
+
+fn is_convex(polygon: &[Vec2], indices: &[usize]) -> bool {
+    let count = indices.len();
+    (0..count).all(|i| {
+        let prev = polygon[indices[(i + count - 1) % count]];
+        let curr = polygon[indices[i]];
+        let next = polygon[indices[(i + 1) % count]];
+        turn(prev, curr, next) > COLLINEAR_TOLERANCE
+    })
+}
+
+fn indices_of(polygon: &[Vec2]) -> Vec<usize> {
+    (0..polygon.len()).collect()
+}
+
+pub(crate) fn signed_area(vertices: &[Vec2]) -> f32 {
+    let count = vertices.len();
+    0.5 * (0..count)
+        .map(|i| vertices[i].perp_dot(vertices[(i + 1) % count]))
+        .sum::<f32>()
+}
+
+/// Sine of the angle turned at `b` when walking from `a` to `c`. Positive for left turns.
+fn turn(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+    let (ab, bc) = (b - a, c - b);
+    let length_product = ab.length() * bc.length();
+    if length_product == 0. {
+        return 0.;
+    }
+    ab.perp_dot(bc) / length_product
+}
+
+fn is_point_in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    (b - a).perp_dot(p - a) >= 0. && (c - b).perp_dot(p - b) >= 0. && (a - c).perp_dot(p - c) >= 0.
+}
+
+fn segments_intersect(p1: Vec2, p2: Vec2, q1: Vec2, q2: Vec2) -> bool {
+    let d1 = (p2 - p1).perp_dot(q1 - p1);
+    let d2 = (p2 - p1).perp_dot(q2 - p1);
+    let d3 = (q2 - q1).perp_dot(p1 - q1);
+    let d4 = (q2 - q1).perp_dot(p2 - q1);
+    if d1 * d2 < 0. && d3 * d4 < 0. {
+        return true;
+    }
+    (d1 == 0. && is_on_segment(q1, p1, p2))
+        || (d2 == 0. && is_on_segment(q2, p1, p2))
+        || (d3 == 0. && is_on_segment(p1, q1, q2))
+        || (d4 == 0. && is_on_segment(p2, q1, q2))
+}
+
+fn is_on_segment(p: Vec2, a: Vec2, b: Vec2) -> bool {
+    p.cmpge(a.min(b)).all() && p.cmple(a.max(b)).all()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_valid_decomposition(vertices: &[Vec2]) -> Vec<Vec<Vec2>> {
+        let pieces: Vec<Vec<Vec2>> = b2Shape::decompose_polygon(vertices)
+            .unwrap()
+            .into_iter()
+            .map(|piece| match piece {
+                b2Shape::Polygon { vertices } => vertices,
+                other => panic!("expected a polygon, got {:?}", other),
+            })
+            .collect();
+
+        for piece in &pieces {
+            assert!(piece.len() >= 3 && piece.len() <= MAX_POLYGON_VERTICES);
+            assert!(is_convex(piece, &indices_of(piece)), "{:?}", piece);
+            assert!(piece.iter().all(|vertex| vertices.contains(vertex)));
+        }
+        let total_area: f32 = pieces.iter().map(|piece| signed_area(piece)).sum();
+        let expected_area = signed_area(vertices).abs();
+        assert!(
+            (total_area - expected_area).abs() <= expected_area * 1e-5,
+            "pieces cover {} instead of {}",
+            total_area,
+            expected_area
+        );
+        pieces
+    }
+
+    fn l_shape() -> Vec<Vec2> {
+        [(0., 0.), (2., 0.), (2., 1.), (1., 1.), (1., 3.), (0., 3.)]
+            .into_iter()
+            .map(|(x, y)| Vec2::new(x, y))
+            .collect()
+    }
+
+    #[test]
+    fn l_shape_is_split_at_its_inner_corner() {
+        let pieces = assert_valid_decomposition(&l_shape());
+        assert_eq!(pieces.len(), 2);
+    }
+
+    #[test]
+    fn convex_polygon_with_too_many_vertices_is_split() {
+        let b2Shape::Polygon { vertices } = b2Shape::create_regular_polygon(20, 2., 0.) else {
+            unreachable!()
+        };
+        let pieces = assert_valid_decomposition(&vertices);
+        assert!(pieces.len() > 1);
+    }
+
+    #[test]
+    fn small_convex_polygon_is_kept_as_is() {
+        let b2Shape::Polygon { vertices } = b2Shape::create_box(1., 1.) else {
+            unreachable!()
+        };
+        assert_eq!(assert_valid_decomposition(&vertices), [vertices]);
+    }
+
+    #[test]
+    fn clockwise_input_gives_counter_clockwise_pieces() {
+        let mut vertices = l_shape();
+        vertices.reverse();
+        for piece in assert_valid_decomposition(&vertices) {
+            assert!(signed_area(&piece) > 0.);
+        }
+    }
+
+    #[test]
+    fn self_intersecting_input_is_rejected() {
+        let bow_tie = [
+            Vec2::new(0., 0.),
+            Vec2::new(2., 2.),
+            Vec2::new(2., 0.),
+            Vec2::new(0., 1.),
+        ];
+        assert!(matches!(
+            b2Shape::decompose_polygon(&bow_tie),
+            Err(PolygonDecompositionError::SelfIntersecting { .. })
+        ));
+    }
+}
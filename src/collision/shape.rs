@@ -0,0 +1,207 @@
+use autocxx::prelude::UniquePtr;
+use autocxx::WithinUniquePtr;
+use bevy::prelude::*;
+use libliquidfun_sys::box2d::ffi::b2Vec2;
+use libliquidfun_sys::box2d::*;
+use std::f32::consts::PI;
+use std::fmt::{Debug, Formatter};
+
+use crate::internal::*;
+
+/// The maximum number of vertices Box2D accepts for a single convex polygon.
+pub const MAX_POLYGON_VERTICES: usize = 8;
+
+/// Box2D's collision and constraint tolerance, in meters.
+pub const LINEAR_SLOP: f32 = 0.005;
+
+/// The margin the broad-phase adds around the AABB of every fixture, in meters, so moving
+/// fixtures don't have to be reinserted every step. It is compiled into LiquidFun and can't be
+/// changed at runtime, which makes the fat AABBs of very small fixtures mostly margin.
+pub const AABB_EXTENSION: f32 = 0.1;
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum b2Shape {
+    Circle {
+        radius: f32,
+        position: Vec2,
+    },
+    EdgeTwoSided {
+        v1: Vec2,
+        v2: Vec2,
+    },
+    Polygon {
+        vertices: Vec<Vec2>,
+    },
+    /// A closed chain of edges, e.g. for level geometry. The last vertex connects back to the
+    /// first one, and only the right side of every edge collides, so a loop around solid ground
+    /// goes counter-clockwise.
+    ChainLoop {
+        vertices: Vec<Vec2>,
+    },
+}
+
+impl b2Shape {
+    pub fn create_box(half_width: f32, half_height: f32) -> b2Shape {
+        b2Shape::Polygon {
+            vertices: vec![
+                Vec2::new(-half_width, -half_height),
+                Vec2::new(half_width, -half_height),
+                Vec2::new(half_width, half_height),
+                Vec2::new(-half_width, half_height),
+            ],
+        }
+    }
+
+    pub fn create_box_with_offset(half_width: f32, half_height: f32, offset: Vec2) -> b2Shape {
+        b2Shape::Polygon {
+            vertices: vec![
+                Vec2::new(-half_width, -half_height) + offset,
+                Vec2::new(half_width, -half_height) + offset,
+                Vec2::new(half_width, half_height) + offset,
+                Vec2::new(-half_width, half_height) + offset,
+            ],
+        }
+    }
+
+    pub fn create_regular_polygon(vertex_count: i8, radius: f32, angle: f32) -> b2Shape {
+        let angle_step_per_vertex = 2. * PI / f32::from(vertex_count);
+        let vertices = (0..vertex_count)
+            .map(|i| {
+                let i = f32::from(i);
+                Vec2::new(
+                    radius * f32::cos(angle_step_per_vertex * i + angle),
+                    radius * f32::sin(angle_step_per_vertex * i + angle),
+                )
+            })
+            .collect();
+        b2Shape::Polygon { vertices }
+    }
+
+    /// Returns a copy of the shape rotated by `angle` and then moved by `translation`.
+    pub fn transformed(&self, translation: Vec2, angle: f32) -> b2Shape {
+        let rotation = Vec2::from_angle(angle);
+        let transform_point = |point: &Vec2| rotation.rotate(*point) + translation;
+        match self {
+            b2Shape::Circle { radius, position } => b2Shape::Circle {
+                radius: *radius,
+                position: transform_point(position),
+            },
+            b2Shape::EdgeTwoSided { v1, v2 } => b2Shape::EdgeTwoSided {
+                v1: transform_point(v1),
+                v2: transform_point(v2),
+            },
+            b2Shape::Polygon { vertices } => b2Shape::Polygon {
+                vertices: vertices.iter().map(transform_point).collect(),
+            },
+            b2Shape::ChainLoop { vertices } => b2Shape::ChainLoop {
+                vertices: vertices.iter().map(transform_point).collect(),
+            },
+        }
+    }
+
+    /// The distance from the origin of the body to the farthest point of the shape, so the
+    /// shape stays within a circle of this radius around the body whichever way it is rotated.
+    pub fn bounding_radius(&self) -> f32 {
+        match self {
+            b2Shape::Circle { radius, position } => position.length() + radius,
+            b2Shape::EdgeTwoSided { v1, v2 } => v1.length().max(v2.length()),
+            b2Shape::Polygon { vertices } | b2Shape::ChainLoop { vertices } => vertices
+                .iter()
+                .map(|vertex| vertex.length())
+                .fold(0., f32::max),
+        }
+    }
+
+    /// Box2D copies shapes when creating fixtures and particle groups, so the returned shape
+    /// only has to be kept around until then.
+    pub(crate) fn to_ffi(&self) -> OwnedFfiShape {
+        match self {
+            b2Shape::Circle { radius, position } => circle_to_ffi(*radius, *position),
+            b2Shape::EdgeTwoSided { v1, v2 } => edge_to_ffi(*v1, *v2),
+            b2Shape::Polygon { vertices } => polygon_to_ffi(vertices),
+            b2Shape::ChainLoop { vertices } => chain_loop_to_ffi(vertices),
+        }
+    }
+}
+
+impl Default for b2Shape {
+    fn default() -> Self {
+        Self::Circle {
+            radius: 1.0,
+            position: Vec2::default(),
+        }
+    }
+}
+
+/// A Box2D shape owned by the Rust side, which is freed when dropped.
+pub(crate) enum OwnedFfiShape {
+    Circle(UniquePtr<ffi::b2CircleShape>),
+    Edge(UniquePtr<ffi::b2EdgeShape>),
+    Polygon(UniquePtr<ffi::b2PolygonShape>),
+    Chain(UniquePtr<ffi::b2ChainShape>),
+}
+
+impl Debug for OwnedFfiShape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OwnedFfiShape").finish_non_exhaustive()
+    }
+}
+
+impl OwnedFfiShape {
+    pub(crate) fn as_ffi(&self) -> &ffi::b2Shape {
+        match self {
+            OwnedFfiShape::Circle(shape) => shape.as_ref().unwrap().as_ref(),
+            OwnedFfiShape::Edge(shape) => shape.as_ref().unwrap().as_ref(),
+            OwnedFfiShape::Polygon(shape) => shape.as_ref().unwrap().as_ref(),
+            OwnedFfiShape::Chain(shape) => shape.as_ref().unwrap().as_ref(),
+        }
+    }
+}
+
+/// The number of children of a shape, one for every edge of a chain and one for other shapes.
+pub(crate) fn ffi_child_count(shape: &ffi::b2Shape) -> i32 {
+    i32::from(shape.GetChildCount())
+}
+
+fn circle_to_ffi(radius: f32, position: Vec2) -> OwnedFfiShape {
+    let mut shape = ffi::b2CircleShape::new().within_unique_ptr();
+    ffi::SetCircleRadius(shape.pin_mut(), radius);
+    ffi::SetCirclePosition(shape.pin_mut(), &to_b2Vec2(&position));
+    OwnedFfiShape::Circle(shape)
+}
+
+fn edge_to_ffi(v1: Vec2, v2: Vec2) -> OwnedFfiShape {
+    let mut shape = ffi::b2EdgeShape::new().within_unique_ptr();
+    shape
+        .pin_mut()
+        .SetTwoSided(&to_b2Vec2(&v1), &to_b2Vec2(&v2));
+    OwnedFfiShape::Edge(shape)
+}
+
+fn polygon_to_ffi(vertices: &Vec<Vec2>) -> OwnedFfiShape {
+    let mut shape = ffi::b2PolygonShape::new().within_unique_ptr();
+    let vertices: Vec<b2Vec2> = vertices.iter().map(|v| to_b2Vec2(v)).collect();
+    let count: i32 = vertices.len().try_into().unwrap();
+    // Set copies the vertices
+    unsafe {
+        shape
+            .pin_mut()
+            .Set(vertices.as_ptr(), ffi::int32::from(count));
+    }
+    OwnedFfiShape::Polygon(shape)
+}
+
+fn chain_loop_to_ffi(vertices: &[Vec2]) -> OwnedFfiShape {
+    let mut shape = ffi::b2ChainShape::new().within_unique_ptr();
+    let vertices: Vec<b2Vec2> = vertices.iter().map(to_b2Vec2).collect();
+    let count: i32 = vertices.len().try_into().unwrap();
+    // CreateLoop copies the vertices
+    unsafe {
+        shape
+            .pin_mut()
+            .CreateLoop(vertices.as_ptr(), ffi::int32::from(count));
+    }
+    OwnedFfiShape::Chain(shape)
+}
@@ -0,0 +1,252 @@
+use std::error::Error;
+use std::fmt;
+
+use bevy::math::Vec2;
+
+use crate::collision::{b2Shape, LINEAR_SLOP, MAX_POLYGON_VERTICES};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeValidationError {
+    /// The shape has fewer vertices than its type requires.
+    TooFewVertices { count: usize, min: usize },
+    /// The polygon has more vertices than [`MAX_POLYGON_VERTICES`].
+    TooManyVertices { count: usize, max: usize },
+    /// A coordinate or the radius is NaN or infinite.
+    NonFiniteValue,
+    /// Circle radii have to be strictly positive.
+    NonPositiveRadius(f32),
+    /// The edge starting at the given vertex index is shorter than [`LINEAR_SLOP`].
+    EdgeTooShort { index: usize, length: f32 },
+    /// The polygon has no area, so it has no well-defined winding order.
+    ZeroArea,
+    /// The polygon turns the wrong way at the given vertex index.
+    NotConvex { index: usize },
+}
+
+impl fmt::Display for ShapeValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooFewVertices { count, min } => {
+                write!(
+                    f,
+                    "shape has {} vertices, at least {} are required",
+                    count, min
+                )
+            }
+            Self::TooManyVertices { count, max } => {
+                write!(
+                    f,
+                    "polygon has {} vertices, at most {} are allowed",
+                    count, max
+                )
+            }
+            Self::NonFiniteValue => write!(f, "shape contains a NaN or infinite value"),
+            Self::NonPositiveRadius(radius) => {
+                write!(f, "radius must be positive, got {}", radius)
+            }
+            Self::EdgeTooShort { index, length } => write!(
+                f,
+                "edge starting at vertex {} has length {}, minimum is {}",
+                index, length, LINEAR_SLOP
+            ),
+            Self::ZeroArea => write!(f, "polygon has no area"),
+            Self::NotConvex { index } => write!(f, "polygon is not convex at vertex {}", index),
+        }
+    }
+}
+
+impl Error for ShapeValidationError {}
+
+impl b2Shape {
+    /// Checks that the shape can be handed to Box2D as is. Box2D itself only asserts on these
+    /// conditions deep inside the C++ library, without any hint of which entity caused it.
+    ///
+    /// Polygons may use either winding order, as Box2D reorders the vertices anyway. Concave
+    /// polygons can be split up with [`b2Shape::decompose_polygon`].
+    pub fn validate(&self) -> Result<(), ShapeValidationError> {
+        match self {
+            b2Shape::Circle { radius, position } => {
+                if !radius.is_finite() || !position.is_finite() {
+                    return Err(ShapeValidationError::NonFiniteValue);
+                }
+                if *radius <= 0. {
+                    return Err(ShapeValidationError::NonPositiveRadius(*radius));
+                }
+                Ok(())
+            }
+            b2Shape::EdgeTwoSided { v1, v2 } => {
+                if !v1.is_finite() || !v2.is_finite() {
+                    return Err(ShapeValidationError::NonFiniteValue);
+                }
+                validate_edge_length(0, *v1, *v2)
+            }
+            b2Shape::Polygon { vertices } => validate_polygon(vertices),
+            b2Shape::ChainLoop { vertices } => validate_chain_loop(vertices),
+        }
+    }
+}
+
+fn validate_polygon(vertices: &[Vec2]) -> Result<(), ShapeValidationError> {
+    let count = vertices.len();
+    if count < 3 {
+        return Err(ShapeValidationError::TooFewVertices { count, min: 3 });
+    }
+    if count > MAX_POLYGON_VERTICES {
+        return Err(ShapeValidationError::TooManyVertices {
+            count,
+            max: MAX_POLYGON_VERTICES,
+        });
+    }
+    if vertices.iter().any(|v| !v.is_finite()) {
+        return Err(ShapeValidationError::NonFiniteValue);
+    }
+
+    for index in 0..count {
+        validate_edge_length(index, vertices[index], vertices[(index + 1) % count])?;
+    }
+
+    let doubled_area: f32 = (0..count)
+        .map(|i| vertices[i].perp_dot(vertices[(i + 1) % count]))
+        .sum();
+    if doubled_area.abs() <= 2. * f32::EPSILON {
+        return Err(ShapeValidationError::ZeroArea);
+    }
+
+    // Every corner has to turn the same way as the polygon as a whole
+    let winding = doubled_area.signum();
+    for index in 0..count {
+        let prev = vertices[(index + count - 1) % count];
+        let curr = vertices[index];
+        let next = vertices[(index + 1) % count];
+        let turn = (curr - prev).perp_dot(next - curr) * winding;
+        if turn < -f32::EPSILON * (curr - prev).length() * (next - curr).length() {
+            return Err(ShapeValidationError::NotConvex { index });
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_chain_loop(vertices: &[Vec2]) -> Result<(), ShapeValidationError> {
+    let count = vertices.len();
+    if count < 3 {
+        return Err(ShapeValidationError::TooFewVertices { count, min: 3 });
+    }
+    if vertices.iter().any(|v| !v.is_finite()) {
+        return Err(ShapeValidationError::NonFiniteValue);
+    }
+    for index in 0..count {
+        validate_edge_length(index, vertices[index], vertices[(index + 1) % count])?;
+    }
+    Ok(())
+}
+
+fn validate_edge_length(index: usize, v1: Vec2, v2: Vec2) -> Result<(), ShapeValidationError> {
+    let length = v1.distance(v2);
+    if length < LINEAR_SLOP {
+        return Err(ShapeValidationError::EdgeTooShort { index, length });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(vertices: &[(f32, f32)]) -> b2Shape {
+        b2Shape::Polygon {
+            vertices: vertices.iter().map(|&(x, y)| Vec2::new(x, y)).collect(),
+        }
+    }
+
+    #[test]
+    fn accepts_boxes_in_either_winding_order() {
+        assert_eq!(b2Shape::create_box(1., 0.5).validate(), Ok(()));
+        let clockwise = polygon(&[(0., 0.), (0., 1.), (1., 1.), (1., 0.)]);
+        assert_eq!(clockwise.validate(), Ok(()));
+    }
+
+    #[test]
+    fn too_few_vertices() {
+        assert_eq!(
+            polygon(&[(0., 0.), (1., 0.)]).validate(),
+            Err(ShapeValidationError::TooFewVertices { count: 2, min: 3 })
+        );
+        let chain = b2Shape::ChainLoop {
+            vertices: vec![Vec2::ZERO, Vec2::X],
+        };
+        assert_eq!(
+            chain.validate(),
+            Err(ShapeValidationError::TooFewVertices { count: 2, min: 3 })
+        );
+    }
+
+    #[test]
+    fn too_many_vertices() {
+        let shape = b2Shape::create_regular_polygon(9, 1., 0.);
+        assert_eq!(
+            shape.validate(),
+            Err(ShapeValidationError::TooManyVertices {
+                count: 9,
+                max: MAX_POLYGON_VERTICES
+            })
+        );
+    }
+
+    #[test]
+    fn collinear_vertices_have_no_area() {
+        let shape = polygon(&[(0., 0.), (1., 0.), (2., 0.)]);
+        assert_eq!(shape.validate(), Err(ShapeValidationError::ZeroArea));
+    }
+
+    #[test]
+    fn non_convex_polygon() {
+        // An arrow head pointing right, with its notch at the last vertex
+        let shape = polygon(&[(0., 0.), (2., 1.), (0., 2.), (1., 1.)]);
+        assert_eq!(
+            shape.validate(),
+            Err(ShapeValidationError::NotConvex { index: 3 })
+        );
+    }
+
+    #[test]
+    fn degenerate_radius() {
+        let circle = |radius| b2Shape::Circle {
+            radius,
+            position: Vec2::ZERO,
+        };
+        assert_eq!(
+            circle(0.).validate(),
+            Err(ShapeValidationError::NonPositiveRadius(0.))
+        );
+        assert_eq!(
+            circle(-1.).validate(),
+            Err(ShapeValidationError::NonPositiveRadius(-1.))
+        );
+    }
+
+    #[test]
+    fn nan_and_infinite_values() {
+        let circle = b2Shape::Circle {
+            radius: f32::NAN,
+            position: Vec2::ZERO,
+        };
+        assert_eq!(circle.validate(), Err(ShapeValidationError::NonFiniteValue));
+        let edge = b2Shape::EdgeTwoSided {
+            v1: Vec2::ZERO,
+            v2: Vec2::new(f32::INFINITY, 0.),
+        };
+        assert_eq!(edge.validate(), Err(ShapeValidationError::NonFiniteValue));
+        let shape = polygon(&[(0., 0.), (1., f32::NAN), (0., 1.)]);
+        assert_eq!(shape.validate(), Err(ShapeValidationError::NonFiniteValue));
+    }
+
+    #[test]
+    fn edge_too_short() {
+        let shape = polygon(&[(0., 0.), (1., 0.), (1., 0.001), (0., 1.)]);
+        assert!(matches!(
+            shape.validate(),
+            Err(ShapeValidationError::EdgeTooShort { index: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,66 @@
+use bevy::math::{UVec2, Vec2};
+
+use crate::collision::b2Shape;
+use crate::collision::grid_outline::trace_outlines;
+
+/// A grid of tiles that are either solid or empty, with the tile at `(0, 0)` in the bottom left.
+pub trait GridView {
+    /// The number of tiles along x and y.
+    fn size(&self) -> UVec2;
+
+    fn is_solid(&self, x: u32, y: u32) -> bool;
+}
+
+/// A [`GridView`] that asks a closure whether a tile is solid.
+pub struct FnGridView<F> {
+    size: UVec2,
+    is_solid: F,
+}
+
+impl<F: Fn(u32, u32) -> bool> FnGridView<F> {
+    pub fn new(size: UVec2, is_solid: F) -> Self {
+        Self { size, is_solid }
+    }
+}
+
+impl<F: Fn(u32, u32) -> bool> GridView for FnGridView<F> {
+    fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    fn is_solid(&self, x: u32, y: u32) -> bool {
+        (self.is_solid)(x, y)
+    }
+}
+
+/// Merges the edges between solid and empty tiles into [`b2Shape::ChainLoop`]s, one around every
+/// region of solid tiles and one inside every hole in a region. Unlike a box per tile, the loops
+/// have no seams for bodies sliding along them to catch on.
+///
+/// Regions are wound counter-clockwise and holes clockwise, so the solid tiles are always on the
+/// left of the edges and bodies collide with them from the empty side. Tiles beyond the borders
+/// of the grid count as empty, and tiles that only touch at a corner belong to separate regions.
+/// `origin` is the position of the bottom left corner of the grid.
+pub fn build_chain_shapes_from_grid(
+    grid: &impl GridView,
+    tile_size: f32,
+    origin: Vec2,
+) -> Vec<b2Shape> {
+    let size = grid.size();
+    let is_solid = |x: i32, y: i32| {
+        x >= 0
+            && y >= 0
+            && (x as u32) < size.x
+            && (y as u32) < size.y
+            && grid.is_solid(x as u32, y as u32)
+    };
+    trace_outlines(size.x as i32, size.y as i32, is_solid)
+        .into_iter()
+        .map(|outline| b2Shape::ChainLoop {
+            vertices: outline
+                .into_iter()
+                .map(|corner| origin + corner * tile_size)
+                .collect(),
+        })
+        .collect()
+}
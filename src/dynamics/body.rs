@@ -1,12 +1,17 @@
-use crate::dynamics::b2World;
+use crate::collision::{build_chain_shapes_from_grid, GridView};
+use crate::dynamics::{b2Fixture, b2FixtureDef, b2Fixtures, b2World, PhysicsError};
 use crate::internal::{to_Vec2, to_b2Vec2};
+use autocxx::WithinBox;
+use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
+#[cfg(feature = "bevy-inspector-egui")]
+use bevy_inspector_egui::prelude::*;
 use libliquidfun_sys::box2d::ffi;
 use libliquidfun_sys::box2d::ffi::b2BodyType::{b2_dynamicBody, b2_kinematicBody, b2_staticBody};
-use std::collections::HashSet;
 
 #[allow(non_camel_case_types)]
-#[derive(Debug, Default, Copy, Clone)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum b2BodyType {
     #[default]
     Static,
@@ -35,10 +40,14 @@ impl Into<ffi::b2BodyType> for b2BodyType {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Reflect)]
+#[cfg_attr(
+    feature = "bevy-inspector-egui",
+    derive(InspectorOptions),
+    reflect(InspectorOptions)
+)]
+#[reflect(Component)]
 pub struct b2Body {
-    pub(crate) fixtures: HashSet<Entity>,
-
     pub body_type: b2BodyType,
     pub position: Vec2,
     pub angle: f32,
@@ -47,38 +56,125 @@ pub struct b2Body {
     pub awake: bool,
     pub allow_sleep: bool,
     pub fixed_rotation: bool,
+    /// Treat this body as a fast moving projectile, enabling continuous collision
+    /// detection against other dynamic bodies.
+    pub bullet: bool,
+    /// Disabled bodies are not simulated, don't collide and are ignored by queries, but keep
+    /// their fixtures and joints.
+    pub enabled: bool,
+    /// Reduces the linear velocity over time, independently of the contacts.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
+    pub linear_damping: f32,
+    /// Reduces the angular velocity over time, independently of the contacts.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
+    pub angular_damping: f32,
 
     mass: f32,
+    inertia: f32,
+    local_center: Vec2,
+    world_center: Vec2,
+
+    /// The state before the last step, used for [`crate::dynamics::TransformSyncMode::Interpolate`]
+    previous_position: Vec2,
+    previous_angle: f32,
 }
 
 impl b2Body {
     pub fn new(body_def: &b2BodyDef) -> Self {
         b2Body {
-            fixtures: HashSet::new(),
             body_type: body_def.body_type,
             position: body_def.position,
             angle: body_def.angle,
             linear_velocity: Vec2::ZERO,
             angular_velocity: 0.,
             mass: 0.,
+            inertia: 0.,
+            local_center: Vec2::ZERO,
+            world_center: body_def.position,
             awake: true,
             allow_sleep: body_def.allow_sleep,
             fixed_rotation: body_def.fixed_rotation,
+            bullet: body_def.bullet,
+            enabled: body_def.enabled,
+            linear_damping: body_def.linear_damping,
+            angular_damping: body_def.angular_damping,
+            previous_position: body_def.position,
+            previous_angle: body_def.angle,
         }
     }
 
-    pub fn sync_with_world(&mut self, entity: Entity, world: &b2World) {
-        let body_ptr = world.get_body_ptr(entity).unwrap();
-        self.position = to_Vec2(body_ptr.as_ref().GetPosition());
-        self.angle = body_ptr.as_ref().GetAngle();
-        self.linear_velocity = to_Vec2(body_ptr.as_ref().GetLinearVelocity());
-        self.angular_velocity = body_ptr.as_ref().GetAngularVelocity();
-        self.mass = body_ptr.as_ref().GetMass();
-        self.awake = body_ptr.as_ref().IsAwake();
+    /// Returns whether the previous transform changed.
+    pub(crate) fn store_previous_transform(&mut self) -> bool {
+        self.set_previous_transform(self.position, self.angle)
+    }
+
+    /// Returns whether the previous transform changed.
+    pub(crate) fn set_previous_transform(&mut self, position: Vec2, angle: f32) -> bool {
+        let changed = set_if_different(&mut self.previous_position, position);
+        set_if_different(&mut self.previous_angle, angle) || changed
+    }
+
+    pub(crate) fn get_previous_transform(&self) -> (Vec2, f32) {
+        (self.previous_position, self.previous_angle)
     }
 
-    pub fn sync_to_world(&self, entity: Entity, world: &mut b2World) {
-        let body_ptr = world.get_body_ptr_mut(entity).unwrap();
+    /// Whether the body is asleep in the physics world and this component has already caught up
+    /// with it, so there is nothing to sync until it wakes up again. Adding or removing fixtures
+    /// changes the mass without waking the body, so the mass is compared as well.
+    pub(crate) fn is_settled(&self, body_ptr: &ffi::b2Body) -> bool {
+        !self.awake
+            && self.previous_position == self.position
+            && self.previous_angle == self.angle
+            && !body_ptr.IsAwake()
+            && body_ptr.GetMass() == self.mass
+    }
+
+    /// Only writes the fields whose values differ, and returns whether any did.
+    pub fn sync_with_world(
+        &mut self,
+        entity: Entity,
+        world: &b2World,
+    ) -> Result<bool, PhysicsError> {
+        let Some(body_ptr) = world.get_body_ptr(entity) else {
+            return Err(PhysicsError::MissingBody(entity));
+        };
+        Ok(self.sync_with_body(&body_ptr.as_ref()))
+    }
+
+    pub(crate) fn sync_with_body(&mut self, body_ptr: &ffi::b2Body) -> bool {
+        let mut changed = false;
+        changed |= set_if_different(&mut self.position, to_Vec2(body_ptr.GetPosition()));
+        changed |= set_if_different(&mut self.angle, body_ptr.GetAngle());
+        changed |= set_if_different(
+            &mut self.linear_velocity,
+            to_Vec2(body_ptr.GetLinearVelocity()),
+        );
+        changed |= set_if_different(&mut self.angular_velocity, body_ptr.GetAngularVelocity());
+        changed |= set_if_different(&mut self.mass, body_ptr.GetMass());
+        changed |= set_if_different(&mut self.inertia, body_ptr.GetInertia());
+        changed |= set_if_different(&mut self.local_center, to_Vec2(body_ptr.GetLocalCenter()));
+        changed |= set_if_different(&mut self.world_center, to_Vec2(body_ptr.GetWorldCenter()));
+        changed |= set_if_different(&mut self.awake, body_ptr.IsAwake());
+        changed |= set_if_different(&mut self.enabled, body_ptr.IsEnabled());
+        // The fields that only change through sync_to_world are read back too, so one that isn't
+        // passed on to the physics world doesn't go unnoticed
+        changed |= set_if_different(&mut self.body_type, body_ptr.GetType().into());
+        changed |= set_if_different(&mut self.allow_sleep, body_ptr.IsSleepingAllowed());
+        changed |= set_if_different(&mut self.fixed_rotation, body_ptr.IsFixedRotation());
+        changed |= set_if_different(&mut self.bullet, body_ptr.IsBullet());
+        changed |= set_if_different(&mut self.linear_damping, body_ptr.GetLinearDamping());
+        changed |= set_if_different(&mut self.angular_damping, body_ptr.GetAngularDamping());
+        changed
+    }
+
+    pub fn sync_to_world(&self, entity: Entity, world: &mut b2World) -> Result<(), PhysicsError> {
+        let Some(body_ptr) = world.get_body_ptr_mut(entity) else {
+            return Err(PhysicsError::MissingBody(entity));
+        };
+        // Box2D ignores SetType calls that don't change the type, and resets the mass data and
+        // wakes the body when it does. The new mass is picked up by the next sync_with_world.
+        let type_changed = b2BodyType::from(body_ptr.as_ref().GetType()) != self.body_type;
+        body_ptr.as_mut().SetType(self.body_type.into());
         body_ptr
             .as_mut()
             .SetTransform(&to_b2Vec2(&self.position), self.angle);
@@ -86,23 +182,155 @@ impl b2Body {
             .as_mut()
             .SetLinearVelocity(&to_b2Vec2(&self.linear_velocity));
         body_ptr.as_mut().SetAngularVelocity(self.angular_velocity);
-        body_ptr.as_mut().SetAwake(self.awake);
+        // Static bodies are never awake, so a body that just stopped being one would be put
+        // right back to sleep
+        body_ptr.as_mut().SetAwake(self.awake || type_changed);
         body_ptr.as_mut().SetSleepingAllowed(self.allow_sleep);
+        body_ptr.as_mut().SetFixedRotation(self.fixed_rotation);
+        body_ptr.as_mut().SetBullet(self.bullet);
+        body_ptr.as_mut().SetEnabled(self.enabled);
+        body_ptr.as_mut().SetLinearDamping(self.linear_damping);
+        body_ptr.as_mut().SetAngularDamping(self.angular_damping);
+        Ok(())
     }
 
     pub fn get_mass(&self) -> f32 {
         self.mass
     }
+
+    /// The rotational inertia about the local origin of the body.
+    pub fn get_inertia(&self) -> f32 {
+        self.inertia
+    }
+
+    /// The center of mass in the local coordinates of the body.
+    pub fn get_local_center(&self) -> Vec2 {
+        self.local_center
+    }
+
+    /// The center of mass in world coordinates.
+    pub fn get_world_center(&self) -> Vec2 {
+        self.world_center
+    }
+
+    /// Converts a point from the local coordinates of the body to world coordinates, based on
+    /// the state of the last physics step.
+    pub fn world_point(&self, local_point: Vec2) -> Vec2 {
+        self.position + self.world_vector(local_point)
+    }
+
+    /// Converts a point from world coordinates to the local coordinates of the body.
+    pub fn local_point(&self, world_point: Vec2) -> Vec2 {
+        self.local_vector(world_point - self.position)
+    }
+
+    /// Rotates a vector from the local coordinates of the body to world coordinates.
+    pub fn world_vector(&self, local_vector: Vec2) -> Vec2 {
+        Vec2::from_angle(self.angle).rotate(local_vector)
+    }
+
+    /// Rotates a vector from world coordinates to the local coordinates of the body.
+    pub fn local_vector(&self, world_vector: Vec2) -> Vec2 {
+        Vec2::from_angle(-self.angle).rotate(world_vector)
+    }
+
+    /// The velocity of the point of the body at the given world coordinates.
+    pub fn linear_velocity_at_world_point(&self, world_point: Vec2) -> Vec2 {
+        self.linear_velocity + self.angular_velocity * (world_point - self.world_center).perp()
+    }
+
+    /// The velocity of the point of the body at the given local coordinates.
+    pub fn linear_velocity_at_local_point(&self, local_point: Vec2) -> Vec2 {
+        self.linear_velocity_at_world_point(self.world_point(local_point))
+    }
+}
+
+fn set_if_different<T: PartialEq>(field: &mut T, value: T) -> bool {
+    if *field == value {
+        return false;
+    }
+    *field = value;
+    true
+}
+
+impl Default for b2Body {
+    fn default() -> Self {
+        b2Body::new(&b2BodyDef::default())
+    }
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct b2BodyDef {
     pub body_type: b2BodyType,
     pub position: Vec2,
     pub angle: f32,
     pub allow_sleep: bool,
     pub fixed_rotation: bool,
+    /// Treat this body as a fast moving projectile, enabling continuous collision
+    /// detection against other dynamic bodies.
+    pub bullet: bool,
+    /// Does this body start out enabled?
+    pub enabled: bool,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    /// Scale the gravity applied to this body.
+    pub gravity_scale: f32,
+}
+
+impl Default for b2BodyDef {
+    fn default() -> Self {
+        Self {
+            body_type: b2BodyType::default(),
+            position: Vec2::ZERO,
+            angle: 0.,
+            allow_sleep: false,
+            fixed_rotation: false,
+            bullet: false,
+            enabled: true,
+            linear_damping: 0.,
+            angular_damping: 0.,
+            gravity_scale: 1.,
+        }
+    }
+}
+
+/// Scales the gravity applied to the body on the same entity. Bodies without this component
+/// use a gravity scale of 1.
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct GravityScale(pub f32);
+
+impl Default for GravityScale {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+/// Whether the `Transform` of the body on the same entity is written by the physics plugin.
+/// Bodies without this component are treated as [`PhysicsTransformMode::Automatic`].
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub enum PhysicsTransformMode {
+    /// The translation and rotation follow the body, the Z coordinate and scale are kept.
+    #[default]
+    Automatic,
+    /// The `Transform` is left alone, for entities whose `Transform` is driven by the user.
+    Manual,
+}
+
+/// Moves the body on the same entity whenever its `Transform` is changed by something other
+/// than the physics plugin, such as an animation or an editor gizmo. Mostly useful for
+/// kinematic bodies.
+///
+/// The body is teleported rather than moved, so with [`crate::dynamics::TransformSyncMode`]
+/// `Interpolate` it doesn't get interpolated from its previous position.
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component)]
+pub struct SyncTransformToBody {
+    /// The transform last written by the physics plugin, to tell its changes apart from others
+    #[reflect(ignore)]
+    pub(crate) last_written: Option<Transform>,
 }
 
 #[allow(non_camel_case_types)]
@@ -110,6 +338,7 @@ pub struct b2BodyDef {
 pub struct b2BodyBundle {
     pub transform: TransformBundle,
     pub body: b2Body,
+    pub gravity_scale: GravityScale,
 }
 
 impl b2BodyBundle {
@@ -124,6 +353,7 @@ impl b2BodyBundle {
                 ..default()
             },
             body: b2Body::new(def),
+            gravity_scale: GravityScale(def.gravity_scale),
         }
     }
 }
@@ -134,10 +364,213 @@ impl Default for b2BodyBundle {
     }
 }
 
-#[derive(Component, Debug, Default)]
+/// Spawning helpers for bodies and their fixtures.
+pub trait BodyCommandsExt {
+    /// Spawns a body with a single fixture for each item in one go, e.g. when loading a level.
+    ///
+    /// The fixtures are added as [`b2Fixtures`] on the body entities rather than as separate
+    /// entities, and all of the bodies are created in the physics world in the same frame.
+    fn spawn_bodies_batch<I>(&mut self, bodies: I)
+    where
+        I: IntoIterator<Item = (b2BodyDef, b2FixtureDef)>,
+        I::IntoIter: Send + Sync + 'static;
+
+    /// Spawns a body with a separate [`b2Fixture`] entity for each def and returns the body
+    /// entity.
+    ///
+    /// `fixture_builder` is called with the index of each fixture and its entity, e.g. to add
+    /// debug draw components or components that identify the fixture.
+    fn spawn_multi_fixture_body<I, F>(
+        &mut self,
+        body_def: &b2BodyDef,
+        fixture_defs: I,
+        fixture_builder: F,
+    ) -> Entity
+    where
+        I: IntoIterator<Item = b2FixtureDef>,
+        F: FnMut(usize, &mut EntityCommands);
+
+    /// Spawns a static body with a fixture for every loop of [`build_chain_shapes_from_grid`]
+    /// and returns the body entity. The fixtures are copies of `fixture_def` with their shape
+    /// replaced, and are added as [`b2Fixtures`] on the body entity.
+    fn spawn_tilemap_colliders(
+        &mut self,
+        grid: &impl GridView,
+        tile_size: f32,
+        origin: Vec2,
+        fixture_def: &b2FixtureDef,
+    ) -> Entity;
+}
+
+impl BodyCommandsExt for Commands<'_, '_> {
+    fn spawn_bodies_batch<I>(&mut self, bodies: I)
+    where
+        I: IntoIterator<Item = (b2BodyDef, b2FixtureDef)>,
+        I::IntoIter: Send + Sync + 'static,
+    {
+        self.spawn_batch(bodies.into_iter().map(|(body_def, fixture_def)| {
+            (b2BodyBundle::new(&body_def), b2Fixtures::new([fixture_def]))
+        }));
+    }
+
+    fn spawn_multi_fixture_body<I, F>(
+        &mut self,
+        body_def: &b2BodyDef,
+        fixture_defs: I,
+        mut fixture_builder: F,
+    ) -> Entity
+    where
+        I: IntoIterator<Item = b2FixtureDef>,
+        F: FnMut(usize, &mut EntityCommands),
+    {
+        let body_entity = self.spawn(b2BodyBundle::new(body_def)).id();
+        for (index, fixture_def) in fixture_defs.into_iter().enumerate() {
+            let mut fixture_commands = self.spawn(b2Fixture::new(body_entity, &fixture_def));
+            fixture_builder(index, &mut fixture_commands);
+        }
+        body_entity
+    }
+
+    fn spawn_tilemap_colliders(
+        &mut self,
+        grid: &impl GridView,
+        tile_size: f32,
+        origin: Vec2,
+        fixture_def: &b2FixtureDef,
+    ) -> Entity {
+        let fixture_defs = build_chain_shapes_from_grid(grid, tile_size, origin)
+            .into_iter()
+            .map(|shape| b2FixtureDef {
+                shape,
+                ..fixture_def.clone()
+            });
+        self.spawn((
+            b2BodyBundle::new(&b2BodyDef::default()),
+            b2Fixtures::new(fixture_defs),
+        ))
+        .id()
+    }
+}
+
+/// Moves the kinematic body on the same entity towards a target by setting its velocities,
+/// rather than teleporting it, so that it pushes and carries dynamic bodies along correctly.
+///
+/// The velocities are recomputed before every physics step to reach the target in that step, so
+/// the body stops at the target even when a frame runs several steps. Bodies without this
+/// component keep their current velocities.
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct KinematicTarget {
+    pub position: Vec2,
+    pub angle: f32,
+    pub max_linear_speed: Option<f32>,
+    pub max_angular_speed: Option<f32>,
+}
+
+impl KinematicTarget {
+    pub fn new(position: Vec2, angle: f32) -> Self {
+        Self {
+            position,
+            angle,
+            ..default()
+        }
+    }
+
+    pub(crate) fn velocities_from(
+        &self,
+        position: Vec2,
+        angle: f32,
+        time_step: f32,
+    ) -> (Vec2, f32) {
+        let mut linear_velocity = (self.position - position) / time_step;
+        if let Some(max_linear_speed) = self.max_linear_speed {
+            linear_velocity = linear_velocity.clamp_length_max(max_linear_speed);
+        }
+        let mut angular_velocity = (self.angle - angle) / time_step;
+        if let Some(max_angular_speed) = self.max_angular_speed {
+            angular_velocity = angular_velocity.clamp(-max_angular_speed, max_angular_speed);
+        }
+        (linear_velocity, angular_velocity)
+    }
+}
+
+/// Moves the body on the same entity before the next physics step, overriding any change made
+/// to its [`b2Body`] in the same frame. The component is removed once it has been applied.
+///
+/// Changes made to [`b2Body::position`] directly also take effect in the next step, from
+/// `Update` or any other point of the frame, except from systems that run unordered alongside
+/// the physics systems: the state of the body after the step can overwrite them.
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TeleportBody {
+    pub position: Vec2,
+    pub angle: f32,
+}
+
+/// Sets the velocities of the body on the same entity before the next physics step, overriding
+/// any change made to its [`b2Body`] in the same frame. The component is removed once it has
+/// been applied.
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct SetBodyVelocity {
+    pub linear_velocity: Vec2,
+    pub angular_velocity: f32,
+}
+
+/// Recomputes the mass properties of the body on the same entity from the density of its
+/// fixtures before the next physics step. The component is removed once it has been applied.
+///
+/// Box2D already does so when fixtures are created or destroyed, or when their density changes.
+/// A [`b2MassData`] on the body still overrides the recomputed mass properties.
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct RecomputeMass;
+
+/// Shorthands for [`TeleportBody`], [`SetBodyVelocity`] and [`RecomputeMass`].
+pub trait BodyEntityCommandsExt {
+    fn teleport_body(&mut self, position: Vec2, angle: f32) -> &mut Self;
+
+    fn set_body_velocity(&mut self, linear_velocity: Vec2, angular_velocity: f32) -> &mut Self;
+
+    fn recompute_mass(&mut self) -> &mut Self;
+}
+
+impl BodyEntityCommandsExt for EntityCommands<'_, '_, '_> {
+    fn teleport_body(&mut self, position: Vec2, angle: f32) -> &mut Self {
+        self.insert(TeleportBody { position, angle })
+    }
+
+    fn set_body_velocity(&mut self, linear_velocity: Vec2, angular_velocity: f32) -> &mut Self {
+        self.insert(SetBodyVelocity {
+            linear_velocity,
+            angular_velocity,
+        })
+    }
+
+    fn recompute_mass(&mut self) -> &mut Self {
+        self.insert(RecomputeMass)
+    }
+}
+
+/// Sent when the body on `entity` falls asleep or wakes up.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2BodySleepEvent {
+    pub entity: Entity,
+    pub asleep: bool,
+}
+
+/// A force and torque applied to the body on the same entity in every physics step.
+///
+/// The force is cleared in `PreUpdate`, before the systems in `Update` that set it, so it applies
+/// to all of the steps that run in the frame. Persistent forces are not cleared and keep applying
+/// until they are changed or cleared manually.
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
 pub struct ExternalForce {
     force: Vec2,
     pub should_wake: bool,
+    pub persistent: bool,
     torque: f32,
 }
 
@@ -145,6 +578,7 @@ impl ExternalForce {
     pub const ZERO: Self = Self {
         force: Vec2::ZERO,
         should_wake: false,
+        persistent: false,
         torque: 0.,
     };
 
@@ -152,6 +586,15 @@ impl ExternalForce {
         Self { force, ..default() }
     }
 
+    /// A force that is not cleared every frame, e.g. a constant thrust.
+    pub fn persistent(force: Vec2) -> Self {
+        Self {
+            force,
+            persistent: true,
+            ..default()
+        }
+    }
+
     pub fn set_force(&mut self, force: Vec2) -> &mut Self {
         self.force = force;
         self
@@ -173,6 +616,17 @@ impl ExternalForce {
         self
     }
 
+    /// Like [`ExternalForce::apply_force_at_point`], using the center of mass of the body as of
+    /// the last physics step.
+    pub fn apply_force_at_body_point(
+        &mut self,
+        force: Vec2,
+        point: Vec2,
+        body: &b2Body,
+    ) -> &mut Self {
+        self.apply_force_at_point(force, point, body.get_world_center())
+    }
+
     pub fn force(&self) -> Vec2 {
         self.force
     }
@@ -186,3 +640,163 @@ impl ExternalForce {
         self.torque = 0.;
     }
 }
+
+/// Like [`ExternalForce`], with the forces and points in the local coordinates of the body.
+///
+/// The forces are rotated by the angle of the body at each physics step, so they keep pointing
+/// the same way relative to the body over all of the steps that run in a frame.
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct LocalExternalForce {
+    force: Vec2,
+    pub should_wake: bool,
+    pub persistent: bool,
+    /// The torque about the origin of the body, converted to the torque about the center of
+    /// mass when the force is applied.
+    torque: f32,
+}
+
+impl LocalExternalForce {
+    pub fn new(force: Vec2) -> Self {
+        Self { force, ..default() }
+    }
+
+    pub fn set_force(&mut self, force: Vec2) -> &mut Self {
+        self.force = force;
+        self
+    }
+
+    pub fn apply_force(&mut self, force: Vec2) -> &mut Self {
+        self.force += force;
+        self
+    }
+
+    pub fn apply_force_at_point(&mut self, force: Vec2, point: Vec2) -> &mut Self {
+        self.force += force;
+        self.torque += point.perp_dot(force);
+        self
+    }
+
+    pub fn apply_torque(&mut self, torque: f32) -> &mut Self {
+        self.torque += torque;
+        self
+    }
+
+    pub fn force(&self) -> Vec2 {
+        self.force
+    }
+
+    /// The force in world coordinates for a body at the given angle.
+    pub fn world_force(&self, angle: f32) -> Vec2 {
+        Vec2::from_angle(angle).rotate(self.force)
+    }
+
+    /// The torque about the center of mass, given in the local coordinates of the body.
+    pub fn torque(&self, local_center: Vec2) -> f32 {
+        self.torque - local_center.perp_dot(self.force)
+    }
+
+    /// The torque about the origin of the body.
+    pub fn local_torque(&self) -> f32 {
+        self.torque
+    }
+
+    pub fn clear(&mut self) {
+        self.force = Vec2::ZERO;
+        self.torque = 0.;
+    }
+}
+
+/// Overrides the mass properties the body on the same entity derives from its fixtures, for
+/// instance to give it an artificially low inertia. Box2D recomputes the mass properties when
+/// fixtures are added or their density changes, so the override is reapplied afterwards.
+#[allow(non_camel_case_types)]
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct b2MassData {
+    pub mass: f32,
+    /// The center of mass in the local coordinates of the body.
+    pub center: Vec2,
+    /// The rotational inertia about the local origin of the body.
+    pub inertia: f32,
+}
+
+impl b2MassData {
+    pub(crate) fn apply_to_world(&self, entity: Entity, world: &mut b2World, force: bool) {
+        let Some(body_ptr) = world.get_body_ptr_mut(entity) else {
+            return;
+        };
+        if !force && body_ptr.as_ref().GetMass() == self.mass {
+            return;
+        }
+
+        let mut mass_data = ffi::b2MassData::new().within_box();
+        mass_data.mass = self.mass;
+        mass_data.center = to_b2Vec2(&self.center);
+        mass_data.I = self.inertia;
+        body_ptr.as_mut().SetMassData(&*mass_data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    fn moving_body() -> b2Body {
+        let mut body = b2Body::new(&b2BodyDef {
+            body_type: b2BodyType::Dynamic,
+            position: Vec2::new(3., -2.),
+            angle: 0.7,
+            ..default()
+        });
+        body.linear_velocity = Vec2::new(1., 0.5);
+        body.angular_velocity = 2.;
+        body
+    }
+
+    #[test]
+    fn local_and_world_points_round_trip() {
+        let body = moving_body();
+        for point in [Vec2::ZERO, Vec2::new(1., 2.), Vec2::new(-4.5, 0.25)] {
+            assert!(body
+                .world_point(body.local_point(point))
+                .abs_diff_eq(point, 1e-5));
+            assert!(body
+                .local_point(body.world_point(point))
+                .abs_diff_eq(point, 1e-5));
+        }
+    }
+
+    #[test]
+    fn world_point_rotates_then_translates() {
+        let mut body = moving_body();
+        body.angle = FRAC_PI_2;
+        assert!(body
+            .world_point(Vec2::new(1., 0.))
+            .abs_diff_eq(Vec2::new(3., -1.), 1e-5));
+        assert!(body
+            .local_point(body.position)
+            .abs_diff_eq(Vec2::ZERO, 1e-5));
+    }
+
+    #[test]
+    fn velocity_at_a_point_adds_the_rotation() {
+        let body = moving_body();
+        let point = Vec2::new(4., 1.);
+        let r = point - body.get_world_center();
+        let omega_cross_r = Vec2::new(-body.angular_velocity * r.y, body.angular_velocity * r.x);
+        assert!(body
+            .linear_velocity_at_world_point(point)
+            .abs_diff_eq(body.linear_velocity + omega_cross_r, 1e-5));
+        assert_eq!(
+            body.linear_velocity_at_world_point(body.get_world_center()),
+            body.linear_velocity
+        );
+        let local_point = body.local_point(point);
+        assert!(body
+            .linear_velocity_at_local_point(local_point)
+            .abs_diff_eq(body.linear_velocity_at_world_point(point), 1e-5));
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+/// Moves the dynamic body on the same entity like a platformer character, towards the velocity
+/// of its [`MovementIntent`], and keeps its [`Grounded`] state up to date. Requires the
+/// [`crate::plugins::LiquidFunCharacterControllerPlugin`].
+///
+/// The body should have a fixed rotation, and works best with a rounded bottom, e.g. a circle,
+/// which rolls over small steps instead of catching on them.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct CharacterController {
+    /// The steepest ground the character can stand on, as the angle in radians between the ground
+    /// normal and the up direction. Steeper contacts are walls.
+    pub max_slope_angle: f32,
+    /// How far below the character the ground is still found when it isn't touching it, so that it
+    /// stays grounded when running over bumps and down slopes.
+    pub ground_snap_distance: f32,
+    /// The largest change in velocity per second the movement can cause on the ground.
+    pub max_acceleration: f32,
+    /// The largest change in velocity per second the movement can cause in the air.
+    pub max_air_acceleration: f32,
+    /// The upward speed given by a jump.
+    pub jump_speed: f32,
+    /// How long a jump requested in the air is remembered, so that it happens on landing.
+    pub jump_buffer_time: f32,
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        Self {
+            max_slope_angle: 50f32.to_radians(),
+            ground_snap_distance: 0.1,
+            max_acceleration: 40.,
+            max_air_acceleration: 10.,
+            jump_speed: 6.,
+            jump_buffer_time: 0.15,
+        }
+    }
+}
+
+impl CharacterController {
+    /// Whether a contact normal, pointing away from the other fixture, is ground rather than a
+    /// wall or a ceiling.
+    pub fn is_walkable(&self, normal: Vec2, up: Vec2) -> bool {
+        normal.dot(up) >= self.max_slope_angle.cos()
+    }
+}
+
+/// What a [`CharacterController`] is standing on, updated before every physics step.
+#[derive(Component, Debug, Copy, Clone)]
+pub struct Grounded {
+    pub is_grounded: bool,
+    /// The normal of the ground, pointing away from it, or the up direction in the air.
+    pub normal: Vec2,
+    /// The body the character stands on.
+    pub ground_body: Option<Entity>,
+    /// The velocity of the ground under the character, which the movement is relative to.
+    pub ground_velocity: Vec2,
+    /// The normal of a wall the character is touching, pointing away from it.
+    pub wall_normal: Option<Vec2>,
+}
+
+impl Default for Grounded {
+    fn default() -> Self {
+        Self {
+            is_grounded: false,
+            normal: Vec2::Y,
+            ground_body: None,
+            ground_velocity: Vec2::ZERO,
+            wall_normal: None,
+        }
+    }
+}
+
+/// How a [`CharacterController`] wants to move, set by gameplay code.
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component)]
+pub struct MovementIntent {
+    /// The velocity the character accelerates towards. Only the horizontal part is used, along
+    /// the ground when grounded, the vertical motion comes from gravity and jumps.
+    pub desired_velocity: Vec2,
+    jump_timer: Option<f32>,
+}
+
+impl MovementIntent {
+    /// Jumps as soon as the character is grounded, if that happens within the jump buffer time.
+    pub fn jump(&mut self) {
+        self.jump_timer = Some(0.);
+    }
+
+    pub(crate) fn take_jump(
+        &mut self,
+        elapsed: f32,
+        jump_buffer_time: f32,
+        grounded: bool,
+    ) -> bool {
+        let Some(timer) = self.jump_timer.as_mut() else {
+            return false;
+        };
+        if grounded {
+            self.jump_timer = None;
+            return true;
+        }
+        *timer += elapsed;
+        if *timer > jump_buffer_time {
+            self.jump_timer = None;
+        }
+        false
+    }
+}
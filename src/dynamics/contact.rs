@@ -0,0 +1,416 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+
+use autocxx::WithinBox;
+use bevy::prelude::{Component, Entity, Event, Resource, Vec2};
+use libliquidfun_sys::box2d::ffi;
+
+use crate::internal::{body_entity, fixture_entity, fixture_index, to_Vec2};
+
+/// A contact between two fixtures whose bounding boxes overlap. The fixtures themselves only
+/// touch if `is_touching` is set.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone)]
+pub struct b2Contact {
+    pub fixture_a: Entity,
+    pub fixture_b: Entity,
+    /// The index of fixture A in the [`crate::dynamics::b2Fixtures`] of `fixture_a`, which is
+    /// then the body entity. `None` for fixtures with an entity of their own.
+    pub fixture_index_a: Option<usize>,
+    pub fixture_index_b: Option<usize>,
+    pub body_a: Entity,
+    pub body_b: Entity,
+    pub manifold: b2WorldManifold,
+    pub is_touching: bool,
+    /// Disabled contacts, e.g. with a [`crate::dynamics::OneWayPlatform`] that is passed
+    /// through, are not solved during the current step.
+    pub is_enabled: bool,
+    pub is_sensor_a: bool,
+    pub is_sensor_b: bool,
+}
+
+impl b2Contact {
+    pub(crate) fn from_ffi_contact(mut contact: Pin<&mut ffi::b2Contact>) -> Self {
+        unsafe {
+            let mut fixture_a =
+                Pin::new_unchecked(contact.as_mut().GetFixtureA().as_mut().unwrap());
+            let mut fixture_b =
+                Pin::new_unchecked(contact.as_mut().GetFixtureB().as_mut().unwrap());
+            let body_a = Pin::new_unchecked(fixture_a.as_ref().GetBody().as_mut().unwrap());
+            let body_b = Pin::new_unchecked(fixture_b.as_ref().GetBody().as_mut().unwrap());
+
+            let is_sensor_a = fixture_a.as_ref().IsSensor();
+            let is_sensor_b = fixture_b.as_ref().IsSensor();
+            b2Contact {
+                fixture_a: fixture_entity(fixture_a.as_mut()),
+                fixture_b: fixture_entity(fixture_b.as_mut()),
+                fixture_index_a: fixture_index(fixture_a),
+                fixture_index_b: fixture_index(fixture_b),
+                body_a: body_entity(body_a),
+                body_b: body_entity(body_b),
+                is_touching: contact.as_ref().IsTouching(),
+                is_enabled: contact.as_ref().IsEnabled(),
+                is_sensor_a,
+                is_sensor_b,
+                manifold: b2WorldManifold::from_ffi_contact(contact),
+            }
+        }
+    }
+
+    /// The fixture entities of a contact, without copying the rest of it.
+    pub(crate) fn fixture_entities(mut contact: Pin<&mut ffi::b2Contact>) -> (Entity, Entity) {
+        unsafe {
+            let fixture_a = Pin::new_unchecked(contact.as_mut().GetFixtureA().as_mut().unwrap());
+            let fixture_b = Pin::new_unchecked(contact.as_mut().GetFixtureB().as_mut().unwrap());
+            (fixture_entity(fixture_a), fixture_entity(fixture_b))
+        }
+    }
+
+    /// The sensor fixtures of the contact, paired with the fixture and body they overlap.
+    pub fn sensors(&self) -> impl Iterator<Item = b2SensorOverlap> {
+        let overlap_a = self.is_sensor_a.then_some(b2SensorOverlap {
+            sensor: self.fixture_a,
+            sensor_index: self.fixture_index_a,
+            sensor_body: self.body_a,
+            other: self.fixture_b,
+            other_index: self.fixture_index_b,
+            other_body: self.body_b,
+        });
+        let overlap_b = self.is_sensor_b.then_some(b2SensorOverlap {
+            sensor: self.fixture_b,
+            sensor_index: self.fixture_index_b,
+            sensor_body: self.body_b,
+            other: self.fixture_a,
+            other_index: self.fixture_index_a,
+            other_body: self.body_a,
+        });
+        overlap_a.into_iter().chain(overlap_b)
+    }
+}
+
+/// A sensor fixture overlapping another fixture.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone)]
+pub struct b2SensorOverlap {
+    pub sensor: Entity,
+    /// The index in the [`crate::dynamics::b2Fixtures`] of `sensor`, see
+    /// [`b2Contact::fixture_index_a`].
+    pub sensor_index: Option<usize>,
+    pub sensor_body: Entity,
+    pub other: Entity,
+    pub other_index: Option<usize>,
+    pub other_body: Entity,
+}
+
+/// The contact manifold in world coordinates.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct b2WorldManifold {
+    /// World vector pointing from A to B.
+    pub normal: Vec2,
+
+    /// World contact points (points of intersection). Only the first `point_count` are valid.
+    pub points: [Vec2; 2],
+
+    /// A negative value indicates overlap, in meters. Only the first `point_count` are valid.
+    pub separations: [f32; 2],
+
+    /// The number of manifold points, between 0 and 2.
+    pub point_count: usize,
+}
+
+impl b2WorldManifold {
+    fn from_ffi_contact(mut contact: Pin<&mut ffi::b2Contact>) -> Self {
+        unsafe {
+            let manifold = contact.as_mut().GetManifold().as_ref().unwrap();
+            let point_count = i32::from(manifold.pointCount) as usize;
+
+            let mut world_manifold = ffi::b2WorldManifold::new().within_box();
+            contact
+                .as_ref()
+                .GetWorldManifold(world_manifold.as_mut().get_unchecked_mut());
+
+            b2WorldManifold {
+                normal: to_Vec2(&world_manifold.normal),
+                points: [
+                    to_Vec2(&world_manifold.points[0]),
+                    to_Vec2(&world_manifold.points[1]),
+                ],
+                separations: world_manifold.separations,
+                point_count,
+            }
+        }
+    }
+
+    pub fn points(&self) -> &[Vec2] {
+        &self.points[..self.point_count]
+    }
+
+    pub fn separations(&self) -> &[f32] {
+        &self.separations[..self.point_count]
+    }
+}
+
+/// Contact impulses computed by the solver, matching the points of the contact manifold.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct b2ContactImpulse {
+    pub normal_impulses: [f32; 2],
+    pub tangent_impulses: [f32; 2],
+    pub count: usize,
+}
+
+impl b2ContactImpulse {
+    pub(crate) fn from_ffi(impulse: &ffi::b2ContactImpulse) -> Self {
+        b2ContactImpulse {
+            normal_impulses: impulse.normalImpulses,
+            tangent_impulses: impulse.tangentImpulses,
+            count: i32::from(impulse.count) as usize,
+        }
+    }
+
+    pub fn normal_impulses(&self) -> &[f32] {
+        &self.normal_impulses[..self.count]
+    }
+
+    pub fn tangent_impulses(&self) -> &[f32] {
+        &self.tangent_impulses[..self.count]
+    }
+
+    /// The largest normal impulse over all contact points, a good measure of how hard the hit was.
+    pub fn max_normal_impulse(&self) -> f32 {
+        self.normal_impulses().iter().copied().fold(0., f32::max)
+    }
+}
+
+/// All the contacts that currently exist in the physics world, updated after every step.
+#[allow(non_camel_case_types)]
+#[derive(Resource, Debug, Default)]
+pub struct b2Contacts {
+    contacts: Vec<b2Contact>,
+    /// The indices of the contacts of every body and fixture entity.
+    by_entity: HashMap<Entity, Vec<usize>>,
+}
+
+impl b2Contacts {
+    pub fn contacts(&self) -> &[b2Contact] {
+        &self.contacts
+    }
+
+    /// The contacts of a body or fixture, without going through all the contacts.
+    pub fn contacts_of(&self, entity: Entity) -> impl Iterator<Item = &b2Contact> {
+        self.by_entity
+            .get(&entity)
+            .into_iter()
+            .flatten()
+            .map(|index| &self.contacts[*index])
+    }
+
+    pub(crate) fn contacts_mut(&mut self) -> &mut Vec<b2Contact> {
+        &mut self.contacts
+    }
+
+    /// Has to be called whenever the contacts have changed.
+    pub(crate) fn rebuild_index(&mut self) {
+        // Keeps the allocations of the entities that are still in contact
+        for indices in self.by_entity.values_mut() {
+            indices.clear();
+        }
+        for (index, contact) in self.contacts.iter().enumerate() {
+            for entity in [
+                contact.fixture_a,
+                contact.fixture_b,
+                contact.body_a,
+                contact.body_b,
+            ] {
+                let indices = self.by_entity.entry(entity).or_default();
+                // The fixtures of a [`crate::dynamics::b2Fixtures`] component share the entity
+                // of their body
+                if indices.last() != Some(&index) {
+                    indices.push(index);
+                }
+            }
+        }
+        self.by_entity.retain(|_, indices| !indices.is_empty());
+    }
+}
+
+/// Replaces the values Box2D mixes from the fixtures of a contact. Fields left at `None` keep the
+/// mixed value.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct ContactOverride {
+    pub friction: Option<f32>,
+    pub restitution: Option<f32>,
+    /// The speed of the surface along the contact tangent in m/s, e.g. for conveyor belts.
+    pub tangent_speed: Option<f32>,
+}
+
+impl ContactOverride {
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = Some(friction);
+        self
+    }
+
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.restitution = Some(restitution);
+        self
+    }
+
+    pub fn with_tangent_speed(mut self, tangent_speed: f32) -> Self {
+        self.tangent_speed = Some(tangent_speed);
+        self
+    }
+
+    pub(crate) fn apply(&self, mut contact: Pin<&mut ffi::b2Contact>) {
+        if let Some(friction) = self.friction {
+            contact.as_mut().SetFriction(friction);
+        }
+        if let Some(restitution) = self.restitution {
+            contact.as_mut().SetRestitution(restitution);
+        }
+        if let Some(tangent_speed) = self.tangent_speed {
+            contact.as_mut().SetTangentSpeed(tangent_speed);
+        }
+    }
+
+    /// Restores the mixed values of the fixtures.
+    pub(crate) fn reset(mut contact: Pin<&mut ffi::b2Contact>) {
+        contact.as_mut().ResetFriction();
+        contact.as_mut().ResetRestitution();
+        contact.as_mut().SetTangentSpeed(0.);
+    }
+}
+
+/// Overrides for the contacts between specific pairs of fixture entities, in either order. For
+/// fixtures created through [`crate::dynamics::b2Fixtures`], use the body entity instead.
+///
+/// The overrides are applied in the PreSolve callback of every step, so they also cover contacts
+/// that begin during the step. Contacts whose override is removed go back to the mixed values of
+/// their fixtures.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct ContactOverrides {
+    overrides: HashMap<(Entity, Entity), ContactOverride>,
+}
+
+impl ContactOverrides {
+    fn key(fixture_a: Entity, fixture_b: Entity) -> (Entity, Entity) {
+        (fixture_a.min(fixture_b), fixture_a.max(fixture_b))
+    }
+
+    pub fn insert(
+        &mut self,
+        fixture_a: Entity,
+        fixture_b: Entity,
+        contact_override: ContactOverride,
+    ) -> Option<ContactOverride> {
+        self.overrides
+            .insert(Self::key(fixture_a, fixture_b), contact_override)
+    }
+
+    pub fn remove(&mut self, fixture_a: Entity, fixture_b: Entity) -> Option<ContactOverride> {
+        self.overrides.remove(&Self::key(fixture_a, fixture_b))
+    }
+
+    pub fn get(&self, fixture_a: Entity, fixture_b: Entity) -> Option<&ContactOverride> {
+        self.overrides.get(&Self::key(fixture_a, fixture_b))
+    }
+
+    pub fn clear(&mut self) {
+        self.overrides.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    /// The fixture pairs that have an override here but not in `other`.
+    pub(crate) fn removed_from(&self, other: &ContactOverrides) -> Vec<(Entity, Entity)> {
+        self.overrides
+            .keys()
+            .filter(|key| !other.overrides.contains_key(key))
+            .copied()
+            .collect()
+    }
+}
+
+/// Sent when two fixtures start touching.
+///
+/// When several steps run in one frame, `step` and `time` tell which of them the contact began
+/// in.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2BeginContactEvent {
+    pub contact: b2Contact,
+    /// The [`crate::dynamics::PhysicsStepCount`] at the end of the step.
+    pub step: u64,
+    /// The simulated time in seconds at the end of the step, see
+    /// [`crate::dynamics::b2World::get_elapsed_time`].
+    pub time: f64,
+}
+
+/// Sent when two fixtures stop touching. The manifold is usually empty at that point.
+///
+/// Contacts that end because a fixture or body is destroyed between steps carry the `step` and
+/// `time` of the last step.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2EndContactEvent {
+    pub contact: b2Contact,
+    pub step: u64,
+    pub time: f64,
+}
+
+/// Sent for every touching contact after the solver has run, with the impulses that were applied.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2PostSolveEvent {
+    pub contact: b2Contact,
+    pub impulse: b2ContactImpulse,
+}
+
+/// Sent when the shape of a sensor fixture starts overlapping another fixture, rather than just
+/// its bounding box. Sent once for every sensor if both fixtures are sensors, and not affected by
+/// the [`ContactEventFilter`].
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2SensorBeginOverlapEvent(pub b2SensorOverlap);
+
+/// Sent when a sensor fixture stops overlapping another fixture.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2SensorEndOverlapEvent(pub b2SensorOverlap);
+
+/// Sent once per frame for every contact in [`b2Contacts`] whose fixtures are touching, if
+/// enabled with [`ContactEventFilter::stay_events`]. `impulse` is the impulse of the last step
+/// of the frame, if the contact was solved, e.g. it is `None` for sensors.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2ContactStayEvent {
+    pub contact: b2Contact,
+    pub impulse: Option<b2ContactImpulse>,
+}
+
+/// Limits which contacts [`b2BeginContactEvent`]s and [`b2ContactStayEvent`]s are sent for, to
+/// avoid a flood of events from e.g. a pile of resting boxes. [`b2EndContactEvent`]s are only
+/// filtered by [`EmitContactEvents`], so every filtered begin event is still matched by an end
+/// event.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct ContactEventFilter {
+    /// Only send events for contacts involving a fixture or body with [`EmitContactEvents`],
+    /// [`b2PostSolveEvent`]s included. The other contacts aren't even recorded during the step,
+    /// which saves a lot of work in busy worlds. Sensor overlap events are sent regardless.
+    pub require_marker: bool,
+    /// The minimum speed the bodies approach each other with along the contact normal, as of
+    /// the last frame.
+    pub min_relative_speed: f32,
+    /// The minimum of [`b2ContactImpulse::max_normal_impulse`] during the last step. Contacts
+    /// that weren't solved, such as sensor contacts, are filtered out when this is positive.
+    pub min_normal_impulse: f32,
+    /// Whether [`b2ContactStayEvent`]s are sent at all.
+    pub stay_events: bool,
+}
+
+/// Marks a fixture or body entity whose contacts generate events when
+/// [`ContactEventFilter::require_marker`] is set.
+#[derive(Component, Debug, Default, Copy, Clone)]
+pub struct EmitContactEvents;
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+use bevy::prelude::{Component, Entity, Reflect, ReflectComponent, Resource};
+use libliquidfun_sys::box2d::ffi;
+use libliquidfun_sys::box2d::ffi::b2ContactFilterImpl;
+
+use crate::internal::fixture_entity;
+
+/// Custom collision filtering, replacing the default [`crate::dynamics::b2Filter`] rules.
+///
+/// The callback is invoked by Box2D in the middle of a step, whenever the bounding boxes of two
+/// fixtures start overlapping. It therefore can't access the Bevy `World`; anything it needs
+/// has to be captured up front, see [`b2TeamContactFilter`] for an example.
+#[cfg(not(feature = "parallel_step"))]
+#[allow(non_camel_case_types)]
+pub trait b2ContactFilterCallback: Debug {
+    fn should_collide(&mut self, fixture_a: Entity, fixture_b: Entity) -> bool;
+}
+
+/// Custom collision filtering, replacing the default [`crate::dynamics::b2Filter`] rules.
+///
+/// The callback is invoked by Box2D in the middle of a step, whenever the bounding boxes of two
+/// fixtures start overlapping. It therefore can't access the Bevy `World`; anything it needs
+/// has to be captured up front, see [`b2TeamContactFilter`] for an example. With the
+/// `parallel_step` feature the worlds are stepped on other threads, so the callback has to be
+/// `Send`.
+#[cfg(feature = "parallel_step")]
+#[allow(non_camel_case_types)]
+pub trait b2ContactFilterCallback: Debug + Send {
+    fn should_collide(&mut self, fixture_a: Entity, fixture_b: Entity) -> bool;
+}
+
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub(crate) struct b2ContactFilter {
+    callback: Box<dyn b2ContactFilterCallback>,
+}
+
+impl b2ContactFilter {
+    pub fn new(callback: impl b2ContactFilterCallback + 'static) -> Self {
+        Self {
+            callback: Box::new(callback),
+        }
+    }
+}
+
+#[allow(unused_variables)]
+impl b2ContactFilterImpl for b2ContactFilter {
+    fn should_collide(
+        &mut self,
+        fixture_a: &mut ffi::b2Fixture,
+        fixture_b: &mut ffi::b2Fixture,
+    ) -> bool {
+        let (fixture_a, fixture_b) = unsafe {
+            (
+                fixture_entity(Pin::new_unchecked(fixture_a)),
+                fixture_entity(Pin::new_unchecked(fixture_b)),
+            )
+        };
+        self.callback.should_collide(fixture_a, fixture_b)
+    }
+
+    fn should_collide_fixture_particle(
+        &mut self,
+        fixture: &mut ffi::b2Fixture,
+        particle_system: &mut ffi::b2ParticleSystem,
+        particle_index: i32,
+    ) -> bool {
+        true
+    }
+
+    fn should_collide_particle_particle(
+        &mut self,
+        particle_system: &mut ffi::b2ParticleSystem,
+        particle_index_a: i32,
+        particle_index_b: i32,
+    ) -> bool {
+        true
+    }
+}
+
+/// The team of a fixture for [`b2TeamContactFilter`]. Goes on the fixture entity, or on the
+/// body entity when the fixtures are created through [`crate::dynamics::b2Fixtures`].
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Component)]
+pub struct CollisionTeam(pub u32);
+
+/// A contact filter that stops fixtures of the same [`CollisionTeam`] from colliding. Fixtures
+/// without a team collide with everything.
+///
+/// Inserting this as a resource installs it on the [`crate::dynamics::b2World`], and the team
+/// snapshot it works on is refreshed from the [`CollisionTeam`] components every frame.
+/// Removing the resource restores the default filtering.
+#[allow(non_camel_case_types)]
+#[derive(Resource, Debug, Default, Clone)]
+pub struct b2TeamContactFilter {
+    teams: Arc<RwLock<HashMap<Entity, u32>>>,
+}
+
+impl b2TeamContactFilter {
+    pub fn update_snapshot(&self, teams: impl IntoIterator<Item = (Entity, u32)>) {
+        let mut snapshot = self.teams.write().unwrap();
+        snapshot.clear();
+        snapshot.extend(teams);
+    }
+}
+
+impl b2ContactFilterCallback for b2TeamContactFilter {
+    fn should_collide(&mut self, fixture_a: Entity, fixture_b: Entity) -> bool {
+        let teams = self.teams.read().unwrap();
+        match (teams.get(&fixture_a), teams.get(&fixture_b)) {
+            (Some(team_a), Some(team_b)) => team_a != team_b,
+            _ => true,
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::{HashMap, HashSet};
+use std::pin::Pin;
+
+use bevy::prelude::Entity;
+
+use libliquidfun_sys::box2d::ffi;
+use libliquidfun_sys::box2d::ffi::b2ContactListenerImpl;
+
+use crate::dynamics::{
+    b2BeginContactEvent, b2Contact, b2ContactImpulse, b2EndContactEvent, ContactOverrides,
+    EmitContactEvents, OneWayPlatform,
+};
+use crate::internal::{body_entity, fixture_entity};
+
+/// Records the contact callbacks Box2D makes during a step, so they can be sent as events
+/// once the step is done.
+#[derive(Debug, Default)]
+#[allow(non_camel_case_types)]
+pub(crate) struct b2ContactListener {
+    begun_contacts: Vec<b2BeginContactEvent>,
+    ended_contacts: Vec<b2EndContactEvent>,
+    /// The step count and simulated time at the end of the step that is running.
+    step: u64,
+    time: f64,
+    post_solve_contacts: Vec<(b2Contact, b2ContactImpulse)>,
+
+    /// Mirrors the [`OneWayPlatform`] components, keyed by fixture entity, since the ECS can't
+    /// be accessed during the step.
+    one_way_platforms: HashMap<Entity, OneWayPlatform>,
+    /// Mirrors the [`ContactOverrides`] resource.
+    contact_overrides: ContactOverrides,
+    /// The entities with [`EmitContactEvents`] while
+    /// [`crate::dynamics::ContactEventFilter::require_marker`] is set, `None` otherwise.
+    marked_entities: Option<HashSet<Entity>>,
+}
+
+impl b2ContactListener {
+    pub fn begin_step(&mut self, step: u64, time: f64) {
+        self.step = step;
+        self.time = time;
+    }
+
+    pub fn begun_contacts(&self) -> &[b2BeginContactEvent] {
+        &self.begun_contacts
+    }
+
+    pub fn ended_contacts(&self) -> &[b2EndContactEvent] {
+        &self.ended_contacts
+    }
+
+    pub fn post_solve_contacts(&self) -> &[(b2Contact, b2ContactImpulse)] {
+        &self.post_solve_contacts
+    }
+
+    /// Forgets the recorded contacts once they have been sent, keeping the capacity of the
+    /// buffers so the next steps don't have to grow them again.
+    pub fn clear_recorded_contacts(&mut self) {
+        self.begun_contacts.clear();
+        self.ended_contacts.clear();
+        self.post_solve_contacts.clear();
+    }
+
+    pub fn set_one_way_platform(&mut self, fixture_entity: Entity, platform: OneWayPlatform) {
+        self.one_way_platforms.insert(fixture_entity, platform);
+    }
+
+    pub fn remove_one_way_platform(&mut self, fixture_entity: Entity) {
+        self.one_way_platforms.remove(&fixture_entity);
+    }
+
+    pub fn set_marked_entities(&mut self, marked_entities: Option<HashSet<Entity>>) {
+        self.marked_entities = marked_entities;
+    }
+
+    pub fn mark_entity(&mut self, entity: Entity) {
+        if let Some(marked_entities) = &mut self.marked_entities {
+            marked_entities.insert(entity);
+        }
+    }
+
+    pub fn unmark_entity(&mut self, entity: Entity) {
+        if let Some(marked_entities) = &mut self.marked_entities {
+            marked_entities.remove(&entity);
+        }
+    }
+
+    /// Whether the callbacks for a contact are recorded. Without the marker on any of its
+    /// fixtures or bodies no events are sent for it, so the contact isn't copied in the first
+    /// place. Sensor contacts are always recorded for the sensor overlap events.
+    fn records(&self, mut contact: Pin<&mut ffi::b2Contact>) -> bool {
+        let Some(marked_entities) = &self.marked_entities else {
+            return true;
+        };
+        unsafe {
+            let mut fixture_a =
+                Pin::new_unchecked(contact.as_mut().GetFixtureA().as_mut().unwrap());
+            let mut fixture_b =
+                Pin::new_unchecked(contact.as_mut().GetFixtureB().as_mut().unwrap());
+            if fixture_a.as_ref().IsSensor() || fixture_b.as_ref().IsSensor() {
+                return true;
+            }
+            let body_a = Pin::new_unchecked(fixture_a.as_mut().GetBody().as_mut().unwrap());
+            let body_b = Pin::new_unchecked(fixture_b.as_mut().GetBody().as_mut().unwrap());
+            [
+                fixture_entity(fixture_a),
+                fixture_entity(fixture_b),
+                body_entity(body_a),
+                body_entity(body_b),
+            ]
+            .iter()
+            .any(|entity| marked_entities.contains(entity))
+        }
+    }
+
+    /// Returns the fixture pairs whose override was removed.
+    pub fn set_contact_overrides(&mut self, overrides: &ContactOverrides) -> Vec<(Entity, Entity)> {
+        let removed = self.contact_overrides.removed_from(overrides);
+        self.contact_overrides = overrides.clone();
+        removed
+    }
+}
+
+#[allow(unused_variables)]
+impl b2ContactListenerImpl for b2ContactListener {
+    fn begin_contact(&mut self, contact: &mut ffi::b2Contact) {
+        let mut contact = unsafe { Pin::new_unchecked(contact) };
+        if !self.records(contact.as_mut()) {
+            return;
+        }
+        let contact = b2Contact::from_ffi_contact(contact);
+        self.begun_contacts.push(b2BeginContactEvent {
+            contact,
+            step: self.step,
+            time: self.time,
+        });
+    }
+
+    fn end_contact(&mut self, contact: &mut ffi::b2Contact) {
+        let mut contact = unsafe { Pin::new_unchecked(contact) };
+        if !self.records(contact.as_mut()) {
+            return;
+        }
+        let contact = b2Contact::from_ffi_contact(contact);
+        self.ended_contacts.push(b2EndContactEvent {
+            contact,
+            step: self.step,
+            time: self.time,
+        });
+    }
+
+    fn pre_solve(&mut self, contact: &mut ffi::b2Contact, old_manifold: &ffi::b2Manifold) {
+        let mut contact = unsafe { Pin::new_unchecked(contact) };
+        if !self.contact_overrides.is_empty() {
+            let (fixture_a, fixture_b) = b2Contact::fixture_entities(contact.as_mut());
+            if let Some(contact_override) = self.contact_overrides.get(fixture_a, fixture_b) {
+                contact_override.apply(contact.as_mut());
+            }
+        }
+
+        if self.one_way_platforms.is_empty() {
+            return;
+        }
+
+        let b2_contact = b2Contact::from_ffi_contact(contact.as_mut());
+        let (platform, platform_is_a) =
+            if let Some(platform) = self.one_way_platforms.get(&b2_contact.fixture_a) {
+                (platform, true)
+            } else if let Some(platform) = self.one_way_platforms.get(&b2_contact.fixture_b) {
+                (platform, false)
+            } else {
+                return;
+            };
+
+        let should_collide = unsafe {
+            let fixture_a = contact.as_mut().GetFixtureA().as_mut().unwrap();
+            let fixture_b = contact.as_mut().GetFixtureB().as_mut().unwrap();
+            let body_a = Pin::new_unchecked(fixture_a).GetBody().as_mut().unwrap();
+            let body_b = Pin::new_unchecked(fixture_b).GetBody().as_mut().unwrap();
+            let (platform_body, other_body) = if platform_is_a {
+                (body_a, body_b)
+            } else {
+                (body_b, body_a)
+            };
+
+            // The manifold normal points from fixture A to fixture B
+            let manifold = b2_contact.manifold;
+            let normal = if platform_is_a {
+                manifold.normal
+            } else {
+                -manifold.normal
+            };
+            platform.should_collide(
+                Pin::new_unchecked(platform_body),
+                Pin::new_unchecked(other_body),
+                normal,
+                manifold.points().first().copied(),
+            )
+        };
+
+        if !should_collide {
+            contact.as_mut().SetEnabled(false);
+        }
+    }
+
+    fn post_solve(&mut self, contact: &mut ffi::b2Contact, impulse: &ffi::b2ContactImpulse) {
+        let mut contact = unsafe { Pin::new_unchecked(contact) };
+        if !self.records(contact.as_mut()) {
+            return;
+        }
+        let contact = b2Contact::from_ffi_contact(contact);
+        let impulse = b2ContactImpulse::from_ffi(impulse);
+        self.post_solve_contacts.push((contact, impulse));
+    }
+
+    fn begin_particle_body_contact(
+        &mut self,
+        particle_system: &mut ffi::b2ParticleSystem,
+        particle_body_contact: &mut ffi::b2ParticleBodyContact,
+    ) {
+    }
+
+    fn end_particle_body_contact(
+        &mut self,
+        fixture: &mut ffi::b2Fixture,
+        particle_system: &mut ffi::b2ParticleSystem,
+        index: i32,
+    ) {
+    }
+
+    fn begin_particle_particle_contact(
+        &mut self,
+        particle_system: &mut ffi::b2ParticleSystem,
+        particle_contact: &mut ffi::b2ParticleContact,
+    ) {
+    }
+
+    fn end_particle_particle_contact(
+        &mut self,
+        particle_system: &mut ffi::b2ParticleSystem,
+        index_a: i32,
+        index_b: i32,
+    ) {
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::HashSet;
+use std::pin::Pin;
+
+use libliquidfun_sys::box2d::ffi;
+use libliquidfun_sys::box2d::ffi::b2DestructionListenerImpl;
+
+/// Records the objects Box2D destroys implicitly, either during a step (particles and particle
+/// groups) or as a side effect of destroying a body (joints), so the Rust side can drop its
+/// pointers to them and report them as events.
+#[derive(Debug, Default)]
+#[allow(non_camel_case_types)]
+pub(crate) struct b2DestructionListener {
+    destroyed_joints: HashSet<*const ffi::b2Joint>,
+    destroyed_particle_groups: Vec<*const ffi::b2ParticleGroup>,
+    /// The particle system, the index the particle had and the address of its handle, which is
+    /// freed right after.
+    destroyed_particles: Vec<(*const ffi::b2ParticleSystem, i32, usize)>,
+}
+
+impl b2DestructionListener {
+    pub fn take_destroyed_joints(&mut self) -> HashSet<*const ffi::b2Joint> {
+        std::mem::take(&mut self.destroyed_joints)
+    }
+
+    pub fn take_destroyed_particle_groups(&mut self) -> Vec<*const ffi::b2ParticleGroup> {
+        std::mem::take(&mut self.destroyed_particle_groups)
+    }
+
+    pub fn take_destroyed_particles(&mut self) -> Vec<(*const ffi::b2ParticleSystem, i32, usize)> {
+        std::mem::take(&mut self.destroyed_particles)
+    }
+}
+
+#[allow(unused_variables)]
+impl b2DestructionListenerImpl for b2DestructionListener {
+    fn say_goodbye_joint(&mut self, joint: &mut ffi::b2Joint) {
+        self.destroyed_joints.insert(joint);
+    }
+
+    fn say_goodbye_fixture(&mut self, fixture: &mut ffi::b2Fixture) {}
+
+    fn say_goodbye_particle_group(&mut self, group: &mut ffi::b2ParticleGroup) {
+        self.destroyed_particle_groups.push(group);
+    }
+
+    fn say_goodbye_particle(&mut self, particle_system: &mut ffi::b2ParticleSystem, index: i32) {
+        let mut particle_system = unsafe { Pin::new_unchecked(particle_system) };
+        // Still owned by the particle system at this point, it is freed once this returns
+        let handle = particle_system
+            .as_mut()
+            .GetParticleHandleFromIndex(ffi::int32::from(index)) as usize;
+        let particle_system: *const ffi::b2ParticleSystem = particle_system.as_ref().get_ref();
+        self.destroyed_particles
+            .push((particle_system, index, handle));
+    }
+}
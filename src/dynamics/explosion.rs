@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use bevy::prelude::{Entity, Event, Vec2};
+
+use crate::collision::{b2Shape, ffi_child_count, ffi_distance};
+use crate::dynamics::{
+    b2BodyType, b2QueryFilter, b2RayCastAny, b2RayCastFilter, b2World, PhysicsWorldId,
+};
+use crate::internal::{to_Vec2, to_b2Vec2};
+
+/// How the impulse of an [`Explosion`] decreases with the distance from its center.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum Falloff {
+    /// The full impulse everywhere within the radius.
+    Constant,
+    /// The full impulse at the center, down to nothing at the radius.
+    #[default]
+    Linear,
+    /// The full impulse at the center, divided by one plus the squared distance in meters.
+    InverseSquare,
+}
+
+impl Falloff {
+    pub fn factor(&self, distance: f32, radius: f32) -> f32 {
+        match self {
+            Falloff::Constant => 1.,
+            Falloff::Linear => (1. - distance / radius).max(0.),
+            Falloff::InverseSquare => 1. / (1. + distance * distance),
+        }
+    }
+}
+
+/// Pushes the dynamic bodies within a radius away from a point, applying the impulse at the
+/// point of each body closest to the center.
+#[derive(Debug, Clone)]
+pub struct Explosion {
+    pub center: Vec2,
+    pub radius: f32,
+    /// The impulse at the center, in N*s, before the falloff.
+    pub impulse: f32,
+    pub falloff: Falloff,
+    /// Only the bodies with fixtures that pass the filter are pushed.
+    pub filter: b2QueryFilter,
+    /// Whether the blast is blocked by the fixtures between the center and a body.
+    pub occlusion: bool,
+    /// The impulse applied to every particle within the radius, before the falloff. Particles
+    /// are not occluded.
+    pub particle_impulse: f32,
+    /// The world an [`ExplosionEvent`] is applied in, the default world unless set.
+    pub world: PhysicsWorldId,
+}
+
+impl Explosion {
+    pub fn new(center: Vec2, radius: f32, impulse: f32) -> Self {
+        Self {
+            center,
+            radius,
+            impulse,
+            falloff: Falloff::default(),
+            filter: b2QueryFilter::default(),
+            occlusion: false,
+            particle_impulse: 0.,
+            world: PhysicsWorldId::DEFAULT,
+        }
+    }
+
+    pub fn with_falloff(mut self, falloff: Falloff) -> Self {
+        self.falloff = falloff;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: b2QueryFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_occlusion(mut self) -> Self {
+        self.occlusion = true;
+        self
+    }
+
+    pub fn with_particle_impulse(mut self, particle_impulse: f32) -> Self {
+        self.particle_impulse = particle_impulse;
+        self
+    }
+
+    pub fn in_world(mut self, world: PhysicsWorldId) -> Self {
+        self.world = world;
+        self
+    }
+
+    /// Returns the bodies that were pushed, in entity order.
+    pub(crate) fn apply(&self, world: &mut b2World) -> Vec<Entity> {
+        let center_shape = b2Shape::Circle {
+            radius: 0.,
+            position: Vec2::ZERO,
+        }
+        .to_ffi();
+
+        // The closest point of every body and its distance from the center
+        let mut closest_points: HashMap<Entity, (Vec2, f32)> = HashMap::new();
+        for hit in world.overlap_circle(self.center, self.radius, &self.filter) {
+            let Some(fixture_ptr) = world.get_fixture_ptr_mut(&hit.fixture_entity) else {
+                continue;
+            };
+            let (shape, body) = unsafe {
+                (
+                    fixture_ptr.as_mut().GetShape().as_ref().unwrap(),
+                    fixture_ptr.as_mut().GetBody().as_ref().unwrap(),
+                )
+            };
+            if !matches!(b2BodyType::from(body.GetType()), b2BodyType::Dynamic) {
+                continue;
+            }
+            for child in 0..ffi_child_count(shape) {
+                let output = ffi_distance(
+                    (center_shape.as_ffi(), 0),
+                    (self.center, 0.),
+                    (shape, child),
+                    (to_Vec2(body.GetPosition()), body.GetAngle()),
+                );
+                let closest = closest_points
+                    .entry(hit.body_entity)
+                    .or_insert((output.point_b, output.distance));
+                if output.distance < closest.1 {
+                    *closest = (output.point_b, output.distance);
+                }
+            }
+        }
+
+        let mut closest_points: Vec<_> = closest_points.into_iter().collect();
+        closest_points.sort_by_key(|(body_entity, _)| *body_entity);
+        let mut pushed_bodies = Vec::new();
+        for (body_entity, (point, distance)) in closest_points {
+            if self.occlusion && self.is_occluded(world, body_entity, point) {
+                continue;
+            }
+            let Some(body_ptr) = world.get_body_ptr_mut(body_entity) else {
+                continue;
+            };
+            // The center can be inside the body, then it is pushed away from its center of mass
+            let direction = (point - self.center).try_normalize().unwrap_or_else(|| {
+                (to_Vec2(body_ptr.as_ref().GetWorldCenter()) - self.center).normalize_or_zero()
+            });
+            let impulse = direction * self.impulse * self.falloff.factor(distance, self.radius);
+            body_ptr
+                .as_mut()
+                .ApplyLinearImpulse(&to_b2Vec2(&impulse), &to_b2Vec2(&point), true);
+            pushed_bodies.push(body_entity);
+        }
+
+        if self.particle_impulse != 0. {
+            self.apply_to_particles(world);
+        }
+        pushed_bodies
+    }
+
+    fn is_occluded(&self, world: &b2World, body_entity: Entity, point: Vec2) -> bool {
+        let filter = b2RayCastFilter::excluding_bodies([body_entity]).without_sensors();
+        world
+            .ray_cast_with_filter(b2RayCastAny::new(), &self.center, &point, &filter)
+            .is_some()
+    }
+
+    fn apply_to_particles(&self, world: &mut b2World) {
+        let area = b2Shape::Circle {
+            radius: self.radius,
+            position: self.center,
+        };
+        for particle_system_entity in world.get_particle_system_entities() {
+            let indices =
+                world.query_particles_shape(particle_system_entity, &area, Vec2::ZERO, 0.);
+            let Some(particle_system_ptr) =
+                world.get_particle_system_ptr_mut(&particle_system_entity)
+            else {
+                continue;
+            };
+            let positions = particle_system_ptr.as_mut().GetPositionBuffer();
+            for index in indices {
+                let position = unsafe { to_Vec2(positions.add(index as usize).as_ref().unwrap()) };
+                let offset = position - self.center;
+                let impulse = offset.normalize_or_zero()
+                    * self.particle_impulse
+                    * self.falloff.factor(offset.length(), self.radius);
+                particle_system_ptr
+                    .as_mut()
+                    .ParticleApplyLinearImpulse(index.into(), &to_b2Vec2(&impulse));
+            }
+        }
+    }
+}
+
+/// Applies an [`Explosion`] right before the next physics step.
+#[derive(Event, Debug, Clone)]
+pub struct ExplosionEvent(pub Explosion);
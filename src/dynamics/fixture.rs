@@ -1,17 +1,39 @@
 use crate::collision::b2Shape;
+use crate::dynamics::b2World;
 use autocxx::WithinBox;
-use bevy::prelude::{Component, Entity};
+use bevy::ecs::entity::{EntityMapper, MapEntities};
+use bevy::ecs::reflect::ReflectMapEntities;
+use bevy::prelude::{Component, Entity, FromWorld, Reflect, ReflectComponent, Vec2, World};
 use bevy::utils::default;
+#[cfg(feature = "bevy-inspector-egui")]
+use bevy_inspector_egui::prelude::*;
 use libliquidfun_sys::box2d::ffi;
+use libliquidfun_sys::box2d::ffi::{int16, uint16};
 use std::pin::Pin;
 
+/// A fixture attached to a body on another entity, created with
+/// `b2Fixture::new(body_entity, &fixture_def)` on its own entity.
+///
+/// To keep the fixtures on the body entity itself, use [`b2Fixtures`] instead.
 #[allow(non_camel_case_types)]
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Reflect)]
+#[cfg_attr(
+    feature = "bevy-inspector-egui",
+    derive(InspectorOptions),
+    reflect(InspectorOptions)
+)]
+#[reflect(Component, MapEntities)]
 pub struct b2Fixture {
     body: Entity,
     shape: b2Shape,
-    density: f32,
-    friction: f32,
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
+    pub density: f32,
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0, max = 1.0))]
+    pub friction: f32,
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0, max = 1.0))]
+    pub restitution: f32,
+    pub is_sensor: bool,
+    pub filter: b2Filter,
 }
 
 impl b2Fixture {
@@ -21,6 +43,9 @@ impl b2Fixture {
             shape: fixture_def.shape.clone(),
             density: fixture_def.density,
             friction: fixture_def.friction,
+            restitution: fixture_def.restitution,
+            is_sensor: fixture_def.is_sensor,
+            filter: fixture_def.filter,
         }
     }
 
@@ -32,21 +57,78 @@ impl b2Fixture {
         &self.shape
     }
 
+    pub(crate) fn transform_shape(&mut self, translation: Vec2, angle: f32) {
+        self.shape = self.shape.transformed(translation, angle);
+    }
+
     pub(crate) fn extract_fixture_def(&self) -> b2FixtureDef {
         b2FixtureDef {
             shape: self.shape.clone(),
             density: self.density,
             friction: self.friction,
+            restitution: self.restitution,
+            is_sensor: self.is_sensor,
+            filter: self.filter,
         }
     }
+
+    pub(crate) fn sync_to_world(&self, entity: Entity, world: &mut b2World) {
+        // Fixtures with an invalid shape are never created in the physics world
+        let Some(fixture_ptr) = world.get_fixture_ptr_mut(&entity) else {
+            return;
+        };
+        fixture_ptr.as_mut().SetFriction(self.friction);
+        fixture_ptr.as_mut().SetRestitution(self.restitution);
+        fixture_ptr.as_mut().SetSensor(self.is_sensor);
+        // Setting the filter flags every contact of the fixture for refiltering, so it is only
+        // done when the filter actually changed rather than with every other property
+        if b2Filter::from_ffi(fixture_ptr.as_ref().GetFilterData()) != self.filter {
+            fixture_ptr.as_mut().SetFilterData(&self.filter.to_ffi());
+        }
+
+        let density_changed = fixture_ptr.as_ref().GetDensity() != self.density;
+        if density_changed {
+            fixture_ptr.as_mut().SetDensity(self.density);
+
+            // Box2D doesn't recompute the body mass when the fixture density changes
+            if let Some(body_ptr) = world.get_body_ptr_mut(self.body) {
+                body_ptr.as_mut().ResetMassData();
+            }
+        }
+    }
+}
+
+impl FromWorld for b2Fixture {
+    /// Only used by reflection, the body is filled in when a scene is loaded.
+    fn from_world(_world: &mut World) -> Self {
+        Self::new(Entity::PLACEHOLDER, &b2FixtureDef::default())
+    }
+}
+
+impl MapEntities for b2Fixture {
+    fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+        self.body = entity_mapper.get_or_reserve(self.body);
+    }
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(
+    feature = "bevy-inspector-egui",
+    derive(InspectorOptions),
+    reflect(InspectorOptions)
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct b2FixtureDef {
     pub shape: b2Shape,
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
     pub density: f32,
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0, max = 1.0))]
     pub friction: f32,
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0, max = 1.0))]
+    pub restitution: f32,
+    pub is_sensor: bool,
+    pub filter: b2Filter,
 }
 
 impl b2FixtureDef {
@@ -58,10 +140,15 @@ impl b2FixtureDef {
         }
     }
 
-    pub(crate) fn to_ffi(&self) -> Pin<Box<ffi::b2FixtureDef>> {
+    /// The definition points to `shape`, which has to outlive it.
+    pub(crate) fn to_ffi(&self, shape: &ffi::b2Shape) -> Pin<Box<ffi::b2FixtureDef>> {
         let mut b2fixture_def = ffi::b2FixtureDef::new().within_box();
         b2fixture_def.density = self.density;
-        b2fixture_def.shape = self.shape.to_ffi();
+        b2fixture_def.friction = self.friction;
+        b2fixture_def.restitution = self.restitution;
+        b2fixture_def.isSensor = self.is_sensor;
+        b2fixture_def.filter = self.filter.to_ffi();
+        b2fixture_def.shape = shape;
 
         return b2fixture_def;
     }
@@ -73,6 +160,252 @@ impl Default for b2FixtureDef {
             shape: b2Shape::default(),
             density: 0.,
             friction: 0.,
+            restitution: 0.,
+            is_sensor: false,
+            filter: b2Filter::default(),
         }
     }
 }
+
+/// Multiple fixtures attached to the body on the same entity, as an alternative to spawning a
+/// separate entity with a [`b2Fixture`] for each of them. Fixtures are identified by their index.
+/// Changes made after the fixtures have been created are not synced to the physics world.
+#[allow(non_camel_case_types)]
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component)]
+pub struct b2Fixtures {
+    defs: Vec<b2FixtureDef>,
+}
+
+impl b2Fixtures {
+    pub fn new(defs: impl IntoIterator<Item = b2FixtureDef>) -> Self {
+        Self {
+            defs: defs.into_iter().collect(),
+        }
+    }
+
+    pub fn defs(&self) -> &[b2FixtureDef] {
+        &self.defs
+    }
+
+    pub fn get(&self, index: usize) -> Option<&b2FixtureDef> {
+        self.defs.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.defs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.defs.is_empty()
+    }
+}
+
+/// Collision filtering data. Two fixtures collide if they share a positive group index, or if
+/// each fixture's category bits overlap with the other's mask bits and they don't share a
+/// negative group index.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct b2Filter {
+    /// The collision category bits. Normally you would just set one bit.
+    pub category_bits: u16,
+
+    /// The collision mask bits. This states the categories that this
+    /// shape would accept for collision.
+    pub mask_bits: u16,
+
+    /// Collision groups allow a certain group of objects to never collide (negative)
+    /// or always collide (positive). Zero means no collision group. Non-zero group
+    /// filtering always wins against the mask bits.
+    pub group_index: i16,
+}
+
+impl Default for b2Filter {
+    fn default() -> Self {
+        Self {
+            category_bits: 0x0001,
+            mask_bits: 0xFFFF,
+            group_index: 0,
+        }
+    }
+}
+
+impl b2Filter {
+    /// Collides with nothing.
+    pub const NONE: b2Filter = b2Filter {
+        category_bits: 0,
+        mask_bits: 0,
+        group_index: 0,
+    };
+
+    /// Belongs to all categories and collides with all of them.
+    pub const ALL: b2Filter = b2Filter {
+        category_bits: 0xFFFF,
+        mask_bits: 0xFFFF,
+        group_index: 0,
+    };
+
+    /// A filter for a fixture that belongs to the `memberships` layers and collides with the
+    /// fixtures in the `filters` layers.
+    pub fn from_layers<L: PhysicsLayer>(
+        memberships: impl IntoIterator<Item = L>,
+        filters: impl IntoIterator<Item = L>,
+    ) -> Self {
+        Self {
+            category_bits: layer_bits(memberships),
+            mask_bits: layer_bits(filters),
+            group_index: 0,
+        }
+    }
+
+    /// Whether fixtures with these filters collide, following the same rules as Box2D: a shared
+    /// non-zero group index decides on its own, otherwise the category bits of each filter have
+    /// to overlap with the mask bits of the other.
+    pub fn interacts_with(&self, other: &b2Filter) -> bool {
+        if self.group_index == other.group_index && self.group_index != 0 {
+            return self.group_index > 0;
+        }
+        self.mask_bits & other.category_bits != 0 && self.category_bits & other.mask_bits != 0
+    }
+
+    pub(crate) fn from_ffi(filter: &ffi::b2Filter) -> Self {
+        Self {
+            category_bits: u16::from(filter.categoryBits),
+            mask_bits: u16::from(filter.maskBits),
+            group_index: i16::from(filter.groupIndex),
+        }
+    }
+
+    pub(crate) fn to_ffi(&self) -> ffi::b2Filter {
+        ffi::b2Filter {
+            categoryBits: uint16::from(self.category_bits),
+            maskBits: uint16::from(self.mask_bits),
+            groupIndex: int16::from(self.group_index),
+        }
+    }
+}
+
+/// A collision layer, usually implemented for a fieldless enum, to build [`b2Filter`]s without
+/// handling the bits directly. Every layer maps to one of the 16 category bits.
+pub trait PhysicsLayer: Copy {
+    /// The index of the category bit of the layer, below 16.
+    fn index(self) -> u8;
+
+    fn bits(self) -> u16 {
+        debug_assert!(self.index() < 16, "Box2D only has 16 collision categories");
+        1 << self.index()
+    }
+}
+
+/// The category bits of all the given layers combined.
+pub fn layer_bits<L: PhysicsLayer>(layers: impl IntoIterator<Item = L>) -> u16 {
+    layers
+        .into_iter()
+        .fold(0, |bits, layer| bits | layer.bits())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Copy, Clone)]
+    enum Layer {
+        Ground,
+        Player,
+        Enemy,
+        Sensor,
+    }
+
+    impl PhysicsLayer for Layer {
+        fn index(self) -> u8 {
+            self as u8
+        }
+    }
+
+    fn assert_symmetric(a: &b2Filter, b: &b2Filter, expected: bool) {
+        assert_eq!(a.interacts_with(b), expected, "{:?} with {:?}", a, b);
+        assert_eq!(b.interacts_with(a), expected, "{:?} with {:?}", b, a);
+    }
+
+    #[test]
+    fn layers_map_to_one_bit_each() {
+        assert_eq!(layer_bits([Layer::Ground]), 0b0001);
+        assert_eq!(layer_bits([Layer::Sensor]), 0b1000);
+        assert_eq!(layer_bits([Layer::Player, Layer::Enemy]), 0b0110);
+        assert_eq!(layer_bits([Layer::Enemy, Layer::Enemy]), 0b0100);
+        assert_eq!(layer_bits::<Layer>([]), 0);
+    }
+
+    #[test]
+    fn filters_from_layers() {
+        let filter = b2Filter::from_layers([Layer::Player], [Layer::Ground, Layer::Enemy]);
+        assert_eq!(
+            filter,
+            b2Filter {
+                category_bits: 0b0010,
+                mask_bits: 0b0101,
+                group_index: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn both_masks_have_to_accept_the_other_category() {
+        let ground = b2Filter::from_layers([Layer::Ground], [Layer::Player, Layer::Enemy]);
+        let player = b2Filter::from_layers([Layer::Player], [Layer::Ground, Layer::Enemy]);
+        let enemy = b2Filter::from_layers([Layer::Enemy], [Layer::Ground]);
+        let sensor = b2Filter::from_layers([Layer::Sensor], [Layer::Player]);
+
+        assert_symmetric(&ground, &player, true);
+        assert_symmetric(&ground, &enemy, true);
+        // The player accepts enemies, but enemies don't accept players
+        assert_symmetric(&player, &enemy, false);
+        assert_symmetric(&sensor, &player, false);
+        assert_symmetric(&sensor, &ground, false);
+        assert_symmetric(&enemy, &enemy, false);
+    }
+
+    #[test]
+    fn default_none_and_all_filters() {
+        let default = b2Filter::default();
+        assert_symmetric(&default, &default, true);
+        assert_symmetric(&b2Filter::ALL, &default, true);
+        assert_symmetric(&b2Filter::NONE, &default, false);
+        assert_symmetric(&b2Filter::NONE, &b2Filter::ALL, false);
+    }
+
+    #[test]
+    fn group_index_overrides_the_layers() {
+        // Players don't accept each other through the bits, but a shared positive group wins
+        let player = b2Filter::from_layers([Layer::Player], [Layer::Ground]);
+        assert_symmetric(&player, &player, false);
+        let grouped = b2Filter {
+            group_index: 1,
+            ..player
+        };
+        assert_symmetric(&grouped, &grouped, true);
+
+        let ungrouped = b2Filter {
+            group_index: -1,
+            ..b2Filter::default()
+        };
+        assert_symmetric(&ungrouped, &ungrouped, false);
+        // Different negative groups fall back to the bits
+        let other_group = b2Filter {
+            group_index: -2,
+            ..b2Filter::default()
+        };
+        assert_symmetric(&ungrouped, &other_group, true);
+    }
+
+    #[test]
+    fn filters_survive_the_ffi_round_trip() {
+        let filter = b2Filter {
+            category_bits: 0x8001,
+            mask_bits: 0xFFFE,
+            group_index: -3,
+        };
+        assert_eq!(b2Filter::from_ffi(&filter.to_ffi()), filter);
+    }
+}
@@ -2,14 +2,27 @@ use std::pin::Pin;
 
 use bevy::ecs::system::EntityCommand;
 use bevy::math::Vec2;
-use bevy::prelude::{Component, Entity, World};
+use bevy::prelude::{Component, Entity, Reflect, ReflectComponent, World};
 use libliquidfun_sys::box2d::ffi;
 
-use crate::dynamics::{b2Joint, b2JointType, b2World, JointPtr};
+use crate::collision::LINEAR_SLOP;
+use crate::dynamics::{
+    b2Joint, b2JointReactions, b2JointType, b2World, JointPtr, SyncJointFromWorld,
+    SyncJointToWorld, ToJointPtr,
+};
 use crate::internal::to_b2Vec2;
+#[cfg(feature = "bevy-inspector-egui")]
+use bevy_inspector_egui::prelude::*;
 
 #[allow(non_camel_case_types)]
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Reflect)]
+#[cfg_attr(
+    feature = "bevy-inspector-egui",
+    derive(InspectorOptions),
+    reflect(InspectorOptions)
+)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct b2DistanceJoint {
     /// The local anchor point relative to bodyA's origin.
     local_anchor_a: Vec2,
@@ -18,19 +31,27 @@ pub struct b2DistanceJoint {
     local_anchor_b: Vec2,
 
     /// The minimum distance between the two anchors.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
     pub min_length: f32,
 
     /// The maximum distance between the two anchors.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
     pub max_length: f32,
 
     /// The linear stiffness in N/m.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
     pub stiffness: f32,
 
     /// The linear damping in N*s/m.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
     pub damping: f32,
 
     /// The rest length that the joint targets.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
     pub length: f32,
+
+    /// The current distance between the two anchors, read back from the physics world.
+    current_length: f32,
 }
 
 impl b2DistanceJoint {
@@ -43,16 +64,39 @@ impl b2DistanceJoint {
             stiffness: def.stiffness,
             damping: def.damping,
             length: def.length,
+            current_length: 0.,
         }
     }
 
-    pub(crate) fn create_ffi_joint<'a>(
+    pub fn current_length(&self) -> f32 {
+        self.current_length
+    }
+
+    /// The lengths as Box2D accepts them: at least [`LINEAR_SLOP`], with the maximum length no
+    /// smaller than the minimum one and the rest length in between.
+    pub fn clamped_lengths(&self) -> (f32, f32, f32) {
+        let min_length = self.min_length.max(LINEAR_SLOP);
+        let max_length = self.max_length.max(min_length);
+        let length = self.length.clamp(min_length, max_length);
+        (min_length, length, max_length)
+    }
+}
+
+impl Default for b2DistanceJoint {
+    fn default() -> Self {
+        Self::new(&b2DistanceJointDef::default())
+    }
+}
+
+impl ToJointPtr for b2DistanceJoint {
+    fn create_ffi_joint<'a>(
         &self,
         b2_world: &mut b2World,
         body_a: Entity,
         body_b: Entity,
         collide_connected: bool,
     ) -> JointPtr<'a> {
+        let (min_length, length, max_length) = self.clamped_lengths();
         unsafe {
             let body_a = b2_world.get_body_ptr_mut(body_a).unwrap().as_mut();
             let body_a = body_a.get_unchecked_mut() as *mut ffi::b2Body;
@@ -66,9 +110,9 @@ impl b2DistanceJoint {
                 collide_connected,
                 to_b2Vec2(&self.local_anchor_a),
                 to_b2Vec2(&self.local_anchor_b),
-                self.length,
-                self.min_length,
-                self.max_length,
+                length,
+                min_length,
+                max_length,
                 self.stiffness,
                 self.damping,
             );
@@ -76,13 +120,29 @@ impl b2DistanceJoint {
             JointPtr::Distance(ffi_joint)
         }
     }
+}
 
-    pub(crate) fn sync_to_world(&self, mut joint_ptr: Pin<&mut ffi::b2DistanceJoint>) {
-        joint_ptr.as_mut().SetLength(self.length);
-        joint_ptr.as_mut().SetMinLength(self.min_length);
-        joint_ptr.as_mut().SetMaxLength(self.max_length);
-        joint_ptr.as_mut().SetStiffness(self.stiffness);
-        joint_ptr.as_mut().SetDamping(self.damping);
+impl SyncJointToWorld for b2DistanceJoint {
+    fn sync_to_world(&self, joint_ptr: &mut JointPtr) {
+        if let JointPtr::Distance(joint_ptr) = joint_ptr {
+            let (min_length, length, max_length) = self.clamped_lengths();
+            // Box2D clamps each bound against the other one, so the minimum length is set again
+            // in case it was above the previous maximum length
+            joint_ptr.as_mut().SetMinLength(min_length);
+            joint_ptr.as_mut().SetMaxLength(max_length);
+            joint_ptr.as_mut().SetMinLength(min_length);
+            joint_ptr.as_mut().SetLength(length);
+            joint_ptr.as_mut().SetStiffness(self.stiffness);
+            joint_ptr.as_mut().SetDamping(self.damping);
+        }
+    }
+}
+
+impl SyncJointFromWorld for b2DistanceJoint {
+    fn sync_from_world(&mut self, joint_ptr: &JointPtr, _inv_dt: f32) {
+        if let JointPtr::Distance(joint_ptr) = joint_ptr {
+            self.current_length = joint_ptr.as_ref().GetCurrentLength();
+        }
     }
 }
 
@@ -130,6 +190,8 @@ impl EntityCommand for CreateDistanceJoint {
             self.collide_connected,
         );
         let distance_joint = b2DistanceJoint::new(&self.def);
-        world.entity_mut(id).insert((joint, distance_joint));
+        world
+            .entity_mut(id)
+            .insert((joint, b2JointReactions::default(), distance_joint));
     }
 }
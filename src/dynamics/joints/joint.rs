@@ -1,10 +1,17 @@
 use std::pin::Pin;
 
-use bevy::prelude::{Component, Entity};
+use bevy::ecs::entity::{EntityMapper, MapEntities};
+use bevy::ecs::reflect::ReflectMapEntities;
+use bevy::math::Vec2;
+use bevy::prelude::{Component, Entity, Event, FromWorld, Reflect, ReflectComponent, World};
 use libliquidfun_sys::box2d::ffi;
 
+use crate::dynamics::b2World;
+use crate::internal::to_Vec2;
+
 #[allow(non_camel_case_types)]
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component, MapEntities)]
 pub struct b2Joint {
     joint_type: b2JointType,
     body_a: Entity,
@@ -44,16 +51,67 @@ impl b2Joint {
     }
 }
 
+impl FromWorld for b2Joint {
+    /// Only used by reflection, the bodies are filled in when a scene is loaded.
+    fn from_world(_world: &mut World) -> Self {
+        Self::new(
+            b2JointType::Revolute,
+            Entity::PLACEHOLDER,
+            Entity::PLACEHOLDER,
+            false,
+        )
+    }
+}
+
+impl MapEntities for b2Joint {
+    fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+        self.body_a = entity_mapper.get_or_reserve(self.body_a);
+        self.body_b = entity_mapper.get_or_reserve(self.body_b);
+    }
+}
+
+/// Why a joint entity was despawned by the physics plugin.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum JointDestroyedReason {
+    /// The [`crate::dynamics::b2Body`] of one of the connected bodies was removed, e.g. because
+    /// its entity was despawned. Box2D destroys the joints of a body along with it.
+    BodyRemoved(Entity),
+    /// One of the connected bodies didn't exist when the joint was created.
+    MissingBody,
+}
+
+/// Sent when the physics plugin despawns a joint entity because the joint can't exist without
+/// its bodies. The joint entity is already gone by the time the event is read.
 #[allow(non_camel_case_types)]
-#[derive(Debug)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2JointDestroyedEvent {
+    pub joint: Entity,
+    pub reason: JointDestroyedReason,
+}
+
+/// The force and torque the joint applied to bodyB during the last physics step.
+/// Both are zero while the connected bodies are asleep.
+#[allow(non_camel_case_types)]
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct b2JointReactions {
+    /// The reaction force on bodyB at the joint anchor in Newtons.
+    pub force: Vec2,
+
+    /// The reaction torque on bodyB in N*m.
+    pub torque: f32,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Reflect)]
 pub enum b2JointType {
     Revolute,
     Prismatic,
     Distance, // TODO
-    _Pulley,
+    Pulley,
     Mouse,
     _Gear,
-    _Wheel,
+    Wheel,
     _Weld,
     _Friction,
     _Rope,
@@ -65,13 +123,109 @@ pub(crate) enum JointPtr<'a> {
     Revolute(Pin<&'a mut ffi::b2RevoluteJoint>),
     Prismatic(Pin<&'a mut ffi::b2PrismaticJoint>),
     Distance(Pin<&'a mut ffi::b2DistanceJoint>),
-    _Pulley,
+    Pulley(Pin<&'a mut ffi::b2PulleyJoint>),
     Mouse(Pin<&'a mut ffi::b2MouseJoint>),
     _Gear,
-    _Wheel,
+    Wheel(Pin<&'a mut ffi::b2WheelJoint>),
     _Weld,
     _Friction,
     _Rope,
     _Motor,
     _Area,
 }
+
+impl<'a> JointPtr<'a> {
+    /// The address of the joint as its `b2Joint` base class, which is how Box2D reports it in
+    /// its callbacks.
+    pub(crate) fn as_ffi_joint(&self) -> *const ffi::b2Joint {
+        match self {
+            JointPtr::Revolute(joint_ptr) => base_joint_ptr(joint_ptr),
+            JointPtr::Prismatic(joint_ptr) => base_joint_ptr(joint_ptr),
+            JointPtr::Distance(joint_ptr) => base_joint_ptr(joint_ptr),
+            JointPtr::Pulley(joint_ptr) => base_joint_ptr(joint_ptr),
+            JointPtr::Mouse(joint_ptr) => base_joint_ptr(joint_ptr),
+            JointPtr::Wheel(joint_ptr) => base_joint_ptr(joint_ptr),
+            _ => std::ptr::null(),
+        }
+    }
+
+    pub(crate) fn get_reaction_force(&self, inv_dt: f32) -> Vec2 {
+        let force = match self {
+            JointPtr::Revolute(joint_ptr) => joint_ptr.as_ref().GetReactionForce(inv_dt),
+            JointPtr::Prismatic(joint_ptr) => joint_ptr.as_ref().GetReactionForce(inv_dt),
+            JointPtr::Distance(joint_ptr) => joint_ptr.as_ref().GetReactionForce(inv_dt),
+            JointPtr::Pulley(joint_ptr) => joint_ptr.as_ref().GetReactionForce(inv_dt),
+            JointPtr::Mouse(joint_ptr) => joint_ptr.as_ref().GetReactionForce(inv_dt),
+            JointPtr::Wheel(joint_ptr) => joint_ptr.as_ref().GetReactionForce(inv_dt),
+            _ => return Vec2::ZERO,
+        };
+        to_Vec2(&force)
+    }
+
+    /// The anchor points on bodyA and bodyB in world coordinates.
+    pub(crate) fn get_anchors(&self) -> Option<(Vec2, Vec2)> {
+        let (anchor_a, anchor_b) = match self {
+            JointPtr::Revolute(joint_ptr) => (
+                joint_ptr.as_ref().GetAnchorA(),
+                joint_ptr.as_ref().GetAnchorB(),
+            ),
+            JointPtr::Prismatic(joint_ptr) => (
+                joint_ptr.as_ref().GetAnchorA(),
+                joint_ptr.as_ref().GetAnchorB(),
+            ),
+            JointPtr::Distance(joint_ptr) => (
+                joint_ptr.as_ref().GetAnchorA(),
+                joint_ptr.as_ref().GetAnchorB(),
+            ),
+            JointPtr::Pulley(joint_ptr) => (
+                joint_ptr.as_ref().GetAnchorA(),
+                joint_ptr.as_ref().GetAnchorB(),
+            ),
+            JointPtr::Mouse(joint_ptr) => (
+                joint_ptr.as_ref().GetAnchorA(),
+                joint_ptr.as_ref().GetAnchorB(),
+            ),
+            JointPtr::Wheel(joint_ptr) => (
+                joint_ptr.as_ref().GetAnchorA(),
+                joint_ptr.as_ref().GetAnchorB(),
+            ),
+            _ => return None,
+        };
+        Some((to_Vec2(&anchor_a), to_Vec2(&anchor_b)))
+    }
+
+    pub(crate) fn get_reaction_torque(&self, inv_dt: f32) -> f32 {
+        match self {
+            JointPtr::Revolute(joint_ptr) => joint_ptr.as_ref().GetReactionTorque(inv_dt),
+            JointPtr::Prismatic(joint_ptr) => joint_ptr.as_ref().GetReactionTorque(inv_dt),
+            JointPtr::Distance(joint_ptr) => joint_ptr.as_ref().GetReactionTorque(inv_dt),
+            JointPtr::Pulley(joint_ptr) => joint_ptr.as_ref().GetReactionTorque(inv_dt),
+            JointPtr::Mouse(joint_ptr) => joint_ptr.as_ref().GetReactionTorque(inv_dt),
+            JointPtr::Wheel(joint_ptr) => joint_ptr.as_ref().GetReactionTorque(inv_dt),
+            _ => 0.,
+        }
+    }
+}
+
+fn base_joint_ptr<T>(joint_ptr: &Pin<&mut T>) -> *const ffi::b2Joint {
+    let joint_ptr: *const T = joint_ptr.as_ref().get_ref();
+    joint_ptr.cast()
+}
+
+pub(crate) trait ToJointPtr {
+    fn create_ffi_joint<'a>(
+        &self,
+        b2_world: &mut b2World,
+        body_a: Entity,
+        body_b: Entity,
+        collide_connected: bool,
+    ) -> JointPtr<'a>;
+}
+
+pub(crate) trait SyncJointToWorld {
+    fn sync_to_world(&self, joint_ptr: &mut JointPtr);
+}
+
+pub(crate) trait SyncJointFromWorld {
+    fn sync_from_world(&mut self, joint_ptr: &JointPtr, inv_dt: f32);
+}
@@ -0,0 +1,180 @@
+use bevy::prelude::{Component, Entity, Event};
+
+use crate::collision::LINEAR_SLOP;
+use crate::dynamics::{b2PrismaticJoint, b2RevoluteJoint};
+
+/// Box2D's angular constraint tolerance, in radians.
+const ANGULAR_SLOP: f32 = 2. / 180. * std::f32::consts::PI;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum JointLimit {
+    Lower,
+    Upper,
+}
+
+/// Sent when a joint with [`EmitJointLimitEvents`] reaches one of its limits (`entering`), and
+/// again when it moves away from it.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2JointLimitEvent {
+    pub joint: Entity,
+    pub limit: JointLimit,
+    pub entering: bool,
+}
+
+/// Opts a [`b2RevoluteJoint`] or [`b2PrismaticJoint`] with an enabled limit into
+/// [`b2JointLimitEvent`]s.
+///
+/// The solver lets joints overshoot their limits a little and pushes them back, so a joint
+/// counts as being at a limit within `tolerance` of it and only leaves it beyond twice the
+/// tolerance. A joint resting against its limit therefore doesn't flicker in and out of it.
+#[derive(Component, Debug, Default, Copy, Clone)]
+pub struct EmitJointLimitEvents {
+    /// In radians for revolute joints and meters for prismatic joints. Defaults to the slop of
+    /// the Box2D solver.
+    pub tolerance: Option<f32>,
+    at_limit: Option<JointLimit>,
+}
+
+impl EmitJointLimitEvents {
+    pub fn with_tolerance(tolerance: f32) -> Self {
+        Self {
+            tolerance: Some(tolerance),
+            ..Self::default()
+        }
+    }
+
+    /// The limit the joint was at after the last step.
+    pub fn at_limit(&self) -> Option<JointLimit> {
+        self.at_limit
+    }
+
+    /// Tracks the joint value read back after a step, calling `send` for every limit the joint
+    /// left or entered, in that order.
+    pub(crate) fn update(
+        &mut self,
+        limits: Option<JointLimitState>,
+        mut send: impl FnMut(JointLimit, bool),
+    ) {
+        let at_limit = limits.and_then(|limits| {
+            let tolerance = self.tolerance.unwrap_or(limits.slop);
+            // Leaving takes twice the tolerance
+            let lower_tolerance = match self.at_limit {
+                Some(JointLimit::Lower) => 2. * tolerance,
+                _ => tolerance,
+            };
+            let upper_tolerance = match self.at_limit {
+                Some(JointLimit::Upper) => 2. * tolerance,
+                _ => tolerance,
+            };
+            if limits.value <= limits.lower + lower_tolerance {
+                Some(JointLimit::Lower)
+            } else if limits.value >= limits.upper - upper_tolerance {
+                Some(JointLimit::Upper)
+            } else {
+                None
+            }
+        });
+        if at_limit == self.at_limit {
+            return;
+        }
+        if let Some(limit) = self.at_limit {
+            send(limit, false);
+        }
+        if let Some(limit) = at_limit {
+            send(limit, true);
+        }
+        self.at_limit = at_limit;
+    }
+}
+
+/// The joint value read back from the physics world, and the limits it is compared against.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct JointLimitState {
+    pub value: f32,
+    pub lower: f32,
+    pub upper: f32,
+    pub slop: f32,
+}
+
+pub(crate) trait JointLimits {
+    /// `None` while the limit is disabled.
+    fn limit_state(&self) -> Option<JointLimitState>;
+}
+
+impl JointLimits for b2RevoluteJoint {
+    fn limit_state(&self) -> Option<JointLimitState> {
+        self.enable_limit.then(|| JointLimitState {
+            value: self.joint_angle(),
+            lower: self.lower_angle,
+            upper: self.upper_angle,
+            slop: ANGULAR_SLOP,
+        })
+    }
+}
+
+impl JointLimits for b2PrismaticJoint {
+    fn limit_state(&self) -> Option<JointLimitState> {
+        self.enable_limit.then(|| JointLimitState {
+            value: self.joint_translation(),
+            lower: self.lower_translation,
+            upper: self.upper_translation,
+            slop: LINEAR_SLOP,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(value: f32) -> Option<JointLimitState> {
+        Some(JointLimitState {
+            value,
+            lower: -1.,
+            upper: 1.,
+            slop: 0.1,
+        })
+    }
+
+    fn update(emit: &mut EmitJointLimitEvents, value: f32) -> Vec<(JointLimit, bool)> {
+        let mut events = Vec::new();
+        emit.update(limits(value), |limit, entering| {
+            events.push((limit, entering))
+        });
+        events
+    }
+
+    #[test]
+    fn enters_within_the_tolerance_and_leaves_beyond_twice_of_it() {
+        let mut emit = EmitJointLimitEvents::default();
+        assert!(update(&mut emit, 0.).is_empty());
+        assert!(update(&mut emit, -0.85).is_empty());
+        assert_eq!(update(&mut emit, -0.95), [(JointLimit::Lower, true)]);
+        // Bouncing off the limit within twice the tolerance doesn't leave it
+        assert!(update(&mut emit, -0.85).is_empty());
+        assert!(update(&mut emit, -1.02).is_empty());
+        assert_eq!(emit.at_limit(), Some(JointLimit::Lower));
+        assert_eq!(update(&mut emit, -0.75), [(JointLimit::Lower, false)]);
+        assert_eq!(emit.at_limit(), None);
+    }
+
+    #[test]
+    fn moving_from_one_limit_to_the_other_leaves_before_entering() {
+        let mut emit = EmitJointLimitEvents::with_tolerance(0.05);
+        assert_eq!(update(&mut emit, 1.), [(JointLimit::Upper, true)]);
+        assert_eq!(
+            update(&mut emit, -1.),
+            [(JointLimit::Upper, false), (JointLimit::Lower, true)]
+        );
+    }
+
+    #[test]
+    fn disabling_the_limit_leaves_it() {
+        let mut emit = EmitJointLimitEvents::default();
+        assert_eq!(update(&mut emit, -1.), [(JointLimit::Lower, true)]);
+        let mut events = Vec::new();
+        emit.update(None, |limit, entering| events.push((limit, entering)));
+        assert_eq!(events, [(JointLimit::Lower, false)]);
+    }
+}
@@ -2,14 +2,25 @@ use std::pin::Pin;
 
 use bevy::ecs::system::EntityCommand;
 use bevy::math::Vec2;
-use bevy::prelude::{Component, Entity, World};
+use bevy::prelude::{Component, Entity, Reflect, ReflectComponent, World};
 use libliquidfun_sys::box2d::ffi;
 
-use crate::dynamics::{b2Joint, b2JointType, b2World, JointPtr};
+use crate::dynamics::{
+    b2Joint, b2JointReactions, b2JointType, b2World, JointPtr, SyncJointToWorld, ToJointPtr,
+};
 use crate::internal::to_b2Vec2;
+#[cfg(feature = "bevy-inspector-egui")]
+use bevy_inspector_egui::prelude::*;
 
 #[allow(non_camel_case_types)]
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Reflect)]
+#[cfg_attr(
+    feature = "bevy-inspector-egui",
+    derive(InspectorOptions),
+    reflect(InspectorOptions)
+)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct b2MouseJoint {
     /// The local anchor point relative to bodyA's origin. This should typically be on a "ground body" at (0, 0) and can thus be considered world coordinates.
     anchor_a: Vec2,
@@ -21,12 +32,15 @@ pub struct b2MouseJoint {
     pub target: Vec2,
 
     /// The maximum force in Newtons.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
     pub max_force: f32,
 
     /// The linear stiffness in N/m.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
     pub stiffness: f32,
 
     /// The linear damping in N*s/m.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
     pub damping: f32,
 }
 
@@ -41,8 +55,16 @@ impl b2MouseJoint {
             damping: def.damping,
         }
     }
+}
+
+impl Default for b2MouseJoint {
+    fn default() -> Self {
+        Self::new(&b2MouseJointDef::default())
+    }
+}
 
-    pub(crate) fn create_ffi_joint<'a>(
+impl ToJointPtr for b2MouseJoint {
+    fn create_ffi_joint<'a>(
         &self,
         b2_world: &mut b2World,
         body_a: Entity,
@@ -69,9 +91,13 @@ impl b2MouseJoint {
             JointPtr::Mouse(ffi_joint)
         }
     }
+}
 
-    pub(crate) fn sync_to_world(&self, mut joint_ptr: Pin<&mut ffi::b2MouseJoint>) {
-        joint_ptr.as_mut()..SetTarget(to_b2Vec2(&self.target));
+impl SyncJointToWorld for b2MouseJoint {
+    fn sync_to_world(&self, joint_ptr: &mut JointPtr) {
+        if let JointPtr::Mouse(joint_ptr) = joint_ptr {
+            joint_ptr.as_mut().SetTarget(&to_b2Vec2(&self.target));
+        }
     }
 }
 
@@ -118,6 +144,8 @@ impl EntityCommand for CreateMouseJoint {
             self.collide_connected,
         );
         let mouse_joint = b2MouseJoint::new(&self.def);
-        world.entity_mut(id).insert((joint, mouse_joint));
+        world
+            .entity_mut(id)
+            .insert((joint, b2JointReactions::default(), mouse_joint));
     }
 }
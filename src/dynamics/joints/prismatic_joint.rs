@@ -1,12 +1,24 @@
-use crate::dynamics::{b2Joint, b2JointType, b2World, JointPtr};
+use crate::dynamics::{
+    b2Joint, b2JointReactions, b2JointType, b2World, JointPtr, SyncJointFromWorld,
+    SyncJointToWorld, ToJointPtr,
+};
 use crate::internal::to_b2Vec2;
 use bevy::ecs::system::EntityCommand;
 use bevy::prelude::*;
+#[cfg(feature = "bevy-inspector-egui")]
+use bevy_inspector_egui::prelude::*;
 use libliquidfun_sys::box2d::ffi;
 use std::pin::Pin;
 
 #[allow(non_camel_case_types)]
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Reflect)]
+#[cfg_attr(
+    feature = "bevy-inspector-egui",
+    derive(InspectorOptions),
+    reflect(InspectorOptions)
+)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct b2PrismaticJoint {
     /// The local anchor point relative to bodyA's origin.
     local_anchor_a: Vec2,
@@ -32,11 +44,22 @@ pub struct b2PrismaticJoint {
     /// Enable/disable the joint motor.
     pub enable_motor: bool,
 
-    /// The maximum motor torque, usually in N-m.
+    /// The maximum motor force, usually in N.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
     pub max_motor_force: f32,
 
-    /// The desired motor speed in radians per second.
+    /// The desired motor speed, usually in meters per second.
     pub motor_speed: f32,
+
+    /// The current joint translation, usually in meters, read back from the physics world.
+    joint_translation: f32,
+
+    /// The current joint translation speed, usually in meters per second, read back from the
+    /// physics world.
+    joint_speed: f32,
+
+    /// The motor force applied during the last step in N, read back from the physics world.
+    motor_force: f32,
 }
 
 impl b2PrismaticJoint {
@@ -52,10 +75,33 @@ impl b2PrismaticJoint {
             enable_motor: def.enable_motor,
             max_motor_force: def.max_motor_force,
             motor_speed: def.motor_speed,
+            joint_translation: 0.,
+            joint_speed: 0.,
+            motor_force: 0.,
         }
     }
 
-    pub(crate) fn create_ffi_joint<'a>(
+    pub fn joint_translation(&self) -> f32 {
+        self.joint_translation
+    }
+
+    pub fn joint_speed(&self) -> f32 {
+        self.joint_speed
+    }
+
+    pub fn motor_force(&self) -> f32 {
+        self.motor_force
+    }
+}
+
+impl Default for b2PrismaticJoint {
+    fn default() -> Self {
+        Self::new(&b2PrismaticJointDef::default())
+    }
+}
+
+impl ToJointPtr for b2PrismaticJoint {
+    fn create_ffi_joint<'a>(
         &self,
         b2_world: &mut b2World,
         body_a: Entity,
@@ -88,15 +134,29 @@ impl b2PrismaticJoint {
             JointPtr::Prismatic(ffi_joint)
         }
     }
+}
 
-    pub(crate) fn sync_to_world(&self, mut joint_ptr: Pin<&mut ffi::b2PrismaticJoint>) {
-        joint_ptr.as_mut().EnableLimit(self.enable_limit);
-        joint_ptr
-            .as_mut()
-            .SetLimits(self.lower_translation, self.upper_translation);
-        joint_ptr.as_mut().EnableMotor(self.enable_motor);
-        joint_ptr.as_mut().SetMaxMotorForce(self.max_motor_force);
-        joint_ptr.as_mut().SetMotorSpeed(self.motor_speed);
+impl SyncJointToWorld for b2PrismaticJoint {
+    fn sync_to_world(&self, joint_ptr: &mut JointPtr) {
+        if let JointPtr::Prismatic(joint_ptr) = joint_ptr {
+            joint_ptr.as_mut().EnableLimit(self.enable_limit);
+            joint_ptr
+                .as_mut()
+                .SetLimits(self.lower_translation, self.upper_translation);
+            joint_ptr.as_mut().EnableMotor(self.enable_motor);
+            joint_ptr.as_mut().SetMaxMotorForce(self.max_motor_force);
+            joint_ptr.as_mut().SetMotorSpeed(self.motor_speed);
+        }
+    }
+}
+
+impl SyncJointFromWorld for b2PrismaticJoint {
+    fn sync_from_world(&mut self, joint_ptr: &JointPtr, inv_dt: f32) {
+        if let JointPtr::Prismatic(joint_ptr) = joint_ptr {
+            self.joint_translation = joint_ptr.as_ref().GetJointTranslation();
+            self.joint_speed = joint_ptr.as_ref().GetJointSpeed();
+            self.motor_force = joint_ptr.as_ref().GetMotorForce(inv_dt);
+        }
     }
 }
 
@@ -127,10 +187,10 @@ pub struct b2PrismaticJointDef {
     /// Enable/disable the joint motor.
     pub enable_motor: bool,
 
-    /// The maximum motor torque, usually in N-m.
+    /// The maximum motor force, usually in N.
     pub max_motor_force: f32,
 
-    /// The desired motor speed in radians per second.
+    /// The desired motor speed, usually in meters per second.
     pub motor_speed: f32,
 }
 
@@ -166,6 +226,8 @@ impl EntityCommand for CreatePrismaticJoint {
             self.collide_connected,
         );
         let prismatic_joint = b2PrismaticJoint::new(&self.def);
-        world.entity_mut(id).insert((joint, prismatic_joint));
+        world
+            .entity_mut(id)
+            .insert((joint, b2JointReactions::default(), prismatic_joint));
     }
 }
@@ -2,14 +2,26 @@ use std::pin::Pin;
 
 use bevy::ecs::system::EntityCommand;
 use bevy::math::Vec2;
-use bevy::prelude::{Component, Entity, World};
+use bevy::prelude::{Component, Entity, Reflect, ReflectComponent, World};
 use libliquidfun_sys::box2d::ffi;
 
-use crate::dynamics::{b2Joint, b2JointType, b2World, JointPtr};
+use crate::dynamics::{
+    b2Joint, b2JointReactions, b2JointType, b2World, JointPtr, SyncJointFromWorld,
+    SyncJointToWorld, ToJointPtr,
+};
 use crate::internal::to_b2Vec2;
+#[cfg(feature = "bevy-inspector-egui")]
+use bevy_inspector_egui::prelude::*;
 
 #[allow(non_camel_case_types)]
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Reflect)]
+#[cfg_attr(
+    feature = "bevy-inspector-egui",
+    derive(InspectorOptions),
+    reflect(InspectorOptions)
+)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct b2RevoluteJoint {
     /// The local anchor point relative to bodyA's origin.
     local_anchor_a: Vec2,
@@ -37,7 +49,17 @@ pub struct b2RevoluteJoint {
 
     /// The maximum motor torque used to achieve the desired motor speed.
     /// Usually in N-m.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
     pub max_motor_torque: f32,
+
+    /// The current joint angle in radians, read back from the physics world.
+    joint_angle: f32,
+
+    /// The current joint angular speed in radians per second, read back from the physics world.
+    joint_speed: f32,
+
+    /// The motor torque applied during the last step in N-m, read back from the physics world.
+    motor_torque: f32,
 }
 
 impl b2RevoluteJoint {
@@ -52,10 +74,33 @@ impl b2RevoluteJoint {
             enable_motor: def.enable_motor,
             motor_speed: def.motor_speed,
             max_motor_torque: def.max_motor_torque,
+            joint_angle: 0.,
+            joint_speed: 0.,
+            motor_torque: 0.,
         }
     }
 
-    pub(crate) fn create_ffi_joint<'a>(
+    pub fn joint_angle(&self) -> f32 {
+        self.joint_angle
+    }
+
+    pub fn joint_speed(&self) -> f32 {
+        self.joint_speed
+    }
+
+    pub fn motor_torque(&self) -> f32 {
+        self.motor_torque
+    }
+}
+
+impl Default for b2RevoluteJoint {
+    fn default() -> Self {
+        Self::new(&b2RevoluteJointDef::default())
+    }
+}
+
+impl ToJointPtr for b2RevoluteJoint {
+    fn create_ffi_joint<'a>(
         &self,
         b2_world: &mut b2World,
         body_a: Entity,
@@ -87,15 +132,29 @@ impl b2RevoluteJoint {
             JointPtr::Revolute(ffi_joint)
         }
     }
+}
 
-    pub(crate) fn sync_to_world(&self, mut joint_ptr: Pin<&mut ffi::b2RevoluteJoint>) {
-        joint_ptr.as_mut().EnableLimit(self.enable_limit);
-        joint_ptr
-            .as_mut()
-            .SetLimits(self.lower_angle, self.upper_angle);
-        joint_ptr.as_mut().EnableMotor(self.enable_motor);
-        joint_ptr.as_mut().SetMaxMotorTorque(self.max_motor_torque);
-        joint_ptr.as_mut().SetMotorSpeed(self.motor_speed);
+impl SyncJointToWorld for b2RevoluteJoint {
+    fn sync_to_world(&self, joint_ptr: &mut JointPtr) {
+        if let JointPtr::Revolute(joint_ptr) = joint_ptr {
+            joint_ptr.as_mut().EnableLimit(self.enable_limit);
+            joint_ptr
+                .as_mut()
+                .SetLimits(self.lower_angle, self.upper_angle);
+            joint_ptr.as_mut().EnableMotor(self.enable_motor);
+            joint_ptr.as_mut().SetMaxMotorTorque(self.max_motor_torque);
+            joint_ptr.as_mut().SetMotorSpeed(self.motor_speed);
+        }
+    }
+}
+
+impl SyncJointFromWorld for b2RevoluteJoint {
+    fn sync_from_world(&mut self, joint_ptr: &JointPtr, inv_dt: f32) {
+        if let JointPtr::Revolute(joint_ptr) = joint_ptr {
+            self.joint_angle = joint_ptr.as_ref().GetJointAngle();
+            self.joint_speed = joint_ptr.as_ref().GetJointSpeed();
+            self.motor_torque = joint_ptr.as_ref().GetMotorTorque(inv_dt);
+        }
     }
 }
 
@@ -145,6 +204,8 @@ impl EntityCommand for CreateRevoluteJoint {
             self.collide_connected,
         );
         let revolute_joint = b2RevoluteJoint::new(&self.def);
-        world.entity_mut(id).insert((joint, revolute_joint));
+        world
+            .entity_mut(id)
+            .insert((joint, b2JointReactions::default(), revolute_joint));
     }
 }
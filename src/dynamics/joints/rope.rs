@@ -0,0 +1,200 @@
+use bevy::ecs::system::EntityCommand;
+use bevy::prelude::*;
+
+use crate::collision::b2Shape;
+use crate::dynamics::{
+    b2Body, b2BodyBundle, b2BodyDef, b2BodyType, b2DistanceJointDef, b2Filter, b2FixtureDef,
+    b2Fixtures, b2RevoluteJointDef, CreateDistanceJoint, CreateRevoluteJoint,
+};
+
+/// The joints that connect the segments of a rope.
+#[derive(Debug, Clone)]
+pub enum RopeJointType {
+    /// Rigid joints, with the relative angle of neighboring segments optionally limited.
+    Revolute { angle_limits: Option<(f32, f32)> },
+    /// Soft joints, with the stiffness in N/m and the damping in N*s/m.
+    Distance { stiffness: f32, damping: f32 },
+}
+
+impl Default for RopeJointType {
+    fn default() -> Self {
+        RopeJointType::Revolute { angle_limits: None }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RopeDef {
+    pub segment_count: usize,
+    /// The width of the box shape of the segments.
+    pub thickness: f32,
+    pub density: f32,
+    pub friction: f32,
+    /// The fixtures of all segments share this group, a negative one keeps them from colliding
+    /// with each other.
+    pub group_index: i16,
+    pub joint_type: RopeJointType,
+}
+
+impl Default for RopeDef {
+    fn default() -> Self {
+        Self {
+            segment_count: 10,
+            thickness: 0.1,
+            density: 1.,
+            friction: 0.2,
+            group_index: -1,
+            joint_type: RopeJointType::default(),
+        }
+    }
+}
+
+/// The segments and joints of a rope spawned with [`CreateRope`], in order from the start to the
+/// end of the rope. The segments are dynamic bodies with their fixture in [`b2Fixtures`].
+#[derive(Component, Debug, Clone)]
+pub struct Rope {
+    pub segments: Vec<Entity>,
+    pub joints: Vec<Entity>,
+}
+
+/// Spawns a rope as a chain of segments between two points in world coordinates, and inserts a
+/// [`Rope`] on the entity it is applied to.
+///
+/// Each end can be attached to an existing body, which must already have a [`b2Body`].
+pub struct CreateRope {
+    start: Vec2,
+    end: Vec2,
+    start_body: Option<Entity>,
+    end_body: Option<Entity>,
+    def: RopeDef,
+}
+
+impl CreateRope {
+    pub fn new(start: Vec2, end: Vec2, def: &RopeDef) -> Self {
+        Self {
+            start,
+            end,
+            start_body: None,
+            end_body: None,
+            def: def.clone(),
+        }
+    }
+
+    pub fn with_start_body(mut self, body: Entity) -> Self {
+        self.start_body = Some(body);
+        self
+    }
+
+    pub fn with_end_body(mut self, body: Entity) -> Self {
+        self.end_body = Some(body);
+        self
+    }
+
+    fn spawn_segment(&self, world: &mut World, center: Vec2, angle: f32, length: f32) -> Entity {
+        let body_def = b2BodyDef {
+            body_type: b2BodyType::Dynamic,
+            position: center,
+            angle,
+            ..default()
+        };
+        let fixture_def = b2FixtureDef {
+            shape: b2Shape::create_box(length / 2., self.def.thickness / 2.),
+            density: self.def.density,
+            friction: self.def.friction,
+            filter: b2Filter {
+                group_index: self.def.group_index,
+                ..default()
+            },
+            ..default()
+        };
+        world
+            .spawn((b2BodyBundle::new(&body_def), b2Fixtures::new([fixture_def])))
+            .id()
+    }
+
+    /// Spawns a joint connecting both bodies, with the anchors in the local coordinates of each
+    /// of them.
+    fn spawn_joint(
+        &self,
+        world: &mut World,
+        body_a: Entity,
+        local_anchor_a: Vec2,
+        body_b: Entity,
+        local_anchor_b: Vec2,
+    ) -> Entity {
+        let joint_entity = world.spawn_empty().id();
+        match self.def.joint_type {
+            RopeJointType::Revolute { angle_limits } => {
+                let (lower_angle, upper_angle) = angle_limits.unwrap_or_default();
+                let joint_def = b2RevoluteJointDef {
+                    local_anchor_a,
+                    local_anchor_b,
+                    enable_limit: angle_limits.is_some(),
+                    lower_angle,
+                    upper_angle,
+                    ..default()
+                };
+                CreateRevoluteJoint::new(body_a, body_b, false, &joint_def)
+                    .apply(joint_entity, world);
+            }
+            RopeJointType::Distance { stiffness, damping } => {
+                let joint_def = b2DistanceJointDef {
+                    local_anchor_a,
+                    local_anchor_b,
+                    stiffness,
+                    damping,
+                    ..default()
+                };
+                CreateDistanceJoint::new(body_a, body_b, false, &joint_def)
+                    .apply(joint_entity, world);
+            }
+        }
+        joint_entity
+    }
+}
+
+impl EntityCommand for CreateRope {
+    fn apply(self, id: Entity, world: &mut World) {
+        let segment_count = self.def.segment_count.max(1);
+        let direction = self.end - self.start;
+        let length = direction.length() / segment_count as f32;
+        let angle = direction.y.atan2(direction.x);
+        let half_segment = Vec2::new(length / 2., 0.);
+
+        let segments: Vec<Entity> = (0..segment_count)
+            .map(|index| {
+                let center = self.start + direction * ((index as f32 + 0.5) / segment_count as f32);
+                self.spawn_segment(world, center, angle, length)
+            })
+            .collect();
+
+        let mut joints = Vec::new();
+        if let Some(start_body) = self.start_body {
+            let local_anchor = world
+                .get::<b2Body>(start_body)
+                .unwrap()
+                .local_point(self.start);
+            joints.push(self.spawn_joint(
+                world,
+                start_body,
+                local_anchor,
+                segments[0],
+                -half_segment,
+            ));
+        }
+        for pair in segments.windows(2) {
+            joints.push(self.spawn_joint(world, pair[0], half_segment, pair[1], -half_segment));
+        }
+        if let Some(end_body) = self.end_body {
+            let local_anchor = world.get::<b2Body>(end_body).unwrap().local_point(self.end);
+            joints.push(self.spawn_joint(
+                world,
+                *segments.last().unwrap(),
+                half_segment,
+                end_body,
+                local_anchor,
+            ));
+        }
+
+        world.entity_mut(id).insert(Rope { segments, joints });
+    }
+}
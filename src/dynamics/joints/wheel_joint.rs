@@ -0,0 +1,265 @@
+use std::pin::Pin;
+
+use bevy::ecs::system::EntityCommand;
+use bevy::math::Vec2;
+use bevy::prelude::{Component, Entity, Reflect, ReflectComponent, World};
+use libliquidfun_sys::box2d::ffi;
+
+use crate::dynamics::{
+    b2Joint, b2JointReactions, b2JointType, b2World, JointPtr, SyncJointFromWorld,
+    SyncJointToWorld, ToJointPtr,
+};
+use crate::internal::to_b2Vec2;
+#[cfg(feature = "bevy-inspector-egui")]
+use bevy_inspector_egui::prelude::*;
+
+#[allow(non_camel_case_types)]
+#[derive(Component, Debug, Clone, Reflect)]
+#[cfg_attr(
+    feature = "bevy-inspector-egui",
+    derive(InspectorOptions),
+    reflect(InspectorOptions)
+)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct b2WheelJoint {
+    /// The local anchor point relative to bodyA's origin.
+    local_anchor_a: Vec2,
+
+    /// The local anchor point relative to bodyB's origin.
+    local_anchor_b: Vec2,
+
+    /// The local translation axis in bodyA.
+    local_axis_a: Vec2,
+
+    /// Enable/disable the joint limit.
+    pub enable_limit: bool,
+
+    /// The lower translation limit, usually in meters.
+    pub lower_translation: f32,
+
+    /// The upper translation limit, usually in meters.
+    pub upper_translation: f32,
+
+    /// Enable/disable the joint motor.
+    pub enable_motor: bool,
+
+    /// The maximum motor torque, usually in N-m.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
+    pub max_motor_torque: f32,
+
+    /// The desired motor speed in radians per second.
+    pub motor_speed: f32,
+
+    /// Suspension stiffness. Typically in units N/m.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
+    pub stiffness: f32,
+
+    /// Suspension damping. Typically in units of N*s/m.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
+    pub damping: f32,
+
+    /// The current translation along the suspension axis, read back from the physics world.
+    joint_translation: f32,
+
+    /// The current translation speed along the suspension axis, read back from the physics world.
+    joint_linear_speed: f32,
+
+    /// The current rotational speed of the wheel, read back from the physics world.
+    joint_angular_speed: f32,
+}
+
+impl b2WheelJoint {
+    pub fn new(def: &b2WheelJointDef) -> Self {
+        Self {
+            local_anchor_a: def.local_anchor_a,
+            local_anchor_b: def.local_anchor_b,
+            local_axis_a: def.local_axis_a,
+            enable_limit: def.enable_limit,
+            lower_translation: def.lower_translation,
+            upper_translation: def.upper_translation,
+            enable_motor: def.enable_motor,
+            max_motor_torque: def.max_motor_torque,
+            motor_speed: def.motor_speed,
+            stiffness: def.stiffness,
+            damping: def.damping,
+            joint_translation: 0.,
+            joint_linear_speed: 0.,
+            joint_angular_speed: 0.,
+        }
+    }
+
+    pub fn joint_translation(&self) -> f32 {
+        self.joint_translation
+    }
+
+    pub fn joint_linear_speed(&self) -> f32 {
+        self.joint_linear_speed
+    }
+
+    pub fn joint_angular_speed(&self) -> f32 {
+        self.joint_angular_speed
+    }
+}
+
+impl Default for b2WheelJoint {
+    fn default() -> Self {
+        Self::new(&b2WheelJointDef::default())
+    }
+}
+
+impl ToJointPtr for b2WheelJoint {
+    fn create_ffi_joint<'a>(
+        &self,
+        b2_world: &mut b2World,
+        body_a: Entity,
+        body_b: Entity,
+        collide_connected: bool,
+    ) -> JointPtr<'a> {
+        unsafe {
+            let body_a = b2_world.get_body_ptr_mut(body_a).unwrap().as_mut();
+            let body_a = body_a.get_unchecked_mut() as *mut ffi::b2Body;
+            let body_b = b2_world.get_body_ptr_mut(body_b).unwrap().as_mut();
+            let body_b = body_b.get_unchecked_mut() as *mut ffi::b2Body;
+            let ffi_world = b2_world.get_world_ptr().as_mut();
+            let ffi_joint = ffi::CreateWheelJoint(
+                ffi_world,
+                body_a,
+                body_b,
+                collide_connected,
+                to_b2Vec2(&self.local_anchor_a),
+                to_b2Vec2(&self.local_anchor_b),
+                to_b2Vec2(&self.local_axis_a),
+                self.enable_limit,
+                self.lower_translation,
+                self.upper_translation,
+                self.enable_motor,
+                self.max_motor_torque,
+                self.motor_speed,
+                self.stiffness,
+                self.damping,
+            );
+            let ffi_joint = Pin::new_unchecked(ffi_joint.as_mut().unwrap());
+            JointPtr::Wheel(ffi_joint)
+        }
+    }
+}
+
+impl SyncJointToWorld for b2WheelJoint {
+    fn sync_to_world(&self, joint_ptr: &mut JointPtr) {
+        if let JointPtr::Wheel(joint_ptr) = joint_ptr {
+            joint_ptr.as_mut().EnableLimit(self.enable_limit);
+            joint_ptr
+                .as_mut()
+                .SetLimits(self.lower_translation, self.upper_translation);
+            joint_ptr.as_mut().EnableMotor(self.enable_motor);
+            joint_ptr.as_mut().SetMaxMotorTorque(self.max_motor_torque);
+            joint_ptr.as_mut().SetMotorSpeed(self.motor_speed);
+            joint_ptr.as_mut().SetStiffness(self.stiffness);
+            joint_ptr.as_mut().SetDamping(self.damping);
+        }
+    }
+}
+
+impl SyncJointFromWorld for b2WheelJoint {
+    fn sync_from_world(&mut self, joint_ptr: &JointPtr, _inv_dt: f32) {
+        if let JointPtr::Wheel(joint_ptr) = joint_ptr {
+            self.joint_translation = joint_ptr.as_ref().GetJointTranslation();
+            self.joint_linear_speed = joint_ptr.as_ref().GetJointLinearSpeed();
+            self.joint_angular_speed = joint_ptr.as_ref().GetJointAngularSpeed();
+        }
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub struct b2WheelJointDef {
+    /// The local anchor point relative to bodyA's origin.
+    pub local_anchor_a: Vec2,
+
+    /// The local anchor point relative to bodyB's origin.
+    pub local_anchor_b: Vec2,
+
+    /// The local translation axis in bodyA.
+    pub local_axis_a: Vec2,
+
+    /// Enable/disable the joint limit.
+    pub enable_limit: bool,
+
+    /// The lower translation limit, usually in meters.
+    pub lower_translation: f32,
+
+    /// The upper translation limit, usually in meters.
+    pub upper_translation: f32,
+
+    /// Enable/disable the joint motor.
+    pub enable_motor: bool,
+
+    /// The maximum motor torque, usually in N-m.
+    pub max_motor_torque: f32,
+
+    /// The desired motor speed in radians per second.
+    pub motor_speed: f32,
+
+    /// Suspension stiffness. Typically in units N/m.
+    pub stiffness: f32,
+
+    /// Suspension damping. Typically in units of N*s/m.
+    pub damping: f32,
+}
+
+impl Default for b2WheelJointDef {
+    fn default() -> Self {
+        Self {
+            local_anchor_a: Vec2::ZERO,
+            local_anchor_b: Vec2::ZERO,
+            local_axis_a: Vec2::Y,
+            enable_limit: false,
+            lower_translation: 0.,
+            upper_translation: 0.,
+            enable_motor: false,
+            max_motor_torque: 0.,
+            motor_speed: 0.,
+            stiffness: 0.,
+            damping: 0.,
+        }
+    }
+}
+
+pub struct CreateWheelJoint {
+    body_a: Entity,
+    body_b: Entity,
+    collide_connected: bool,
+    def: b2WheelJointDef,
+}
+
+impl CreateWheelJoint {
+    pub fn new(
+        body_a: Entity,
+        body_b: Entity,
+        collide_connected: bool,
+        def: &b2WheelJointDef,
+    ) -> Self {
+        Self {
+            body_a,
+            body_b,
+            collide_connected,
+            def: def.clone(),
+        }
+    }
+}
+
+impl EntityCommand for CreateWheelJoint {
+    fn apply(self, id: Entity, world: &mut World) {
+        let joint = b2Joint::new(
+            b2JointType::Wheel,
+            self.body_a,
+            self.body_b,
+            self.collide_connected,
+        );
+        let wheel_joint = b2WheelJoint::new(&self.def);
+        world
+            .entity_mut(id)
+            .insert((joint, b2JointReactions::default(), wheel_joint));
+    }
+}
@@ -0,0 +1,55 @@
+use std::pin::Pin;
+
+use bevy::prelude::{Component, Reflect, ReflectComponent, Vec2};
+use libliquidfun_sys::box2d::ffi;
+
+use crate::internal::{to_Vec2, to_b2Vec2};
+
+/// Bodies moving away from the platform faster than this pass through it.
+const PASS_THROUGH_SPEED: f32 = 0.5;
+
+/// Makes a fixture only collide with bodies that land on it from the `up` side, so they can
+/// jump up through it from below. Goes on the fixture entity, or on the body entity when the
+/// fixtures are created through [`crate::dynamics::b2Fixtures`].
+///
+/// The decision is made in the PreSolve callback of every step, based on the contact normal and
+/// the relative velocity at the contact point.
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct OneWayPlatform {
+    /// The solid side of the platform, in the local coordinates of the platform body.
+    pub up: Vec2,
+}
+
+impl Default for OneWayPlatform {
+    fn default() -> Self {
+        Self { up: Vec2::Y }
+    }
+}
+
+impl OneWayPlatform {
+    /// `normal` points from the platform towards the other body.
+    pub(crate) fn should_collide(
+        &self,
+        platform_body: Pin<&mut ffi::b2Body>,
+        other_body: Pin<&mut ffi::b2Body>,
+        normal: Vec2,
+        point: Option<Vec2>,
+    ) -> bool {
+        let up = to_Vec2(&platform_body.as_ref().GetWorldVector(&to_b2Vec2(&self.up)));
+        if normal.dot(up) < 0.5 {
+            return false;
+        }
+
+        let Some(point) = point else {
+            return true;
+        };
+        let point = to_b2Vec2(&point);
+        let platform_velocity = platform_body
+            .as_ref()
+            .GetLinearVelocityFromWorldPoint(&point);
+        let other_velocity = other_body.as_ref().GetLinearVelocityFromWorldPoint(&point);
+        let relative_velocity = to_Vec2(&other_velocity) - to_Vec2(&platform_velocity);
+        relative_velocity.dot(up) < PASS_THROUGH_SPEED
+    }
+}
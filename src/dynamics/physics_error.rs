@@ -0,0 +1,29 @@
+use std::error::Error;
+use std::fmt;
+
+use bevy::prelude::Entity;
+
+/// An operation on the physics world that refers to something that doesn't exist in it, e.g.
+/// because an entity was despawned in an unexpected order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PhysicsError {
+    MissingBody(Entity),
+    MissingFixture(Entity),
+    MissingJoint(Entity),
+    MissingParticleSystem(Entity),
+}
+
+impl fmt::Display for PhysicsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingBody(entity) => write!(f, "no body exists for {:?}", entity),
+            Self::MissingFixture(entity) => write!(f, "no fixture exists for {:?}", entity),
+            Self::MissingJoint(entity) => write!(f, "no joint exists for {:?}", entity),
+            Self::MissingParticleSystem(entity) => {
+                write!(f, "no particle system exists for {:?}", entity)
+            }
+        }
+    }
+}
+
+impl Error for PhysicsError {}
@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::pin::Pin;
+
+use bevy::prelude::{Entity, Vec2};
+
+use libliquidfun_sys::box2d::ffi;
+use libliquidfun_sys::box2d::ffi::b2Fixture as ffi_b2Fixture;
+use libliquidfun_sys::box2d::ffi::{b2ParticleSystem, b2QueryCallbackImpl, int32};
+
+use crate::collision::{ffi_child_count, OwnedFfiShape};
+use crate::dynamics::{layer_bits, PhysicsLayer};
+use crate::internal::{body_entity, fixture_entity, lock_box2d_globals, to_b2Vec2};
+
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub(crate) struct b2Query<T: b2QueryCallback> {
+    callback: T,
+    filter: b2QueryFilter,
+    overlap_test: Option<b2OverlapTest>,
+}
+
+impl<T: b2QueryCallback> b2Query<T> {
+    pub fn new(callback: T, filter: b2QueryFilter) -> Self {
+        Self {
+            callback,
+            filter,
+            overlap_test: None,
+        }
+    }
+
+    /// Only reports fixtures that pass the exact overlap test, rather than all the fixtures
+    /// whose bounding boxes overlap the query.
+    pub fn with_overlap_test(
+        callback: T,
+        filter: b2QueryFilter,
+        overlap_test: b2OverlapTest,
+    ) -> Self {
+        Self {
+            callback,
+            filter,
+            overlap_test: Some(overlap_test),
+        }
+    }
+
+    pub fn extract_hits(self) -> T::Result {
+        self.callback.into_result()
+    }
+}
+
+#[allow(unused_variables)]
+impl<T: b2QueryCallback> b2QueryCallbackImpl for b2Query<T> {
+    fn report_fixture(&mut self, fixture: &mut ffi_b2Fixture) -> bool {
+        let mut fixture = unsafe { Pin::new_unchecked(fixture) };
+        if !self.filter.is_sensor_allowed(fixture.as_ref().IsSensor()) {
+            return true;
+        }
+        let category_bits = u16::from(fixture.as_ref().GetFilterData().categoryBits);
+        if !self.filter.is_category_allowed(category_bits) {
+            return true;
+        }
+
+        let body_entity = unsafe {
+            let body = fixture.as_mut().GetBody().as_mut().unwrap();
+            body_entity(Pin::new_unchecked(body))
+        };
+        if self.filter.excluded_bodies.contains(&body_entity) {
+            return true;
+        }
+
+        if let Some(overlap_test) = &self.overlap_test {
+            if !overlap_test.overlaps(fixture.as_mut()) {
+                return true;
+            }
+        }
+
+        let fixture_entity = fixture_entity(fixture);
+        self.callback.report_fixture(b2QueryHit {
+            fixture_entity,
+            body_entity,
+        })
+    }
+
+    fn report_particle(&mut self, particle_system: &b2ParticleSystem, index: i32) -> bool {
+        false
+    }
+
+    fn should_query_particle_system(&mut self, particle_system: *const b2ParticleSystem) -> bool {
+        false
+    }
+}
+
+/// Collects the indices of the particles inside the queried bounding box.
+#[derive(Debug, Default)]
+#[allow(non_camel_case_types)]
+pub(crate) struct b2ParticleQuery {
+    indices: Vec<i32>,
+}
+
+impl b2ParticleQuery {
+    pub fn extract_indices(self) -> Vec<i32> {
+        self.indices
+    }
+}
+
+#[allow(unused_variables)]
+impl b2QueryCallbackImpl for b2ParticleQuery {
+    fn report_fixture(&mut self, fixture: &mut ffi_b2Fixture) -> bool {
+        true
+    }
+
+    fn report_particle(&mut self, particle_system: &b2ParticleSystem, index: i32) -> bool {
+        self.indices.push(index);
+        true
+    }
+
+    fn should_query_particle_system(&mut self, particle_system: *const b2ParticleSystem) -> bool {
+        true
+    }
+}
+
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub(crate) enum b2OverlapTest {
+    Point(Vec2),
+    Shape {
+        shape: OwnedFfiShape,
+        transform: Pin<Box<ffi::b2Transform>>,
+    },
+}
+
+impl b2OverlapTest {
+    fn overlaps(&self, mut fixture: Pin<&mut ffi_b2Fixture>) -> bool {
+        match self {
+            b2OverlapTest::Point(point) => fixture.as_ref().TestPoint(&to_b2Vec2(point)),
+            b2OverlapTest::Shape { shape, transform } => unsafe {
+                let fixture_shape = fixture.as_mut().GetShape().as_ref().unwrap();
+                let body = fixture.as_mut().GetBody().as_ref().unwrap();
+                let _box2d_globals = lock_box2d_globals();
+                // Every edge of a chain is a separate child
+                (0..ffi_child_count(fixture_shape)).any(|fixture_child| {
+                    (0..ffi_child_count(shape.as_ffi())).any(|shape_child| {
+                        ffi::b2TestOverlap(
+                            fixture_shape,
+                            int32::from(fixture_child),
+                            shape.as_ffi(),
+                            int32::from(shape_child),
+                            body.GetTransform(),
+                            transform.as_ref().get_ref(),
+                        )
+                    })
+                })
+            },
+        }
+    }
+}
+
+#[allow(non_camel_case_types)]
+pub trait b2QueryCallback: Debug {
+    /// The default is returned if the query can't be completed.
+    type Result: Default;
+
+    /// Called for every fixture whose bounding box overlaps the query. Return false to stop the
+    /// query.
+    fn report_fixture(&mut self, hit: b2QueryHit) -> bool;
+
+    fn into_result(self) -> Self::Result;
+}
+
+/// Restricts which fixtures are reported by a query.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub struct b2QueryFilter {
+    /// Fixtures of these bodies are never reported.
+    pub excluded_bodies: HashSet<Entity>,
+
+    /// Only fixtures with at least one of these category bits set are reported.
+    pub category_bits: u16,
+
+    /// Whether sensor fixtures are reported.
+    pub include_sensors: bool,
+}
+
+impl Default for b2QueryFilter {
+    fn default() -> Self {
+        Self {
+            excluded_bodies: HashSet::new(),
+            category_bits: 0xFFFF,
+            include_sensors: true,
+        }
+    }
+}
+
+impl b2QueryFilter {
+    /// Only reports the fixtures in at least one of the given layers.
+    pub fn with_layers<L: PhysicsLayer>(mut self, layers: impl IntoIterator<Item = L>) -> Self {
+        self.category_bits = layer_bits(layers);
+        self
+    }
+
+    pub(crate) fn is_sensor_allowed(&self, is_sensor: bool) -> bool {
+        self.include_sensors || !is_sensor
+    }
+
+    pub(crate) fn is_category_allowed(&self, category_bits: u16) -> bool {
+        self.category_bits & category_bits != 0
+    }
+}
+
+/// Collects all the fixtures overlapping the query.
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub struct b2QueryAABB {
+    result: Vec<b2QueryHit>,
+}
+
+impl b2QueryAABB {
+    pub fn new() -> Self {
+        b2QueryAABB { result: Vec::new() }
+    }
+}
+
+impl b2QueryCallback for b2QueryAABB {
+    type Result = Vec<b2QueryHit>;
+
+    fn report_fixture(&mut self, hit: b2QueryHit) -> bool {
+        self.result.push(hit);
+        true
+    }
+
+    fn into_result(self) -> Self::Result {
+        self.result
+    }
+}
+
+/// Stops at the first fixture overlapping the query, for cheap overlap tests.
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub struct b2QueryAny {
+    result: Option<b2QueryHit>,
+}
+
+impl b2QueryAny {
+    pub fn new() -> Self {
+        b2QueryAny { result: None }
+    }
+}
+
+impl b2QueryCallback for b2QueryAny {
+    type Result = Option<b2QueryHit>;
+
+    fn report_fixture(&mut self, hit: b2QueryHit) -> bool {
+        self.result = Some(hit);
+        false
+    }
+
+    fn into_result(self) -> Self::Result {
+        self.result
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[allow(non_camel_case_types)]
+pub struct b2QueryHit {
+    pub fixture_entity: Entity,
+    pub body_entity: Entity,
+}
@@ -1,22 +1,53 @@
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::pin::Pin;
 
-use bevy::prelude::{Entity, Vec2};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{default, Entity, NonSend, Vec2};
 
 use libliquidfun_sys::box2d::ffi::b2Fixture as ffi_b2Fixture;
 use libliquidfun_sys::box2d::ffi::{b2ParticleSystem, b2RayCastCallbackImpl, b2Vec2};
 
-use crate::internal::to_Vec2;
+use crate::collision::b2Shape;
+use crate::dynamics::{
+    b2AABB, b2Filter, b2QueryAABB, b2QueryFilter, b2QueryHit, b2ShapeCastHit, b2World, b2Worlds,
+    layer_bits, PhysicsLayer, PhysicsWorldId,
+};
+use crate::internal::{body_entity, fixture_entity, to_Vec2};
 
 #[derive(Debug)]
 #[allow(non_camel_case_types)]
 pub(crate) struct b2RayCast<T: b2RayCastCallback> {
     callback: T,
+    filter: b2RayCastFilter,
+    particle_systems: HashMap<*const b2ParticleSystem, Entity>,
 }
 
 impl<T: b2RayCastCallback> b2RayCast<T> {
-    pub fn new(callback: T) -> Self {
-        Self { callback }
+    pub fn new(
+        callback: T,
+        filter: b2RayCastFilter,
+        particle_systems: HashMap<*const b2ParticleSystem, Entity>,
+    ) -> Self {
+        Self {
+            callback,
+            filter,
+            particle_systems,
+        }
+    }
+
+    /// Box2D reports the fractions along the ray clipped to `max_fraction`, the callbacks see
+    /// them along the full ray.
+    fn report_fraction(&self, fraction: f32) -> f32 {
+        fraction * self.filter.max_fraction
+    }
+
+    fn clip_fraction(&self, fraction: f32) -> f32 {
+        if fraction < 0. {
+            fraction
+        } else {
+            fraction / self.filter.max_fraction
+        }
     }
 
     pub fn extract_hits(self) -> T::Result {
@@ -33,16 +64,26 @@ impl<T: b2RayCastCallback> b2RayCastCallbackImpl for b2RayCast<T> {
         normal: &b2Vec2,
         fraction: f32,
     ) -> f32 {
-        let entity = unsafe {
-            let mut ffi_fixture = Pin::new_unchecked(fixture);
-            let user_data = ffi_fixture.as_mut().GetUserData();
-            let pointer_to_entity_bits = user_data.get_unchecked_mut().pointer;
-            *(pointer_to_entity_bits as *const Entity)
+        let mut fixture = unsafe { Pin::new_unchecked(fixture) };
+        let body_entity = unsafe {
+            let body = fixture.as_mut().GetBody().as_mut().unwrap();
+            body_entity(Pin::new_unchecked(body))
         };
+        let is_sensor = fixture.as_ref().IsSensor();
+        let filter = b2Filter::from_ffi(fixture.as_ref().GetFilterData());
+        let entity = fixture_entity(fixture);
+        if !self
+            .filter
+            .should_use(entity, body_entity, &filter, is_sensor)
+        {
+            return -1.;
+        }
 
-        return self
-            .callback
-            .report_fixture(entity, &to_Vec2(point), &to_Vec2(normal), fraction);
+        let fraction = self.report_fraction(fraction);
+        let fraction =
+            self.callback
+                .report_fixture(entity, &to_Vec2(point), &to_Vec2(normal), fraction);
+        self.clip_fraction(fraction)
     }
 
     fn report_particle(
@@ -53,21 +94,280 @@ impl<T: b2RayCastCallback> b2RayCastCallbackImpl for b2RayCast<T> {
         normal: &b2Vec2,
         fraction: f32,
     ) -> f32 {
-        todo!()
+        let particle_system: *const b2ParticleSystem = particle_system;
+        let Some(particle_system) = self.particle_systems.get(&particle_system) else {
+            return 1.;
+        };
+        let fraction = self.report_fraction(fraction);
+        let fraction = self.callback.report_particle(
+            *particle_system,
+            index,
+            &to_Vec2(point),
+            &to_Vec2(normal),
+            fraction,
+        );
+        self.clip_fraction(fraction)
     }
 
     fn should_query_particle_system(&mut self, particle_system: *const b2ParticleSystem) -> bool {
-        todo!()
+        self.callback.should_query_particles()
+            && self.particle_systems.contains_key(&particle_system)
+    }
+}
+
+/// Restricts which fixtures can be hit by a ray cast.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub struct b2RayCastFilter {
+    /// Fixtures of these bodies are never hit.
+    pub excluded_bodies: HashSet<Entity>,
+
+    /// These fixtures are never hit.
+    pub excluded_fixtures: HashSet<Entity>,
+
+    /// Only fixtures with at least one of these category bits set are hit.
+    pub category_bits: u16,
+
+    /// When set, the ray behaves like a fixture of this category, and only hits the fixtures whose
+    /// mask bits contain it.
+    pub ray_category_bits: Option<u16>,
+
+    /// Whether sensor fixtures are hit.
+    pub include_sensors: bool,
+
+    /// The fraction of the ray between the start and end point that is cast, in `(0, 1]`. The
+    /// fractions reported to the callback are still along the full ray.
+    pub max_fraction: f32,
+}
+
+impl Default for b2RayCastFilter {
+    fn default() -> Self {
+        Self {
+            excluded_bodies: HashSet::new(),
+            excluded_fixtures: HashSet::new(),
+            category_bits: 0xFFFF,
+            ray_category_bits: None,
+            include_sensors: true,
+            max_fraction: 1.,
+        }
+    }
+}
+
+impl b2RayCastFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn excluding_bodies(excluded_bodies: impl IntoIterator<Item = Entity>) -> Self {
+        Self {
+            excluded_bodies: excluded_bodies.into_iter().collect(),
+            ..default()
+        }
+    }
+
+    /// Only hits the fixtures in at least one of the given layers.
+    pub fn with_layers<L: PhysicsLayer>(mut self, layers: impl IntoIterator<Item = L>) -> Self {
+        self.category_bits = layer_bits(layers);
+        self
+    }
+
+    /// Only hits the fixtures that would collide with a fixture in the given layers.
+    pub fn as_layers<L: PhysicsLayer>(mut self, layers: impl IntoIterator<Item = L>) -> Self {
+        self.ray_category_bits = Some(layer_bits(layers));
+        self
+    }
+
+    pub fn without_sensors(mut self) -> Self {
+        self.include_sensors = false;
+        self
+    }
+
+    pub fn with_max_fraction(mut self, max_fraction: f32) -> Self {
+        self.max_fraction = max_fraction;
+        self
+    }
+
+    /// Whether the ray can hit a fixture with the given properties.
+    pub fn should_use(
+        &self,
+        fixture_entity: Entity,
+        body_entity: Entity,
+        filter: &b2Filter,
+        is_sensor: bool,
+    ) -> bool {
+        if is_sensor && !self.include_sensors {
+            return false;
+        }
+        if self.category_bits & filter.category_bits == 0 {
+            return false;
+        }
+        if let Some(ray_category_bits) = self.ray_category_bits {
+            if filter.mask_bits & ray_category_bits == 0 {
+                return false;
+            }
+        }
+        !self.excluded_bodies.contains(&body_entity)
+            && !self.excluded_fixtures.contains(&fixture_entity)
+    }
+}
+
+/// Read-only access to the ray casts and queries of the [`b2World`], for systems that don't
+/// otherwise touch the world.
+///
+/// Queries only need a shared reference to the world, so systems with this parameter don't
+/// conflict with each other, but the world is a non-send resource and all of them still run on
+/// the main thread, one after another. They do run alongside the systems that don't use the world
+/// at all, which a `NonSendMut<b2World>` would not allow either.
+///
+/// The methods query the default world, [`PhysicsRayCaster::in_world`] those of
+/// [`b2Worlds`].
+#[derive(SystemParam)]
+pub struct PhysicsRayCaster<'w> {
+    world: NonSend<'w, b2World<'static>>,
+    worlds: Option<NonSend<'w, b2Worlds>>,
+}
+
+impl<'w> PhysicsRayCaster<'w> {
+    /// The ray casts and queries of the world with the given id, `None` if there is no such
+    /// world.
+    pub fn in_world(&self, id: PhysicsWorldId) -> Option<WorldRayCaster<'_>> {
+        if id == PhysicsWorldId::DEFAULT {
+            return Some(self.in_default_world());
+        }
+        let world = self.worlds.as_ref()?.get(id)?;
+        Some(WorldRayCaster { world })
+    }
+
+    fn in_default_world(&self) -> WorldRayCaster<'_> {
+        WorldRayCaster { world: &self.world }
+    }
+
+    /// The closest fixture hit by the ray from `start` to `end`.
+    pub fn cast_closest(
+        &self,
+        start: Vec2,
+        end: Vec2,
+        filter: &b2RayCastFilter,
+    ) -> Option<b2RayCastHit> {
+        self.in_default_world().cast_closest(start, end, filter)
+    }
+
+    /// Any fixture hit by the ray, which is cheaper than [`PhysicsRayCaster::cast_closest`] for
+    /// line of sight checks.
+    pub fn cast_any(
+        &self,
+        start: Vec2,
+        end: Vec2,
+        filter: &b2RayCastFilter,
+    ) -> Option<b2RayCastHit> {
+        self.in_default_world().cast_any(start, end, filter)
+    }
+
+    /// All the fixtures hit by the ray, in no particular order.
+    pub fn cast_all(&self, start: Vec2, end: Vec2, filter: &b2RayCastFilter) -> Vec<b2RayCastHit> {
+        self.in_default_world().cast_all(start, end, filter)
+    }
+
+    /// The first fixture hit by `shape` moving along `translation`, see [`b2World::shape_cast`].
+    pub fn shape_cast(
+        &self,
+        shape: &b2Shape,
+        start_transform: (Vec2, f32),
+        translation: Vec2,
+        filter: &b2RayCastFilter,
+    ) -> Option<b2ShapeCastHit> {
+        self.in_default_world()
+            .shape_cast(shape, start_transform, translation, filter)
+    }
+
+    /// All the fixtures whose bounding boxes overlap `aabb`.
+    pub fn overlap_aabb(&self, aabb: &b2AABB, filter: &b2QueryFilter) -> Vec<b2QueryHit> {
+        self.in_default_world().overlap_aabb(aabb, filter)
+    }
+
+    pub fn world(&self) -> &b2World<'static> {
+        &self.world
+    }
+}
+
+/// The ray casts and queries of a single world, see [`PhysicsRayCaster::in_world`]. The methods
+/// are those of [`PhysicsRayCaster`].
+pub struct WorldRayCaster<'a> {
+    world: &'a b2World<'static>,
+}
+
+impl<'a> WorldRayCaster<'a> {
+    pub fn cast_closest(
+        &self,
+        start: Vec2,
+        end: Vec2,
+        filter: &b2RayCastFilter,
+    ) -> Option<b2RayCastHit> {
+        self.world
+            .ray_cast_with_filter(b2RayCastClosest::new(), &start, &end, filter)
+    }
+
+    pub fn cast_any(
+        &self,
+        start: Vec2,
+        end: Vec2,
+        filter: &b2RayCastFilter,
+    ) -> Option<b2RayCastHit> {
+        self.world
+            .ray_cast_with_filter(b2RayCastAny::new(), &start, &end, filter)
+    }
+
+    pub fn cast_all(&self, start: Vec2, end: Vec2, filter: &b2RayCastFilter) -> Vec<b2RayCastHit> {
+        self.world
+            .ray_cast_with_filter(b2RayCastAll::new(), &start, &end, filter)
+    }
+
+    pub fn shape_cast(
+        &self,
+        shape: &b2Shape,
+        start_transform: (Vec2, f32),
+        translation: Vec2,
+        filter: &b2RayCastFilter,
+    ) -> Option<b2ShapeCastHit> {
+        self.world
+            .shape_cast(shape, start_transform, translation, filter)
+    }
+
+    pub fn overlap_aabb(&self, aabb: &b2AABB, filter: &b2QueryFilter) -> Vec<b2QueryHit> {
+        self.world
+            .query_aabb_with_filter(b2QueryAABB::new(), aabb, filter)
+    }
+
+    pub fn world(&self) -> &'a b2World<'static> {
+        self.world
     }
 }
 
 #[allow(non_camel_case_types)]
 pub trait b2RayCastCallback: Debug {
-    type Result;
+    /// The default is returned if the ray cast can't be completed.
+    type Result: Default;
 
     fn report_fixture(&mut self, entity: Entity, point: &Vec2, normal: &Vec2, fraction: f32)
         -> f32;
 
+    /// Called for every particle hit by the ray if [`b2RayCastCallback::should_query_particles`]
+    /// returns true. The return value has the same meaning as for fixtures.
+    fn report_particle(
+        &mut self,
+        _particle_system: Entity,
+        _index: i32,
+        _point: &Vec2,
+        _normal: &Vec2,
+        _fraction: f32,
+    ) -> f32 {
+        1.
+    }
+
+    fn should_query_particles(&self) -> bool {
+        false
+    }
+
     fn into_result(self) -> Self::Result;
 }
 
@@ -75,11 +375,21 @@ pub trait b2RayCastCallback: Debug {
 #[allow(non_camel_case_types)]
 pub struct b2RayCastClosest {
     result: Option<b2RayCastHit>,
+    include_particles: bool,
 }
 
 impl b2RayCastClosest {
     pub fn new() -> Self {
-        b2RayCastClosest { result: None }
+        b2RayCastClosest {
+            result: None,
+            include_particles: false,
+        }
+    }
+
+    /// Also report particles hit by the ray.
+    pub fn with_particles(mut self) -> Self {
+        self.include_particles = true;
+        self
     }
 }
 
@@ -95,12 +405,34 @@ impl b2RayCastCallback for b2RayCastClosest {
     ) -> f32 {
         self.result = Some(b2RayCastHit {
             entity,
+            kind: b2RayCastHitKind::Fixture,
             point: *point,
             normal: *normal,
         });
         fraction
     }
 
+    fn report_particle(
+        &mut self,
+        particle_system: Entity,
+        index: i32,
+        point: &Vec2,
+        normal: &Vec2,
+        fraction: f32,
+    ) -> f32 {
+        self.result = Some(b2RayCastHit {
+            entity: particle_system,
+            kind: b2RayCastHitKind::Particle { index },
+            point: *point,
+            normal: *normal,
+        });
+        fraction
+    }
+
+    fn should_query_particles(&self) -> bool {
+        self.include_particles
+    }
+
     fn into_result(self) -> Self::Result {
         self.result
     }
@@ -110,11 +442,21 @@ impl b2RayCastCallback for b2RayCastClosest {
 #[allow(non_camel_case_types)]
 pub struct b2RayCastAny {
     result: Option<b2RayCastHit>,
+    include_particles: bool,
 }
 
 impl b2RayCastAny {
     pub fn new() -> Self {
-        b2RayCastAny { result: None }
+        b2RayCastAny {
+            result: None,
+            include_particles: false,
+        }
+    }
+
+    /// Also report particles hit by the ray.
+    pub fn with_particles(mut self) -> Self {
+        self.include_particles = true;
+        self
     }
 }
 
@@ -130,12 +472,34 @@ impl b2RayCastCallback for b2RayCastAny {
     ) -> f32 {
         self.result = Some(b2RayCastHit {
             entity,
+            kind: b2RayCastHitKind::Fixture,
+            point: *point,
+            normal: *normal,
+        });
+        0.
+    }
+
+    fn report_particle(
+        &mut self,
+        particle_system: Entity,
+        index: i32,
+        point: &Vec2,
+        normal: &Vec2,
+        _fraction: f32,
+    ) -> f32 {
+        self.result = Some(b2RayCastHit {
+            entity: particle_system,
+            kind: b2RayCastHitKind::Particle { index },
             point: *point,
             normal: *normal,
         });
         0.
     }
 
+    fn should_query_particles(&self) -> bool {
+        self.include_particles
+    }
+
     fn into_result(self) -> Self::Result {
         self.result
     }
@@ -145,11 +509,21 @@ impl b2RayCastCallback for b2RayCastAny {
 #[allow(non_camel_case_types)]
 pub struct b2RayCastAll {
     result: Vec<b2RayCastHit>,
+    include_particles: bool,
 }
 
 impl b2RayCastAll {
     pub fn new() -> Self {
-        b2RayCastAll { result: Vec::new() }
+        b2RayCastAll {
+            result: Vec::new(),
+            include_particles: false,
+        }
+    }
+
+    /// Also report particles hit by the ray.
+    pub fn with_particles(mut self) -> Self {
+        self.include_particles = true;
+        self
     }
 }
 
@@ -165,20 +539,53 @@ impl b2RayCastCallback for b2RayCastAll {
     ) -> f32 {
         self.result.push(b2RayCastHit {
             entity,
+            kind: b2RayCastHitKind::Fixture,
             point: *point,
             normal: *normal,
         });
         1.
     }
 
+    fn report_particle(
+        &mut self,
+        particle_system: Entity,
+        index: i32,
+        point: &Vec2,
+        normal: &Vec2,
+        _fraction: f32,
+    ) -> f32 {
+        self.result.push(b2RayCastHit {
+            entity: particle_system,
+            kind: b2RayCastHitKind::Particle { index },
+            point: *point,
+            normal: *normal,
+        });
+        1.
+    }
+
+    fn should_query_particles(&self) -> bool {
+        self.include_particles
+    }
+
     fn into_result(self) -> Self::Result {
         self.result
     }
 }
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum b2RayCastHitKind {
+    /// `entity` is the fixture that was hit.
+    Fixture,
+    /// `entity` is the particle system of the particle that was hit.
+    Particle { index: i32 },
+}
+
 #[derive(Debug, Copy, Clone)]
 #[allow(non_camel_case_types)]
 pub struct b2RayCastHit {
     pub entity: Entity,
+    pub kind: b2RayCastHitKind,
     pub point: Vec2,
     pub normal: Vec2,
 }
@@ -0,0 +1,166 @@
+use std::pin::Pin;
+
+use bevy::prelude::{Entity, Vec2};
+
+use libliquidfun_sys::box2d::ffi;
+use libliquidfun_sys::box2d::ffi::b2Fixture as ffi_b2Fixture;
+use libliquidfun_sys::box2d::ffi::{b2ParticleSystem, b2QueryCallbackImpl};
+
+use crate::collision::{ffi_child_count, ffi_distance, OwnedFfiShape, LINEAR_SLOP};
+use crate::dynamics::{b2Filter, b2RayCastFilter};
+use crate::internal::{body_entity, fixture_entity, to_Vec2};
+
+/// The shapes are moved until they are this far apart, so that the cast shape can be placed at
+/// the hit without overlapping the fixture.
+const TARGET_DISTANCE: f32 = LINEAR_SLOP;
+const TOLERANCE: f32 = 0.25 * LINEAR_SLOP;
+const MAX_ITERATIONS: usize = 20;
+
+/// The first fixture hit by a shape cast.
+#[derive(Debug, Copy, Clone)]
+#[allow(non_camel_case_types)]
+pub struct b2ShapeCastHit {
+    pub fixture_entity: Entity,
+    pub body_entity: Entity,
+    /// The point on the fixture closest to the cast shape at the time of the hit.
+    pub point: Vec2,
+    /// Points from the fixture towards the cast shape.
+    pub normal: Vec2,
+    /// How far along the translation the shape can be moved before it hits the fixture, between 0
+    /// and 1.
+    pub fraction: f32,
+}
+
+/// Finds the first fixture hit by a shape moving along a translation, among the fixtures whose
+/// bounding boxes overlap the swept area.
+///
+/// Every candidate fixture is checked with conservative advancement: the shape is moved towards
+/// the fixture by the distance between them, which can't overshoot, until they touch.
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub(crate) struct b2ShapeCast {
+    shape: OwnedFfiShape,
+    start: (Vec2, f32),
+    translation: Vec2,
+    filter: b2RayCastFilter,
+    hit: Option<b2ShapeCastHit>,
+}
+
+impl b2ShapeCast {
+    pub fn new(
+        shape: OwnedFfiShape,
+        start: (Vec2, f32),
+        translation: Vec2,
+        filter: b2RayCastFilter,
+    ) -> Self {
+        Self {
+            shape,
+            start,
+            translation,
+            filter,
+            hit: None,
+        }
+    }
+
+    pub fn extract_hit(self) -> Option<b2ShapeCastHit> {
+        self.hit
+    }
+
+    /// Returns the fraction of the translation and the closest point and normal at the hit of
+    /// one child of the cast shape against one child of the fixture shape.
+    fn cast_against(
+        &self,
+        shape_child: i32,
+        fixture_shape: (&ffi::b2Shape, i32),
+        fixture_transform: (Vec2, f32),
+        max_fraction: f32,
+    ) -> Option<(f32, Vec2, Vec2)> {
+        let mut fraction = 0.;
+        for _ in 0..MAX_ITERATIONS {
+            let position = self.start.0 + self.translation * fraction;
+            let output = ffi_distance(
+                (self.shape.as_ffi(), shape_child),
+                (position, self.start.1),
+                fixture_shape,
+                fixture_transform,
+            );
+            if output.distance < TARGET_DISTANCE + TOLERANCE {
+                let normal = if output.distance > 0. {
+                    (output.point_a - output.point_b) / output.distance
+                } else {
+                    // Already overlapping at the start, there is no meaningful normal
+                    -self.translation.normalize_or_zero()
+                };
+                return Some((fraction, output.point_b, normal));
+            }
+
+            let direction = (output.point_b - output.point_a) / output.distance;
+            let closing_distance = self.translation.dot(direction);
+            if closing_distance <= 0. {
+                return None;
+            }
+            fraction += (output.distance - TARGET_DISTANCE) / closing_distance;
+            if fraction > max_fraction {
+                return None;
+            }
+        }
+        None
+    }
+}
+
+#[allow(unused_variables)]
+impl b2QueryCallbackImpl for b2ShapeCast {
+    fn report_fixture(&mut self, fixture: &mut ffi_b2Fixture) -> bool {
+        let mut fixture = unsafe { Pin::new_unchecked(fixture) };
+        let (body_entity, body_transform) = unsafe {
+            let mut body = Pin::new_unchecked(fixture.as_mut().GetBody().as_mut().unwrap());
+            let transform = (
+                to_Vec2(body.as_ref().GetPosition()),
+                body.as_ref().GetAngle(),
+            );
+            (body_entity(body.as_mut()), transform)
+        };
+        let is_sensor = fixture.as_ref().IsSensor();
+        let filter = b2Filter::from_ffi(fixture.as_ref().GetFilterData());
+        let fixture_shape = unsafe { fixture.as_mut().GetShape().as_ref().unwrap() };
+        let fixture_entity = fixture_entity(fixture);
+        if !self
+            .filter
+            .should_use(fixture_entity, body_entity, &filter, is_sensor)
+        {
+            return true;
+        }
+
+        // Chains are cast against edge by edge
+        for fixture_child in 0..ffi_child_count(fixture_shape) {
+            for shape_child in 0..ffi_child_count(self.shape.as_ffi()) {
+                let max_fraction = self
+                    .hit
+                    .map_or(self.filter.max_fraction, |hit| hit.fraction);
+                if let Some((fraction, point, normal)) = self.cast_against(
+                    shape_child,
+                    (fixture_shape, fixture_child),
+                    body_transform,
+                    max_fraction,
+                ) {
+                    self.hit = Some(b2ShapeCastHit {
+                        fixture_entity,
+                        body_entity,
+                        point,
+                        normal,
+                        fraction,
+                    });
+                }
+            }
+        }
+        true
+    }
+
+    fn report_particle(&mut self, particle_system: &b2ParticleSystem, index: i32) -> bool {
+        false
+    }
+
+    fn should_query_particle_system(&mut self, particle_system: *const b2ParticleSystem) -> bool {
+        false
+    }
+}
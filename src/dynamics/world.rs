@@ -1,25 +1,77 @@
-use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::cell::{RefCell, RefMut};
 use std::pin::Pin;
 use std::sync::Arc;
 
 use autocxx::WithinBox;
 use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
 
-use libliquidfun_sys::box2d::ffi::{b2RayCastCallbackWrapper, int32};
+use libliquidfun_sys::box2d::ffi::{
+    b2ContactFilterWrapper, b2ContactListenerWrapper, b2DestructionListenerWrapper,
+    b2QueryCallbackWrapper, b2RayCastCallbackWrapper, int32, uint32,
+};
 use libliquidfun_sys::box2d::*;
 
-use crate::dynamics::{b2Body, b2Fixture, b2Joint, b2RayCast, b2RayCastCallback, JointPtr};
+use crate::collision::{b2Shape, ffi_child_count, OwnedFfiShape, LINEAR_SLOP};
+use crate::dynamics::{
+    b2Body, b2Contact, b2ContactFilter, b2ContactFilterCallback, b2ContactListener,
+    b2DestructionListener, b2Fixture, b2FixtureDef, b2Fixtures, b2Joint, b2OverlapTest,
+    b2ParticleQuery, b2Query, b2QueryAABB, b2QueryCallback, b2QueryFilter, b2QueryHit, b2RayCast,
+    b2RayCastCallback, b2RayCastFilter, b2ShapeCast, b2ShapeCastHit, ContactOverride, Explosion,
+    Falloff, GravityScale, JointPtr, KinematicTarget, PhysicsError, PhysicsWorldId,
+};
 use crate::internal::*;
-use crate::particles::{b2ParticleGroup, b2ParticleSystem};
+use crate::particles::{
+    b2ParticleColor, b2ParticleFlags, b2ParticleGroup, b2ParticleHandle, b2ParticleSystem,
+};
+#[cfg(feature = "bevy-inspector-egui")]
+use bevy_inspector_egui::prelude::*;
 
 #[allow(non_camel_case_types)]
-#[derive(Resource, Clone)]
+#[derive(Resource, Clone, Reflect)]
+#[cfg_attr(
+    feature = "bevy-inspector-egui",
+    derive(InspectorOptions),
+    reflect(InspectorOptions)
+)]
+#[reflect(Resource)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct b2WorldSettings {
+    /// The simulated time per step in seconds. Can be changed at any time, the time that hasn't
+    /// been simulated yet is kept as the same fraction of a step.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.001, max = 0.1))]
     pub time_step: f32,
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 1))]
     pub velocity_iterations: i32,
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 1))]
     pub position_iterations: i32,
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 1))]
     pub particle_iterations: i32,
+    /// Computes the particle iterations of every step with
+    /// [`crate::particles::calculate_particle_iterations`] from the gravity and the smallest
+    /// particle radius, rather than using `particle_iterations`.
+    pub auto_particle_iterations: bool,
+    pub transform_sync: TransformSyncMode,
+    /// Whether bodies are allowed to fall asleep when they come to rest.
+    pub allow_sleeping: bool,
+    /// The longest frame time in seconds that is simulated, longer frames are cut short. Without
+    /// it, a frame that takes too long makes the next frame run more steps, which take even
+    /// longer, until the app grinds to a halt.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
+    pub max_frame_delta: f32,
+    /// The most steps that are run in a single frame to catch up with the frame time. Steps
+    /// requested with [`PhysicsTime::step_once`] are always run.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 1))]
+    pub max_steps_per_frame: u32,
+    /// Whether [`crate::dynamics::b2Contacts`] is updated after the steps. Copying every contact
+    /// in the world is wasted work for apps that never read it, but the
+    /// [`crate::dynamics::b2ContactStayEvent`]s, the debug drawn contacts and the ground
+    /// detection of the character controller rely on it.
+    pub copy_contacts: bool,
+    /// The number of bodies from which their state and transforms are synced on several threads.
+    /// Below it, spreading the work over threads costs more than it saves. The
+    /// `sync_threshold` example measures both ways for a range of body counts.
+    pub parallel_sync_threshold: usize,
 }
 
 impl Default for b2WorldSettings {
@@ -29,10 +81,146 @@ impl Default for b2WorldSettings {
             velocity_iterations: 8,
             position_iterations: 3,
             particle_iterations: 4,
+            auto_particle_iterations: false,
+            transform_sync: TransformSyncMode::default(),
+            allow_sleeping: true,
+            max_frame_delta: 0.25,
+            max_steps_per_frame: 8,
+            copy_contacts: true,
+            parallel_sync_threshold: 1024,
         }
     }
 }
 
+impl b2WorldSettings {
+    /// Fewer, longer steps with fewer iterations, for slow devices. Stacks and chains of joints
+    /// are softer and fast bodies are more likely to tunnel through thin fixtures. Transforms
+    /// lag a whole step behind without [`TransformSyncMode::Extrapolate`] or
+    /// [`TransformSyncMode::Interpolate`].
+    pub fn low_quality() -> Self {
+        Self {
+            time_step: 1. / 30.,
+            velocity_iterations: 6,
+            position_iterations: 2,
+            particle_iterations: 2,
+            ..default()
+        }
+    }
+
+    /// Shorter steps with more iterations, for stiff stacks, long joint chains and fluids. Every
+    /// step costs more and twice as many of them run as with the defaults, so the most steps per
+    /// frame are raised to still cover the same frame time.
+    pub fn high_quality() -> Self {
+        Self {
+            time_step: 1. / 120.,
+            velocity_iterations: 10,
+            position_iterations: 8,
+            particle_iterations: 6,
+            max_steps_per_frame: 16,
+            ..default()
+        }
+    }
+}
+
+/// The state of the dynamic AABB tree the broad-phase keeps the fixtures in, see
+/// [`b2World::get_broad_phase_stats`].
+#[allow(non_camel_case_types)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct b2BroadPhaseStats {
+    /// One per child of every fixture, e.g. one per edge of a chain.
+    pub proxy_count: i32,
+    /// A well balanced tree is about `log2(proxy_count)` high.
+    pub tree_height: i32,
+    /// The largest height difference between two sibling nodes.
+    pub tree_balance: i32,
+    /// The summed perimeter of all the nodes relative to that of the root. Lower is better,
+    /// overlapping fat AABBs drive it up.
+    pub tree_quality: f32,
+}
+
+/// Replaces the physics world with an empty one with the same gravity, e.g. for level
+/// transitions. All the entities with physics components are despawned along with their
+/// children, and the contacts and pending physics events are cleared. The reset happens at the
+/// start of `PostUpdate`, so physics entities spawned earlier in the same frame are despawned as
+/// well.
+#[derive(Event, Debug, Default, Copy, Clone)]
+pub struct ResetPhysicsWorld;
+
+/// How the `Transform` of a body is derived from its physics state, which is only updated once
+/// per physics step and therefore usually lags behind the rendered frame.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransformSyncMode {
+    /// Use the state of the last step as is.
+    Snap,
+    /// Extrapolate the state of the last step using the body velocities.
+    #[default]
+    Extrapolate,
+    /// Interpolate between the states of the last two steps. This is always correct, but the
+    /// rendered bodies lag one step behind the simulation.
+    Interpolate,
+}
+
+/// The number of physics steps simulated since the [`b2World`] was created, updated after the
+/// steps of every frame.
+#[derive(Resource, Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct PhysicsStepCount(pub u64);
+
+/// Controls how fast physics time passes relative to the app time, e.g. to freeze the
+/// simulation for debugging while the camera and UI keep running.
+#[derive(Resource, Debug, Clone)]
+pub struct PhysicsTime {
+    /// The frame time is multiplied by this before it is added to the physics time.
+    pub time_scale: f32,
+    paused: bool,
+    pending_steps: u32,
+}
+
+impl Default for PhysicsTime {
+    fn default() -> Self {
+        Self {
+            time_scale: 1.,
+            paused: false,
+            pending_steps: 0,
+        }
+    }
+}
+
+impl PhysicsTime {
+    /// Stops the physics time. No time is accumulated while paused, so the simulation doesn't
+    /// try to catch up once it is resumed.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Runs exactly one physics step during the next update, on top of the regular steps.
+    /// Mostly useful while paused.
+    pub fn step_once(&mut self) {
+        self.pending_steps += 1;
+    }
+
+    pub(crate) fn take_pending_steps(&mut self) -> u32 {
+        std::mem::take(&mut self.pending_steps)
+    }
+
+    /// Lets every world in [`crate::dynamics::b2Worlds`] take the same pending steps.
+    pub(crate) fn pending_steps(&self) -> u32 {
+        self.pending_steps
+    }
+
+    pub(crate) fn set_pending_steps(&mut self, pending_steps: u32) {
+        self.pending_steps = pending_steps;
+    }
+}
+
 #[allow(non_camel_case_types)]
 #[derive(Debug, Clone)]
 pub struct b2AABB {
@@ -47,75 +235,163 @@ impl b2AABB {
             upper_bound,
         }
     }
-}
-
-// time for Query Callbacks, for now we only implement fixture callback. for reference this is how raycast does it
-/* impl b2RayCastCallback for b2RayCastAny {
-    type Result = Option<b2RayCastHit>;
 
-    fn report_fixture(
-        &mut self,
-        entity: Entity,
-        point: &Vec2,
-        normal: &Vec2,
-        _fraction: f32,
-    ) -> f32 {
-        self.result = Some(b2RayCastHit {
-            entity,
-            point: *point,
-            normal: *normal,
-        });
-        0.
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.lower_bound).all() && point.cmple(self.upper_bound).all()
     }
+}
 
-    fn into_result(self) -> Self::Result {
-        self.result
-    }
-} */
-// lets do the same for query_aabb
-#[derive(Debug)]
-#[allow(non_camel_case_types)]
-pub struct b2QueryAABB {
-    result: Vec<Entity>,
+/// What happens to a dynamic body whose position leaves the [`WorldBounds`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum OutOfBoundsPolicy {
+    /// Only send a [`b2BodyOutOfBoundsEvent`].
+    #[default]
+    EventOnly,
+    /// Despawn the body entity along with its children, fixtures and joints.
+    Despawn,
+    /// Put the body to sleep and disable it, so it stays where it left the bounds.
+    Freeze,
 }
 
-impl b2QueryAABB {
-    pub fn new() -> Self {
-        b2QueryAABB { result: Vec::new() }
-    }
+/// Limits the area that dynamic bodies are simulated in, e.g. as a kill plane below the level.
+/// Without this resource, bodies may fall forever.
+///
+/// The positions are checked after they have been synced from the physics world, only for the
+/// bodies that moved, so sleeping and disabled bodies are ignored.
+#[derive(Resource, Debug, Clone)]
+pub struct WorldBounds {
+    pub aabb: b2AABB,
+    pub policy: OutOfBoundsPolicy,
 }
 
-pub trait b2QueryCallback {
-    type Result;
+impl WorldBounds {
+    pub fn new(aabb: b2AABB, policy: OutOfBoundsPolicy) -> Self {
+        Self { aabb, policy }
+    }
+}
 
-    fn report_fixture(&mut self, entity: Entity) -> bool;
-    fn into_result(self) -> Self::Result;
+/// Sent when the position of a dynamic body is outside of the [`WorldBounds`], after every step
+/// it has moved in, until it is despawned, frozen or back in bounds.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2BodyOutOfBoundsEvent {
+    pub entity: Entity,
+    pub position: Vec2,
 }
 
-impl b2QueryCallback for b2QueryAABB {
-    type Result = Vec<Entity>;
+/// The bodies of a [`b2World`], shared with the threads of a parallel query while the world
+/// itself stays on the main thread.
+///
+/// Box2D isn't thread safe, but the getters used to sync the bodies back to the ECS are const
+/// and only read the state of their own body. Reading distinct bodies, or even the same one,
+/// from several threads is therefore fine as long as nothing modifies the world at the same
+/// time, which the shared borrow of the world rules out.
+pub(crate) struct SharedBodies<'w, 'a>(&'w HashMap<Entity, Pin<&'a mut ffi::b2Body>>);
 
-    fn report_fixture(&mut self, entity: Entity) -> bool {
-        self.result.push(entity);
-        true
-    }
+unsafe impl Send for SharedBodies<'_, '_> {}
+unsafe impl Sync for SharedBodies<'_, '_> {}
 
-    fn into_result(self) -> Self::Result {
-        self.result
+impl SharedBodies<'_, '_> {
+    pub(crate) fn get(&self, entity: Entity) -> Option<&ffi::b2Body> {
+        self.0
+            .get(&entity)
+            .map(|body_ptr| body_ptr.as_ref().get_ref())
     }
 }
 
+/// The steps the physics systems decided on for a world in the current frame.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct QueuedSteps {
+    pub(crate) steps: u32,
+    pub(crate) time_step: f32,
+    pub(crate) velocity_iterations: i32,
+    pub(crate) position_iterations: i32,
+    pub(crate) particle_iterations: i32,
+}
+
+/// A force and torque the physics systems apply to a body before every queued step. Box2D clears
+/// the forces after each step, so applying them once would only affect the first step of a
+/// frame.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct StepForce {
+    pub(crate) body: Entity,
+    pub(crate) force: Vec2,
+    pub(crate) torque: f32,
+    pub(crate) should_wake: bool,
+    /// The force is in the local coordinates of the body and the torque is about its origin,
+    /// see [`crate::dynamics::LocalExternalForce`]. Both are converted with the state of the body
+    /// at each step.
+    pub(crate) local: bool,
+}
+
+/// The Box2D world, stored as a non-send resource.
+///
+/// This is the default world, which holds every entity without a [`PhysicsWorldId`]. Further
+/// independent worlds, e.g. with a different gravity, go in [`crate::dynamics::b2Worlds`].
+///
+/// The world hands out raw pointers to its bodies, fixtures and joints, and the contact, filter
+/// and destruction listeners it calls back into during a step share their state with the Rust
+/// side through `RefCell`s, so it isn't `Send` and lives on the main thread. With the
+/// `parallel_step` feature the worlds are moved to the `AsyncComputeTaskPool` for their steps,
+/// which then run while the frame is rendered, see [`crate::plugins::LiquidFunPlugin`].
+///
+/// Two apps that spawn the same entities in the same order and step the same way get
+/// bit-identical results on the same build and machine. The bodies, fixtures and joints are
+/// created and destroyed in entity order, and the maps of the world use a fixed hasher, so
+/// nothing depends on a random seed. Different platforms, compilers or optimization levels may
+/// still round differently.
 #[allow(non_camel_case_types)]
 pub struct b2World<'a> {
     ffi_world: Pin<Box<ffi::b2World>>,
+    /// Dropped after the world, whose bodies and fixtures point to it.
+    user_data: UserDataTable,
 
     body_ptrs: HashMap<Entity, Pin<&'a mut ffi::b2Body>>,
     fixture_ptrs: HashMap<Entity, Pin<&'a mut ffi::b2Fixture>>,
+    indexed_fixture_ptrs: HashMap<Entity, Vec<Pin<&'a mut ffi::b2Fixture>>>,
     joint_ptrs: HashMap<Entity, JointPtr<'a>>,
     particle_system_ptrs: HashMap<Entity, Pin<&'a mut ffi::b2ParticleSystem>>,
+    particle_group_ptrs: HashMap<Entity, Pin<&'a mut ffi::b2ParticleGroup>>,
+    particle_group_entities: HashMap<*const ffi::b2ParticleGroup, Entity>,
+    /// The generation of every particle handle that has been handed out, by particle system and
+    /// address. LiquidFun frees the handle along with its particle and reuses the address for the
+    /// next handle it creates, so the entry is dropped as soon as the destruction listener
+    /// reports the particle. Only the handles in here are ever dereferenced.
+    particle_handle_generations: HashMap<(Entity, usize), u32>,
+    next_particle_handle_generation: u32,
+    /// The handles whose particles only have the `DestructionListenerParticle` flag so their
+    /// destruction is reported, which doesn't send a
+    /// [`crate::particles::b2ParticleDestroyedEvent`].
+    silent_particle_handles: HashSet<(Entity, usize)>,
+    /// The particles destroyed during the steps since the last
+    /// [`b2World::take_destroyed_particles`].
+    destroyed_particles: Vec<(Entity, i32)>,
 
     body_to_fixtures: HashMap<Entity, HashSet<Entity>>,
     fixture_to_body: HashMap<Entity, Entity>,
+    body_to_joints: HashMap<Entity, HashSet<Entity>>,
+    joint_to_bodies: HashMap<Entity, [Entity; 2]>,
+
+    contact_listener: Arc<RefCell<b2ContactListener>>,
+    // Box2D only holds a raw pointer to the listener, so we have to keep it alive
+    _ffi_contact_listener: Arc<RefCell<b2ContactListenerWrapper>>,
+    ffi_contact_filter: Option<Arc<RefCell<b2ContactFilterWrapper>>>,
+    default_contact_filter: Pin<Box<ffi::b2ContactFilter>>,
+    destruction_listener: Arc<RefCell<b2DestructionListener>>,
+    _ffi_destruction_listener: Arc<RefCell<b2DestructionListenerWrapper>>,
+
+    step_count: u64,
+    elapsed_time: f64,
+    queued_steps: QueuedSteps,
+    step_forces: Vec<StepForce>,
+    kinematic_targets: Vec<(Entity, KinematicTarget)>,
+    /// The transforms of the awake bodies before the last step. The map is kept around, so it
+    /// doesn't have to be reallocated every frame.
+    pre_step_transforms: HashMap<Entity, (Vec2, f32)>,
+
+    id: PhysicsWorldId,
+    /// Whether the existing physics entities still have to be created in the world.
+    fresh: bool,
 
     pub gravity: Vec2,
 }
@@ -125,16 +401,113 @@ impl<'a> b2World<'a> {}
 impl<'a> b2World<'a> {
     pub fn new(gravity: Vec2) -> Self {
         let ffi_gravity = to_b2Vec2(&gravity);
-        let ffi_world = ffi::b2World::new(&ffi_gravity).within_box();
+        let mut ffi_world = ffi::b2World::new(&ffi_gravity).within_box();
+
+        let contact_listener = Arc::new(RefCell::new(b2ContactListener::default()));
+        let ffi_contact_listener = b2ContactListenerWrapper::new(contact_listener.clone());
+        unsafe {
+            let ffi_listener: *mut ffi::b2ContactListener = ffi_contact_listener
+                .as_ref()
+                .borrow_mut()
+                .pin_mut()
+                .as_mut()
+                .get_unchecked_mut();
+            ffi_world.as_mut().SetContactListener(ffi_listener);
+        }
+
+        let destruction_listener = Arc::new(RefCell::new(b2DestructionListener::default()));
+        let ffi_destruction_listener =
+            b2DestructionListenerWrapper::new(destruction_listener.clone());
+        unsafe {
+            let ffi_listener: *mut ffi::b2DestructionListener = ffi_destruction_listener
+                .as_ref()
+                .borrow_mut()
+                .pin_mut()
+                .as_mut()
+                .get_unchecked_mut();
+            ffi_world.as_mut().SetDestructionListener(ffi_listener);
+        }
+
         b2World {
             gravity,
+            step_count: 0,
+            elapsed_time: 0.,
+            queued_steps: QueuedSteps::default(),
+            step_forces: Vec::new(),
+            kinematic_targets: Vec::new(),
+            pre_step_transforms: HashMap::default(),
+            id: PhysicsWorldId::DEFAULT,
+            fresh: true,
             ffi_world,
-            body_ptrs: HashMap::new(),
-            fixture_ptrs: HashMap::new(),
-            joint_ptrs: HashMap::new(),
-            particle_system_ptrs: HashMap::new(),
-            body_to_fixtures: HashMap::new(),
-            fixture_to_body: HashMap::new(),
+            user_data: UserDataTable::default(),
+            body_ptrs: HashMap::default(),
+            fixture_ptrs: HashMap::default(),
+            indexed_fixture_ptrs: HashMap::default(),
+            joint_ptrs: HashMap::default(),
+            particle_system_ptrs: HashMap::default(),
+            particle_group_ptrs: HashMap::default(),
+            particle_group_entities: HashMap::default(),
+            particle_handle_generations: HashMap::default(),
+            next_particle_handle_generation: 0,
+            silent_particle_handles: HashSet::default(),
+            destroyed_particles: Vec::new(),
+            body_to_fixtures: HashMap::default(),
+            fixture_to_body: HashMap::default(),
+            body_to_joints: HashMap::default(),
+            joint_to_bodies: HashMap::default(),
+            contact_listener,
+            _ffi_contact_listener: ffi_contact_listener,
+            ffi_contact_filter: None,
+            default_contact_filter: ffi::b2ContactFilter::new().within_box(),
+            destruction_listener,
+            _ffi_destruction_listener: ffi_destruction_listener,
+        }
+    }
+
+    /// The id of the entities in this world, see [`PhysicsWorldId`].
+    pub fn id(&self) -> PhysicsWorldId {
+        self.id
+    }
+
+    pub(crate) fn set_id(&mut self, id: PhysicsWorldId) {
+        self.id = id;
+    }
+
+    /// Whether the world hasn't been synced with the ECS yet, so all the existing physics
+    /// entities of its id still have to be created in it. Stands in for change detection on the
+    /// resource, which can't tell apart the worlds that take turns in it.
+    pub(crate) fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+
+    pub(crate) fn mark_synced(&mut self) {
+        self.fresh = false;
+    }
+
+    /// Changes the gravity of the world. Box2D doesn't wake sleeping bodies when the gravity
+    /// changes, so bodies at rest stay where they are unless `wake_bodies` is set.
+    pub fn set_gravity(&mut self, gravity: Vec2, wake_bodies: bool) {
+        self.gravity = gravity;
+        self.ffi_world.as_mut().SetGravity(&to_b2Vec2(&gravity));
+        if wake_bodies {
+            for body_ptr in self.body_ptrs.values_mut() {
+                body_ptr.as_mut().SetAwake(true);
+            }
+        }
+    }
+
+    /// Disabling sleeping wakes up all the sleeping bodies.
+    pub fn set_allow_sleeping(&mut self, allow_sleeping: bool) {
+        self.ffi_world.as_mut().SetAllowSleeping(allow_sleeping);
+    }
+
+    /// Applies changes made directly to the `gravity` field to the physics world.
+    pub(crate) fn sync_gravity(&mut self) {
+        let ffi_gravity = to_Vec2(&self.ffi_world.as_ref().GetGravity());
+        if ffi_gravity != self.gravity {
+            self.ffi_world
+                .as_mut()
+                .SetGravity(&to_b2Vec2(&self.gravity));
         }
     }
 
@@ -146,6 +519,11 @@ impl<'a> b2World<'a> {
         self.body_ptrs.get(&entity)
     }
 
+    /// Read-only access to the bodies that can be shared with the threads of a parallel query.
+    pub(crate) fn get_shared_bodies(&self) -> SharedBodies<'_, 'a> {
+        SharedBodies(&self.body_ptrs)
+    }
+
     pub(crate) fn get_body_ptr_mut(
         &mut self,
         entity: Entity,
@@ -153,46 +531,159 @@ impl<'a> b2World<'a> {
         self.body_ptrs.get_mut(&entity)
     }
 
-    pub(crate) fn create_body(&mut self, entity: Entity, body: &mut b2Body) {
+    pub(crate) fn get_fixture_ptr_mut(
+        &mut self,
+        entity: &Entity,
+    ) -> Option<&mut Pin<&'a mut ffi::b2Fixture>> {
+        self.fixture_ptrs.get_mut(entity)
+    }
+
+    pub(crate) fn get_indexed_fixture_ptr_mut(
+        &mut self,
+        body_entity: &Entity,
+        index: usize,
+    ) -> Option<&mut Pin<&'a mut ffi::b2Fixture>> {
+        self.indexed_fixture_ptrs
+            .get_mut(body_entity)
+            .and_then(|fixture_ptrs| fixture_ptrs.get_mut(index))
+    }
+
+    /// Creates the bodies in the given order. Box2D copies the definition, so a single one is
+    /// reused for all of them, which matters when spawning thousands of bodies at once.
+    pub(crate) fn create_bodies<'b>(
+        &mut self,
+        bodies: impl ExactSizeIterator<Item = (Entity, &'b b2Body, Option<&'b GravityScale>)>,
+    ) {
+        self.body_ptrs.reserve(bodies.len());
         let mut b2body_def = ffi::b2BodyDef::new().within_box();
-        b2body_def.type_ = body.body_type.into();
-        b2body_def.position = to_b2Vec2(&body.position);
-        b2body_def.fixedRotation = body.fixed_rotation;
+        for (entity, body, gravity_scale) in bodies {
+            b2body_def.type_ = body.body_type.into();
+            b2body_def.position = to_b2Vec2(&body.position);
+            b2body_def.fixedRotation = body.fixed_rotation;
+            b2body_def.bullet = body.bullet;
+            b2body_def.enabled = body.enabled;
+            b2body_def.linearDamping = body.linear_damping;
+            b2body_def.angularDamping = body.angular_damping;
+            b2body_def.gravityScale = gravity_scale.copied().unwrap_or_default().0;
+            b2body_def.userData.pointer = self.user_data.insert(UserData {
+                entity,
+                fixture_index: None,
+            });
 
-        unsafe {
-            let ffi_body = self.ffi_world.as_mut().CreateBody(&*b2body_def);
-            let ffi_body = Pin::new_unchecked(ffi_body.as_mut().unwrap());
-            self.body_ptrs.insert(entity, ffi_body);
+            unsafe {
+                let ffi_body = self.ffi_world.as_mut().CreateBody(&*b2body_def);
+                let ffi_body = Pin::new_unchecked(ffi_body.as_mut().unwrap());
+                self.body_ptrs.insert(entity, ffi_body);
+            }
         }
     }
 
-    pub(crate) fn destroy_body_for_entity(&mut self, entity: Entity) {
-        let body_ptr = self.body_ptrs.remove(&entity).unwrap();
+    pub(crate) fn destroy_body_for_entity(&mut self, entity: Entity) -> Result<(), PhysicsError> {
+        // The body might belong to a world that has been reset since
+        let Some(mut body_ptr) = self.body_ptrs.remove(&entity) else {
+            return Err(PhysicsError::MissingBody(entity));
+        };
+        // Freed once Box2D is done with the body and its fixtures
+        let mut user_data = vec![body_user_data_pointer(body_ptr.as_mut())];
+        if let Some(fixture_ptrs) = self.indexed_fixture_ptrs.remove(&entity) {
+            user_data.extend(fixture_ptrs.into_iter().map(fixture_user_data_pointer));
+        }
         let fixtures = self.body_to_fixtures.remove(&entity);
         if let Some(fixtures) = fixtures {
             fixtures.iter().for_each(|f| {
                 self.fixture_to_body.remove(&f);
-                self.fixture_ptrs.remove(&f);
+                if let Some(fixture_ptr) = self.fixture_ptrs.remove(&f) {
+                    user_data.push(fixture_user_data_pointer(fixture_ptr));
+                }
             });
         }
 
+        // Box2D destroys the joints along with the body, so drop our pointers to them first
+        let joints = self.body_to_joints.remove(&entity);
+        if let Some(joints) = joints {
+            for joint_entity in joints {
+                self.joint_ptrs.remove(&joint_entity);
+                self.forget_joint(joint_entity);
+            }
+        }
+
         unsafe {
             let body_ptr = Pin::into_inner_unchecked(body_ptr);
             self.ffi_world.as_mut().DestroyBody(body_ptr);
         }
+        for pointer in user_data {
+            self.user_data.remove(pointer);
+        }
+        self.remove_destroyed_joints();
+        Ok(())
+    }
+
+    /// Drops the pointers to the joints Box2D destroyed along with a body.
+    fn remove_destroyed_joints(&mut self) {
+        let destroyed_joints = self
+            .destruction_listener
+            .borrow_mut()
+            .take_destroyed_joints();
+        if destroyed_joints.is_empty() {
+            return;
+        }
+        self.joint_ptrs
+            .retain(|_, joint_ptr| !destroyed_joints.contains(&joint_ptr.as_ffi_joint()));
     }
 
     pub(crate) fn create_fixture(
         &mut self,
-        fixture: (Entity, &mut b2Fixture),
-        body: (Entity, &mut b2Body),
-    ) {
-        let (fixture_entity, fixture_component) = fixture;
-        let (body_entity, body_component) = body;
+        fixture_entity: Entity,
+        fixture: &mut b2Fixture,
+        body_entity: Entity,
+    ) -> Result<(), PhysicsError> {
+        let fixture_def = fixture.extract_fixture_def();
+        let ffi_fixture =
+            self.create_ffi_fixture(body_entity, fixture_entity, None, &fixture_def)?;
+        self.fixture_ptrs.insert(fixture_entity, ffi_fixture);
+
+        let fixtures_for_body = self.body_to_fixtures.entry(body_entity).or_default();
+        fixtures_for_body.insert(fixture_entity);
+        self.fixture_to_body.insert(fixture_entity, body_entity);
+        Ok(())
+    }
+
+    /// Creates all the fixtures of a [`b2Fixtures`] component. The fixtures are attached to
+    /// the body on the same entity and are identified by their index in the component.
+    pub(crate) fn create_indexed_fixtures(
+        &mut self,
+        body_entity: Entity,
+        fixtures: &b2Fixtures,
+    ) -> Result<(), PhysicsError> {
+        let ffi_fixtures = fixtures
+            .defs()
+            .iter()
+            .enumerate()
+            .map(|(index, fixture_def)| {
+                self.create_ffi_fixture(body_entity, body_entity, Some(index), fixture_def)
+            })
+            .collect::<Result<_, _>>()?;
+        self.indexed_fixture_ptrs.insert(body_entity, ffi_fixtures);
+        Ok(())
+    }
 
-        let mut body_ptr = self.body_ptrs.get_mut(&body_entity).unwrap().as_mut();
-        let mut b2fixture_def = fixture_component.extract_fixture_def().to_ffi();
-        b2fixture_def.as_mut().userData.pointer = std::ptr::addr_of!(fixture_entity) as usize;
+    fn create_ffi_fixture(
+        &mut self,
+        body_entity: Entity,
+        user_data_entity: Entity,
+        fixture_index: Option<usize>,
+        fixture_def: &b2FixtureDef,
+    ) -> Result<Pin<&'a mut ffi::b2Fixture>, PhysicsError> {
+        let Some(body_ptr) = self.body_ptrs.get_mut(&body_entity) else {
+            return Err(PhysicsError::MissingBody(body_entity));
+        };
+        let mut body_ptr = body_ptr.as_mut();
+        let ffi_shape = fixture_def.shape.to_ffi();
+        let mut b2fixture_def = fixture_def.to_ffi(ffi_shape.as_ffi());
+        b2fixture_def.as_mut().userData.pointer = self.user_data.insert(UserData {
+            entity: user_data_entity,
+            fixture_index,
+        });
 
         unsafe {
             let ffi_fixture = body_ptr
@@ -200,47 +691,102 @@ impl<'a> b2World<'a> {
                 .CreateFixture(&*b2fixture_def)
                 .as_mut()
                 .unwrap();
-            let ffi_fixture = Pin::new_unchecked(ffi_fixture);
-            self.fixture_ptrs.insert(fixture_entity, ffi_fixture);
+            Ok(Pin::new_unchecked(ffi_fixture))
         }
-
-        body_component.fixtures.insert(fixture_entity);
-        let fixtures_for_body = self.body_to_fixtures.entry(body.0).or_default();
-        fixtures_for_body.insert(fixture_entity);
-        self.fixture_to_body.insert(fixture_entity, body_entity);
     }
 
     pub(crate) fn register_joint(
         &mut self,
         joint: (Entity, &b2Joint, JointPtr<'a>),
-        _body_a: (Entity, &mut b2Body), // TODO
-        _body_b: (Entity, &mut b2Body), // TODO
+        body_a: (Entity, &mut b2Body),
+        body_b: (Entity, &mut b2Body),
     ) {
-        self.joint_ptrs.insert(joint.0, joint.2);
+        let joint_entity = joint.0;
+        self.joint_ptrs.insert(joint_entity, joint.2);
+        for body_entity in [body_a.0, body_b.0] {
+            self.body_to_joints
+                .entry(body_entity)
+                .or_default()
+                .insert(joint_entity);
+        }
+        self.joint_to_bodies
+            .insert(joint_entity, [body_a.0, body_b.0]);
     }
-    pub(crate) fn destroy_fixture_for_entity(&mut self, entity: Entity) {
-        let fixture_ptr = self.fixture_ptrs.remove(&entity);
 
-        // The body (and the fixture along with it) might have already been destroyed on the C++
+    pub(crate) fn destroy_joint_for_entity(&mut self, entity: Entity) {
+        // The joint might have already been destroyed on the C++ side along with one of its bodies
+        let Some(joint_ptr) = self.joint_ptrs.remove(&entity) else {
+            return;
+        };
+        self.forget_joint(entity);
+
+        unsafe {
+            let joint_ptr = joint_ptr.as_ffi_joint() as *mut ffi::b2Joint;
+            self.ffi_world.as_mut().DestroyJoint(joint_ptr);
+        }
+    }
+
+    fn forget_joint(&mut self, joint_entity: Entity) {
+        let Some(bodies) = self.joint_to_bodies.remove(&joint_entity) else {
+            return;
+        };
+        for body_entity in bodies {
+            if let Some(joints) = self.body_to_joints.get_mut(&body_entity) {
+                joints.remove(&joint_entity);
+            }
+        }
+    }
+    pub(crate) fn destroy_indexed_fixtures_for_entity(&mut self, body_entity: Entity) {
+        let fixture_ptrs = self.indexed_fixture_ptrs.remove(&body_entity);
+
+        // The body (and the fixtures along with it) might have already been destroyed on the C++
         // side through DestroyBody
-        if let None = fixture_ptr {
+        let Some(fixture_ptrs) = fixture_ptrs else {
+            return;
+        };
+
+        let Some(body_ptr) = self.body_ptrs.get_mut(&body_entity) else {
             return;
+        };
+
+        for mut fixture_ptr in fixture_ptrs {
+            let user_data = fixture_user_data_pointer(fixture_ptr.as_mut());
+            unsafe {
+                let fixture_ptr = fixture_ptr.get_unchecked_mut();
+                body_ptr.as_mut().DestroyFixture(fixture_ptr);
+            }
+            self.user_data.remove(user_data);
         }
+    }
 
-        let fixture_ptr = fixture_ptr.unwrap();
+    pub(crate) fn destroy_fixture_for_entity(
+        &mut self,
+        entity: Entity,
+    ) -> Result<(), PhysicsError> {
+        // The body (and the fixture along with it) might have already been destroyed on the C++
+        // side through DestroyBody
+        let Some(mut fixture_ptr) = self.fixture_ptrs.remove(&entity) else {
+            return Ok(());
+        };
+        let user_data = fixture_user_data_pointer(fixture_ptr.as_mut());
 
-        let body_entity = self.fixture_to_body.remove(&entity).unwrap();
-        self.body_to_fixtures
-            .get_mut(&body_entity)
-            .unwrap()
-            .remove(&entity);
+        let Some(body_entity) = self.fixture_to_body.remove(&entity) else {
+            return Err(PhysicsError::MissingFixture(entity));
+        };
+        if let Some(fixtures) = self.body_to_fixtures.get_mut(&body_entity) {
+            fixtures.remove(&entity);
+        }
 
-        let body_ptr = self.body_ptrs.get_mut(&body_entity).unwrap();
+        let Some(body_ptr) = self.body_ptrs.get_mut(&body_entity) else {
+            return Err(PhysicsError::MissingBody(body_entity));
+        };
 
         unsafe {
             let fixture_ptr = fixture_ptr.get_unchecked_mut();
             body_ptr.as_mut().DestroyFixture(fixture_ptr);
         }
+        self.user_data.remove(user_data);
+        Ok(())
     }
 
     pub(crate) fn create_particle_system(
@@ -248,34 +794,213 @@ impl<'a> b2World<'a> {
         entity: Entity,
         particle_system: &mut b2ParticleSystem,
     ) {
+        particle_system.reserve_buffers();
         let definition = particle_system.get_definition().to_ffi();
         let definition: *const ffi::b2ParticleSystemDef = &definition;
         unsafe {
             let ffi_particle_system = self.ffi_world.as_mut().CreateParticleSystem(definition);
             let mut ffi_particle_system = Pin::new_unchecked(ffi_particle_system.as_mut().unwrap());
-            let positions = particle_system.get_positions_mut();
-            let capacity = i32::try_from(positions.capacity()).unwrap();
-            let capacity: int32 = int32::from(capacity);
+            Self::set_particle_buffers(&mut ffi_particle_system, particle_system);
             ffi_particle_system
                 .as_mut()
-                .SetPositionBuffer(positions.as_mut_ptr() as *mut ffi::b2Vec2, capacity);
+                .SetPaused(particle_system.is_paused());
             self.particle_system_ptrs
                 .insert(entity, ffi_particle_system);
         }
     }
 
+    /// Destroys the particle system along with all of its particles and particle groups. The
+    /// groups are reported with [`crate::particles::b2ParticleGroupDestroyedEvent`]s after the
+    /// next step, like the groups Box2D destroys on its own.
+    pub(crate) fn destroy_particle_system_for_entity(&mut self, entity: Entity) {
+        let Some(particle_system_ptr) = self.particle_system_ptrs.remove(&entity) else {
+            return;
+        };
+        self.particle_handle_generations
+            .retain(|(particle_system_entity, _), _| *particle_system_entity != entity);
+        self.silent_particle_handles
+            .retain(|(particle_system_entity, _)| *particle_system_entity != entity);
+        let particle_system: *const ffi::b2ParticleSystem = particle_system_ptr.as_ref().get_ref();
+        // The pointers to the groups dangle once the system is gone, but the groups stay known
+        // by address until the destruction listener has reported them
+        self.particle_group_ptrs.retain(|_, group_ptr| {
+            let group_particle_system: *const ffi::b2ParticleSystem =
+                group_ptr.as_ref().GetParticleSystem();
+            group_particle_system != particle_system
+        });
+        unsafe {
+            self.ffi_world
+                .as_mut()
+                .DestroyParticleSystem(Pin::into_inner_unchecked(particle_system_ptr));
+        }
+    }
+
+    /// Hands the buffers of the particle system over to Box2D. Has to be called again whenever
+    /// the buffers are reallocated.
+    pub(crate) fn set_particle_buffers(
+        ffi_particle_system: &mut Pin<&'a mut ffi::b2ParticleSystem>,
+        particle_system: &mut b2ParticleSystem,
+    ) {
+        let capacity = i32::try_from(particle_system.buffer_capacity()).unwrap();
+        let capacity: int32 = int32::from(capacity);
+        unsafe {
+            let positions = particle_system.get_positions_mut();
+            ffi_particle_system
+                .as_mut()
+                .SetPositionBuffer(positions.as_mut_ptr() as *mut ffi::b2Vec2, capacity);
+            let velocities = particle_system.get_velocities_mut();
+            ffi_particle_system
+                .as_mut()
+                .SetVelocityBuffer(velocities.as_mut_ptr() as *mut ffi::b2Vec2, capacity);
+            let colors = particle_system.get_colors_mut();
+            ffi_particle_system
+                .as_mut()
+                .SetColorBuffer(colors.as_mut_ptr() as *mut ffi::b2ParticleColor, capacity);
+            if particle_system.has_user_data() {
+                let user_data = particle_system.get_user_data_mut();
+                ffi_particle_system
+                    .as_mut()
+                    .SetUserDataBuffer(user_data.as_mut_ptr() as *mut *mut _, capacity);
+            }
+        }
+    }
+
+    /// `particle_system` is the component of the particle system the group belongs to, whose
+    /// buffers may have to grow first.
     pub(crate) fn create_particle_group(
         &mut self,
-        particle_system_entity: Entity,
-        _entity: Entity,
-        particle_group: &b2ParticleGroup,
-    ) {
-        let particle_system_ptr = self
+        particle_system: &mut b2ParticleSystem,
+        entity: Entity,
+        particle_group: &mut b2ParticleGroup,
+    ) -> Result<(), PhysicsError> {
+        let particle_system_entity = particle_group.get_particle_system_entity();
+        let Some(particle_system_ptr) = self.particle_system_ptrs.get_mut(&particle_system_entity)
+        else {
+            return Err(PhysicsError::MissingParticleSystem(particle_system_entity));
+        };
+        let definition = particle_group.get_definition();
+        let radius = particle_system.get_definition().radius;
+        particle_system
+            .reserve_unlimited(particle_system_ptr, definition.max_particle_count(radius));
+        let first_new_index = i32::from(particle_system_ptr.as_ref().GetParticleCount());
+        let ffi_shape = definition.shape.to_ffi();
+        let def = definition.to_ffi(ffi_shape.as_ffi());
+        let ffi_group = particle_system_ptr
+            .as_mut()
+            .CreateParticleGroup(def.as_ref().unwrap());
+        let ffi_group = unsafe { Pin::new_unchecked(ffi_group.as_mut().unwrap()) };
+
+        let color = definition.color;
+        if !color.is_zero() {
+            let first_index = i32::from(ffi_group.as_ref().GetBufferIndex()) as usize;
+            let count = i32::from(ffi_group.as_ref().GetParticleCount()) as usize;
+            let colors = particle_system_ptr.as_mut().GetColorBuffer() as *mut b2ParticleColor;
+            for index in first_index..first_index + count {
+                unsafe {
+                    *colors.add(index) = color;
+                }
+            }
+        }
+
+        let restored_particles = particle_group.take_restored_particles();
+        if !restored_particles.is_empty() {
+            particle_system.restore_group_particles(
+                particle_system_ptr,
+                i32::from(ffi_group.as_ref().GetBufferIndex()) as usize,
+                i32::from(ffi_group.as_ref().GetParticleCount()) as usize,
+                &restored_particles,
+            );
+        }
+        particle_system.destroy_particles_past_max(particle_system_ptr, first_new_index);
+
+        self.particle_group_entities
+            .insert(ffi_group.as_ref().get_ref(), entity);
+        self.particle_group_ptrs.insert(entity, ffi_group);
+        Ok(())
+    }
+
+    /// Destroys the particles of the group. Box2D destroys the group itself once it is empty,
+    /// during the next step.
+    pub(crate) fn destroy_particle_group_for_entity(&mut self, entity: Entity) {
+        let Some(mut group_ptr) = self.particle_group_ptrs.remove(&entity) else {
+            return;
+        };
+        let group: *const ffi::b2ParticleGroup = group_ptr.as_ref().get_ref();
+        self.particle_group_entities.remove(&group);
+        group_ptr.as_mut().DestroyParticles(false);
+    }
+
+    pub(crate) fn get_particle_group_ptr(
+        &self,
+        entity: &Entity,
+    ) -> Option<&Pin<&'a mut ffi::b2ParticleGroup>> {
+        self.particle_group_ptrs.get(entity)
+    }
+
+    pub(crate) fn get_particle_group_ptr_mut(
+        &mut self,
+        entity: &Entity,
+    ) -> Option<&mut Pin<&'a mut ffi::b2ParticleGroup>> {
+        self.particle_group_ptrs.get_mut(entity)
+    }
+
+    /// Applies an impulse to the center of a particle group, changing the velocity of all of its
+    /// particles at once.
+    pub fn apply_linear_impulse_to_particle_group(&mut self, entity: Entity, impulse: Vec2) {
+        if let Some(group_ptr) = self.particle_group_ptrs.get_mut(&entity) {
+            group_ptr.as_mut().ApplyLinearImpulse(&to_b2Vec2(&impulse));
+        }
+    }
+
+    /// Moves the particles of `group_b` into `group_a`. Both groups have to belong to the same
+    /// particle system. `group_b` is destroyed, which is reported with a
+    /// [`crate::particles::b2ParticleGroupDestroyedEvent`].
+    pub fn join_particle_groups(&mut self, group_a: Entity, group_b: Entity) {
+        if group_a == group_b {
+            return;
+        }
+        let (Some(group_a_ptr), Some(group_b_ptr)) = (
+            self.particle_group_ptrs.get(&group_a),
+            self.particle_group_ptrs.get(&group_b),
+        ) else {
+            return;
+        };
+        let particle_system: *const ffi::b2ParticleSystem =
+            group_a_ptr.as_ref().GetParticleSystem();
+        let particle_system_b: *const ffi::b2ParticleSystem =
+            group_b_ptr.as_ref().GetParticleSystem();
+        if particle_system != particle_system_b {
+            warn!(
+                "Can't join particle groups {:?} and {:?} of different particle systems",
+                group_a, group_b
+            );
+            return;
+        }
+
+        let Some(particle_system_ptr) = self
             .particle_system_ptrs
-            .get_mut(&particle_system_entity)
-            .unwrap();
-        let def = particle_group.get_definition().to_ffi();
-        particle_system_ptr.as_mut().CreateParticleGroup(def);
+            .values_mut()
+            .find(|ptr| std::ptr::eq(ptr.as_ref().get_ref(), particle_system))
+        else {
+            warn!(
+                "Can't join particle groups {:?} and {:?}, their particle system doesn't exist",
+                group_a, group_b
+            );
+            return;
+        };
+        // Both groups were looked up above
+        let (Some(group_b_ptr), Some(group_a_ptr)) = (
+            self.particle_group_ptrs.remove(&group_b),
+            self.particle_group_ptrs.get_mut(&group_a),
+        ) else {
+            return;
+        };
+        unsafe {
+            particle_system_ptr.as_mut().JoinParticleGroups(
+                group_a_ptr.as_mut().get_unchecked_mut(),
+                Pin::into_inner_unchecked(group_b_ptr),
+            );
+        }
     }
 
     pub fn step(
@@ -285,12 +1010,152 @@ impl<'a> b2World<'a> {
         position_iterations: i32,
         particle_iterations: i32,
     ) {
+        self.contact_listener.borrow_mut().begin_step(
+            self.step_count + 1,
+            self.elapsed_time + f64::from(time_step),
+        );
+        self.flag_particles_with_handles();
+        let _box2d_globals = lock_box2d_globals();
         self.ffi_world.as_mut().Step(
             time_step,
             ffi::int32::from(velocity_iterations),
             ffi::int32::from(position_iterations),
             ffi::int32::from(particle_iterations),
-        )
+        );
+        self.step_count += 1;
+        self.elapsed_time += f64::from(time_step);
+        self.collect_destroyed_particles();
+    }
+
+    /// Sets the steps [`b2World::run_queued_steps`] runs, which happens once every world is synced
+    /// with the ECS.
+    pub(crate) fn queue_steps(&mut self, queued_steps: QueuedSteps) {
+        self.queued_steps = queued_steps;
+    }
+
+    /// Replaces the forces applied before each of the queued steps. The buffer is kept around, so
+    /// it doesn't have to be reallocated every frame.
+    pub(crate) fn step_forces_mut(&mut self) -> &mut Vec<StepForce> {
+        &mut self.step_forces
+    }
+
+    fn apply_step_forces(&mut self) {
+        for step_force in &self.step_forces {
+            let Some(body_ptr) = self.body_ptrs.get_mut(&step_force.body) else {
+                continue;
+            };
+            let (force, torque) = if step_force.local {
+                let local_center = to_Vec2(body_ptr.as_ref().GetLocalCenter());
+                (
+                    Vec2::from_angle(body_ptr.as_ref().GetAngle()).rotate(step_force.force),
+                    step_force.torque - local_center.perp_dot(step_force.force),
+                )
+            } else {
+                (step_force.force, step_force.torque)
+            };
+            body_ptr
+                .as_mut()
+                .ApplyForceToCenter(&to_b2Vec2(&force), step_force.should_wake);
+            body_ptr.as_mut().ApplyTorque(torque, false);
+        }
+    }
+
+    /// Replaces the targets the kinematic bodies are moved towards before each of the queued
+    /// steps.
+    pub(crate) fn kinematic_targets_mut(&mut self) -> &mut Vec<(Entity, KinematicTarget)> {
+        &mut self.kinematic_targets
+    }
+
+    /// Sets the velocities that take the bodies to their targets in the next step. They are
+    /// recomputed before every step, so a body that reached its target in an earlier step of the
+    /// frame stops there instead of moving on with the same velocity.
+    fn apply_kinematic_targets(&mut self, time_step: f32) {
+        for (entity, target) in &self.kinematic_targets {
+            let Some(body_ptr) = self.body_ptrs.get_mut(entity) else {
+                continue;
+            };
+            let position = to_Vec2(body_ptr.as_ref().GetPosition());
+            let angle = body_ptr.as_ref().GetAngle();
+            let (linear_velocity, angular_velocity) =
+                target.velocities_from(position, angle, time_step);
+            body_ptr
+                .as_mut()
+                .SetLinearVelocity(&to_b2Vec2(&linear_velocity));
+            body_ptr.as_mut().SetAngularVelocity(angular_velocity);
+        }
+    }
+
+    fn record_pre_step_transforms(&mut self) {
+        self.pre_step_transforms.clear();
+        for (entity, body_ptr) in &self.body_ptrs {
+            let body_ptr = body_ptr.as_ref();
+            if body_ptr.IsAwake() {
+                self.pre_step_transforms.insert(
+                    *entity,
+                    (to_Vec2(body_ptr.GetPosition()), body_ptr.GetAngle()),
+                );
+            }
+        }
+    }
+
+    /// The positions and angles of the bodies right before the last step. Bodies that were asleep
+    /// don't move in that step and aren't included.
+    pub(crate) fn get_pre_step_transforms(&self) -> &HashMap<Entity, (Vec2, f32)> {
+        &self.pre_step_transforms
+    }
+
+    pub(crate) fn run_queued_steps(&mut self) {
+        let queued_steps = std::mem::take(&mut self.queued_steps);
+        for step in 0..queued_steps.steps {
+            self.apply_kinematic_targets(queued_steps.time_step);
+            self.apply_step_forces();
+            if step + 1 == queued_steps.steps {
+                self.record_pre_step_transforms();
+            }
+            self.step(
+                queued_steps.time_step,
+                queued_steps.velocity_iterations,
+                queued_steps.position_iterations,
+                queued_steps.particle_iterations,
+            );
+        }
+    }
+
+    /// The number of steps simulated since the world was created.
+    pub fn get_step_count(&self) -> u64 {
+        self.step_count
+    }
+
+    /// The sum of the time steps simulated since the world was created, in seconds.
+    pub fn get_elapsed_time(&self) -> f64 {
+        self.elapsed_time
+    }
+
+    /// The fixture entities attached to a body, in no particular order. The world is the only
+    /// record of which fixtures belong to which body, it is updated as fixtures are created and
+    /// destroyed. Fixtures in a [`b2Fixtures`] component aren't entities of their own, so they
+    /// aren't included.
+    pub fn fixtures_of(&self, body: Entity) -> impl Iterator<Item = Entity> + '_ {
+        self.body_to_fixtures
+            .get(&body)
+            .into_iter()
+            .flat_map(|fixtures| fixtures.iter().copied())
+    }
+
+    /// The body a fixture entity is attached to, or `None` if the fixture hasn't been created
+    /// in the world (yet).
+    pub fn body_of(&self, fixture: Entity) -> Option<Entity> {
+        self.fixture_to_body.get(&fixture).copied()
+    }
+
+    pub fn get_broad_phase_stats(&self) -> b2BroadPhaseStats {
+        let ffi_world = self.ffi_world.as_ref();
+        b2BroadPhaseStats {
+            proxy_count: i32::from(ffi_world.GetProxyCount()),
+            tree_height: i32::from(ffi_world.GetTreeHeight()),
+            tree_balance: i32::from(ffi_world.GetTreeBalance()),
+            tree_quality: ffi_world.GetTreeQuality(),
+        }
     }
 
     pub(crate) fn get_fixtures_attached_to_entity(
@@ -300,6 +1165,13 @@ impl<'a> b2World<'a> {
         self.body_to_fixtures.get(body_entity)
     }
 
+    pub(crate) fn get_joints_attached_to_entity(
+        &self,
+        body_entity: &Entity,
+    ) -> Option<&HashSet<Entity>> {
+        self.body_to_joints.get(body_entity)
+    }
+
     pub(crate) fn get_particle_system_ptr(
         &self,
         particle_system_entity: &Entity,
@@ -307,17 +1179,282 @@ impl<'a> b2World<'a> {
         self.particle_system_ptrs.get(particle_system_entity)
     }
 
+    /// Copies the flags of all the particles of a particle system.
+    pub(crate) fn get_particle_flags(
+        &self,
+        particle_system_entity: &Entity,
+    ) -> Vec<b2ParticleFlags> {
+        let Some(particle_system_ptr) = self.particle_system_ptrs.get(particle_system_entity)
+        else {
+            return Vec::new();
+        };
+        let particle_count = i32::from(particle_system_ptr.as_ref().GetParticleCount()) as usize;
+        let flags = particle_system_ptr.as_ref().GetFlagsBuffer();
+        (0..particle_count)
+            .map(|index| unsafe { b2ParticleFlags::from_bits_retain(u32::from(*flags.add(index))) })
+            .collect()
+    }
+
+    /// The remaining lifetimes of all the particles of a particle system in seconds, 0 for the
+    /// particles that live forever.
+    pub(crate) fn get_particle_lifetimes(&mut self, particle_system_entity: &Entity) -> Vec<f32> {
+        let Some(particle_system_ptr) = self.particle_system_ptrs.get_mut(particle_system_entity)
+        else {
+            return Vec::new();
+        };
+        let particle_count = i32::from(particle_system_ptr.as_ref().GetParticleCount());
+        (0..particle_count)
+            .map(|index| {
+                let lifetime = particle_system_ptr
+                    .as_mut()
+                    .GetParticleLifetime(int32::from(index));
+                // Box2D reports infinite lifetimes as anything not positive
+                lifetime.max(0.)
+            })
+            .collect()
+    }
+
+    /// A handle to the particle that currently has the given index, see [`b2ParticleHandle`].
+    pub fn particle_handle(
+        &mut self,
+        particle_system_entity: Entity,
+        index: i32,
+    ) -> Option<b2ParticleHandle> {
+        let particle_system_ptr = self.particle_system_ptrs.get_mut(&particle_system_entity)?;
+        let particle_count = i32::from(particle_system_ptr.as_ref().GetParticleCount());
+        if index < 0 || index >= particle_count {
+            return None;
+        }
+        let handle_ptr = particle_system_ptr
+            .as_mut()
+            .GetParticleHandleFromIndex(index.into()) as usize;
+        // Asking for the handle of the same particle again returns the same handle
+        let next_generation = &mut self.next_particle_handle_generation;
+        let generation = *self
+            .particle_handle_generations
+            .entry((particle_system_entity, handle_ptr))
+            .or_insert_with(|| {
+                *next_generation = next_generation.wrapping_add(1);
+                *next_generation
+            });
+        Some(b2ParticleHandle {
+            particle_system: particle_system_entity,
+            ptr: handle_ptr,
+            generation,
+        })
+    }
+
+    /// The current index of the particle of a handle, or `None` if the particle or its particle
+    /// system has been destroyed.
+    pub fn particle_index(&mut self, handle: &b2ParticleHandle) -> Option<i32> {
+        // The address of the handle may have been reused for another particle since
+        let generation = self
+            .particle_handle_generations
+            .get(&(handle.particle_system, handle.ptr))?;
+        if *generation != handle.generation {
+            return None;
+        }
+        // Still known, so the particle hasn't been reported as destroyed and the handle is alive
+        let handle_ptr = handle.ptr as *const ffi::b2ParticleHandle;
+        Some(unsafe { i32::from((*handle_ptr).GetIndex()) })
+    }
+
+    /// LiquidFun only reports the destruction of particles with the `DestructionListenerParticle`
+    /// flag, so the particles with handles get it before every step. Their flags can be replaced
+    /// between two steps, but handles are only ever freed during one, so they are all alive here.
+    fn flag_particles_with_handles(&mut self) {
+        for (particle_system_entity, handle_ptr) in self.particle_handle_generations.keys() {
+            let Some(particle_system_ptr) =
+                self.particle_system_ptrs.get_mut(particle_system_entity)
+            else {
+                continue;
+            };
+            let handle = *handle_ptr as *const ffi::b2ParticleHandle;
+            let index = unsafe { i32::from((*handle).GetIndex()) };
+            let flags = unsafe {
+                let flags = particle_system_ptr.as_ref().GetFlagsBuffer();
+                b2ParticleFlags::from_bits_retain(u32::from(*flags.add(index as usize)))
+            };
+            if flags.contains(b2ParticleFlags::DestructionListenerParticle) {
+                continue;
+            }
+            particle_system_ptr.as_mut().SetParticleFlags(
+                int32::from(index),
+                uint32::from((flags | b2ParticleFlags::DestructionListenerParticle).bits()),
+            );
+            self.silent_particle_handles
+                .insert((*particle_system_entity, *handle_ptr));
+        }
+    }
+
+    /// Drops the handles of the particles destroyed during a step, before LiquidFun gets the
+    /// chance to reuse their addresses, and keeps the particles to report.
+    fn collect_destroyed_particles(&mut self) {
+        let destroyed_particles = self
+            .destruction_listener
+            .borrow_mut()
+            .take_destroyed_particles();
+        for (particle_system, index, handle_ptr) in destroyed_particles {
+            let Some(entity) = self
+                .particle_system_ptrs
+                .iter()
+                .find(|(_, ptr)| std::ptr::eq(ptr.as_ref().get_ref(), particle_system))
+                .map(|(entity, _)| *entity)
+            else {
+                continue;
+            };
+            self.particle_handle_generations
+                .remove(&(entity, handle_ptr));
+            // Removed right away, the address can come up again for a particle without a handle
+            if !self.silent_particle_handles.remove(&(entity, handle_ptr)) {
+                self.destroyed_particles.push((entity, index));
+            }
+        }
+    }
+
+    /// The radius of the particles of the particle system with the smallest ones.
+    pub(crate) fn get_min_particle_radius(&self) -> Option<f32> {
+        self.particle_system_ptrs
+            .values()
+            .map(|particle_system_ptr| particle_system_ptr.as_ref().GetRadius())
+            .reduce(f32::min)
+    }
+
+    /// The particle systems in the world, in entity order.
+    pub(crate) fn get_particle_system_entities(&self) -> Vec<Entity> {
+        let mut entities: Vec<Entity> = self.particle_system_ptrs.keys().copied().collect();
+        entities.sort();
+        entities
+    }
+
+    pub(crate) fn get_particle_system_ptr_mut(
+        &mut self,
+        particle_system_entity: &Entity,
+    ) -> Option<&mut Pin<&'a mut ffi::b2ParticleSystem>> {
+        self.particle_system_ptrs.get_mut(particle_system_entity)
+    }
+
+    pub(crate) fn get_contact_listener(&self) -> RefMut<b2ContactListener> {
+        self.contact_listener.borrow_mut()
+    }
+
+    /// Takes the particles destroyed during the last steps, as pairs of particle system entity
+    /// and the index the particle had before it was destroyed.
+    pub(crate) fn take_destroyed_particles(&mut self) -> Vec<(Entity, i32)> {
+        std::mem::take(&mut self.destroyed_particles)
+    }
+
+    /// Takes the entities of the particle groups destroyed during the last steps.
+    pub(crate) fn take_destroyed_particle_groups(&mut self) -> Vec<Entity> {
+        self.destruction_listener
+            .borrow_mut()
+            .take_destroyed_particle_groups()
+            .into_iter()
+            .filter_map(|group| self.particle_group_entities.remove(&group))
+            .inspect(|entity| {
+                self.particle_group_ptrs.remove(entity);
+            })
+            .collect()
+    }
+
+    /// Replaces the default collision filtering with a custom callback. Fixture pairs that are
+    /// already in contact are only filtered again once their fixtures are refiltered.
+    pub fn set_contact_filter(&mut self, callback: impl b2ContactFilterCallback + 'static) {
+        let contact_filter = Arc::new(RefCell::new(b2ContactFilter::new(callback)));
+        let ffi_contact_filter = b2ContactFilterWrapper::new(contact_filter);
+        unsafe {
+            let ffi_filter: *mut ffi::b2ContactFilter = ffi_contact_filter
+                .as_ref()
+                .borrow_mut()
+                .pin_mut()
+                .as_mut()
+                .get_unchecked_mut();
+            self.ffi_world.as_mut().SetContactFilter(ffi_filter);
+        }
+        // Box2D only holds a raw pointer to the filter, so we have to keep it alive
+        self.ffi_contact_filter = Some(ffi_contact_filter);
+    }
+
+    /// Removes the custom contact filter, restoring the default [`crate::dynamics::b2Filter`]
+    /// based filtering.
+    pub fn clear_contact_filter(&mut self) {
+        unsafe {
+            let ffi_filter: *mut ffi::b2ContactFilter =
+                self.default_contact_filter.as_mut().get_unchecked_mut();
+            self.ffi_world.as_mut().SetContactFilter(ffi_filter);
+        }
+        self.ffi_contact_filter = None;
+    }
+
+    /// Copies all the contacts currently in the contact list of the physics world.
+    pub(crate) fn copy_contacts(&mut self, contacts: &mut Vec<b2Contact>) {
+        contacts.clear();
+        unsafe {
+            let mut contact_ptr = self.ffi_world.as_mut().GetContactList();
+            while let Some(contact) = contact_ptr.as_mut() {
+                let mut contact = Pin::new_unchecked(contact);
+                contacts.push(b2Contact::from_ffi_contact(contact.as_mut()));
+                contact_ptr = contact.as_mut().GetNext();
+            }
+        }
+    }
+
+    /// Restores the mixed friction and restitution of the current contacts between the given
+    /// fixture pairs, and clears their tangent speed.
+    pub(crate) fn reset_contacts(&mut self, fixture_pairs: &[(Entity, Entity)]) {
+        if fixture_pairs.is_empty() {
+            return;
+        }
+        unsafe {
+            let mut contact_ptr = self.ffi_world.as_mut().GetContactList();
+            while let Some(contact) = contact_ptr.as_mut() {
+                let mut contact = Pin::new_unchecked(contact);
+                let (fixture_a, fixture_b) = b2Contact::fixture_entities(contact.as_mut());
+                if fixture_pairs.contains(&(fixture_a, fixture_b))
+                    || fixture_pairs.contains(&(fixture_b, fixture_a))
+                {
+                    ContactOverride::reset(contact.as_mut());
+                }
+                contact_ptr = contact.as_mut().GetNext();
+            }
+        }
+    }
+
+    /// The anchor points of a joint in world coordinates.
+    pub(crate) fn get_joint_anchors(&self, joint_entity: &Entity) -> Option<(Vec2, Vec2)> {
+        self.joint_ptrs.get(joint_entity)?.get_anchors()
+    }
+
     pub(crate) fn get_joint_ptr(&mut self, joint_entity: &Entity) -> Option<&mut JointPtr<'a>> {
         self.joint_ptrs.get_mut(joint_entity)
     }
 
     pub fn ray_cast<T: b2RayCastCallback + 'static>(
-        &mut self,
+        &self,
         callback: T,
         start: &Vec2,
         end: &Vec2,
     ) -> T::Result {
-        let ray_cast_wrapper = b2RayCast::new(callback);
+        self.ray_cast_with_filter(callback, start, end, &b2RayCastFilter::default())
+    }
+
+    pub fn ray_cast_with_filter<T: b2RayCastCallback + 'static>(
+        &self,
+        callback: T,
+        start: &Vec2,
+        end: &Vec2,
+        filter: &b2RayCastFilter,
+    ) -> T::Result {
+        let end = *start + (*end - *start) * filter.max_fraction;
+        let particle_systems = self
+            .particle_system_ptrs
+            .iter()
+            .map(|(entity, ptr)| {
+                let ptr: *const ffi::b2ParticleSystem = ptr.as_ref().get_ref();
+                (ptr, *entity)
+            })
+            .collect();
+        let ray_cast_wrapper = b2RayCast::new(callback, filter.clone(), particle_systems);
         let ray_cast_wrapper = Arc::new(RefCell::new(ray_cast_wrapper));
         let ray_cast_callback_wrapper = b2RayCastCallbackWrapper::new(ray_cast_wrapper.clone());
         unsafe {
@@ -328,30 +1465,280 @@ impl<'a> b2World<'a> {
                 .as_mut()
                 .get_unchecked_mut();
             self.ffi_world
+                .as_ref()
+                .RayCast(ffi_callback, &to_b2Vec2(start), &to_b2Vec2(&end));
+        }
+        take_callback_state(ray_cast_wrapper, ray_cast_callback_wrapper)
+            .map(b2RayCast::extract_hits)
+            .unwrap_or_default()
+    }
+
+    pub fn query_aabb<T: b2QueryCallback + 'static>(
+        &self,
+        callback: T,
+        aabb: &b2AABB,
+    ) -> T::Result {
+        self.query_aabb_with_filter(callback, aabb, &b2QueryFilter::default())
+    }
+
+    pub fn query_aabb_with_filter<T: b2QueryCallback + 'static>(
+        &self,
+        callback: T,
+        aabb: &b2AABB,
+        filter: &b2QueryFilter,
+    ) -> T::Result {
+        self.run_query(aabb, b2Query::new(callback, filter.clone()))
+    }
+
+    /// Finds all the fixtures containing the given point.
+    pub fn overlap_point(&self, point: Vec2, filter: &b2QueryFilter) -> Vec<b2QueryHit> {
+        let aabb = b2AABB::new(
+            point - Vec2::splat(LINEAR_SLOP),
+            point + Vec2::splat(LINEAR_SLOP),
+        );
+        let query = b2Query::with_overlap_test(
+            b2QueryAABB::new(),
+            filter.clone(),
+            b2OverlapTest::Point(point),
+        );
+        self.run_query(&aabb, query)
+    }
+
+    /// Finds all the fixtures overlapping a circle.
+    pub fn overlap_circle(
+        &self,
+        center: Vec2,
+        radius: f32,
+        filter: &b2QueryFilter,
+    ) -> Vec<b2QueryHit> {
+        let shape = b2Shape::Circle {
+            radius,
+            position: Vec2::ZERO,
+        };
+        self.overlap_shape(&shape, center, 0., filter)
+    }
+
+    /// Finds all the fixtures overlapping a convex polygon given in world coordinates.
+    pub fn overlap_polygon(&self, vertices: &[Vec2], filter: &b2QueryFilter) -> Vec<b2QueryHit> {
+        let shape = b2Shape::Polygon {
+            vertices: vertices.to_vec(),
+        };
+        self.overlap_shape(&shape, Vec2::ZERO, 0., filter)
+    }
+
+    /// Finds all the fixtures overlapping a shape placed at the given position and angle.
+    pub fn overlap_shape(
+        &self,
+        shape: &b2Shape,
+        position: Vec2,
+        angle: f32,
+        filter: &b2QueryFilter,
+    ) -> Vec<b2QueryHit> {
+        if let Err(error) = shape.validate() {
+            warn!("Skipping overlap query with invalid shape: {}", error);
+            return Vec::new();
+        }
+
+        let ffi_shape = shape.to_ffi();
+        let transform = to_b2Transform(&position, angle);
+        let aabb = shape_aabb(&ffi_shape, &transform);
+
+        let query = b2Query::with_overlap_test(
+            b2QueryAABB::new(),
+            filter.clone(),
+            b2OverlapTest::Shape {
+                shape: ffi_shape,
+                transform,
+            },
+        );
+        self.run_query(&aabb, query)
+    }
+
+    /// Sweeps a shape placed at `start_transform`, a position and an angle, along `translation`
+    /// and returns the first fixture it hits. The shape doesn't rotate during the sweep.
+    pub fn shape_cast(
+        &self,
+        shape: &b2Shape,
+        start_transform: (Vec2, f32),
+        translation: Vec2,
+        filter: &b2RayCastFilter,
+    ) -> Option<b2ShapeCastHit> {
+        if let Err(error) = shape.validate() {
+            warn!("Skipping shape cast with invalid shape: {}", error);
+            return None;
+        }
+
+        let ffi_shape = shape.to_ffi();
+        let (position, angle) = start_transform;
+        let start_aabb = shape_aabb(&ffi_shape, &to_b2Transform(&position, angle));
+        let end_position = position + translation * filter.max_fraction;
+        let end_aabb = shape_aabb(&ffi_shape, &to_b2Transform(&end_position, angle));
+        let mut ffi_aabb = ffi::b2AABB::new().within_box();
+        ffi_aabb.lowerBound = to_b2Vec2(&start_aabb.lower_bound.min(end_aabb.lower_bound));
+        ffi_aabb.upperBound = to_b2Vec2(&start_aabb.upper_bound.max(end_aabb.upper_bound));
+
+        let shape_cast = b2ShapeCast::new(ffi_shape, start_transform, translation, filter.clone());
+        let query_wrapper = Arc::new(RefCell::new(shape_cast));
+        let query_callback_wrapper = b2QueryCallbackWrapper::new(query_wrapper.clone());
+        unsafe {
+            let ffi_callback: *mut ffi::b2QueryCallback = query_callback_wrapper
+                .as_ref()
+                .borrow_mut()
+                .pin_mut()
                 .as_mut()
-                .RayCast(ffi_callback, &to_b2Vec2(start), &to_b2Vec2(end));
+                .get_unchecked_mut();
+            self.ffi_world.as_ref().QueryAABB(ffi_callback, &ffi_aabb);
         }
-        Arc::try_unwrap(ray_cast_wrapper)
-            .unwrap()
-            .into_inner()
-            .extract_hits()
+        take_callback_state(query_wrapper, query_callback_wrapper)
+            .and_then(b2ShapeCast::extract_hit)
     }
 
-    pub fn query_aabb(
+    /// Pushes the dynamic bodies and particles around a point, see [`Explosion`]. Returns the
+    /// bodies that were pushed.
+    pub fn explode(&mut self, explosion: &Explosion) -> Vec<Entity> {
+        explosion.apply(self)
+    }
+
+    /// Pushes the dynamic bodies within `radius` of `center` away from it, with an impulse of
+    /// `impulse` at the center.
+    pub fn apply_radial_impulse(
         &mut self,
-        callback: &mut dyn b2QueryCallback<Result = Vec<Entity>>,
-        aabb: &b2AABB,
+        center: Vec2,
+        radius: f32,
+        impulse: f32,
+        falloff: Falloff,
+        filter: &b2QueryFilter,
     ) -> Vec<Entity> {
+        let explosion = Explosion::new(center, radius, impulse)
+            .with_falloff(falloff)
+            .with_filter(filter.clone());
+        self.explode(&explosion)
+    }
+
+    /// Finds the indices of all the particles of a particle system inside a bounding box. Finds
+    /// none if the particle system doesn't exist.
+    pub fn query_particles_aabb(
+        &mut self,
+        particle_system_entity: Entity,
+        aabb: &b2AABB,
+    ) -> Vec<i32> {
+        let Some(particle_system_ptr) = self.particle_system_ptrs.get_mut(&particle_system_entity)
+        else {
+            return Vec::new();
+        };
         let mut ffi_aabb = ffi::b2AABB::new().within_box();
         ffi_aabb.lowerBound = to_b2Vec2(&aabb.lower_bound);
         ffi_aabb.upperBound = to_b2Vec2(&aabb.upper_bound);
 
-        let mut ffi_callback = ffi::b2QueryCallbackWrapper::new(callback).within_box();
+        let query_wrapper = Arc::new(RefCell::new(b2ParticleQuery::default()));
+        let query_callback_wrapper = b2QueryCallbackWrapper::new(query_wrapper.clone());
         unsafe {
-            self.ffi_world
+            let ffi_callback: *mut ffi::b2QueryCallback = query_callback_wrapper
+                .as_ref()
+                .borrow_mut()
+                .pin_mut()
                 .as_mut()
-                .QueryAABB(&mut ffi_callback, &ffi_aabb);
+                .get_unchecked_mut();
+            particle_system_ptr
+                .as_ref()
+                .QueryAABB(ffi_callback, &ffi_aabb);
         }
-        ffi_callback.into_result()
+        take_callback_state(query_wrapper, query_callback_wrapper)
+            .map(b2ParticleQuery::extract_indices)
+            .unwrap_or_default()
     }
+
+    /// Finds the indices of all the particles of a particle system inside a shape placed at the
+    /// given position and angle. Finds none if the particle system doesn't exist.
+    pub fn query_particles_shape(
+        &mut self,
+        particle_system_entity: Entity,
+        shape: &b2Shape,
+        position: Vec2,
+        angle: f32,
+    ) -> Vec<i32> {
+        if let Err(error) = shape.validate() {
+            warn!("Skipping particle query with invalid shape: {}", error);
+            return Vec::new();
+        }
+
+        let ffi_shape = shape.to_ffi();
+        let transform = to_b2Transform(&position, angle);
+        let aabb = shape_aabb(&ffi_shape, &transform);
+
+        let candidates = self.query_particles_aabb(particle_system_entity, &aabb);
+        let Some(particle_system_ptr) = self.particle_system_ptrs.get_mut(&particle_system_entity)
+        else {
+            return Vec::new();
+        };
+        let positions = particle_system_ptr.as_mut().GetPositionBuffer();
+        candidates
+            .into_iter()
+            .filter(|index| unsafe {
+                let position = positions.add(*index as usize).as_ref().unwrap();
+                ffi_shape
+                    .as_ffi()
+                    .TestPoint(transform.as_ref().get_ref(), position)
+            })
+            .collect()
+    }
+
+    fn run_query<T: b2QueryCallback + 'static>(
+        &self,
+        aabb: &b2AABB,
+        query: b2Query<T>,
+    ) -> T::Result {
+        let mut ffi_aabb = ffi::b2AABB::new().within_box();
+        ffi_aabb.lowerBound = to_b2Vec2(&aabb.lower_bound);
+        ffi_aabb.upperBound = to_b2Vec2(&aabb.upper_bound);
+
+        let query_wrapper = Arc::new(RefCell::new(query));
+        let query_callback_wrapper = b2QueryCallbackWrapper::new(query_wrapper.clone());
+        unsafe {
+            let ffi_callback: *mut ffi::b2QueryCallback = query_callback_wrapper
+                .as_ref()
+                .borrow_mut()
+                .pin_mut()
+                .as_mut()
+                .get_unchecked_mut();
+            self.ffi_world.as_ref().QueryAABB(ffi_callback, &ffi_aabb);
+        }
+        take_callback_state(query_wrapper, query_callback_wrapper)
+            .map(b2Query::extract_hits)
+            .unwrap_or_default()
+    }
+}
+
+/// Takes the state of a callback back once Box2D is done calling into it. The FFI wrapper holds
+/// the only other reference to the state, so it is dropped first.
+fn take_callback_state<T, W>(state: Arc<RefCell<T>>, ffi_wrapper: W) -> Option<T> {
+    drop(ffi_wrapper);
+    let state = Arc::into_inner(state).map(RefCell::into_inner);
+    if state.is_none() {
+        warn!("Callback state is still shared after the query, dropping its results");
+    }
+    state
+}
+
+/// The bounding box around all the children of a shape, e.g. every edge of a chain.
+fn shape_aabb(shape: &OwnedFfiShape, transform: &Pin<Box<ffi::b2Transform>>) -> b2AABB {
+    let shape = shape.as_ffi();
+    (0..ffi_child_count(shape))
+        .map(|child| unsafe {
+            let mut ffi_aabb = ffi::b2AABB::new().within_box();
+            shape.ComputeAABB(
+                ffi_aabb.as_mut().get_unchecked_mut(),
+                transform.as_ref().get_ref(),
+                int32::from(child),
+            );
+            b2AABB::new(to_Vec2(&ffi_aabb.lowerBound), to_Vec2(&ffi_aabb.upperBound))
+        })
+        .reduce(|combined, aabb| {
+            b2AABB::new(
+                combined.lower_bound.min(aabb.lower_bound),
+                combined.upper_bound.max(aabb.upper_bound),
+            )
+        })
+        // A shape without children can't overlap anything, so any box will do
+        .unwrap_or(b2AABB::new(Vec2::ZERO, Vec2::ZERO))
 }
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+
+use crate::dynamics::{b2Contacts, b2World, PhysicsStepCount};
+#[cfg(feature = "parallel_step")]
+use crate::particles::ParticleBuffers;
+
+/// The physics world the body or particle system on the same entity is simulated in. Fixtures
+/// and joints belong to the world of their bodies, particle groups and emitters to the world of
+/// their particle system.
+///
+/// Entities without this component are in the default world, the [`b2World`] resource. Every
+/// other id needs a world in [`b2Worlds`], the entities of an id without one aren't simulated.
+/// The id is read when the entity is created in the physics world, so moving an entity to
+/// another world afterwards isn't supported.
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhysicsWorldId(pub u32);
+
+impl PhysicsWorldId {
+    /// The id of the [`b2World`] resource.
+    pub const DEFAULT: PhysicsWorldId = PhysicsWorldId(0);
+}
+
+/// The physics worlds besides the default one, stored as a non-send resource and keyed by the
+/// [`PhysicsWorldId`] of their entities. Bodies only ever collide with the bodies of their own
+/// world, and every world has its own gravity.
+///
+/// The worlds are synced in the order of their ids, right before the default world, and stepped
+/// along with it with the same [`crate::dynamics::b2WorldSettings`] and
+/// [`crate::dynamics::PhysicsTime`]. While the systems of the [`crate::plugins::LiquidFunPlugin`]
+/// run for a world, it takes the place of the [`b2World`] resource, and its contacts and step
+/// count that of [`b2Contacts`] and [`PhysicsStepCount`]. The contact, joint and particle events
+/// of all the worlds are sent as the same events.
+///
+/// Removing the [`b2World`] resource pauses all the worlds. The snapshots, the character
+/// controller and the diagnostics only cover the default world.
+#[allow(non_camel_case_types)]
+#[derive(Default)]
+pub struct b2Worlds {
+    worlds: BTreeMap<PhysicsWorldId, ExtraWorld>,
+}
+
+/// A world in [`b2Worlds`], along with the state the plugin keeps in resources for the default
+/// world.
+pub(crate) struct ExtraWorld {
+    pub(crate) world: b2World<'static>,
+    pub(crate) contacts: b2Contacts,
+    pub(crate) step_count: PhysicsStepCount,
+    pub(crate) time_accumulator: f32,
+}
+
+impl ExtraWorld {
+    fn new(world: b2World<'static>) -> Self {
+        Self {
+            world,
+            contacts: b2Contacts::default(),
+            step_count: PhysicsStepCount::default(),
+            time_accumulator: 0.,
+        }
+    }
+}
+
+impl b2Worlds {
+    /// Adds a world for the entities with the given id, replacing the previous one. The existing
+    /// entities of the id are created in it on the next update, as when the [`b2World`] resource
+    /// is replaced. The default id belongs to the [`b2World`] resource and is skipped with a
+    /// warning.
+    pub fn insert(&mut self, id: PhysicsWorldId, mut world: b2World<'static>) {
+        if id == PhysicsWorldId::DEFAULT {
+            warn!(
+                "Skipping world {:?}, the default world is the b2World resource",
+                id
+            );
+            return;
+        }
+        world.set_id(id);
+        self.worlds.insert(id, ExtraWorld::new(world));
+    }
+
+    /// Removes the world along with everything in it. Its entities stay around, but aren't
+    /// simulated until another world with the same id is inserted.
+    pub fn remove(&mut self, id: PhysicsWorldId) -> Option<b2World<'static>> {
+        self.worlds.remove(&id).map(|extra_world| extra_world.world)
+    }
+
+    pub fn get(&self, id: PhysicsWorldId) -> Option<&b2World<'static>> {
+        self.worlds.get(&id).map(|extra_world| &extra_world.world)
+    }
+
+    pub fn get_mut(&mut self, id: PhysicsWorldId) -> Option<&mut b2World<'static>> {
+        self.worlds
+            .get_mut(&id)
+            .map(|extra_world| &mut extra_world.world)
+    }
+
+    /// The contacts of a world, which [`b2Contacts`] holds for the default world.
+    pub fn contacts(&self, id: PhysicsWorldId) -> Option<&b2Contacts> {
+        self.worlds
+            .get(&id)
+            .map(|extra_world| &extra_world.contacts)
+    }
+
+    /// The steps simulated in a world, which [`PhysicsStepCount`] holds for the default world.
+    pub fn step_count(&self, id: PhysicsWorldId) -> Option<u64> {
+        self.worlds
+            .get(&id)
+            .map(|extra_world| extra_world.step_count.0)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = PhysicsWorldId> + '_ {
+        self.worlds.keys().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.worlds.is_empty()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (PhysicsWorldId, &ExtraWorld)> {
+        self.worlds
+            .iter()
+            .map(|(id, extra_world)| (*id, extra_world))
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = (PhysicsWorldId, &mut ExtraWorld)> {
+        self.worlds
+            .iter_mut()
+            .map(|(id, extra_world)| (*id, extra_world))
+    }
+
+    /// Replaces every world with an empty one with the same gravity, see
+    /// [`crate::dynamics::ResetPhysicsWorld`].
+    pub(crate) fn reset(&mut self) {
+        for (id, extra_world) in self.worlds.iter_mut() {
+            let mut world = b2World::new(extra_world.world.gravity);
+            world.set_id(*id);
+            *extra_world = ExtraWorld::new(world);
+        }
+    }
+}
+
+/// Everything a physics step works on, moved out of the ECS while the worlds are stepped on the
+/// `AsyncComputeTaskPool` with the `parallel_step` feature.
+#[cfg(feature = "parallel_step")]
+pub(crate) struct SteppingWorlds {
+    pub(crate) default_world: b2World<'static>,
+    pub(crate) extra_worlds: b2Worlds,
+    pub(crate) particle_buffers: Vec<(Entity, ParticleBuffers)>,
+}
+
+// SAFETY: A world isn't `Send` because of the raw pointers to its Box2D objects and the
+// `Arc<RefCell>`s it shares with its own listener wrappers. None of them are handed out beyond
+// borrows of the world, so moving all the worlds together moves everything they point to along.
+// The only memory outside the worlds that Box2D touches during a step are the particle buffers,
+// which are moved in here from their components, and its global statistics counters, which are
+// guarded by `lock_box2d_globals`. The contact filter callbacks are `Send` with this feature.
+#[cfg(feature = "parallel_step")]
+unsafe impl Send for SteppingWorlds {}
+
+#[cfg(feature = "parallel_step")]
+impl SteppingWorlds {
+    pub(crate) fn run_queued_steps(&mut self) {
+        self.default_world.run_queued_steps();
+        for (_, extra_world) in self.extra_worlds.iter_mut() {
+            extra_world.world.run_queued_steps();
+        }
+    }
+}
@@ -1,4 +1,11 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use autocxx::WithinBox;
 use bevy::math::Vec2;
+use bevy::prelude::Entity;
+use libliquidfun_sys::box2d::ffi;
 use libliquidfun_sys::box2d::ffi::b2Vec2;
 
 #[allow(non_snake_case)]
@@ -10,3 +17,78 @@ pub(crate) fn to_b2Vec2(vec2: &Vec2) -> b2Vec2 {
 pub(crate) fn to_Vec2(b2vec2: &b2Vec2) -> Vec2 {
     unsafe { std::mem::transmute_copy(b2vec2) }
 }
+
+/// What the user data of a Box2D body or fixture points to. Box2D only has room for a pointer
+/// there, which can't hold the bits of an entity on 32-bit targets such as wasm.
+#[derive(Debug)]
+pub(crate) struct UserData {
+    pub(crate) entity: Entity,
+    /// The index of a fixture in the [`crate::dynamics::b2Fixtures`] of its body.
+    pub(crate) fixture_index: Option<usize>,
+}
+
+/// Owns the [`UserData`] the bodies and fixtures of a world point to. Every record is boxed, so it
+/// stays at the same address while the table changes, and it has to outlive the Box2D object
+/// pointing to it, since Box2D still reports contacts and destructions while destroying it.
+#[derive(Debug, Default)]
+pub(crate) struct UserDataTable(HashMap<usize, Box<UserData>>);
+
+impl UserDataTable {
+    /// Adds a record and returns the pointer to store in the user data.
+    pub(crate) fn insert(&mut self, user_data: UserData) -> usize {
+        let user_data = Box::new(user_data);
+        let pointer = &*user_data as *const UserData as usize;
+        self.0.insert(pointer, user_data);
+        pointer
+    }
+
+    pub(crate) fn remove(&mut self, pointer: usize) {
+        self.0.remove(&pointer);
+    }
+}
+
+/// # Safety
+/// The pointer has to come from a [`UserDataTable`] that still holds it.
+unsafe fn user_data<'a>(pointer: usize) -> &'a UserData {
+    &*(pointer as *const UserData)
+}
+
+pub(crate) fn fixture_user_data_pointer(fixture: Pin<&mut ffi::b2Fixture>) -> usize {
+    unsafe { fixture.GetUserData().get_unchecked_mut().pointer }
+}
+
+pub(crate) fn body_user_data_pointer(body: Pin<&mut ffi::b2Body>) -> usize {
+    unsafe { body.GetUserData().get_unchecked_mut().pointer }
+}
+
+/// Reads back the entity stored in the user data of a fixture when it was created.
+pub(crate) fn fixture_entity(fixture: Pin<&mut ffi::b2Fixture>) -> Entity {
+    unsafe { user_data(fixture_user_data_pointer(fixture)).entity }
+}
+
+/// Reads back the index stored in the user data of a fixture when it was created, `None` for
+/// fixtures with an entity of their own.
+pub(crate) fn fixture_index(fixture: Pin<&mut ffi::b2Fixture>) -> Option<usize> {
+    unsafe { user_data(fixture_user_data_pointer(fixture)).fixture_index }
+}
+
+/// Reads back the entity stored in the user data of a body when it was created.
+pub(crate) fn body_entity(body: Pin<&mut ffi::b2Body>) -> Entity {
+    unsafe { user_data(body_user_data_pointer(body)).entity }
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn to_b2Transform(position: &Vec2, angle: f32) -> Pin<Box<ffi::b2Transform>> {
+    let mut transform = ffi::b2Transform::new().within_box();
+    transform.as_mut().Set(&to_b2Vec2(position), angle);
+    transform
+}
+
+/// Box2D counts the calls of its distance and time of impact functions in global variables, so
+/// they can't run on two threads at once. With the `parallel_step` feature the worlds are
+/// stepped on another thread while the main thread may still compute distances, so the steps and
+/// the direct calls of `b2Distance` and `b2TestOverlap` hold this lock.
+pub(crate) fn lock_box2d_globals() -> MutexGuard<'static, ()> {
+    static BOX2D_GLOBALS: Mutex<()> = Mutex::new(());
+    BOX2D_GLOBALS.lock().unwrap_or_else(PoisonError::into_inner)
+}
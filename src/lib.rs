@@ -1,14 +1,43 @@
-pub mod collision;
 pub mod plugins;
+#[cfg(feature = "serde")]
+pub mod snapshot;
 pub mod utils;
 
 pub(crate) mod internal;
 
+pub mod collision {
+    mod shape;
+    pub use shape::*;
+    mod polygon_decomposition;
+    pub use polygon_decomposition::*;
+    mod shape_validation;
+    pub use shape_validation::*;
+    mod distance;
+    pub use distance::*;
+    mod grid_outline;
+    mod mesh_shape;
+    pub use mesh_shape::*;
+    mod tilemap;
+    pub use tilemap::*;
+    #[cfg(feature = "image-colliders")]
+    mod image_shape;
+    #[cfg(feature = "image-colliders")]
+    pub use image_shape::*;
+}
+
 pub mod dynamics {
     mod body;
+    mod character_controller;
+    mod contact;
+    mod contact_filter;
+    mod contact_listener;
+    mod destruction_listener;
+    mod explosion;
     mod joints {
         mod joint;
         pub use joint::*;
+        mod joint_limit;
+        pub use joint_limit::*;
         mod revolute_joint;
         pub use revolute_joint::*;
 
@@ -20,25 +49,99 @@ pub mod dynamics {
 
         mod mouse_joint;
         pub use mouse_joint::*;
+
+        mod wheel_joint;
+        pub use wheel_joint::*;
+
+        mod pulley_joint;
+        pub use pulley_joint::*;
+
+        mod rope;
+        pub use rope::*;
     }
     mod fixture;
+    mod one_way_platform;
+    mod physics_error;
+    mod query;
     mod ray_cast;
+    mod shape_cast;
     mod world;
+    mod worlds;
 
     pub use body::*;
+    pub use character_controller::*;
+    pub use contact::*;
+    pub use contact_filter::*;
+    pub use contact_listener::*;
+    pub use destruction_listener::*;
+    pub use explosion::*;
     pub use fixture::*;
     pub use joints::*;
+    pub use one_way_platform::*;
+    pub use physics_error::*;
+    pub use query::*;
     pub use ray_cast::*;
+    pub use shape_cast::*;
     pub use world::*;
+    pub use worlds::*;
 }
 
 pub mod particles {
+    mod buoyancy;
+    pub use buoyancy::*;
     mod particle;
     pub use particle::*;
+    mod particle_contact;
+    pub use particle_contact::*;
+    mod particle_emitter;
+    pub use particle_emitter::*;
+    mod particle_handle;
+    pub use particle_handle::*;
     mod particle_group;
     pub use particle_group::*;
     mod particle_system;
     pub use particle_system::*;
 }
+/// The commonly used types, meant to be glob imported next to `bevy::prelude`.
+///
+/// The lower level items, such as the query and ray cast callbacks or the contact listeners, stay
+/// in their modules.
+pub mod prelude {
+    pub use crate::collision::{b2Shape, FnGridView, GridView};
+    pub use crate::dynamics::{
+        b2AABB, b2BeginContactEvent, b2Body, b2BodyBundle, b2BodyDef, b2BodyOutOfBoundsEvent,
+        b2BodySleepEvent, b2BodyType, b2BroadPhaseStats, b2Contact, b2ContactStayEvent, b2Contacts,
+        b2DistanceJoint, b2DistanceJointDef, b2EndContactEvent, b2Filter, b2Fixture, b2FixtureDef,
+        b2Fixtures, b2Joint, b2JointDestroyedEvent, b2JointLimitEvent, b2JointType, b2MassData,
+        b2MouseJoint, b2MouseJointDef, b2PostSolveEvent, b2PrismaticJoint, b2PrismaticJointDef,
+        b2PulleyJoint, b2PulleyJointDef, b2QueryFilter, b2QueryHit, b2RayCastAll, b2RayCastAny,
+        b2RayCastClosest, b2RayCastFilter, b2RayCastHit, b2RevoluteJoint, b2RevoluteJointDef,
+        b2SensorBeginOverlapEvent, b2SensorEndOverlapEvent, b2ShapeCastHit, b2WheelJoint,
+        b2WheelJointDef, b2World, b2WorldSettings, b2Worlds, BodyCommandsExt,
+        BodyEntityCommandsExt, CharacterController, CollisionTeam, ContactEventFilter,
+        ContactOverride, ContactOverrides, CreateDistanceJoint, CreateMouseJoint,
+        CreatePrismaticJoint, CreatePulleyJoint, CreateRevoluteJoint, CreateRope, CreateWheelJoint,
+        EmitContactEvents, EmitJointLimitEvents, Explosion, ExplosionEvent, ExternalForce, Falloff,
+        GravityScale, Grounded, JointDestroyedReason, JointLimit, KinematicTarget,
+        LocalExternalForce, MovementIntent, OneWayPlatform, OutOfBoundsPolicy, PhysicsLayer,
+        PhysicsRayCaster, PhysicsStepCount, PhysicsTime, PhysicsTransformMode, PhysicsWorldId,
+        RecomputeMass, ResetPhysicsWorld, Rope, RopeDef, RopeJointType, SetBodyVelocity,
+        SyncTransformToBody, TeleportBody, TransformSyncMode, WorldBounds,
+    };
+    pub use crate::particles::{
+        b2EmitterShape, b2ParticleColor, b2ParticleDef, b2ParticleDestroyedEvent,
+        b2ParticleEmitter, b2ParticleFlags, b2ParticleGroup, b2ParticleGroupDef,
+        b2ParticleGroupDestroyedEvent, b2ParticleHandle, b2ParticleSystem,
+        b2ParticleSystemContacts, b2ParticleSystemDef, Buoyancy,
+    };
+    pub use crate::plugins::{
+        LiquidFunCharacterControllerPlugin, LiquidFunDebugDrawPlugin, LiquidFunDiagnosticsPlugin,
+        LiquidFunParticleRenderPlugin, LiquidFunPlugin,
+    };
+    pub use crate::utils::{
+        DebugDrawConfig, DebugDrawFixtures, DebugDrawParticleSystem, RenderParticleSystem,
+    };
+}
+
 #[cfg(test)]
 mod tests {}
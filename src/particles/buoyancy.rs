@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+
+/// The spacing of particles relative to their diameter, as used by LiquidFun to compute the mass
+/// of a particle.
+pub(crate) const PARTICLE_STRIDE: f32 = 0.75;
+
+/// Pushes the body on the same entity up out of the particles it touches, and slows it down
+/// relative to them.
+///
+/// Only the particle systems with a [`crate::particles::b2ParticleSystemContacts`] component are
+/// taken into account. The displaced fluid is estimated from the particles touching the body,
+/// each weighted by how deep it overlaps, rather than from the submerged volume, so
+/// `density_multiplier` usually needs some tuning per shape.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Buoyancy {
+    /// Scales the buoyant force, 1 for the weight of the displaced particles.
+    pub density_multiplier: f32,
+    /// How quickly the velocity of the body matches the velocity of the particles, per second.
+    pub linear_drag: f32,
+    /// How quickly the rotation of the body is damped by the particles, per second.
+    pub angular_drag: f32,
+}
+
+impl Default for Buoyancy {
+    fn default() -> Self {
+        Self {
+            density_multiplier: 1.,
+            linear_drag: 1.,
+            angular_drag: 0.5,
+        }
+    }
+}
@@ -1,9 +1,20 @@
+use std::os::raw::c_uint;
+use std::pin::Pin;
+
+use autocxx::WithinBox;
 use bevy::math::Vec2;
+use bevy::prelude::{Color, Reflect};
 use bitflags::bitflags;
+use libliquidfun_sys::box2d::ffi;
+use libliquidfun_sys::box2d::ffi::uint32;
+
+use crate::internal::to_b2Vec2;
 
 bitflags! {
     #[allow(non_camel_case_types)]
-    #[derive(Debug, Copy, Clone)]
+    #[derive(Debug, Copy, Clone, Reflect)]
+    #[reflect_value]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct b2ParticleFlags: u32 {
         /// Water particle.
         const WaterParticle = 0;
@@ -54,9 +65,80 @@ bitflags! {
 }
 
 #[allow(non_camel_case_types)]
+#[derive(Debug, Clone)]
 pub struct b2ParticleDef {
     pub flags: b2ParticleFlags,
     pub position: Vec2,
     pub velocity: Vec2,
+    /// The lifetime of the particle in seconds, 0 for infinite.
     pub lifetime: f32,
+    pub color: b2ParticleColor,
+    /// Only kept for particle systems created with
+    /// [`crate::particles::b2ParticleSystem::with_user_data`].
+    pub user_data: u32,
+}
+
+impl Default for b2ParticleDef {
+    fn default() -> Self {
+        Self {
+            flags: b2ParticleFlags::WaterParticle,
+            position: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+            lifetime: 0.,
+            color: b2ParticleColor::ZERO,
+            user_data: 0,
+        }
+    }
+}
+
+impl b2ParticleDef {
+    /// The color and user data aren't part of the FFI definition, they are written to the buffers
+    /// once the particle has been created.
+    pub(crate) fn to_ffi(&self) -> Pin<Box<ffi::b2ParticleDef>> {
+        let mut def = ffi::b2ParticleDef::new().within_box();
+        let flags: c_uint = self.flags.bits() as c_uint;
+        def.flags = uint32::from(flags);
+        def.position = to_b2Vec2(&self.position);
+        def.velocity = to_b2Vec2(&self.velocity);
+        def.lifetime = self.lifetime;
+        def
+    }
+}
+
+/// The color of a particle. Particles with the `ColorMixingParticle` flag blend their colors
+/// with the particles they touch. The all-zero color means the particle has no color.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct b2ParticleColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl b2ParticleColor {
+    pub const ZERO: b2ParticleColor = b2ParticleColor::new(0, 0, 0, 0);
+
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+}
+
+impl From<Color> for b2ParticleColor {
+    fn from(color: Color) -> Self {
+        let [r, g, b, a] = color.as_rgba_u8();
+        Self { r, g, b, a }
+    }
+}
+
+impl From<b2ParticleColor> for Color {
+    fn from(color: b2ParticleColor) -> Self {
+        Color::rgba_u8(color.r, color.g, color.b, color.a)
+    }
 }
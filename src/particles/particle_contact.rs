@@ -0,0 +1,128 @@
+use std::pin::Pin;
+
+use bevy::prelude::{Component, Entity, Vec2};
+
+use crate::dynamics::b2World;
+use crate::internal::{body_entity, fixture_entity, to_Vec2};
+
+/// A contact between two particles of the same particle system. Every pair of particles in
+/// contact is only reported once.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone)]
+pub struct b2ParticleContact {
+    pub index_a: usize,
+    pub index_b: usize,
+
+    /// How deep the particles overlap, roughly between 0 and 1.
+    pub weight: f32,
+
+    /// Points from particle A to particle B.
+    pub normal: Vec2,
+}
+
+/// A contact between a particle and a fixture.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone)]
+pub struct b2ParticleBodyContact {
+    pub index: usize,
+    pub body: Entity,
+    pub fixture: Entity,
+
+    /// How deep the particle overlaps the fixture, roughly between 0 and 1.
+    pub weight: f32,
+
+    /// Points from the particle towards the fixture.
+    pub normal: Vec2,
+
+    /// The effective mass of the particle and the body used to solve the contact.
+    pub mass: f32,
+}
+
+/// The contacts of the particles of the particle system on the same entity with each other and
+/// with fixtures, updated after every step. Dense particle systems have a lot of contacts, so
+/// they are only copied for the particle systems with this component.
+#[allow(non_camel_case_types)]
+#[derive(Component, Debug, Default)]
+pub struct b2ParticleSystemContacts {
+    contacts: Vec<b2ParticleContact>,
+    body_contacts: Vec<b2ParticleBodyContact>,
+}
+
+impl b2ParticleSystemContacts {
+    pub fn contacts(&self) -> &[b2ParticleContact] {
+        &self.contacts
+    }
+
+    /// The contacts of a single particle, as pairs of the index of the other particle and the
+    /// normal pointing towards it.
+    pub fn contacts_of(&self, index: usize) -> impl Iterator<Item = (usize, Vec2)> + '_ {
+        self.contacts.iter().filter_map(move |contact| {
+            if contact.index_a == index {
+                Some((contact.index_b, contact.normal))
+            } else if contact.index_b == index {
+                Some((contact.index_a, -contact.normal))
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn body_contacts(&self) -> &[b2ParticleBodyContact] {
+        &self.body_contacts
+    }
+
+    /// The contacts of the particles with the fixtures of a single body.
+    pub fn body_contacts_of(&self, body: Entity) -> impl Iterator<Item = &b2ParticleBodyContact> {
+        self.body_contacts
+            .iter()
+            .filter(move |contact| contact.body == body)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.contacts.clear();
+        self.body_contacts.clear();
+    }
+
+    /// Leaves the contacts empty if the particle system isn't in the world (anymore).
+    pub(crate) fn copy_from_world(&mut self, particle_system_entity: Entity, world: &b2World) {
+        self.clear();
+        let Some(particle_system_ptr) = world.get_particle_system_ptr(&particle_system_entity)
+        else {
+            return;
+        };
+        let contact_count = i32::from(particle_system_ptr.as_ref().GetContactCount()) as usize;
+        let contacts = particle_system_ptr.as_ref().GetContacts();
+        self.contacts.reserve(contact_count);
+        for i in 0..contact_count {
+            let contact = unsafe { contacts.add(i).as_ref().unwrap() };
+            self.contacts.push(b2ParticleContact {
+                index_a: i32::from(contact.GetIndexA()) as usize,
+                index_b: i32::from(contact.GetIndexB()) as usize,
+                weight: contact.GetWeight(),
+                normal: to_Vec2(contact.GetNormal()),
+            });
+        }
+
+        let body_contact_count =
+            i32::from(particle_system_ptr.as_ref().GetBodyContactCount()) as usize;
+        let body_contacts = particle_system_ptr.as_ref().GetBodyContacts();
+        self.body_contacts.reserve(body_contact_count);
+        for i in 0..body_contact_count {
+            let contact = unsafe { body_contacts.add(i).as_ref().unwrap() };
+            let (body, fixture) = unsafe {
+                (
+                    body_entity(Pin::new_unchecked(contact.body.as_mut().unwrap())),
+                    fixture_entity(Pin::new_unchecked(contact.fixture.as_mut().unwrap())),
+                )
+            };
+            self.body_contacts.push(b2ParticleBodyContact {
+                index: i32::from(contact.index) as usize,
+                body,
+                fixture,
+                weight: contact.weight,
+                normal: to_Vec2(&contact.normal),
+                mass: contact.mass,
+            });
+        }
+    }
+}
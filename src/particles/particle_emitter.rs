@@ -0,0 +1,129 @@
+use std::f32::consts::TAU;
+
+use bevy::math::Vec2;
+use bevy::prelude::{Component, Entity};
+
+use crate::particles::{b2ParticleColor, b2ParticleDef, b2ParticleFlags};
+
+/// The area a [`b2ParticleEmitter`] spawns its particles in, centered on its position.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Default, Copy, Clone)]
+pub enum b2EmitterShape {
+    #[default]
+    Point,
+    Box {
+        half_extents: Vec2,
+    },
+    Circle {
+        radius: f32,
+    },
+}
+
+/// Continuously creates particles in the particle system on another entity.
+///
+/// The emission is based on the simulated time rather than the frame time, so pausing the
+/// physics also pauses the emitters, and the same number of particles is created for the same
+/// number of steps. The random spread of the positions and velocities is deterministic too.
+#[allow(non_camel_case_types)]
+#[derive(Component, Debug, Clone)]
+pub struct b2ParticleEmitter {
+    pub particle_system: Entity,
+    pub position: Vec2,
+    pub shape: b2EmitterShape,
+
+    /// The number of particles created per second.
+    pub rate: f32,
+
+    pub velocity: Vec2,
+    /// The maximum length of the random vector added to the velocity of every particle.
+    pub velocity_spread: f32,
+
+    pub flags: b2ParticleFlags,
+    /// The color of the created particles, [`b2ParticleColor::ZERO`] for none.
+    pub color: b2ParticleColor,
+    /// The lifetime of the created particles in seconds, 0 for infinite.
+    pub lifetime: f32,
+
+    /// Disabled emitters only create the particles requested with
+    /// [`b2ParticleEmitter::emit_now`].
+    pub enabled: bool,
+
+    accumulated: f32,
+    burst: u32,
+    random_state: u32,
+}
+
+impl b2ParticleEmitter {
+    pub fn new(particle_system: Entity, position: Vec2, rate: f32) -> Self {
+        Self {
+            particle_system,
+            position,
+            shape: b2EmitterShape::Point,
+            rate,
+            velocity: Vec2::ZERO,
+            velocity_spread: 0.,
+            flags: b2ParticleFlags::WaterParticle,
+            color: b2ParticleColor::ZERO,
+            lifetime: 0.,
+            enabled: true,
+            accumulated: 0.,
+            burst: 0,
+            random_state: 0x9E37_79B9,
+        }
+    }
+
+    /// Creates `count` particles before the next step, whether or not the emitter is enabled.
+    pub fn emit_now(&mut self, count: u32) {
+        self.burst += count;
+    }
+
+    /// Returns the number of particles to create for `elapsed` seconds of simulated time.
+    pub(crate) fn take_particle_count(&mut self, elapsed: f32) -> u32 {
+        let mut count = std::mem::take(&mut self.burst);
+        if self.enabled && self.rate > 0. {
+            self.accumulated += elapsed * self.rate;
+            let whole = self.accumulated.floor();
+            self.accumulated -= whole;
+            count += whole as u32;
+        }
+        count
+    }
+
+    pub(crate) fn next_particle_def(&mut self) -> b2ParticleDef {
+        let offset = match self.shape {
+            b2EmitterShape::Point => Vec2::ZERO,
+            b2EmitterShape::Box { half_extents } => {
+                Vec2::new(self.random_signed(), self.random_signed()) * half_extents
+            }
+            b2EmitterShape::Circle { radius } => self.random_in_circle(radius),
+        };
+        let velocity = self.velocity + self.random_in_circle(self.velocity_spread);
+        b2ParticleDef {
+            flags: self.flags,
+            position: self.position + offset,
+            velocity,
+            lifetime: self.lifetime,
+            color: self.color,
+            user_data: 0,
+        }
+    }
+
+    fn random_in_circle(&mut self, radius: f32) -> Vec2 {
+        let angle = self.random() * TAU;
+        Vec2::from_angle(angle) * radius * self.random().sqrt()
+    }
+
+    fn random_signed(&mut self) -> f32 {
+        self.random() * 2. - 1.
+    }
+
+    /// A xorshift generator, uniform in [0, 1).
+    fn random(&mut self) -> f32 {
+        let mut x = self.random_state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.random_state = x;
+        (x >> 8) as f32 / (1 << 24) as f32
+    }
+}
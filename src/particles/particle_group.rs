@@ -1,27 +1,75 @@
 use crate::collision::b2Shape;
-use crate::internal::to_b2Vec2;
-use crate::particles::particle::b2ParticleFlags;
+use crate::dynamics::b2World;
+use crate::internal::{to_Vec2, to_b2Vec2};
+use crate::particles::particle::{b2ParticleColor, b2ParticleDef, b2ParticleFlags};
+use autocxx::prelude::UniquePtr;
+use bevy::ecs::entity::{EntityMapper, MapEntities};
+use bevy::ecs::reflect::ReflectMapEntities;
 use bevy::math::Vec2;
-use bevy::prelude::{Component, Entity};
+use bevy::prelude::{Component, Entity, Event, FromWorld, Reflect, ReflectComponent, World};
 use libliquidfun_sys::box2d::ffi;
 use libliquidfun_sys::box2d::ffi::uint32;
 use std::os::raw::c_uint;
 
 #[allow(non_camel_case_types)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct b2ParticleGroupDef {
     pub flags: b2ParticleFlags,
     pub shape: b2Shape,
+
+    /// The color given to all the particles of the group, [`b2ParticleColor::ZERO`] for none.
+    pub color: b2ParticleColor,
+
+    /// The lifetime of the particles of the group in seconds, 0 for infinite.
+    pub lifetime: f32,
 }
 
+/// Box2D's default spacing of the particles of a group, relative to their diameter.
+const PARTICLE_STRIDE: f32 = 0.75;
+
 impl b2ParticleGroupDef {
-    pub(crate) fn to_ffi<'a>(&self) -> &ffi::b2ParticleGroupDef {
-        let ffi_shape = self.shape.to_ffi();
+    /// An upper bound of the number of particles Box2D creates for the group, given the radius
+    /// of the particles. Shapes are filled on a grid, while edges and chains are only traced.
+    pub(crate) fn max_particle_count(&self, particle_radius: f32) -> usize {
+        let stride = PARTICLE_STRIDE * 2. * particle_radius;
+        if stride <= 0. || !stride.is_finite() {
+            return 0;
+        }
+        let fill = |lower: Vec2, upper: Vec2| {
+            let cells = ((upper - lower) / stride).ceil() + 2.;
+            (cells.x * cells.y) as usize
+        };
+        let trace = |v1: Vec2, v2: Vec2| (v1.distance(v2) / stride).ceil() as usize + 2;
+        match &self.shape {
+            b2Shape::Circle { radius, position } => fill(
+                *position - Vec2::splat(*radius),
+                *position + Vec2::splat(*radius),
+            ),
+            b2Shape::EdgeTwoSided { v1, v2 } => trace(*v1, *v2),
+            b2Shape::Polygon { vertices } => {
+                let lower = vertices.iter().copied().fold(Vec2::INFINITY, Vec2::min);
+                let upper = vertices.iter().copied().fold(Vec2::NEG_INFINITY, Vec2::max);
+                if vertices.is_empty() {
+                    0
+                } else {
+                    fill(lower, upper)
+                }
+            }
+            b2Shape::ChainLoop { vertices } => (0..vertices.len())
+                .map(|i| trace(vertices[i], vertices[(i + 1) % vertices.len()]))
+                .sum(),
+        }
+    }
+
+    /// The definition points to `shape`, which has to outlive it.
+    pub(crate) fn to_ffi(&self, shape: &ffi::b2Shape) -> UniquePtr<ffi::b2ParticleGroupDef> {
         let flags = self.flags.bits();
         let flags: c_uint = flags as c_uint;
         let flags = uint32::from(flags);
         unsafe {
-            return ffi::CreateParticleGroupDef(
+            // CreateParticleGroupDef allocates the definition, hand its ownership to a UniquePtr
+            UniquePtr::from_raw(ffi::CreateParticleGroupDef(
                 flags,
                 uint32::from(0),
                 to_b2Vec2(&Vec2::ZERO),
@@ -29,21 +77,29 @@ impl b2ParticleGroupDef {
                 to_b2Vec2(&Vec2::ZERO),
                 0.,
                 1.,
-                ffi_shape,
-                0.,
+                shape,
                 0.,
-            )
-            .as_ref()
-            .unwrap();
+                self.lifetime,
+            ))
         }
     }
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component, MapEntities)]
 pub struct b2ParticleGroup {
     particle_system_entity: Entity,
     definition: b2ParticleGroupDef,
+
+    particle_count: usize,
+    center: Vec2,
+    linear_velocity: Vec2,
+
+    /// Replaces the state of the particles created from the definition, see
+    /// [`b2ParticleGroup::with_restored_particles`].
+    #[reflect(ignore)]
+    restored_particles: Vec<b2ParticleDef>,
 }
 
 impl b2ParticleGroup {
@@ -51,9 +107,26 @@ impl b2ParticleGroup {
         b2ParticleGroup {
             particle_system_entity,
             definition: def.clone(),
+            particle_count: 0,
+            center: Vec2::ZERO,
+            linear_velocity: Vec2::ZERO,
+            restored_particles: Vec::new(),
         }
     }
 
+    /// The group is still created from its definition, after which its particles are given the
+    /// state of `particles`, e.g. when restoring a snapshot. Particles the definition creates in
+    /// excess are destroyed, and the ones it doesn't create room for are created outside of the
+    /// group.
+    pub(crate) fn with_restored_particles(mut self, particles: Vec<b2ParticleDef>) -> Self {
+        self.restored_particles = particles;
+        self
+    }
+
+    pub(crate) fn take_restored_particles(&mut self) -> Vec<b2ParticleDef> {
+        std::mem::take(&mut self.restored_particles)
+    }
+
     pub fn get_particle_system_entity(&self) -> Entity {
         self.particle_system_entity
     }
@@ -61,4 +134,57 @@ impl b2ParticleGroup {
     pub fn get_definition(&self) -> &b2ParticleGroupDef {
         &self.definition
     }
+
+    /// The number of particles in the group as of the last physics step.
+    pub fn get_particle_count(&self) -> usize {
+        self.particle_count
+    }
+
+    /// The center of mass of the group as of the last physics step.
+    pub fn get_center(&self) -> Vec2 {
+        self.center
+    }
+
+    /// The average velocity of the particles of the group as of the last physics step.
+    pub fn get_linear_velocity(&self) -> Vec2 {
+        self.linear_velocity
+    }
+
+    pub(crate) fn sync_with_world(&mut self, entity: Entity, world: &b2World) {
+        let Some(group_ptr) = world.get_particle_group_ptr(&entity) else {
+            return;
+        };
+        self.particle_count = i32::from(group_ptr.as_ref().GetParticleCount()) as usize;
+        self.center = to_Vec2(&group_ptr.as_ref().GetCenter());
+        self.linear_velocity = to_Vec2(&group_ptr.as_ref().GetLinearVelocity());
+    }
+}
+
+impl FromWorld for b2ParticleGroup {
+    /// Only used by reflection, the particle system is filled in when a scene is loaded.
+    fn from_world(_world: &mut World) -> Self {
+        b2ParticleGroup::new(
+            Entity::PLACEHOLDER,
+            &b2ParticleGroupDef {
+                flags: b2ParticleFlags::WaterParticle,
+                shape: b2Shape::default(),
+                color: b2ParticleColor::ZERO,
+                lifetime: 0.,
+            },
+        )
+    }
+}
+
+impl MapEntities for b2ParticleGroup {
+    fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+        self.particle_system_entity = entity_mapper.get_or_reserve(self.particle_system_entity);
+    }
+}
+
+/// Sent when Box2D destroys a particle group, for instance once all of its particles are gone.
+/// The [`b2ParticleGroup`] component itself is left in place.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2ParticleGroupDestroyedEvent {
+    pub group: Entity,
 }
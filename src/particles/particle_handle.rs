@@ -0,0 +1,28 @@
+use bevy::prelude::Entity;
+
+/// A reference to a particle that stays valid when the indices of the particles change, as
+/// LiquidFun compacts its buffers whenever particles are destroyed.
+///
+/// Handles are created with [`crate::dynamics::b2World::particle_handle`] and resolved to the
+/// current index of their particle with [`crate::dynamics::b2World::particle_index`]. Once the
+/// particle or its particle system is destroyed, the handle never resolves again, even after
+/// LiquidFun has reused its memory for the handle of another particle.
+///
+/// LiquidFun only reports destroyed particles with the `DestructionListenerParticle` flag, so
+/// particles with handles get it before every step. That doesn't send a
+/// [`crate::particles::b2ParticleDestroyedEvent`] for them unless they had the flag already.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct b2ParticleHandle {
+    pub(crate) particle_system: Entity,
+    /// The address of the handle in the particle system, which owns it.
+    pub(crate) ptr: usize,
+    /// Tells apart the particles LiquidFun gave the same handle address to over time.
+    pub(crate) generation: u32,
+}
+
+impl b2ParticleHandle {
+    pub fn get_particle_system_entity(&self) -> Entity {
+        self.particle_system
+    }
+}
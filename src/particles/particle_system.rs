@@ -1,17 +1,34 @@
+use std::pin::Pin;
+
 use bevy::math::Vec2;
-use bevy::prelude::{Component, Entity};
+use bevy::prelude::{warn, Component, Entity, Event, Reflect, ReflectComponent};
 use libliquidfun_sys::box2d::ffi;
-use libliquidfun_sys::box2d::ffi::int32;
+use libliquidfun_sys::box2d::ffi::{int32, uint32};
 
-use crate::dynamics::b2World;
+use crate::dynamics::{b2World, PhysicsError};
+use crate::particles::{b2ParticleColor, b2ParticleDef, b2ParticleFlags};
+#[cfg(feature = "bevy-inspector-egui")]
+use bevy_inspector_egui::prelude::*;
 
 #[allow(non_camel_case_types)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(
+    feature = "bevy-inspector-egui",
+    derive(InspectorOptions),
+    reflect(InspectorOptions)
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct b2ParticleSystemDef {
     pub strict_contact_check: bool,
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.0))]
     pub density: f32,
     pub gravity_scale: f32,
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0.001))]
     pub radius: f32,
+    /// The maximum number of particles, or 0 for no maximum. The buffers shared with the physics
+    /// world are allocated for this many particles up front. Without a maximum they start out
+    /// small and are reallocated whenever particles are about to be created that don't fit.
+    #[cfg_attr(feature = "bevy-inspector-egui", inspector(min = 0))]
     pub max_count: i32,
     pub pressure_strength: f32,
     pub damping_strength: f32,
@@ -27,6 +44,9 @@ pub struct b2ParticleSystemDef {
     pub static_pressure_relaxation: f32,
     pub static_pressure_iterations: i32,
     pub color_mixing_strength: f32,
+
+    /// Whether particles with a lifetime are destroyed once it runs out, see
+    /// [`b2ParticleSystem::set_particle_lifetime`].
     pub destroy_by_age: bool,
     pub lifetime_granularity: f32,
 }
@@ -90,38 +110,636 @@ impl b2ParticleSystemDef {
     }
 }
 
+/// The number of particles the buffers of a particle system without a maximum start out with.
+const UNLIMITED_INITIAL_CAPACITY: usize = 256;
+
+fn initial_capacity(def: &b2ParticleSystemDef) -> usize {
+    match def.max_count {
+        0 => UNLIMITED_INITIAL_CAPACITY,
+        max_count => max_count.max(0) as usize,
+    }
+}
+
 #[allow(non_camel_case_types)]
-#[derive(Component, Debug)]
+/// The position, velocity and color buffers are handed over to the physics world, which writes
+/// to them directly. They are never pushed to, only their length is updated after every step,
+/// and whenever they are reallocated they are handed over to the physics world again.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
 pub struct b2ParticleSystem {
+    /// The buffers are shared with the physics world, so only the definition is reflected.
+    #[reflect(ignore)]
     positions: Vec<Vec2>,
+    #[reflect(ignore)]
+    velocities: Vec<Vec2>,
+    #[reflect(ignore)]
+    colors: Vec<b2ParticleColor>,
+    /// Only allocated with [`b2ParticleSystem::with_user_data`]. Box2D stores a pointer per
+    /// particle, which is used to hold a plain `u32` tag here.
+    #[reflect(ignore)]
+    user_data: Vec<usize>,
+    /// Copied from the physics world after every step, Box2D doesn't allow sharing this buffer.
+    #[reflect(ignore)]
+    flags: Vec<b2ParticleFlags>,
     definition: b2ParticleSystemDef,
+    #[reflect(ignore)]
+    definition_changed: bool,
+    /// Box2D can't lower the maximum below the current number of particles, so a lower
+    /// `max_count` waits until enough particles are gone.
+    #[reflect(ignore)]
+    max_count_pending: bool,
+    /// Paused particle systems are skipped by the step, while the rest of the world keeps moving.
+    paused: bool,
+    #[reflect(ignore)]
+    paused_changed: bool,
+    #[reflect(ignore)]
+    pending_flags: Vec<(usize, b2ParticleFlags)>,
+    #[reflect(ignore)]
+    pending_lifetimes: Vec<(usize, f32)>,
+    #[reflect(ignore)]
+    pending_particles: Vec<b2ParticleDef>,
+}
+
+/// The buffers of a [`b2ParticleSystem`] the physics world writes to during a step.
+#[cfg(feature = "parallel_step")]
+pub(crate) struct ParticleBuffers {
+    positions: Vec<Vec2>,
+    velocities: Vec<Vec2>,
+    colors: Vec<b2ParticleColor>,
+    user_data: Vec<usize>,
+}
+
+impl Default for b2ParticleSystem {
+    fn default() -> Self {
+        b2ParticleSystem::new(&b2ParticleSystemDef::default())
+    }
 }
 
 impl b2ParticleSystem {
     pub fn new(def: &b2ParticleSystemDef) -> b2ParticleSystem {
+        let capacity = initial_capacity(def);
         b2ParticleSystem {
-            positions: Vec::with_capacity(def.max_count as usize),
+            positions: Vec::with_capacity(capacity),
+            velocities: Vec::with_capacity(capacity),
+            colors: Vec::with_capacity(capacity),
+            user_data: Vec::new(),
+            flags: Vec::new(),
             definition: def.clone(),
+            definition_changed: false,
+            max_count_pending: false,
+            paused: false,
+            paused_changed: false,
+            pending_flags: Vec::new(),
+            pending_lifetimes: Vec::new(),
+            pending_particles: Vec::new(),
         }
     }
 
+    /// Gives every particle a `u32` of user data, see [`b2ParticleSystem::get_user_data`]. Has
+    /// to be called before the particle system is added to the world.
+    pub fn with_user_data(mut self) -> Self {
+        self.user_data = Vec::with_capacity(initial_capacity(&self.definition));
+        self
+    }
+
+    pub fn has_user_data(&self) -> bool {
+        self.user_data.capacity() > 0
+    }
+
     pub fn get_definition(&self) -> &b2ParticleSystemDef {
         &self.definition
     }
 
+    /// The following setters change the parameters Box2D allows changing after the particle
+    /// system has been created. The new values are applied before the next step.
+    pub fn set_density(&mut self, density: f32) {
+        self.definition.density = density;
+        self.definition_changed = true;
+    }
+
+    pub fn set_gravity_scale(&mut self, gravity_scale: f32) {
+        self.definition.gravity_scale = gravity_scale;
+        self.definition_changed = true;
+    }
+
+    pub fn set_radius(&mut self, radius: f32) {
+        self.definition.radius = radius;
+        self.definition_changed = true;
+    }
+
+    pub fn set_damping_strength(&mut self, damping_strength: f32) {
+        self.definition.damping_strength = damping_strength;
+        self.definition_changed = true;
+    }
+
+    /// Raising the maximum reallocates the shared buffers, and 0 removes it. Lowering it below
+    /// the current number of particles keeps the existing particles, but no new ones are created
+    /// until enough of them are destroyed. The particles of new groups that don't fit are
+    /// destroyed during the next step.
+    pub fn set_max_count(&mut self, max_count: i32) {
+        self.definition.max_count = max_count;
+        self.definition_changed = true;
+    }
+
+    /// Pauses or resumes the simulation of this particle system before the next step. Particles
+    /// can still be created and changed while the system is paused.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.paused_changed = true;
+    }
+
+    /// Whether the particle system was paused during the last step.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Makes room for `max_count` particles in the buffers before they are handed over to the
+    /// physics world, since the definition may have changed since they were allocated, e.g. when
+    /// the component was loaded from a scene.
+    pub(crate) fn reserve_buffers(&mut self) {
+        if self.definition.max_count < 0 {
+            warn!(
+                "Particle system with a max_count of {} can't hold any particles",
+                self.definition.max_count
+            );
+        }
+        let capacity = initial_capacity(&self.definition);
+        let additional = capacity.saturating_sub(self.positions.len());
+        self.positions.reserve_exact(additional);
+        self.velocities.reserve_exact(additional);
+        self.colors.reserve_exact(additional);
+        if self.has_user_data() {
+            self.user_data.reserve_exact(additional);
+        }
+    }
+
+    /// Reallocates the shared buffers to hold at least `capacity` particles and hands them over
+    /// to the physics world again. Box2D may have created particles since the buffers were last
+    /// synced, so those are moved along too.
+    fn grow_buffers(
+        &mut self,
+        particle_system_ptr: &mut Pin<&mut ffi::b2ParticleSystem>,
+        capacity: usize,
+    ) {
+        if capacity <= self.buffer_capacity() {
+            return;
+        }
+        let particle_count = i32::from(particle_system_ptr.as_ref().GetParticleCount()) as usize;
+        let particle_count = particle_count.min(self.buffer_capacity());
+        let additional = capacity - particle_count;
+        unsafe {
+            self.positions.set_len(particle_count);
+            self.velocities.set_len(particle_count);
+            self.colors.set_len(particle_count);
+            if self.has_user_data() {
+                self.user_data.set_len(particle_count);
+            }
+        }
+        self.positions.reserve_exact(additional);
+        self.velocities.reserve_exact(additional);
+        self.colors.reserve_exact(additional);
+        if self.has_user_data() {
+            self.user_data.reserve_exact(additional);
+        }
+        b2World::set_particle_buffers(particle_system_ptr, self);
+    }
+
+    /// Box2D only applies the maximum when it grows its own buffers, which it may already have
+    /// done for a higher maximum. Destroys the particles created from `first_new_index` on that
+    /// don't fit anymore.
+    pub(crate) fn destroy_particles_past_max(
+        &self,
+        particle_system_ptr: &mut Pin<&mut ffi::b2ParticleSystem>,
+        first_new_index: i32,
+    ) {
+        let max_count = self.definition.max_count;
+        if max_count <= 0 {
+            return;
+        }
+        let particle_count = i32::from(particle_system_ptr.as_ref().GetParticleCount());
+        for index in first_new_index.max(max_count)..particle_count {
+            particle_system_ptr.as_mut().SetParticleFlags(
+                int32::from(index),
+                uint32::from(b2ParticleFlags::ZombieParticle.bits()),
+            );
+        }
+    }
+
+    /// Makes room for `additional` more particles before they are created, if the particle
+    /// system has no maximum. Box2D can't grow buffers it doesn't own on its own, it just stops
+    /// creating particles once they are full.
+    pub(crate) fn reserve_unlimited(
+        &mut self,
+        particle_system_ptr: &mut Pin<&mut ffi::b2ParticleSystem>,
+        additional: usize,
+    ) {
+        if self.definition.max_count != 0 {
+            return;
+        }
+        let particle_count = i32::from(particle_system_ptr.as_ref().GetParticleCount()) as usize;
+        let required = particle_count + additional;
+        if required > self.buffer_capacity() {
+            let capacity = required.max(2 * self.buffer_capacity());
+            self.grow_buffers(particle_system_ptr, capacity);
+        }
+    }
+
+    /// The number of particles all of the buffers have room for. Box2D refuses to create more
+    /// particles than this.
+    pub(crate) fn buffer_capacity(&self) -> usize {
+        let capacity = self
+            .positions
+            .capacity()
+            .min(self.velocities.capacity())
+            .min(self.colors.capacity());
+        if self.has_user_data() {
+            capacity.min(self.user_data.capacity())
+        } else {
+            capacity
+        }
+    }
+
+    /// Moves the buffers the physics world writes to out of the component, while the world is
+    /// stepped on another thread. Their allocations stay where they are, so the world keeps
+    /// writing to the same memory, but the ECS can't see it until the buffers are restored.
+    #[cfg(feature = "parallel_step")]
+    pub(crate) fn take_buffers(&mut self) -> ParticleBuffers {
+        ParticleBuffers {
+            positions: std::mem::take(&mut self.positions),
+            velocities: std::mem::take(&mut self.velocities),
+            colors: std::mem::take(&mut self.colors),
+            user_data: std::mem::take(&mut self.user_data),
+        }
+    }
+
+    #[cfg(feature = "parallel_step")]
+    pub(crate) fn restore_buffers(&mut self, buffers: ParticleBuffers) {
+        self.positions = buffers.positions;
+        self.velocities = buffers.velocities;
+        self.colors = buffers.colors;
+        self.user_data = buffers.user_data;
+    }
+
     pub(crate) fn get_positions_mut(&mut self) -> &mut Vec<Vec2> {
         &mut self.positions
     }
-    pub fn get_positions(&self) -> &Vec<Vec2> {
-        return &self.positions;
+
+    /// The number of particles as of the last step. Particles queued with
+    /// [`b2ParticleSystem::queue_particle`] are only counted once they have been created.
+    pub fn particle_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// The positions of the live particles as of the last step, indexed like the other
+    /// per-particle getters. The unused capacity of the shared buffer isn't included.
+    pub fn get_positions(&self) -> &[Vec2] {
+        &self.positions
+    }
+
+    pub(crate) fn get_velocities_mut(&mut self) -> &mut Vec<Vec2> {
+        &mut self.velocities
+    }
+
+    pub fn get_velocities(&self) -> &[Vec2] {
+        &self.velocities
+    }
+
+    pub fn get_velocity(&self, index: usize) -> Vec2 {
+        self.velocities[index]
+    }
+
+    /// Sets the velocity of a single particle. The velocity buffer is shared with the physics
+    /// world, so the change is picked up by the next step.
+    pub fn set_particle_velocity(&mut self, index: usize, velocity: Vec2) {
+        self.velocities[index] = velocity;
+    }
+
+    pub(crate) fn get_colors_mut(&mut self) -> &mut Vec<b2ParticleColor> {
+        &mut self.colors
+    }
+
+    pub fn get_colors(&self) -> &[b2ParticleColor] {
+        &self.colors
+    }
+
+    pub fn get_color(&self, index: usize) -> b2ParticleColor {
+        self.colors[index]
+    }
+
+    /// Sets the color of a single particle. Like the velocity, the color buffer is shared with the
+    /// physics world.
+    pub fn set_particle_color(&mut self, index: usize, color: impl Into<b2ParticleColor>) {
+        self.colors[index] = color.into();
+    }
+
+    pub(crate) fn get_user_data_mut(&mut self) -> &mut Vec<usize> {
+        &mut self.user_data
+    }
+
+    /// The user data of a particle, 0 unless it was set with
+    /// [`b2ParticleSystem::set_particle_user_data`] or [`b2ParticleDef::user_data`]. Box2D moves
+    /// the user data along with the particle when other particles are destroyed.
+    ///
+    /// # Panics
+    ///
+    /// If the particle system was created without [`b2ParticleSystem::with_user_data`].
+    pub fn get_user_data(&self, index: usize) -> u32 {
+        assert!(
+            self.has_user_data(),
+            "Particle system was created without user data"
+        );
+        self.user_data[index] as u32
+    }
+
+    /// Sets the user data of a single particle. The user data buffer is shared with the physics
+    /// world, so the change is visible right away.
+    ///
+    /// # Panics
+    ///
+    /// If the particle system was created without [`b2ParticleSystem::with_user_data`].
+    pub fn set_particle_user_data(&mut self, index: usize, user_data: u32) {
+        assert!(
+            self.has_user_data(),
+            "Particle system was created without user data"
+        );
+        self.user_data[index] = user_data as usize;
+    }
+
+    /// The flags of all the particles as of the last step.
+    pub fn get_flags(&self) -> &[b2ParticleFlags] {
+        &self.flags
+    }
+
+    pub fn get_particle_flags(&self, index: usize) -> b2ParticleFlags {
+        self.flags[index]
     }
 
-    pub(crate) fn sync_with_world(&mut self, entity: Entity, b2_world: &b2World) {
-        let particle_system_ptr = b2_world.get_particle_system_ptr(&entity).unwrap();
+    /// Replaces the flags of a single particle, e.g. to turn water into powder. The flags are
+    /// applied before the next step, [`b2ParticleSystem::get_particle_flags`] returns the new
+    /// flags right away.
+    pub fn set_particle_flags(&mut self, index: usize, flags: b2ParticleFlags) {
+        if let Some(current_flags) = self.flags.get_mut(index) {
+            *current_flags = flags;
+        }
+        self.pending_flags.push((index, flags));
+    }
+
+    /// Makes a particle expire after the given number of seconds, or never if `seconds` is not
+    /// positive. Expired particles are only destroyed when `destroy_by_age` is set on the
+    /// definition. The lifetime is applied before the next step.
+    pub fn set_particle_lifetime(&mut self, index: usize, seconds: f32) {
+        self.pending_lifetimes.push((index, seconds));
+    }
+
+    /// Creates a single particle before the next step. The particle is dropped if the system
+    /// already holds `max_count` particles by then.
+    pub fn queue_particle(&mut self, def: b2ParticleDef) {
+        self.pending_particles.push(def);
+    }
+
+    /// Fails if the particle system hasn't been created in the physics world, e.g. because the
+    /// world was replaced, in which case the pending changes are kept for later.
+    pub(crate) fn sync_to_world(
+        &mut self,
+        entity: Entity,
+        b2_world: &mut b2World,
+    ) -> Result<(), PhysicsError> {
+        if b2_world.get_particle_system_ptr(&entity).is_none() {
+            return Err(PhysicsError::MissingParticleSystem(entity));
+        }
+
+        if self.definition_changed {
+            self.definition_changed = false;
+            self.apply_definition(entity, b2_world)?;
+        }
+
+        let Some(particle_system_ptr) = b2_world.get_particle_system_ptr_mut(&entity) else {
+            return Err(PhysicsError::MissingParticleSystem(entity));
+        };
+        if self.paused_changed {
+            self.paused_changed = false;
+            particle_system_ptr.as_mut().SetPaused(self.paused);
+        }
+        if self.max_count_pending {
+            let particle_count = i32::from(particle_system_ptr.as_ref().GetParticleCount());
+            if particle_count <= self.definition.max_count {
+                self.max_count_pending = false;
+                particle_system_ptr
+                    .as_mut()
+                    .SetMaxParticleCount(int32::from(self.definition.max_count));
+            }
+        }
+
+        if self.pending_flags.is_empty()
+            && self.pending_lifetimes.is_empty()
+            && self.pending_particles.is_empty()
+        {
+            return Ok(());
+        }
+
+        for (index, flags) in self.pending_flags.drain(..) {
+            if index >= self.positions.len() {
+                continue;
+            }
+            particle_system_ptr
+                .as_mut()
+                .SetParticleFlags(int32::from(index as i32), uint32::from(flags.bits()));
+        }
+
+        for (index, seconds) in self.pending_lifetimes.drain(..) {
+            if index >= self.positions.len() {
+                continue;
+            }
+            particle_system_ptr
+                .as_mut()
+                .SetParticleLifetime(int32::from(index as i32), seconds);
+        }
+
+        self.reserve_unlimited(particle_system_ptr, self.pending_particles.len());
+        let has_user_data = self.has_user_data();
+        let max_count = self.definition.max_count;
+        for def in self.pending_particles.drain(..) {
+            create_particle(particle_system_ptr, &def, max_count, has_user_data);
+        }
+        Ok(())
+    }
+
+    /// Gives the `count` particles from `first_index` on, which were just created for a group,
+    /// the state of `particles`, see [`crate::particles::b2ParticleGroup`].
+    pub(crate) fn restore_group_particles(
+        &self,
+        particle_system_ptr: &mut Pin<&mut ffi::b2ParticleSystem>,
+        first_index: usize,
+        count: usize,
+        particles: &[b2ParticleDef],
+    ) {
+        let has_user_data = self.has_user_data();
+        let positions = particle_system_ptr.as_mut().GetPositionBuffer() as *mut Vec2;
+        let velocities = particle_system_ptr.as_mut().GetVelocityBuffer() as *mut Vec2;
+        let colors = particle_system_ptr.as_mut().GetColorBuffer() as *mut b2ParticleColor;
+        for (index, particle) in (first_index..first_index + count).zip(particles) {
+            unsafe {
+                *positions.add(index) = particle.position;
+                *velocities.add(index) = particle.velocity;
+                *colors.add(index) = particle.color;
+                if has_user_data {
+                    let user_data = particle_system_ptr.as_mut().GetUserDataBuffer() as *mut usize;
+                    *user_data.add(index) = particle.user_data as usize;
+                }
+            }
+            let index = int32::from(index as i32);
+            particle_system_ptr
+                .as_mut()
+                .SetParticleFlags(index, uint32::from(particle.flags.bits()));
+            if particle.lifetime != 0. {
+                particle_system_ptr
+                    .as_mut()
+                    .SetParticleLifetime(index, particle.lifetime);
+            }
+        }
+
+        for index in (first_index + particles.len())..(first_index + count) {
+            particle_system_ptr.as_mut().SetParticleFlags(
+                int32::from(index as i32),
+                uint32::from(b2ParticleFlags::ZombieParticle.bits()),
+            );
+        }
+        for particle in particles.iter().skip(count) {
+            create_particle(
+                particle_system_ptr,
+                particle,
+                self.definition.max_count,
+                has_user_data,
+            );
+        }
+    }
+
+    fn apply_definition(
+        &mut self,
+        entity: Entity,
+        b2_world: &mut b2World,
+    ) -> Result<(), PhysicsError> {
+        let Some(particle_system_ptr) = b2_world.get_particle_system_ptr_mut(&entity) else {
+            return Err(PhysicsError::MissingParticleSystem(entity));
+        };
+        let definition = &self.definition;
+        particle_system_ptr.as_mut().SetDensity(definition.density);
+        particle_system_ptr
+            .as_mut()
+            .SetGravityScale(definition.gravity_scale);
+        particle_system_ptr.as_mut().SetRadius(definition.radius);
+        particle_system_ptr
+            .as_mut()
+            .SetDamping(definition.damping_strength);
+
+        // Without a maximum, the buffers are grown right before particles are created instead
+        let particle_count = i32::from(particle_system_ptr.as_ref().GetParticleCount());
+        let max_count = match definition.max_count {
+            0 => 0,
+            max_count => max_count.max(particle_count),
+        };
+        self.max_count_pending = max_count != self.definition.max_count;
+        self.grow_buffers(particle_system_ptr, max_count.max(0) as usize);
+        particle_system_ptr
+            .as_mut()
+            .SetMaxParticleCount(int32::from(max_count));
+        Ok(())
+    }
+
+    pub(crate) fn sync_with_world(
+        &mut self,
+        entity: Entity,
+        b2_world: &b2World,
+    ) -> Result<(), PhysicsError> {
+        let Some(particle_system_ptr) = b2_world.get_particle_system_ptr(&entity) else {
+            return Err(PhysicsError::MissingParticleSystem(entity));
+        };
         let particle_count = particle_system_ptr.as_ref().GetParticleCount();
-        let particle_count = i32::from(particle_count) as usize;
+        let mut particle_count = i32::from(particle_count) as usize;
+        let was_paused = self.paused;
+        if !self.paused_changed {
+            self.paused = particle_system_ptr.as_ref().GetPaused();
+        }
+        // Box2D is limited to the capacity of the buffers, so this only guards the lengths below
+        if particle_count > self.buffer_capacity() {
+            warn!(
+                "Particle system {:?} outgrew its buffers, ignoring the particles past {}",
+                entity,
+                self.buffer_capacity()
+            );
+            particle_count = self.buffer_capacity();
+        }
         unsafe {
             self.positions.set_len(particle_count);
+            self.velocities.set_len(particle_count);
+            self.colors.set_len(particle_count);
+            if self.has_user_data() {
+                self.user_data.set_len(particle_count);
+            }
         }
+
+        // The particles didn't move, so the flags only have to be copied for new particles
+        if was_paused && self.paused && particle_count == self.flags.len() {
+            return Ok(());
+        }
+
+        let flags = particle_system_ptr.as_ref().GetFlagsBuffer();
+        self.flags.clear();
+        self.flags.extend((0..particle_count).map(|index| unsafe {
+            b2ParticleFlags::from_bits_retain(u32::from(*flags.add(index)))
+        }));
+        Ok(())
     }
 }
+
+/// Creates a particle along with its color and user data, unless the particle system already
+/// holds `max_count` particles. Box2D only applies the maximum when it grows its own buffers.
+fn create_particle(
+    particle_system_ptr: &mut Pin<&mut ffi::b2ParticleSystem>,
+    def: &b2ParticleDef,
+    max_count: i32,
+    has_user_data: bool,
+) {
+    let particle_count = i32::from(particle_system_ptr.as_ref().GetParticleCount());
+    if max_count > 0 && particle_count >= max_count {
+        return;
+    }
+    let index = particle_system_ptr.as_mut().CreateParticle(&*def.to_ffi());
+    let index = i32::from(index);
+    // The particle system is full
+    if index < 0 {
+        return;
+    }
+    if has_user_data {
+        unsafe {
+            let user_data = particle_system_ptr.as_mut().GetUserDataBuffer() as *mut usize;
+            *user_data.add(index as usize) = def.user_data as usize;
+        }
+    }
+    if def.color.is_zero() {
+        return;
+    }
+    unsafe {
+        let colors = particle_system_ptr.as_mut().GetColorBuffer() as *mut b2ParticleColor;
+        *colors.add(index as usize) = def.color;
+    }
+}
+
+/// The number of particle iterations LiquidFun recommends for particles of the given radius
+/// under the given gravity, in m/s², so that they neither overlap nor bounce off each other.
+pub fn calculate_particle_iterations(gravity: f32, radius: f32, time_step: f32) -> i32 {
+    i32::from(ffi::b2CalculateParticleIterations(
+        gravity, radius, time_step,
+    ))
+}
+
+/// Sent for every particle destroyed during a step, either because its lifetime ran out or
+/// because it was removed explicitly. Only sent for particles with the
+/// `DestructionListenerParticle` flag. `index` is the index the particle had before it was
+/// destroyed, the remaining particles are compacted afterwards.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2ParticleDestroyedEvent {
+    pub system: Entity,
+    pub index: i32,
+}
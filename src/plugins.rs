@@ -1,168 +1,1103 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
 use crate::collision::b2Shape;
+#[cfg(feature = "parallel_step")]
+use crate::dynamics::SteppingWorlds;
 use crate::dynamics::{
-    b2Body, b2DistanceJoint, b2Fixture, b2Joint, b2PrismaticJoint, b2RevoluteJoint, b2World,
-    b2WorldSettings, ExternalForce, JointPtr,
+    b2BeginContactEvent, b2Body, b2BodyOutOfBoundsEvent, b2BodySleepEvent, b2BodyType, b2Contact,
+    b2ContactImpulse, b2ContactStayEvent, b2Contacts, b2DistanceJoint, b2EndContactEvent,
+    b2Fixture, b2Fixtures, b2Joint, b2JointDestroyedEvent, b2JointLimitEvent, b2JointReactions,
+    b2MassData, b2MouseJoint, b2PostSolveEvent, b2PrismaticJoint, b2PulleyJoint, b2RayCastFilter,
+    b2RevoluteJoint, b2SensorBeginOverlapEvent, b2SensorEndOverlapEvent, b2TeamContactFilter,
+    b2WheelJoint, b2World, b2WorldSettings, b2Worlds, CharacterController, CollisionTeam,
+    ContactEventFilter, ContactOverrides, EmitContactEvents, EmitJointLimitEvents, ExplosionEvent,
+    ExternalForce, ExtraWorld, GravityScale, Grounded, JointDestroyedReason, JointLimits,
+    KinematicTarget, LocalExternalForce, MovementIntent, OneWayPlatform, OutOfBoundsPolicy,
+    PhysicsError, PhysicsStepCount, PhysicsTime, PhysicsTransformMode, PhysicsWorldId, QueuedSteps,
+    RecomputeMass, ResetPhysicsWorld, SetBodyVelocity, StepForce, SyncJointFromWorld,
+    SyncJointToWorld, SyncTransformToBody, TeleportBody, ToJointPtr, TransformSyncMode,
+    WorldBounds,
+};
+use crate::internal::{to_Vec2, to_b2Vec2};
+use crate::particles::{
+    b2ParticleDestroyedEvent, b2ParticleEmitter, b2ParticleGroup, b2ParticleGroupDestroyedEvent,
+    b2ParticleSystem, b2ParticleSystemContacts, calculate_particle_iterations, Buoyancy,
+    PARTICLE_STRIDE,
 };
-use crate::internal::to_b2Vec2;
-use crate::particles::{b2ParticleGroup, b2ParticleSystem};
-use crate::utils::{DebugDrawFixtures, DebugDrawParticleSystem};
+use crate::utils::{
+    DebugDrawConfig, DebugDrawFixtures, DebugDrawParticleSystem, RenderParticleSystem,
+};
+use bevy::diagnostic::{Diagnostic, DiagnosticId, Diagnostics, RegisterDiagnostic};
+use bevy::ecs::schedule::{ScheduleLabel, SystemConfigs};
+use bevy::hierarchy::despawn_with_children_recursive;
 use bevy::prelude::*;
+use bevy::render::mesh::Indices;
+use bevy::render::render_resource::{Extent3d, PrimitiveTopology, TextureDimension, TextureFormat};
+use bevy::render::view::NoFrustumCulling;
+use bevy::sprite::MaterialMesh2dBundle;
+#[cfg(feature = "parallel_step")]
+use bevy::tasks::futures_lite::future;
+#[cfg(feature = "parallel_step")]
+use bevy::tasks::{AsyncComputeTaskPool, Task};
 use bevy::transform::TransformSystem;
 
-#[derive(Default)]
+/// Steps the physics world and keeps it in sync with the ECS.
+///
+/// Changes are applied to the physics world in entity order rather than query order, since the
+/// order bodies and fixtures are created, destroyed and moved in affects the results of the
+/// solver. Identical spawn sequences therefore give identical simulations, as long as they run
+/// the same build on the same platform and step the same number of times; the number of steps
+/// per frame depends on the frame time.
+///
+/// The plugin inserts a [`b2World`] with the configured gravity. It can be removed, which pauses
+/// the physics, and replaced with `insert_non_send_resource` at any time. The existing physics
+/// entities are then created again in the new world, keeping the state of their components,
+/// except for the particle groups which start over from their definitions.
+///
+/// Further worlds can be added to the [`b2Worlds`] resource the plugin inserts, the systems then
+/// run once for each of them before running for the default world.
+///
+/// Only the time plugin is required, so the physics also runs with `MinimalPlugins`, e.g. on a
+/// server.
+///
+/// With the `parallel_step` feature and outside of [`LiquidFunPlugin::new_in_fixed_update`], the
+/// steps run on the `AsyncComputeTaskPool` from the end of `Last` until the start of the next
+/// frame, while the frame is rendered. Their results are synced back in `First`, so they are
+/// rendered a frame later than without the feature. While the steps run, the [`b2World`] and
+/// [`b2Worlds`] resources are missing and the particle buffers are empty, so systems that use
+/// them shouldn't run in `Last`.
 pub struct LiquidFunPlugin {
     settings: b2WorldSettings,
+    gravity: Vec2,
+    sync_transforms: bool,
+    fixed_update: bool,
+}
+
+impl Default for LiquidFunPlugin {
+    fn default() -> Self {
+        LiquidFunPlugin::new(b2WorldSettings::default())
+    }
 }
 
 impl LiquidFunPlugin {
     pub fn new(settings: b2WorldSettings) -> LiquidFunPlugin {
-        LiquidFunPlugin { settings }
+        LiquidFunPlugin {
+            settings,
+            gravity: Vec2::new(0., -9.81),
+            sync_transforms: true,
+            fixed_update: false,
+        }
+    }
+
+    /// Runs the physics in `FixedUpdate` instead of `PostUpdate`, one step per fixed update, so
+    /// it stays in lockstep with gameplay systems in `FixedUpdate`. The `time_step` of the
+    /// settings follows the timestep of `Time<Fixed>`, and changes to it are passed on to
+    /// `Time<Fixed>`. The time scale and step limits of [`PhysicsTime`] and [`b2WorldSettings`]
+    /// are replaced by those of Bevy's virtual time.
+    pub fn new_in_fixed_update(settings: b2WorldSettings) -> LiquidFunPlugin {
+        LiquidFunPlugin {
+            fixed_update: true,
+            ..LiquidFunPlugin::new(settings)
+        }
+    }
+
+    /// Leaves the `Transform` of the bodies alone, for apps that only need the physics state,
+    /// such as headless servers.
+    pub fn headless(mut self) -> LiquidFunPlugin {
+        self.sync_transforms = false;
+        self
+    }
+
+    pub fn with_gravity(mut self, gravity: Vec2) -> LiquidFunPlugin {
+        self.gravity = gravity;
+        self
     }
 }
 
 impl Plugin for LiquidFunPlugin {
     fn build(&self, app: &mut App) {
+        let sync_transforms = self.sync_transforms;
         app.insert_resource(self.settings.clone())
+            .insert_non_send_resource(b2World::new(self.gravity))
+            .init_non_send_resource::<b2Worlds>()
             .insert_resource(PhysicsTimeAccumulator(0.))
+            .init_resource::<PhysicsTime>()
+            .init_resource::<PhysicsStepCount>()
+            .init_resource::<b2Contacts>()
+            .init_resource::<ContactEventFilter>()
+            .init_resource::<ContactOverrides>()
+            .register_type::<b2WorldSettings>()
+            .register_type::<b2Body>()
+            .register_type::<GravityScale>()
+            .register_type::<PhysicsWorldId>()
+            .register_type::<ExternalForce>()
+            .register_type::<LocalExternalForce>()
+            .register_type::<KinematicTarget>()
+            .register_type::<TeleportBody>()
+            .register_type::<SetBodyVelocity>()
+            .register_type::<RecomputeMass>()
+            .register_type::<b2MassData>()
+            .register_type::<PhysicsTransformMode>()
+            .register_type::<SyncTransformToBody>()
+            .register_type::<b2Fixture>()
+            .register_type::<b2Fixtures>()
+            .register_type::<OneWayPlatform>()
+            .register_type::<CollisionTeam>()
+            .register_type::<b2Joint>()
+            .register_type::<b2JointReactions>()
+            .register_type::<b2RevoluteJoint>()
+            .register_type::<b2PrismaticJoint>()
+            .register_type::<b2DistanceJoint>()
+            .register_type::<b2PulleyJoint>()
+            .register_type::<b2MouseJoint>()
+            .register_type::<b2WheelJoint>()
+            .register_type::<b2ParticleSystem>()
+            .register_type::<b2ParticleGroup>()
+            .register_type::<Buoyancy>()
+            .add_event::<b2BeginContactEvent>()
+            .add_event::<b2EndContactEvent>()
+            .add_event::<b2PostSolveEvent>()
+            .add_event::<b2ContactStayEvent>()
+            .add_event::<b2SensorBeginOverlapEvent>()
+            .add_event::<b2SensorEndOverlapEvent>()
+            .add_event::<b2BodySleepEvent>()
+            .add_event::<b2BodyOutOfBoundsEvent>()
+            .add_event::<b2JointDestroyedEvent>()
+            .add_event::<b2JointLimitEvent>()
+            .add_event::<b2ParticleDestroyedEvent>()
+            .add_event::<b2ParticleGroupDestroyedEvent>()
+            .add_event::<ExplosionEvent>()
+            .add_event::<ResetPhysicsWorld>()
             .add_systems(PreUpdate, clear_forces)
+            .add_systems(
+                PostUpdate,
+                reset_physics_world
+                    .run_if(physics_world_exists)
+                    .before(create_bodies),
+            );
+
+        if self.fixed_update {
+            app.add_systems(
+                FixedUpdate,
+                sync_time_step_from_fixed_time.before(create_bodies),
+            )
+            .add_systems(
+                FixedUpdate,
+                physics_systems(true)
+                    .after(sync_time_step_from_fixed_time)
+                    .run_if(physics_world_exists),
+            )
             .add_systems(
                 PostUpdate,
                 (
-                    create_bodies,
-                    create_fixtures,
-                    create_revolute_joints,
-                    create_prismatic_joints,
-                    create_distance_joints,
-                    create_particle_systems,
-                    create_particle_groups,
-                    destroy_removed_fixtures,
-                    destroy_removed_bodies,
-                    apply_deferred,
-                    sync_bodies_to_world,
-                    sync_revolute_joints_to_world,
-                    sync_prismatic_joints_to_world,
-                    sync_distance_joints_to_world,
-                    apply_forces,
-                    step_physics,
-                    sync_bodies_from_world,
-                    sync_particle_systems_from_world,
-                    update_transforms,
+                    sync_overstep_from_fixed_time,
+                    update_transforms.run_if(move || sync_transforms),
                 )
                     .chain(),
             );
+        } else {
+            #[cfg(not(feature = "parallel_step"))]
+            app.add_systems(
+                PostUpdate,
+                physics_systems(false)
+                    .after(reset_physics_world)
+                    .run_if(physics_world_exists),
+            )
+            .add_systems(
+                PostUpdate,
+                update_transforms
+                    .run_if(move || sync_transforms)
+                    .after(sync_bodies_from_world),
+            );
+            // The steps run from the end of the frame to the start of the next one, alongside
+            // the rendering, so the systems that draw the physics state go first
+            #[cfg(feature = "parallel_step")]
+            app.add_systems(
+                PostUpdate,
+                sync_worlds_before_step(false)
+                    .after(reset_physics_world)
+                    .run_if(physics_world_exists),
+            )
+            .add_systems(
+                Last,
+                start_steps
+                    .after(draw_fixtures)
+                    .after(draw_particle_systems)
+                    .after(draw_joints)
+                    .after(update_particle_meshes)
+                    .run_if(physics_world_exists),
+            )
+            .add_systems(First, finish_steps)
+            .add_systems(
+                First,
+                (
+                    sync_worlds_after_step(),
+                    update_transforms.run_if(move || sync_transforms),
+                )
+                    .chain()
+                    .after(finish_steps)
+                    .run_if(physics_world_exists),
+            );
+        }
     }
 }
 
+/// Everything that happens around a physics step, in order, for the default world and every
+/// world in [`b2Worlds`]. All the worlds are synced to before any of them is stepped, so
+/// [`step_worlds`] can step them together.
+fn physics_systems(fixed_update: bool) -> SystemConfigs {
+    (
+        sync_worlds_before_step(fixed_update),
+        step_worlds,
+        sync_worlds_after_step(),
+    )
+        .chain()
+}
+
+fn sync_worlds_before_step(fixed_update: bool) -> SystemConfigs {
+    (
+        move |world: &mut World| run_extra_worlds(world, ExtraWorldPhase::BeforeStep, fixed_update),
+        physics_systems_before_step(fixed_update),
+    )
+        .chain()
+}
+
+fn sync_worlds_after_step() -> SystemConfigs {
+    (
+        // Only the schedules of the phase before the step depend on the update mode
+        |world: &mut World| run_extra_worlds(world, ExtraWorldPhase::AfterStep, false),
+        physics_systems_after_step(),
+    )
+        .chain()
+}
+
+/// Syncs a world with the ECS and decides how many steps it takes.
+fn physics_systems_before_step(fixed_update: bool) -> SystemConfigs {
+    let step = if fixed_update {
+        step_physics_fixed.into_configs()
+    } else {
+        step_physics.into_configs()
+    };
+    (
+        create_bodies,
+        create_fixtures,
+        (
+            create_joints::<b2RevoluteJoint>,
+            create_joints::<b2PrismaticJoint>,
+            create_joints::<b2DistanceJoint>,
+            create_joints::<b2MouseJoint>,
+            create_joints::<b2WheelJoint>,
+            create_joints::<b2PulleyJoint>,
+        ),
+        create_particle_systems,
+        create_particle_groups,
+        (
+            destroy_removed_joints,
+            destroy_removed_fixtures,
+            destroy_removed_bodies,
+            destroy_removed_particle_groups,
+            destroy_removed_particle_systems,
+        )
+            .chain(),
+        apply_deferred,
+        (
+            sync_settings_to_world,
+            sync_gravity_to_world,
+            sync_transforms_to_bodies.before(sync_bodies_to_world),
+            sync_bodies_to_world,
+            apply_body_overrides.after(sync_bodies_to_world),
+            apply_gravity_scale,
+            sync_fixtures_to_world,
+            recompute_mass
+                .after(sync_bodies_to_world)
+                .after(sync_fixtures_to_world),
+            apply_mass_data
+                .after(sync_bodies_to_world)
+                .after(sync_fixtures_to_world)
+                .after(recompute_mass),
+            sync_one_way_platforms,
+            sync_contact_event_markers,
+            sync_contact_overrides,
+            update_team_contact_filter,
+            emit_particles.before(sync_particle_systems_to_world),
+            sync_particle_systems_to_world,
+        ),
+        (
+            sync_joints_to_world::<b2RevoluteJoint>,
+            sync_joints_to_world::<b2PrismaticJoint>,
+            sync_joints_to_world::<b2DistanceJoint>,
+            sync_joints_to_world::<b2MouseJoint>,
+            sync_joints_to_world::<b2WheelJoint>,
+            sync_joints_to_world::<b2PulleyJoint>,
+        ),
+        (
+            apply_kinematic_targets,
+            apply_forces,
+            apply_particle_group_forces,
+            apply_buoyancy,
+            apply_explosions,
+        ),
+        step,
+        mark_world_synced,
+    )
+        .chain()
+}
+
+/// Syncs the ECS with a world that has been stepped.
+fn physics_systems_after_step() -> SystemConfigs {
+    (
+        sync_step_count,
+        (
+            copy_contacts,
+            copy_particle_contacts,
+            send_contact_events.after(copy_contacts),
+            send_particle_destruction_events,
+        ),
+        sync_bodies_from_world,
+        check_world_bounds.run_if(resource_exists::<WorldBounds>()),
+        (
+            sync_joints_from_world::<b2RevoluteJoint>,
+            sync_joints_from_world::<b2PrismaticJoint>,
+            sync_joints_from_world::<b2DistanceJoint>,
+            sync_joints_from_world::<b2WheelJoint>,
+            sync_joints_from_world::<b2PulleyJoint>,
+            sync_joint_reactions_from_world,
+        ),
+        (
+            send_joint_limit_events::<b2RevoluteJoint>,
+            send_joint_limit_events::<b2PrismaticJoint>,
+        ),
+        (
+            sync_particle_systems_from_world,
+            sync_particle_groups_from_world,
+        ),
+    )
+        .chain()
+}
+
+/// Whether an entity with the given id belongs to the world the physics systems currently run
+/// for. The systems run for every world in turn, so they have to skip the entities of the others.
+fn in_world(b2_world: &b2World, world_id: Option<&PhysicsWorldId>) -> bool {
+    world_id.copied().unwrap_or_default() == b2_world.id()
+}
+
+/// The schedules the physics systems run in for a world in [`b2Worlds`], one before and one after
+/// the step. Every world has its own, so the systems keep their change detection and local state
+/// per world.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct ExtraWorldSchedule(PhysicsWorldId, ExtraWorldPhase);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum ExtraWorldPhase {
+    BeforeStep,
+    AfterStep,
+}
+
+/// Runs one phase of the physics systems for every world in [`b2Worlds`]. Each world takes turns
+/// in the [`b2World`] resource, along with its contacts, step count and leftover time, and is
+/// stepped for the same pending steps of [`PhysicsTime`] as the default world.
+fn run_extra_worlds(world: &mut World, phase: ExtraWorldPhase, fixed_update: bool) {
+    if world
+        .get_non_send_resource::<b2Worlds>()
+        .map_or(true, |extra_worlds| extra_worlds.is_empty())
+    {
+        return;
+    }
+    let mut extra_worlds = world.remove_non_send_resource::<b2Worlds>().unwrap();
+    let pending_steps = world.resource::<PhysicsTime>().pending_steps();
+    for (id, extra_world) in extra_worlds.iter_mut() {
+        let label = ExtraWorldSchedule(id, phase);
+        if !world.resource::<Schedules>().contains(label.clone()) {
+            let mut schedule = Schedule::new(label.clone());
+            match phase {
+                ExtraWorldPhase::BeforeStep => {
+                    schedule.add_systems(physics_systems_before_step(fixed_update))
+                }
+                ExtraWorldPhase::AfterStep => schedule.add_systems(physics_systems_after_step()),
+            };
+            world.add_schedule(schedule);
+        }
+
+        world
+            .resource_mut::<PhysicsTime>()
+            .set_pending_steps(pending_steps);
+        swap_world_state(world, extra_world);
+        world.run_schedule(label);
+        swap_world_state(world, extra_world);
+    }
+    world
+        .resource_mut::<PhysicsTime>()
+        .set_pending_steps(pending_steps);
+    world.insert_non_send_resource(extra_worlds);
+}
+
+fn swap_world_state(world: &mut World, extra_world: &mut ExtraWorld) {
+    let mut b2_world = world.non_send_resource_mut::<b2World>();
+    std::mem::swap(b2_world.bypass_change_detection(), &mut extra_world.world);
+    let mut contacts = world.resource_mut::<b2Contacts>();
+    std::mem::swap(
+        contacts.bypass_change_detection(),
+        &mut extra_world.contacts,
+    );
+    let mut step_count = world.resource_mut::<PhysicsStepCount>();
+    std::mem::swap(
+        step_count.bypass_change_detection(),
+        &mut extra_world.step_count,
+    );
+    let mut time_accumulator = world.resource_mut::<PhysicsTimeAccumulator>();
+    std::mem::swap(
+        &mut time_accumulator.bypass_change_detection().0,
+        &mut extra_world.time_accumulator,
+    );
+}
+
+/// Ends the first sync of a new world, see [`b2World::is_fresh`].
+fn mark_world_synced(mut b2_world: NonSendMut<b2World>) {
+    if b2_world.is_fresh() {
+        b2_world.mark_synced();
+    }
+}
+
+/// Pauses the systems that need a [`b2World`] while there is none, e.g. between two levels.
+fn physics_world_exists(b2_world: Option<NonSend<b2World>>) -> bool {
+    b2_world.is_some()
+}
+
 #[derive(Resource)]
 struct PhysicsTimeAccumulator(f32);
 
+fn reset_physics_world(world: &mut World) {
+    let mut reset_events = world.resource_mut::<Events<ResetPhysicsWorld>>();
+    if reset_events.drain().count() == 0 {
+        return;
+    }
+
+    let physics_entities: Vec<Entity> = world
+        .query_filtered::<Entity, Or<(
+            With<b2Body>,
+            With<b2Fixture>,
+            With<b2Joint>,
+            With<b2ParticleSystem>,
+            With<b2ParticleGroup>,
+            With<b2ParticleEmitter>,
+        )>>()
+        .iter(world)
+        .collect();
+    for entity in physics_entities {
+        // Might have been despawned as the child of another physics entity
+        if world.get_entity(entity).is_some() {
+            despawn_with_children_recursive(world, entity);
+        }
+    }
+
+    let gravity = world.non_send_resource::<b2World>().gravity;
+    // Drops the old Box2D world, which frees everything that was created in it
+    world.insert_non_send_resource(b2World::new(gravity));
+    if let Some(mut extra_worlds) = world.get_non_send_resource_mut::<b2Worlds>() {
+        extra_worlds.reset();
+    }
+    world.resource_mut::<PhysicsTimeAccumulator>().0 = 0.;
+    *world.resource_mut::<PhysicsStepCount>() = PhysicsStepCount(0);
+    let mut contacts = world.resource_mut::<b2Contacts>();
+    contacts.contacts_mut().clear();
+    contacts.rebuild_index();
+    // The new world starts out with the default settings
+    world.resource_mut::<b2WorldSettings>().set_changed();
+
+    world.resource_mut::<Events<b2BeginContactEvent>>().clear();
+    world.resource_mut::<Events<b2EndContactEvent>>().clear();
+    world.resource_mut::<Events<b2PostSolveEvent>>().clear();
+    world.resource_mut::<Events<b2ContactStayEvent>>().clear();
+    world
+        .resource_mut::<Events<b2SensorBeginOverlapEvent>>()
+        .clear();
+    world
+        .resource_mut::<Events<b2SensorEndOverlapEvent>>()
+        .clear();
+    world.resource_mut::<Events<b2BodySleepEvent>>().clear();
+    world
+        .resource_mut::<Events<b2BodyOutOfBoundsEvent>>()
+        .clear();
+    world
+        .resource_mut::<Events<b2JointDestroyedEvent>>()
+        .clear();
+    world.resource_mut::<Events<b2JointLimitEvent>>().clear();
+    world.resource_mut::<Events<ExplosionEvent>>().clear();
+    world
+        .resource_mut::<Events<b2ParticleDestroyedEvent>>()
+        .clear();
+    world
+        .resource_mut::<Events<b2ParticleGroupDestroyedEvent>>()
+        .clear();
+}
+
+/// Decides how many steps the world takes this frame, they are run by [`step_worlds`].
 fn step_physics(
     mut b2_world: NonSendMut<b2World>,
     settings: Res<b2WorldSettings>,
     time: Res<Time>,
     mut physics_time_accumulator: ResMut<PhysicsTimeAccumulator>,
+    mut physics_time: ResMut<PhysicsTime>,
+    mut last_fallen_behind_warning: Local<Option<f32>>,
+    mut logged_particle_iterations: Local<bool>,
+    mut last_time_step: Local<Option<f32>>,
 ) {
-    physics_time_accumulator.0 += time.delta_seconds();
+    if !(settings.time_step.is_finite() && settings.time_step > 0.) {
+        if *last_time_step != Some(settings.time_step) {
+            warn!(
+                "Not stepping the physics, time step {} is not positive",
+                settings.time_step
+            );
+            *last_time_step = Some(settings.time_step);
+        }
+        return;
+    }
+    // Keeps the leftover time the same fraction of a step, so interpolated transforms don't
+    // jump when the time step is changed, e.g. from a quality setting. Drifts from real time
+    // by less than a step, once.
+    if let Some(last_time_step) = last_time_step.filter(|last| last.is_finite() && *last > 0.) {
+        if last_time_step != settings.time_step {
+            physics_time_accumulator.0 *= settings.time_step / last_time_step;
+        }
+    }
+    *last_time_step = Some(settings.time_step);
 
-    while physics_time_accumulator.0 >= settings.time_step {
-        b2_world.step(
-            settings.time_step,
-            settings.velocity_iterations,
-            settings.position_iterations,
-            settings.particle_iterations,
-        );
-        physics_time_accumulator.0 -= settings.time_step;
+    let mut steps = physics_time.take_pending_steps();
+    if !physics_time.is_paused() {
+        let mut fallen_behind = time.delta_seconds() > settings.max_frame_delta;
+        let delta = time.delta_seconds().min(settings.max_frame_delta);
+        physics_time_accumulator.0 += delta * physics_time.time_scale;
+        let mut time_steps = 0;
+        while physics_time_accumulator.0 >= settings.time_step {
+            physics_time_accumulator.0 -= settings.time_step;
+            time_steps += 1;
+        }
+        if time_steps > settings.max_steps_per_frame {
+            time_steps = settings.max_steps_per_frame;
+            fallen_behind = true;
+        }
+        steps += time_steps;
+
+        let now = time.elapsed_seconds();
+        let warned_recently = last_fallen_behind_warning.is_some_and(|warned| now - warned < 5.);
+        if fallen_behind && !warned_recently {
+            warn!("Physics steps were dropped, the simulation is falling behind real time");
+            *last_fallen_behind_warning = Some(now);
+        }
     }
+
+    queue_steps(
+        &mut b2_world,
+        &settings,
+        steps,
+        &mut logged_particle_iterations,
+    );
 }
 
-fn clear_forces(mut external_forces: Query<&mut ExternalForce>) {
-    for mut force in external_forces.iter_mut() {
-        force.clear()
+/// Runs one step per fixed update, Bevy decides how many fixed updates run per frame.
+fn step_physics_fixed(
+    mut b2_world: NonSendMut<b2World>,
+    settings: Res<b2WorldSettings>,
+    mut physics_time: ResMut<PhysicsTime>,
+    mut logged_particle_iterations: Local<bool>,
+) {
+    let mut steps = physics_time.take_pending_steps();
+    if !physics_time.is_paused() {
+        steps += 1;
     }
+    queue_steps(
+        &mut b2_world,
+        &settings,
+        steps,
+        &mut logged_particle_iterations,
+    );
 }
-fn create_bodies(
+
+fn queue_steps(
+    b2_world: &mut b2World,
+    settings: &b2WorldSettings,
+    steps: u32,
+    logged_particle_iterations: &mut bool,
+) {
+    let particle_iterations = particle_iterations(b2_world, settings, logged_particle_iterations);
+    b2_world.queue_steps(QueuedSteps {
+        steps,
+        time_step: settings.time_step,
+        velocity_iterations: settings.velocity_iterations,
+        position_iterations: settings.position_iterations,
+        particle_iterations,
+    });
+}
+
+/// Runs the queued steps of the default world and every world in [`b2Worlds`], once all of them
+/// are synced with the ECS.
+fn step_worlds(
     mut b2_world: NonSendMut<b2World>,
-    mut added: Query<(Entity, &mut b2Body), Added<b2Body>>,
+    mut extra_worlds: Option<NonSendMut<b2Worlds>>,
+    // Box2D writes into the particle buffers of the components during the step
+    _particle_systems: Query<&mut b2ParticleSystem>,
 ) {
-    for (entity, mut body) in added.iter_mut() {
-        b2_world.create_body(entity, &mut body);
+    b2_world.run_queued_steps();
+    for extra_worlds in extra_worlds.iter_mut() {
+        for (_, extra_world) in extra_worlds.iter_mut() {
+            extra_world.world.run_queued_steps();
+        }
     }
 }
 
-fn create_fixtures(
-    mut b2_world: NonSendMut<b2World>,
-    mut added: Query<(Entity, &mut b2Fixture), Added<b2Fixture>>,
-    mut bodies: Query<(Entity, &mut b2Body)>,
+/// The steps started by [`start_steps`], which hold the worlds until they are done.
+#[cfg(feature = "parallel_step")]
+#[derive(Resource)]
+struct PendingSteps(Task<SteppingWorlds>);
+
+/// Moves the worlds to the `AsyncComputeTaskPool` to run their queued steps, along with the
+/// particle buffers Box2D writes to. The physics components stay in the ECS, but the worlds and
+/// the particles are missing until [`finish_steps`] brings them back at the start of the next
+/// frame.
+#[cfg(feature = "parallel_step")]
+fn start_steps(
+    world: &mut World,
+    particle_systems: &mut QueryState<(Entity, &mut b2ParticleSystem)>,
+) {
+    let default_world = world.remove_non_send_resource::<b2World>().unwrap();
+    let extra_worlds = world
+        .remove_non_send_resource::<b2Worlds>()
+        .unwrap_or_default();
+    let particle_buffers = particle_systems
+        .iter_mut(world)
+        .map(|(entity, mut particle_system)| {
+            (
+                entity,
+                particle_system.bypass_change_detection().take_buffers(),
+            )
+        })
+        .collect();
+    let mut stepping_worlds = SteppingWorlds {
+        default_world,
+        extra_worlds,
+        particle_buffers,
+    };
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        stepping_worlds.run_queued_steps();
+        stepping_worlds
+    });
+    world.insert_resource(PendingSteps(task));
+}
+
+/// Waits for the steps started by [`start_steps`] and puts the worlds and the particle buffers
+/// back, before anything else in the frame can look at them.
+#[cfg(feature = "parallel_step")]
+fn finish_steps(world: &mut World, particle_systems: &mut QueryState<&mut b2ParticleSystem>) {
+    let Some(PendingSteps(task)) = world.remove_resource::<PendingSteps>() else {
+        return;
+    };
+    let stepping_worlds = future::block_on(task);
+    for (entity, buffers) in stepping_worlds.particle_buffers {
+        if let Ok(mut particle_system) = particle_systems.get_mut(world, entity) {
+            particle_system
+                .bypass_change_detection()
+                .restore_buffers(buffers);
+        }
+    }
+    world.insert_non_send_resource(stepping_worlds.default_world);
+    world.insert_non_send_resource(stepping_worlds.extra_worlds);
+}
+
+/// Passes the steps taken by [`step_worlds`] on to the [`PhysicsStepCount`] of the world.
+fn sync_step_count(b2_world: NonSend<b2World>, mut step_count: ResMut<PhysicsStepCount>) {
+    step_count.set_if_neq(PhysicsStepCount(b2_world.get_step_count()));
+}
+
+/// The configured particle iterations, or the computed ones with
+/// [`b2WorldSettings::auto_particle_iterations`]. Logs once when the computed ones are far off
+/// the configured ones, as the fluids then behave quite differently.
+fn particle_iterations(b2_world: &b2World, settings: &b2WorldSettings, logged: &mut bool) -> i32 {
+    if !settings.auto_particle_iterations {
+        return settings.particle_iterations;
+    }
+    let Some(radius) = b2_world.get_min_particle_radius() else {
+        return settings.particle_iterations;
+    };
+    let iterations =
+        calculate_particle_iterations(b2_world.gravity.length(), radius, settings.time_step);
+    let ratio = iterations as f32 / settings.particle_iterations.max(1) as f32;
+    if !*logged && !(0.5..=2.).contains(&ratio) {
+        info!(
+            "Using {} particle iterations instead of the configured {}",
+            iterations, settings.particle_iterations
+        );
+        *logged = true;
+    }
+    iterations
+}
+
+/// Changes to the `time_step` of the settings are passed on to `Time<Fixed>`, so they aren't
+/// overwritten with the old timestep. Bevy keeps the overstep when the timestep changes.
+fn sync_time_step_from_fixed_time(
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut settings: ResMut<b2WorldSettings>,
+    mut last_time_step: Local<Option<f32>>,
+) {
+    let changed_in_settings = last_time_step.is_some_and(|last| last != settings.time_step);
+    if changed_in_settings && settings.time_step.is_finite() && settings.time_step > 0. {
+        fixed_time.set_timestep_seconds(settings.time_step as f64);
+    }
+    let time_step = fixed_time.timestep().as_secs_f32();
+    if settings.time_step != time_step {
+        settings.time_step = time_step;
+    }
+    *last_time_step = Some(time_step);
+}
+
+/// The time that hasn't been simulated yet is what the transforms are extrapolated or
+/// interpolated by. Every world takes one step per fixed update, so they all have the same.
+fn sync_overstep_from_fixed_time(
+    fixed_time: Res<Time<Fixed>>,
+    mut physics_time_accumulator: ResMut<PhysicsTimeAccumulator>,
+    extra_worlds: Option<NonSendMut<b2Worlds>>,
 ) {
-    for (fixture_entity, mut fixture) in added.iter_mut() {
-        let mut body = bodies.get_mut(fixture.get_body_entity()).unwrap();
-        b2_world.create_fixture((fixture_entity, &mut fixture), (body.0, &mut body.1));
+    let overstep = fixed_time.overstep().as_secs_f32();
+    physics_time_accumulator.0 = overstep;
+    if let Some(mut extra_worlds) = extra_worlds {
+        for (_, extra_world) in extra_worlds.bypass_change_detection().iter_mut() {
+            extra_world.time_accumulator = overstep;
+        }
     }
 }
 
-fn create_revolute_joints(
+fn copy_contacts(
     mut b2_world: NonSendMut<b2World>,
-    mut added: Query<(Entity, &b2Joint, &b2RevoluteJoint), Added<b2RevoluteJoint>>,
-    mut bodies: Query<(Entity, &mut b2Body)>,
+    settings: Res<b2WorldSettings>,
+    mut contacts: ResMut<b2Contacts>,
 ) {
-    for (joint_entity, joint, revolute_joint) in added.iter_mut() {
-        let [mut body_a, mut body_b] = bodies
-            .get_many_mut([*joint.body_a(), *joint.body_b()])
-            .unwrap();
-        let joint_ptr = revolute_joint.create_ffi_joint(
-            &mut b2_world,
-            body_a.0,
-            body_b.0,
-            joint.collide_connected(),
+    if !settings.copy_contacts {
+        // Doesn't leave stale contacts behind when the copying is turned off
+        if !contacts.contacts().is_empty() {
+            contacts.contacts_mut().clear();
+            contacts.rebuild_index();
+        }
+        return;
+    }
+    b2_world.copy_contacts(contacts.contacts_mut());
+    contacts.rebuild_index();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn send_contact_events(
+    b2_world: NonSend<b2World>,
+    contacts: Res<b2Contacts>,
+    filter: Res<ContactEventFilter>,
+    bodies: Query<&b2Body>,
+    markers: Query<(), With<EmitContactEvents>>,
+    mut begin_contact_events: EventWriter<b2BeginContactEvent>,
+    mut end_contact_events: EventWriter<b2EndContactEvent>,
+    mut post_solve_events: EventWriter<b2PostSolveEvent>,
+    mut stay_contact_events: EventWriter<b2ContactStayEvent>,
+    mut sensor_begin_events: EventWriter<b2SensorBeginOverlapEvent>,
+    mut sensor_end_events: EventWriter<b2SensorEndOverlapEvent>,
+    mut impulses: Local<HashMap<(Entity, Entity), b2ContactImpulse>>,
+) {
+    let mut contact_listener = b2_world.get_contact_listener();
+    // Later steps overwrite the impulses of earlier ones. The map is kept between frames so it
+    // doesn't have to grow again every frame, and is only filled when something looks it up
+    impulses.clear();
+    if filter.stay_events || filter.min_normal_impulse > 0. {
+        impulses.extend(
+            contact_listener
+                .post_solve_contacts()
+                .iter()
+                .map(|(contact, impulse)| ((contact.fixture_a, contact.fixture_b), *impulse)),
         );
-        b2_world.register_joint(
-            (joint_entity, &joint, joint_ptr),
-            (body_a.0, &mut body_a.1),
-            (body_b.0, &mut body_b.1),
+    }
+    let impulse_of = |contact: &b2Contact| {
+        impulses
+            .get(&(contact.fixture_a, contact.fixture_b))
+            .copied()
+    };
+    let has_marker = |contact: &b2Contact| {
+        !filter.require_marker
+            || [
+                contact.fixture_a,
+                contact.fixture_b,
+                contact.body_a,
+                contact.body_b,
+            ]
+            .into_iter()
+            .any(|entity| markers.contains(entity))
+    };
+    let passes_filter = |contact: &b2Contact| {
+        if !has_marker(contact) {
+            return false;
+        }
+        if filter.min_normal_impulse > 0. {
+            let normal_impulse =
+                impulse_of(contact).map_or(0., |impulse| impulse.max_normal_impulse());
+            if normal_impulse < filter.min_normal_impulse {
+                return false;
+            }
+        }
+        if filter.min_relative_speed > 0. {
+            let (Ok(body_a), Ok(body_b)) = (bodies.get(contact.body_a), bodies.get(contact.body_b))
+            else {
+                return false;
+            };
+            let point = contact
+                .manifold
+                .points()
+                .first()
+                .copied()
+                .unwrap_or(body_a.position);
+            let relative_velocity = body_b.linear_velocity_at_world_point(point)
+                - body_a.linear_velocity_at_world_point(point);
+            // The normal points from A to B, so approaching bodies have a negative speed
+            let approach_speed = -relative_velocity.dot(contact.manifold.normal);
+            if approach_speed < filter.min_relative_speed {
+                return false;
+            }
+        }
+        true
+    };
+
+    // Box2D only calls back when the touching state of a contact changes
+    let begun_contacts = contact_listener.begun_contacts();
+    sensor_begin_events.send_batch(
+        begun_contacts
+            .iter()
+            .flat_map(|event| event.contact.sensors())
+            .map(b2SensorBeginOverlapEvent),
+    );
+    begin_contact_events.send_batch(
+        begun_contacts
+            .iter()
+            .filter(|event| passes_filter(&event.contact))
+            .copied(),
+    );
+    let ended_contacts = contact_listener.ended_contacts();
+    sensor_end_events.send_batch(
+        ended_contacts
+            .iter()
+            .flat_map(|event| event.contact.sensors())
+            .map(b2SensorEndOverlapEvent),
+    );
+    end_contact_events.send_batch(
+        ended_contacts
+            .iter()
+            .filter(|event| has_marker(&event.contact))
+            .copied(),
+    );
+    if filter.stay_events {
+        stay_contact_events.send_batch(
+            contacts
+                .contacts()
+                .iter()
+                .filter(|contact| contact.is_touching && passes_filter(contact))
+                .map(|contact| b2ContactStayEvent {
+                    contact: *contact,
+                    impulse: impulse_of(contact),
+                }),
         );
     }
+    post_solve_events.send_batch(
+        contact_listener
+            .post_solve_contacts()
+            .iter()
+            .map(|&(contact, impulse)| b2PostSolveEvent { contact, impulse }),
+    );
+    contact_listener.clear_recorded_contacts();
 }
 
-fn create_prismatic_joints(
+fn clear_forces(
+    mut external_forces: Query<&mut ExternalForce>,
+    mut local_external_forces: Query<&mut LocalExternalForce>,
+) {
+    for mut force in external_forces.iter_mut() {
+        if !force.persistent {
+            force.clear()
+        }
+    }
+    for mut force in local_external_forces.iter_mut() {
+        if !force.persistent {
+            force.clear()
+        }
+    }
+}
+/// A [`b2World`] inserted in place of another one starts out empty, so the creation systems
+/// create every existing body, fixture, joint and particle system of its id in it again, not
+/// only the added ones. Particle groups are recreated from their definitions.
+fn create_bodies(
     mut b2_world: NonSendMut<b2World>,
-    mut added: Query<(Entity, &b2Joint, &b2PrismaticJoint), Added<b2PrismaticJoint>>,
-    mut bodies: Query<(Entity, &mut b2Body)>,
+    bodies: Query<(
+        Entity,
+        Ref<b2Body>,
+        Option<&GravityScale>,
+        Option<&PhysicsWorldId>,
+    )>,
 ) {
-    for (joint_entity, joint, prismatic_joint) in added.iter_mut() {
-        let [mut body_a, mut body_b] = bodies
-            .get_many_mut([*joint.body_a(), *joint.body_b()])
-            .unwrap();
-        let joint_ptr = prismatic_joint.create_ffi_joint(
-            &mut b2_world,
-            body_a.0,
-            body_b.0,
-            joint.collide_connected(),
-        );
-        b2_world.register_joint(
-            (joint_entity, &joint, joint_ptr),
-            (body_a.0, &mut body_a.1),
-            (body_b.0, &mut body_b.1),
-        );
+    let world_replaced = b2_world.is_fresh();
+    let mut added: Vec<_> = bodies
+        .iter()
+        .filter(|(_, body, _, world_id)| {
+            in_world(&b2_world, *world_id) && (world_replaced || body.is_added())
+        })
+        .map(|(entity, body, gravity_scale, _)| (entity, body.into_inner(), gravity_scale))
+        .collect();
+    added.sort_by_key(|(entity, ..)| *entity);
+    b2_world.create_bodies(added.into_iter());
+}
+
+fn create_fixtures(
+    mut b2_world: NonSendMut<b2World>,
+    added: Query<Entity, Added<b2Fixture>>,
+    mut fixtures: Query<(Entity, &mut b2Fixture, Option<&Transform>, Option<&Parent>)>,
+    indexed: Query<(Entity, Ref<b2Fixtures>, Option<&PhysicsWorldId>), With<b2Body>>,
+    bodies: Query<Option<&PhysicsWorldId>, With<b2Body>>,
+    mut pending: Local<Vec<Entity>>,
+    mut commands: Commands,
+) {
+    // Fixtures whose body doesn't exist yet are retried on the following frames
+    let mut candidates: Vec<Entity> = pending.drain(..).chain(added.iter()).collect();
+    // Recreated fixtures already had their transform applied to their shape
+    let mut recreated = HashSet::new();
+    if b2_world.is_fresh() {
+        recreated = fixtures.iter().map(|(entity, ..)| entity).collect();
+        for entity in &candidates {
+            recreated.remove(entity);
+        }
+        candidates.extend(recreated.iter());
+    }
+    candidates.sort();
+    for fixture_entity in candidates {
+        let Ok((fixture_entity, mut fixture, transform, parent)) = fixtures.get_mut(fixture_entity)
+        else {
+            continue;
+        };
+        let body_entity = fixture.get_body_entity();
+        let Ok(body_world_id) = bodies.get(body_entity) else {
+            if commands.get_entity(body_entity).is_some() {
+                pending.push(fixture_entity);
+            } else {
+                warn!(
+                    "Despawning fixture {:?}, its body {:?} doesn't exist",
+                    fixture_entity, body_entity
+                );
+                commands.entity(fixture_entity).despawn_recursive();
+            }
+            continue;
+        };
+        if !in_world(&b2_world, body_world_id) {
+            continue;
+        }
+        if let Err(error) = fixture.get_shape().validate() {
+            warn!(
+                "Skipping fixture {:?} with invalid shape: {}",
+                fixture_entity, error
+            );
+            continue;
+        }
+
+        // Fixtures spawned as children of their body are offset by their own transform
+        if let (Some(transform), Some(parent)) = (transform, parent) {
+            if parent.get() == body_entity && !recreated.contains(&fixture_entity) {
+                let angle = transform.rotation.to_euler(EulerRot::ZYX).0;
+                fixture.transform_shape(transform.translation.truncate(), angle);
+            }
+        }
+        if let Err(error) = b2_world.create_fixture(fixture_entity, &mut fixture, body_entity) {
+            warn!("Skipping fixture {:?}: {}", fixture_entity, error);
+        }
+    }
+
+    let world_replaced = b2_world.is_fresh();
+    let mut added_indexed: Vec<_> = indexed
+        .iter()
+        .filter(|(_, fixtures, world_id)| {
+            in_world(&b2_world, *world_id) && (world_replaced || fixtures.is_added())
+        })
+        .collect();
+    added_indexed.sort_by_key(|(body_entity, ..)| *body_entity);
+    'bodies: for (body_entity, fixtures, _) in added_indexed {
+        // The fixtures are addressed by index, so skipping single ones would shift the others
+        for (index, fixture_def) in fixtures.defs().iter().enumerate() {
+            if let Err(error) = fixture_def.shape.validate() {
+                warn!(
+                    "Skipping fixtures of {:?}, fixture {} has an invalid shape: {}",
+                    body_entity, index, error
+                );
+                continue 'bodies;
+            }
+        }
+        if let Err(error) = b2_world.create_indexed_fixtures(body_entity, fixtures) {
+            warn!("Skipping fixtures of {:?}: {}", body_entity, error);
+        }
     }
 }
 
-fn create_distance_joints(
+fn create_joints<T: Component + ToJointPtr>(
     mut b2_world: NonSendMut<b2World>,
-    mut added: Query<(Entity, &b2Joint, &b2DistanceJoint), Added<b2DistanceJoint>>,
-    mut bodies: Query<(Entity, &mut b2Body)>,
+    added: Query<Entity, Added<T>>,
+    joints: Query<(Entity, &b2Joint, &T)>,
+    mut bodies: Query<(Entity, &mut b2Body, Option<&PhysicsWorldId>)>,
+    mut pending: Local<Vec<Entity>>,
+    mut joint_destroyed_events: EventWriter<b2JointDestroyedEvent>,
+    mut commands: Commands,
 ) {
-    for (joint_entity, joint, distance_joint) in added.iter_mut() {
-        let [mut body_a, mut body_b] = bodies
-            .get_many_mut([*joint.body_a(), *joint.body_b()])
-            .unwrap();
-        let joint_ptr = distance_joint.create_ffi_joint(
+    // Joints whose bodies don't exist yet are retried on the following frames
+    let mut candidates: Vec<Entity> = pending.drain(..).chain(added.iter()).collect();
+    if b2_world.is_fresh() {
+        candidates.extend(joints.iter().map(|(entity, ..)| entity));
+    }
+    candidates.sort();
+    candidates.dedup();
+    for joint_entity in candidates {
+        let Ok((joint_entity, joint, joint_component)) = joints.get(joint_entity) else {
+            continue;
+        };
+
+        let body_entities = [*joint.body_a(), *joint.body_b()];
+        let Ok([mut body_a, mut body_b]) = bodies.get_many_mut(body_entities) else {
+            if body_entities
+                .iter()
+                .all(|body_entity| commands.get_entity(*body_entity).is_some())
+            {
+                pending.push(joint_entity);
+            } else {
+                warn!(
+                    "Despawning joint {:?}, one of its bodies {:?} doesn't exist",
+                    joint_entity, body_entities
+                );
+                commands.entity(joint_entity).despawn_recursive();
+                joint_destroyed_events.send(b2JointDestroyedEvent {
+                    joint: joint_entity,
+                    reason: JointDestroyedReason::MissingBody,
+                });
+            }
+            continue;
+        };
+        // Joints between two worlds are skipped with a warning in both of them
+        if !in_world(&b2_world, body_a.2) && !in_world(&b2_world, body_b.2) {
+            continue;
+        }
+        // The joints look up the bodies in the physics world when they are created
+        if let Some(missing_body) = body_entities
+            .into_iter()
+            .find(|body_entity| b2_world.get_body_ptr(*body_entity).is_none())
+        {
+            warn!(
+                "Skipping joint {:?}: {}",
+                joint_entity,
+                PhysicsError::MissingBody(missing_body)
+            );
+            continue;
+        }
+        let joint_ptr = joint_component.create_ffi_joint(
             &mut b2_world,
             body_a.0,
             body_b.0,
             joint.collide_connected(),
         );
         b2_world.register_joint(
-            (joint_entity, &joint, joint_ptr),
+            (joint_entity, joint, joint_ptr),
             (body_a.0, &mut body_a.1),
             (body_b.0, &mut body_b.1),
         );
@@ -171,206 +1106,1123 @@ fn create_distance_joints(
 
 fn create_particle_systems(
     mut b2_world: NonSendMut<b2World>,
-    mut added: Query<(Entity, &mut b2ParticleSystem), Added<b2ParticleSystem>>,
+    mut particle_systems: Query<(Entity, &mut b2ParticleSystem, Option<&PhysicsWorldId>)>,
 ) {
-    for (entity, mut particle_system) in added.iter_mut() {
+    let world_replaced = b2_world.is_fresh();
+    let mut added: Vec<_> = particle_systems
+        .iter_mut()
+        .filter(|(_, particle_system, world_id)| {
+            in_world(&b2_world, *world_id) && (world_replaced || particle_system.is_added())
+        })
+        .collect();
+    added.sort_by_key(|(entity, ..)| *entity);
+    for (entity, mut particle_system, _) in added {
         b2_world.create_particle_system(entity, &mut particle_system);
     }
 }
 
 fn create_particle_groups(
     mut b2_world: NonSendMut<b2World>,
-    mut added_groups: Query<(Entity, &mut b2ParticleGroup), Added<b2ParticleGroup>>,
+    mut particle_groups: Query<(Entity, &mut b2ParticleGroup)>,
+    mut particle_systems: Query<(&mut b2ParticleSystem, Option<&PhysicsWorldId>)>,
 ) {
-    for (entity, mut particle_group) in added_groups.iter_mut() {
-        b2_world.create_particle_group(
-            particle_group.get_particle_system_entity(),
-            entity,
-            &mut particle_group,
-        );
+    let world_replaced = b2_world.is_fresh();
+    let mut added_groups: Vec<_> = particle_groups
+        .iter_mut()
+        .filter(|(_, particle_group)| world_replaced || particle_group.is_added())
+        .collect();
+    added_groups.sort_by_key(|(entity, _)| *entity);
+    for (entity, mut particle_group) in added_groups {
+        let particle_system_entity = particle_group.get_particle_system_entity();
+        let result = match particle_systems.get_mut(particle_system_entity) {
+            Ok((_, world_id)) if !in_world(&b2_world, world_id) => continue,
+            Ok((mut particle_system, _)) => {
+                b2_world.create_particle_group(&mut particle_system, entity, &mut particle_group)
+            }
+            Err(_) => Err(PhysicsError::MissingParticleSystem(particle_system_entity)),
+        };
+        if let Err(error) = result {
+            warn!("Skipping particle group {:?}: {}", entity, error);
+        }
     }
 }
 
 fn destroy_removed_bodies(
     mut b2_world: NonSendMut<b2World>,
     mut removed: RemovedComponents<b2Body>,
+    mut joint_destroyed_events: EventWriter<b2JointDestroyedEvent>,
     mut commands: Commands,
 ) {
-    for entity in removed.read() {
+    let mut removed: Vec<Entity> = removed.read().collect();
+    removed.sort();
+    for entity in removed {
+        // The fixtures and joints might have been despawned along with the body
         let fixture_entities = b2_world.get_fixtures_attached_to_entity(&entity);
         if let Some(fixture_entities) = fixture_entities {
             fixture_entities.iter().for_each(|fixture_entity| {
-                commands.entity(*fixture_entity).despawn_recursive();
+                if let Some(fixture_commands) = commands.get_entity(*fixture_entity) {
+                    fixture_commands.despawn_recursive();
+                }
             });
         }
 
-        b2_world.destroy_body_for_entity(entity);
+        let joint_entities = b2_world.get_joints_attached_to_entity(&entity);
+        if let Some(joint_entities) = joint_entities {
+            let mut joint_entities: Vec<Entity> = joint_entities.iter().copied().collect();
+            joint_entities.sort();
+            for joint_entity in joint_entities {
+                if let Some(joint_commands) = commands.get_entity(joint_entity) {
+                    joint_commands.despawn_recursive();
+                    joint_destroyed_events.send(b2JointDestroyedEvent {
+                        joint: joint_entity,
+                        reason: JointDestroyedReason::BodyRemoved(entity),
+                    });
+                }
+            }
+        }
+
+        // Bodies of a world that has been reset since were destroyed along with it
+        if let Err(error) = b2_world.destroy_body_for_entity(entity) {
+            debug!("Skipping removed body {:?}: {}", entity, error);
+        }
     }
 }
 
-fn destroy_removed_fixtures(
+fn destroy_removed_particle_groups(
     mut b2_world: NonSendMut<b2World>,
-    mut removed: RemovedComponents<b2Fixture>,
+    mut removed: RemovedComponents<b2ParticleGroup>,
 ) {
-    for entity in removed.read() {
-        b2_world.destroy_fixture_for_entity(entity);
+    let mut removed: Vec<Entity> = removed.read().collect();
+    removed.sort();
+    for entity in removed {
+        b2_world.destroy_particle_group_for_entity(entity);
     }
 }
-fn sync_bodies_to_world(
+
+/// The entity of a removed particle system may still be around, e.g. if only the
+/// [`b2ParticleSystem`] component was removed, so its contacts are cleared as well.
+fn destroy_removed_particle_systems(
     mut b2_world: NonSendMut<b2World>,
-    bodies: Query<(Entity, &b2Body), Changed<b2Body>>,
+    mut removed: RemovedComponents<b2ParticleSystem>,
+    mut particle_contacts: Query<&mut b2ParticleSystemContacts>,
 ) {
-    for (entity, body) in bodies.iter() {
-        body.sync_to_world(entity, &mut b2_world);
+    let mut removed: Vec<Entity> = removed.read().collect();
+    removed.sort();
+    for entity in removed {
+        b2_world.destroy_particle_system_for_entity(entity);
+        if let Ok(mut contacts) = particle_contacts.get_mut(entity) {
+            contacts.clear();
+        }
     }
 }
 
-fn sync_revolute_joints_to_world(
+fn destroy_removed_joints(
     mut b2_world: NonSendMut<b2World>,
-    joints: Query<(Entity, &b2RevoluteJoint), Changed<b2RevoluteJoint>>,
+    mut removed: RemovedComponents<b2Joint>,
 ) {
-    for (entity, joint) in joints.iter() {
-        let joint_ptr = b2_world.get_joint_ptr(&entity).unwrap();
-        if let JointPtr::Revolute(joint_ptr) = joint_ptr {
-            joint.sync_to_world(joint_ptr.as_mut());
-        }
+    let mut removed: Vec<Entity> = removed.read().collect();
+    removed.sort();
+    for entity in removed {
+        b2_world.destroy_joint_for_entity(entity);
     }
 }
 
-fn sync_prismatic_joints_to_world(
+fn destroy_removed_fixtures(
     mut b2_world: NonSendMut<b2World>,
-    joints: Query<(Entity, &b2PrismaticJoint), Changed<b2PrismaticJoint>>,
+    mut removed: RemovedComponents<b2Fixture>,
+    mut removed_indexed: RemovedComponents<b2Fixtures>,
 ) {
-    for (entity, joint) in joints.iter() {
-        let joint_ptr = b2_world.get_joint_ptr(&entity).unwrap();
-        if let JointPtr::Prismatic(joint_ptr) = joint_ptr {
-            joint.sync_to_world(joint_ptr.as_mut());
+    let mut removed: Vec<Entity> = removed.read().collect();
+    removed.sort();
+    for entity in removed {
+        if let Err(error) = b2_world.destroy_fixture_for_entity(entity) {
+            warn!("Failed to destroy fixture {:?}: {}", entity, error);
+        }
+    }
+
+    let mut removed_indexed: Vec<Entity> = removed_indexed.read().collect();
+    removed_indexed.sort();
+    for entity in removed_indexed {
+        b2_world.destroy_indexed_fixtures_for_entity(entity);
+    }
+}
+fn sync_settings_to_world(mut b2_world: NonSendMut<b2World>, settings: Res<b2WorldSettings>) {
+    if settings.is_changed() || b2_world.is_fresh() {
+        b2_world.set_allow_sleeping(settings.allow_sleeping);
+    }
+}
+
+fn sync_gravity_to_world(mut b2_world: NonSendMut<b2World>) {
+    b2_world.sync_gravity();
+}
+
+fn sync_transforms_to_bodies(
+    b2_world: NonSend<b2World>,
+    mut bodies: Query<
+        (
+            &mut b2Body,
+            &Transform,
+            Option<&Parent>,
+            &SyncTransformToBody,
+            Option<&PhysicsWorldId>,
+        ),
+        Changed<Transform>,
+    >,
+    global_transforms: Query<&GlobalTransform>,
+) {
+    for (mut body, transform, parent, sync, world_id) in bodies.iter_mut() {
+        if !in_world(&b2_world, world_id) || sync.last_written == Some(*transform) {
+            continue;
         }
+
+        let parent_transform = parent.and_then(|parent| global_transforms.get(parent.get()).ok());
+        let world_transform = match parent_transform {
+            Some(parent_transform) => parent_transform.mul_transform(*transform),
+            None => GlobalTransform::from(*transform),
+        };
+        let (_, rotation, translation) = world_transform.to_scale_rotation_translation();
+        body.position = translation.truncate();
+        body.angle = rotation.to_euler(EulerRot::ZYX).0;
+        body.store_previous_transform();
     }
 }
 
-fn sync_distance_joints_to_world(
+fn sync_bodies_to_world(
     mut b2_world: NonSendMut<b2World>,
-    joints: Query<(Entity, &b2DistanceJoint), Changed<b2DistanceJoint>>,
+    bodies: Query<(Entity, Ref<b2Body>, Option<&PhysicsWorldId>)>,
 ) {
-    for (entity, joint) in joints.iter() {
-        let joint_ptr = b2_world.get_joint_ptr(&entity).unwrap();
-        if let JointPtr::Distance(joint_ptr) = joint_ptr {
-            joint.sync_to_world(joint_ptr.as_mut());
+    let world_replaced = b2_world.is_fresh();
+    let mut bodies: Vec<_> = bodies
+        .iter()
+        .filter(|(_, body, world_id)| {
+            in_world(&b2_world, *world_id) && (world_replaced || body.is_changed())
+        })
+        .collect();
+    bodies.sort_by_key(|(entity, ..)| *entity);
+    for (entity, body, _) in bodies {
+        if let Err(error) = body.sync_to_world(entity, &mut b2_world) {
+            warn!("Skipping body {:?}: {}", entity, error);
         }
     }
 }
 
-fn apply_forces(
+fn apply_body_overrides(
+    mut commands: Commands,
     mut b2_world: NonSendMut<b2World>,
-    external_forces: Query<(Entity, &ExternalForce)>,
+    teleports: Query<(Entity, &TeleportBody), With<b2Body>>,
+    velocities: Query<(Entity, &SetBodyVelocity), With<b2Body>>,
 ) {
-    for (entity, external_force) in external_forces.iter() {
-        let body_ptr = b2_world.get_body_ptr_mut(entity).unwrap();
-        body_ptr.as_mut().ApplyForceToCenter(
-            &to_b2Vec2(&external_force.force()),
-            external_force.should_wake,
-        );
+    for (entity, teleport) in teleports.iter() {
+        let Some(body_ptr) = b2_world.get_body_ptr_mut(entity) else {
+            continue;
+        };
+        body_ptr
+            .as_mut()
+            .SetTransform(&to_b2Vec2(&teleport.position), teleport.angle);
+        body_ptr.as_mut().SetAwake(true);
+        commands.entity(entity).remove::<TeleportBody>();
+    }
+    for (entity, velocity) in velocities.iter() {
+        let Some(body_ptr) = b2_world.get_body_ptr_mut(entity) else {
+            continue;
+        };
         body_ptr
             .as_mut()
-            .ApplyTorque(external_force.torque(), false);
+            .SetLinearVelocity(&to_b2Vec2(&velocity.linear_velocity));
+        body_ptr
+            .as_mut()
+            .SetAngularVelocity(velocity.angular_velocity);
+        body_ptr.as_mut().SetAwake(true);
+        commands.entity(entity).remove::<SetBodyVelocity>();
     }
 }
 
-fn sync_bodies_from_world(b2_world: NonSend<b2World>, mut bodies: Query<(Entity, &mut b2Body)>) {
-    for (entity, mut body) in bodies.iter_mut() {
-        body.sync_with_world(entity, &b2_world);
+fn sync_fixtures_to_world(
+    mut b2_world: NonSendMut<b2World>,
+    fixtures: Query<(Entity, &b2Fixture), Changed<b2Fixture>>,
+) {
+    let mut fixtures: Vec<_> = fixtures.iter().collect();
+    fixtures.sort_by_key(|(entity, _)| *entity);
+    for (entity, fixture) in fixtures {
+        fixture.sync_to_world(entity, &mut b2_world);
     }
 }
 
-fn sync_particle_systems_from_world(
+fn sync_one_way_platforms(
     b2_world: NonSend<b2World>,
-    mut particle_systems: Query<(Entity, &mut b2ParticleSystem)>,
+    platforms: Query<(Entity, Ref<OneWayPlatform>)>,
+    mut removed: RemovedComponents<OneWayPlatform>,
 ) {
-    for (entity, mut particle_system) in particle_systems.iter_mut() {
-        particle_system.sync_with_world(entity, &b2_world);
+    let world_replaced = b2_world.is_fresh();
+    let mut contact_listener = b2_world.get_contact_listener();
+    for entity in removed.read() {
+        contact_listener.remove_one_way_platform(entity);
+    }
+    for (entity, platform) in platforms.iter() {
+        if world_replaced || platform.is_changed() {
+            contact_listener.set_one_way_platform(entity, *platform);
+        }
     }
 }
 
-fn update_transforms(
-    mut bodies: Query<(&b2Body, &mut Transform)>,
-    physics_time_accumulator: Res<PhysicsTimeAccumulator>,
+/// Only the contacts of the marked entities are recorded by the contact listener while
+/// [`ContactEventFilter::require_marker`] is set.
+fn sync_contact_event_markers(
+    b2_world: NonSend<b2World>,
+    filter: Res<ContactEventFilter>,
+    markers: Query<(Entity, Ref<EmitContactEvents>)>,
+    mut removed: RemovedComponents<EmitContactEvents>,
 ) {
-    let extrapolation_time = physics_time_accumulator.0;
-    for (body, mut transform) in bodies.iter_mut() {
-        let extrapolated_position = body.position + body.linear_velocity * extrapolation_time;
-        transform.translation = extrapolated_position.extend(0.);
-        let extrapolated_rotation = body.angle + body.angular_velocity * extrapolation_time;
-        transform.rotation = Quat::from_rotation_z(extrapolated_rotation);
+    let mut contact_listener = b2_world.get_contact_listener();
+    if b2_world.is_fresh() || filter.is_changed() {
+        removed.clear();
+        let marked_entities = filter
+            .require_marker
+            .then(|| markers.iter().map(|(entity, _)| entity).collect());
+        contact_listener.set_marked_entities(marked_entities);
+        return;
+    }
+    for entity in removed.read() {
+        contact_listener.unmark_entity(entity);
+    }
+    for (entity, marker) in markers.iter() {
+        if marker.is_added() {
+            contact_listener.mark_entity(entity);
+        }
     }
 }
-pub struct LiquidFunDebugDrawPlugin;
 
-impl Plugin for LiquidFunDebugDrawPlugin {
-    fn build(&self, app: &mut App) {
-        app.add_systems(
-            Last,
-            (
-                draw_fixtures
-                    .after(TransformSystem::TransformPropagate)
-                    .after(destroy_removed_bodies),
-                draw_particle_systems.after(TransformSystem::TransformPropagate),
+fn sync_contact_overrides(mut b2_world: NonSendMut<b2World>, overrides: Res<ContactOverrides>) {
+    if !b2_world.is_fresh() && !overrides.is_changed() {
+        return;
+    }
+    let removed = b2_world
+        .get_contact_listener()
+        .set_contact_overrides(&overrides);
+    b2_world.reset_contacts(&removed);
+}
+
+fn update_team_contact_filter(
+    mut b2_world: NonSendMut<b2World>,
+    team_filter: Option<Res<b2TeamContactFilter>>,
+    teams: Query<(Entity, &CollisionTeam)>,
+    mut installed: Local<bool>,
+) {
+    let Some(team_filter) = team_filter else {
+        if *installed {
+            b2_world.clear_contact_filter();
+            *installed = false;
+        }
+        return;
+    };
+
+    if team_filter.is_added() || !*installed || b2_world.is_fresh() {
+        b2_world.set_contact_filter(team_filter.clone());
+        *installed = true;
+    }
+    team_filter.update_snapshot(teams.iter().map(|(entity, team)| (entity, team.0)));
+}
+
+fn recompute_mass(
+    mut commands: Commands,
+    mut b2_world: NonSendMut<b2World>,
+    bodies: Query<Entity, (With<b2Body>, With<RecomputeMass>)>,
+) {
+    for entity in bodies.iter() {
+        let Some(body_ptr) = b2_world.get_body_ptr_mut(entity) else {
+            continue;
+        };
+        body_ptr.as_mut().ResetMassData();
+        commands.entity(entity).remove::<RecomputeMass>();
+    }
+}
+
+fn apply_mass_data(
+    mut b2_world: NonSendMut<b2World>,
+    mass_data: Query<(Entity, Ref<b2MassData>), With<b2Body>>,
+) {
+    for (entity, mass_data) in mass_data.iter() {
+        let changed = mass_data.is_changed() || b2_world.is_fresh();
+        mass_data.apply_to_world(entity, &mut b2_world, changed);
+    }
+}
+
+fn apply_gravity_scale(
+    mut b2_world: NonSendMut<b2World>,
+    gravity_scales: Query<(Entity, &GravityScale), (With<b2Body>, Changed<GravityScale>)>,
+) {
+    for (entity, gravity_scale) in gravity_scales.iter() {
+        // The body might not have been created yet, or was destroyed along with a reset world
+        let Some(body_ptr) = b2_world.get_body_ptr_mut(entity) else {
+            continue;
+        };
+        body_ptr.as_mut().SetGravityScale(gravity_scale.0);
+    }
+}
+
+/// Wakes the connected bodies of every changed joint, since Box2D only does so for some of the
+/// setters, e.g. enabling the motor of a joint between sleeping bodies would have no effect.
+fn sync_joints_to_world<T: Component + SyncJointToWorld>(
+    mut b2_world: NonSendMut<b2World>,
+    joints: Query<(Entity, &b2Joint, &T), Changed<T>>,
+) {
+    for (entity, b2_joint, joint) in joints.iter() {
+        // The joint might have been destroyed along with one of its bodies
+        let Some(joint_ptr) = b2_world.get_joint_ptr(&entity) else {
+            continue;
+        };
+        joint.sync_to_world(joint_ptr);
+        for body_entity in [*b2_joint.body_a(), *b2_joint.body_b()] {
+            if let Some(body_ptr) = b2_world.get_body_ptr_mut(body_entity) {
+                body_ptr.as_mut().SetAwake(true);
+            }
+        }
+    }
+}
+
+/// Read-back state is written without triggering change detection, so that it doesn't cause
+/// the joint to be synced back to the physics world on the next frame.
+fn sync_joints_from_world<T: Component + SyncJointFromWorld>(
+    mut b2_world: NonSendMut<b2World>,
+    settings: Res<b2WorldSettings>,
+    mut joints: Query<(Entity, &mut T)>,
+) {
+    let inv_dt = 1. / settings.time_step;
+    for (entity, mut joint) in joints.iter_mut() {
+        let Some(joint_ptr) = b2_world.get_joint_ptr(&entity) else {
+            continue;
+        };
+        joint
+            .bypass_change_detection()
+            .sync_from_world(joint_ptr, inv_dt);
+    }
+}
+
+fn send_joint_limit_events<T: Component + JointLimits>(
+    b2_world: NonSend<b2World>,
+    mut joints: Query<(Entity, &T, &mut EmitJointLimitEvents)>,
+    mut joint_limit_events: EventWriter<b2JointLimitEvent>,
+) {
+    let mut joints: Vec<_> = joints
+        .iter_mut()
+        .filter(|(entity, ..)| b2_world.get_joint_ptr(entity).is_some())
+        .collect();
+    joints.sort_by_key(|(entity, ..)| *entity);
+    for (entity, joint, mut emit) in joints {
+        emit.bypass_change_detection()
+            .update(joint.limit_state(), |limit, entering| {
+                joint_limit_events.send(b2JointLimitEvent {
+                    joint: entity,
+                    limit,
+                    entering,
+                });
+            });
+    }
+}
+
+fn apply_forces(
+    mut b2_world: NonSendMut<b2World>,
+    external_forces: Query<(Entity, &ExternalForce), With<b2Body>>,
+    local_external_forces: Query<(Entity, &LocalExternalForce), With<b2Body>>,
+) {
+    // Applied before each of the steps of the frame, see StepForce
+    b2_world.step_forces_mut().clear();
+    for (entity, external_force) in external_forces.iter() {
+        if b2_world.get_body_ptr(entity).is_none() {
+            continue;
+        }
+        b2_world.step_forces_mut().push(StepForce {
+            body: entity,
+            force: external_force.force(),
+            torque: external_force.torque(),
+            should_wake: external_force.should_wake,
+            local: false,
+        });
+    }
+    for (entity, local_force) in local_external_forces.iter() {
+        if b2_world.get_body_ptr(entity).is_none() {
+            continue;
+        }
+        // Rotated with the angle of the body at each step, which changes over the steps of a
+        // frame
+        b2_world.step_forces_mut().push(StepForce {
+            body: entity,
+            force: local_force.force(),
+            torque: local_force.local_torque(),
+            should_wake: local_force.should_wake,
+            local: true,
+        });
+    }
+}
+
+fn apply_buoyancy(
+    mut b2_world: NonSendMut<b2World>,
+    particle_systems: Query<(
+        &b2ParticleSystem,
+        &b2ParticleSystemContacts,
+        Option<&PhysicsWorldId>,
+    )>,
+    bodies: Query<(Entity, &b2Body, &Buoyancy, Option<&PhysicsWorldId>)>,
+) {
+    let gravity = b2_world.gravity;
+    for (entity, body, buoyancy, world_id) in bodies.iter() {
+        if !in_world(&b2_world, world_id) {
+            continue;
+        }
+        let mut displaced_mass = 0.;
+        let mut weight = Vec2::ZERO;
+        let mut center = Vec2::ZERO;
+        let mut fluid_velocity = Vec2::ZERO;
+        for (particle_system, contacts, _) in particle_systems
+            .iter()
+            .filter(|(.., world_id)| in_world(&b2_world, *world_id))
+        {
+            let definition = particle_system.get_definition();
+            let particle_size = PARTICLE_STRIDE * 2. * definition.radius;
+            let particle_mass = definition.density * particle_size * particle_size;
+            let positions = particle_system.get_positions();
+            let velocities = particle_system.get_velocities();
+            for contact in contacts.body_contacts_of(entity) {
+                // The contacts are from the last step, particles might have been destroyed since
+                if contact.index >= positions.len() {
+                    continue;
+                }
+                let mass = particle_mass * contact.weight.clamp(0., 1.);
+                displaced_mass += mass;
+                weight += gravity * definition.gravity_scale * mass;
+                center += positions[contact.index] * mass;
+                fluid_velocity += velocities[contact.index] * mass;
+            }
+        }
+        if displaced_mass == 0. {
+            continue;
+        }
+        center /= displaced_mass;
+        fluid_velocity /= displaced_mass;
+
+        let relative_velocity = body.linear_velocity_at_world_point(center) - fluid_velocity;
+        let force = -weight * buoyancy.density_multiplier
+            - relative_velocity * buoyancy.linear_drag * displaced_mass;
+        let torque = -body.angular_velocity * buoyancy.angular_drag * body.get_inertia();
+
+        let Some(body_ptr) = b2_world.get_body_ptr_mut(entity) else {
+            continue;
+        };
+        body_ptr
+            .as_mut()
+            .ApplyForce(&to_b2Vec2(&force), &to_b2Vec2(&center), true);
+        body_ptr.as_mut().ApplyTorque(torque, true);
+    }
+}
+
+fn apply_explosions(
+    mut b2_world: NonSendMut<b2World>,
+    mut explosions: EventReader<ExplosionEvent>,
+) {
+    for explosion in explosions.read() {
+        if explosion.0.world == b2_world.id() {
+            b2_world.explode(&explosion.0);
+        }
+    }
+}
+
+/// Forces on particle groups are spread over their particles, the torque is ignored.
+fn apply_particle_group_forces(
+    mut b2_world: NonSendMut<b2World>,
+    external_forces: Query<(Entity, &ExternalForce), With<b2ParticleGroup>>,
+) {
+    for (entity, external_force) in external_forces.iter() {
+        let Some(group_ptr) = b2_world.get_particle_group_ptr_mut(&entity) else {
+            continue;
+        };
+        group_ptr
+            .as_mut()
+            .ApplyForce(&to_b2Vec2(&external_force.force()));
+    }
+}
+
+fn apply_kinematic_targets(
+    mut b2_world: NonSendMut<b2World>,
+    targets: Query<(Entity, &KinematicTarget), With<b2Body>>,
+) {
+    // The velocities are computed before each of the steps of the frame, from where the body is
+    // at that step
+    let kinematic_targets = b2_world.kinematic_targets_mut();
+    kinematic_targets.clear();
+    kinematic_targets.extend(targets.iter().map(|(entity, target)| (entity, *target)));
+}
+
+fn sync_bodies_from_world(
+    b2_world: NonSend<b2World>,
+    settings: Res<b2WorldSettings>,
+    mut bodies: Query<(Entity, &mut b2Body)>,
+    mut last_step_count: Local<u64>,
+    mut sleep_events: EventWriter<b2BodySleepEvent>,
+) {
+    let stepped = b2_world.get_step_count() != *last_step_count;
+    *last_step_count = b2_world.get_step_count();
+    let shared_bodies = b2_world.get_shared_bodies();
+    let pre_step_transforms = b2_world.get_pre_step_transforms();
+    let changed_sleep_states = Mutex::new(Vec::new());
+    let sync_body = |(entity, mut body): (Entity, Mut<b2Body>)| {
+        let Some(body_ptr) = shared_bodies.get(entity) else {
+            return;
+        };
+        // Sleeping bodies can't have moved. Skipping them and only touching the components that
+        // actually changed keeps Changed<b2Body> meaningful.
+        if body.is_settled(body_ptr) {
+            return;
+        }
+
+        let was_awake = body.awake;
+        let body_mut = body.bypass_change_detection();
+        let mut changed = body_mut.sync_with_body(body_ptr);
+        if stepped {
+            // The state right before the last step, which isn't the state of the last frame when
+            // a frame runs several steps
+            changed |= match pre_step_transforms.get(&entity) {
+                Some(&(position, angle)) => body_mut.set_previous_transform(position, angle),
+                None => body_mut.store_previous_transform(),
+            };
+        }
+        if changed {
+            body.set_changed();
+        }
+        if body.awake != was_awake {
+            changed_sleep_states.lock().unwrap().push(b2BodySleepEvent {
+                entity,
+                asleep: !body.awake,
+            });
+        }
+    };
+    if bodies.iter().len() >= settings.parallel_sync_threshold {
+        bodies.par_iter_mut().for_each(sync_body);
+    } else {
+        bodies.iter_mut().for_each(sync_body);
+    }
+
+    // The threads finish in any order
+    let mut changed_sleep_states = changed_sleep_states.into_inner().unwrap();
+    changed_sleep_states.sort_by_key(|event| event.entity);
+    sleep_events.send_batch(changed_sleep_states);
+}
+
+fn check_world_bounds(
+    b2_world: NonSend<b2World>,
+    bounds: Res<WorldBounds>,
+    mut bodies: Query<(Entity, &mut b2Body, Option<&PhysicsWorldId>), Changed<b2Body>>,
+    mut out_of_bounds_events: EventWriter<b2BodyOutOfBoundsEvent>,
+    mut commands: Commands,
+) {
+    for (entity, mut body, world_id) in bodies.iter_mut() {
+        if !in_world(&b2_world, world_id)
+            || !matches!(body.body_type, b2BodyType::Dynamic)
+            || !body.enabled
+            || bounds.aabb.contains(body.position)
+        {
+            continue;
+        }
+
+        out_of_bounds_events.send(b2BodyOutOfBoundsEvent {
+            entity,
+            position: body.position,
+        });
+        match bounds.policy {
+            OutOfBoundsPolicy::EventOnly => {}
+            OutOfBoundsPolicy::Despawn => commands.entity(entity).despawn_recursive(),
+            OutOfBoundsPolicy::Freeze => {
+                body.awake = false;
+                body.enabled = false;
+            }
+        }
+    }
+}
+
+fn sync_joint_reactions_from_world(
+    mut b2_world: NonSendMut<b2World>,
+    settings: Res<b2WorldSettings>,
+    bodies: Query<&b2Body>,
+    mut joints: Query<(Entity, &b2Joint, &mut b2JointReactions)>,
+) {
+    let inv_dt = 1. / settings.time_step;
+    for (entity, joint, mut reactions) in joints.iter_mut() {
+        let is_awake = bodies
+            .get_many([*joint.body_a(), *joint.body_b()])
+            .map_or(false, |[body_a, body_b]| body_a.awake || body_b.awake);
+        // Only marks the reactions as changed if they are, so sleeping joints stay unchanged
+        if !is_awake {
+            reactions.set_if_neq(b2JointReactions::default());
+            continue;
+        }
+
+        let Some(joint_ptr) = b2_world.get_joint_ptr(&entity) else {
+            continue;
+        };
+        reactions.set_if_neq(b2JointReactions {
+            force: joint_ptr.get_reaction_force(inv_dt),
+            torque: joint_ptr.get_reaction_torque(inv_dt),
+        });
+    }
+}
+
+fn send_particle_destruction_events(
+    mut b2_world: NonSendMut<b2World>,
+    mut particle_destroyed_events: EventWriter<b2ParticleDestroyedEvent>,
+    mut group_destroyed_events: EventWriter<b2ParticleGroupDestroyedEvent>,
+) {
+    particle_destroyed_events.send_batch(
+        b2_world
+            .take_destroyed_particles()
+            .into_iter()
+            .map(|(system, index)| b2ParticleDestroyedEvent { system, index }),
+    );
+    group_destroyed_events.send_batch(
+        b2_world
+            .take_destroyed_particle_groups()
+            .into_iter()
+            .map(|group| b2ParticleGroupDestroyedEvent { group }),
+    );
+}
+
+/// Emits the particles for the steps simulated since the last run, so they are created one
+/// frame late but follow the physics time exactly.
+fn emit_particles(
+    b2_world: NonSend<b2World>,
+    settings: Res<b2WorldSettings>,
+    mut emitters: Query<(Entity, &mut b2ParticleEmitter)>,
+    mut particle_systems: Query<(&mut b2ParticleSystem, Option<&PhysicsWorldId>)>,
+    mut last_step_count: Local<u64>,
+) {
+    // The step count starts over when the world is reset
+    let steps = b2_world.get_step_count().saturating_sub(*last_step_count);
+    *last_step_count = b2_world.get_step_count();
+    let elapsed = steps as f32 * settings.time_step;
+
+    let mut emitters: Vec<_> = emitters.iter_mut().collect();
+    emitters.sort_by_key(|(entity, _)| *entity);
+    for (_, mut emitter) in emitters {
+        // Emitters follow the steps of the world of their particle system
+        let particle_system = particle_systems.get_mut(emitter.particle_system).ok();
+        if particle_system
+            .as_ref()
+            .is_some_and(|(_, world_id)| !in_world(&b2_world, *world_id))
+        {
+            continue;
+        }
+        let count = emitter.take_particle_count(elapsed);
+        if count == 0 {
+            continue;
+        }
+        let Some((mut particle_system, _)) = particle_system else {
+            continue;
+        };
+        for _ in 0..count {
+            particle_system.queue_particle(emitter.next_particle_def());
+        }
+    }
+}
+
+fn sync_particle_systems_to_world(
+    mut b2_world: NonSendMut<b2World>,
+    mut particle_systems: Query<(Entity, &mut b2ParticleSystem, Option<&PhysicsWorldId>)>,
+) {
+    for (entity, mut particle_system, world_id) in particle_systems.iter_mut() {
+        if !in_world(&b2_world, world_id) {
+            continue;
+        }
+        if let Err(error) = particle_system.sync_to_world(entity, &mut b2_world) {
+            warn!("Skipping particle system {:?}: {}", entity, error);
+        }
+    }
+}
+
+fn copy_particle_contacts(
+    b2_world: NonSend<b2World>,
+    mut particle_contacts: Query<(
+        Entity,
+        &b2ParticleSystem,
+        &mut b2ParticleSystemContacts,
+        Option<&PhysicsWorldId>,
+    )>,
+) {
+    for (entity, particle_system, mut particle_contacts, world_id) in particle_contacts.iter_mut() {
+        // The contacts of paused particle systems don't change
+        if particle_system.is_paused() || !in_world(&b2_world, world_id) {
+            continue;
+        }
+        particle_contacts.copy_from_world(entity, &b2_world);
+    }
+}
+
+fn sync_particle_groups_from_world(
+    b2_world: NonSend<b2World>,
+    mut particle_groups: Query<(Entity, &mut b2ParticleGroup)>,
+) {
+    for (entity, mut particle_group) in particle_groups.iter_mut() {
+        particle_group.sync_with_world(entity, &b2_world);
+    }
+}
+
+fn sync_particle_systems_from_world(
+    b2_world: NonSend<b2World>,
+    mut particle_systems: Query<(Entity, &mut b2ParticleSystem, Option<&PhysicsWorldId>)>,
+) {
+    for (entity, mut particle_system, world_id) in particle_systems.iter_mut() {
+        if !in_world(&b2_world, world_id) {
+            continue;
+        }
+        if let Err(error) = particle_system.sync_with_world(entity, &b2_world) {
+            warn!("Skipping particle system {:?}: {}", entity, error);
+        }
+    }
+}
+
+fn update_transforms(
+    mut bodies: Query<(
+        &b2Body,
+        &mut Transform,
+        Option<&Parent>,
+        Option<&PhysicsTransformMode>,
+        Option<&mut SyncTransformToBody>,
+        Option<&PhysicsWorldId>,
+    )>,
+    global_transforms: Query<&GlobalTransform>,
+    settings: Res<b2WorldSettings>,
+    physics_time_accumulator: Res<PhysicsTimeAccumulator>,
+    extra_worlds: Option<NonSend<b2Worlds>>,
+    mut remaining_times: Local<Vec<(PhysicsWorldId, f32)>>,
+) {
+    // Every world has its own leftover time. Kept between frames, so it doesn't allocate again
+    remaining_times.clear();
+    remaining_times.push((PhysicsWorldId::DEFAULT, physics_time_accumulator.0));
+    if let Some(extra_worlds) = extra_worlds {
+        remaining_times.extend(
+            extra_worlds
+                .iter()
+                .map(|(id, extra_world)| (id, extra_world.time_accumulator)),
+        );
+    }
+    let remaining_times = &*remaining_times;
+    let update_transform = |(body, mut transform, parent, mode, sync, world_id): (
+        &b2Body,
+        Mut<Transform>,
+        Option<&Parent>,
+        Option<&PhysicsTransformMode>,
+        Option<Mut<SyncTransformToBody>>,
+        Option<&PhysicsWorldId>,
+    )| {
+        if mode == Some(&PhysicsTransformMode::Manual) {
+            return;
+        }
+        let world_id = world_id.copied().unwrap_or_default();
+        let Some(&(_, remaining_time)) = remaining_times.iter().find(|(id, _)| *id == world_id)
+        else {
+            return;
+        };
+
+        let (position, angle) = match settings.transform_sync {
+            TransformSyncMode::Snap => (body.position, body.angle),
+            TransformSyncMode::Extrapolate => (
+                body.position + body.linear_velocity * remaining_time,
+                body.angle + body.angular_velocity * remaining_time,
             ),
+            TransformSyncMode::Interpolate => {
+                let alpha = remaining_time / settings.time_step;
+                let (previous_position, previous_angle) = body.get_previous_transform();
+                (
+                    previous_position.lerp(body.position, alpha),
+                    previous_angle + (body.angle - previous_angle) * alpha,
+                )
+            }
+        };
+
+        // The physics state is in world space, while Transform is relative to the parent
+        let world_transform = GlobalTransform::from(
+            Transform::from_translation(position.extend(0.))
+                .with_rotation(Quat::from_rotation_z(angle)),
         );
+        let parent_transform = parent.and_then(|parent| global_transforms.get(parent.get()).ok());
+        let local_transform = match parent_transform {
+            Some(parent_transform) => world_transform.reparented_to(parent_transform),
+            None => world_transform.compute_transform(),
+        };
+        transform.translation.x = local_transform.translation.x;
+        transform.translation.y = local_transform.translation.y;
+        transform.rotation = local_transform.rotation;
+        if let Some(mut sync) = sync {
+            sync.last_written = Some(*transform);
+        }
+    };
+    if bodies.iter().len() >= settings.parallel_sync_threshold {
+        bodies.par_iter_mut().for_each(update_transform);
+    } else {
+        bodies.iter_mut().for_each(update_transform);
     }
 }
+pub struct LiquidFunDebugDrawPlugin;
+
+impl Plugin for LiquidFunDebugDrawPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<DebugDrawFixtures>()
+            .register_type::<DebugDrawParticleSystem>()
+            .register_type::<DebugDrawConfig>()
+            .init_resource::<DebugDrawConfig>()
+            .add_systems(
+                Last,
+                (
+                    draw_fixtures
+                        .after(TransformSystem::TransformPropagate)
+                        .after(destroy_removed_bodies),
+                    draw_particle_systems.after(TransformSystem::TransformPropagate),
+                    draw_joints,
+                    draw_contacts,
+                    draw_bodies,
+                )
+                    .run_if(physics_world_exists),
+            );
+    }
+}
+
+/// The part of the world seen by the orthographic cameras, `None` if there are none or culling is
+/// disabled.
+fn visible_area(
+    cameras: &Query<(&OrthographicProjection, &GlobalTransform), With<Camera>>,
+    config: &DebugDrawConfig,
+) -> Option<Rect> {
+    if !config.cull_to_cameras {
+        return None;
+    }
+    cameras
+        .iter()
+        .map(|(projection, transform)| {
+            let (scale, _, translation) = transform.to_scale_rotation_translation();
+            // Rotated cameras see more than their area, so the rect is made large enough to
+            // cover any rotation
+            let half_size = projection.area.half_size() * scale.truncate();
+            let center = translation.truncate() + projection.area.center() * scale.truncate();
+            Rect::from_center_half_size(center, Vec2::splat(half_size.length()))
+        })
+        .reduce(|visible, area| visible.union(area))
+}
 
 fn draw_fixtures(
     fixtures: Query<(&b2Fixture, &DebugDrawFixtures)>,
+    indexed_fixtures: Query<(&b2Fixtures, &b2Body, &GlobalTransform, &DebugDrawFixtures)>,
     bodies: Query<(&b2Body, &GlobalTransform)>,
+    cameras: Query<(&OrthographicProjection, &GlobalTransform), With<Camera>>,
+    config: Res<DebugDrawConfig>,
     mut gizmos: Gizmos,
 ) {
-    let to_global =
-        |transform: &GlobalTransform, p: Vec2| transform.transform_point(p.extend(0.)).truncate();
+    let visible_area = visible_area(&cameras, &config);
+    let is_visible = |body: &b2Body, shape: &b2Shape| match visible_area {
+        Some(visible_area) => visible_area
+            .inflate(shape.bounding_radius())
+            .contains(body.position),
+        None => true,
+    };
+
     for (fixture, debug_draw_fixtures) in fixtures.iter() {
         let body_entity = fixture.get_body_entity();
-        let (body, transform) = bodies.get(body_entity).unwrap();
-        let color = if body.awake {
-            debug_draw_fixtures.awake_color
-        } else {
-            debug_draw_fixtures.asleep_color
+        let Ok((body, transform)) = bodies.get(body_entity) else {
+            continue;
         };
-        let shape = fixture.get_shape();
-        match shape {
-            b2Shape::Circle { radius, position } => {
-                gizmos.circle_2d(to_global(transform, *position), *radius, color);
-            }
-            b2Shape::EdgeTwoSided { v1, v2 } => {
-                gizmos.line_2d(to_global(transform, *v1), to_global(transform, *v2), color);
+        if !is_visible(body, fixture.get_shape()) {
+            continue;
+        }
+        draw_fixture(
+            &mut gizmos,
+            fixture.get_shape(),
+            fixture.is_sensor,
+            body,
+            transform,
+            debug_draw_fixtures,
+            &config,
+        );
+    }
+
+    for (fixtures, body, transform, debug_draw_fixtures) in indexed_fixtures.iter() {
+        for fixture_def in fixtures.defs() {
+            if !is_visible(body, &fixture_def.shape) {
+                continue;
             }
-            b2Shape::Polygon { vertices } => {
-                gizmos.linestrip_2d(
-                    vertices
-                        .iter()
-                        .chain(vertices.iter().take(1))
-                        .map(|v| to_global(transform, *v)),
-                    color,
-                );
+            draw_fixture(
+                &mut gizmos,
+                &fixture_def.shape,
+                fixture_def.is_sensor,
+                body,
+                transform,
+                debug_draw_fixtures,
+                &config,
+            );
+        }
+    }
+}
+
+fn draw_fixture(
+    gizmos: &mut Gizmos,
+    shape: &b2Shape,
+    is_sensor: bool,
+    body: &b2Body,
+    transform: &GlobalTransform,
+    debug_draw_fixtures: &DebugDrawFixtures,
+    config: &DebugDrawConfig,
+) {
+    let to_global =
+        |transform: &GlobalTransform, p: Vec2| transform.transform_point(p.extend(0.)).truncate();
+    let color = if is_sensor {
+        config.sensor_color
+    } else if body.awake {
+        debug_draw_fixtures.awake_color
+    } else {
+        debug_draw_fixtures.asleep_color
+    };
+    // Sensors are drawn with dashed lines, which gizmos don't support directly
+    let line = |gizmos: &mut Gizmos, start: Vec2, end: Vec2| {
+        if is_sensor {
+            draw_dashed_line(gizmos, start, end, config.sensor_dash_length, color);
+        } else {
+            gizmos.line_2d(start, end, color);
+        }
+    };
+    let global_points: Vec<Vec2> = match shape {
+        b2Shape::Circle { radius, position } => {
+            let center = to_global(transform, *position);
+            gizmos.circle_2d(center, *radius, color);
+            vec![center - Vec2::splat(*radius), center + Vec2::splat(*radius)]
+        }
+        b2Shape::EdgeTwoSided { v1, v2 } => {
+            let (v1, v2) = (to_global(transform, *v1), to_global(transform, *v2));
+            line(gizmos, v1, v2);
+            vec![v1, v2]
+        }
+        b2Shape::Polygon { vertices } | b2Shape::ChainLoop { vertices } => {
+            let vertices: Vec<Vec2> = vertices.iter().map(|v| to_global(transform, *v)).collect();
+            for (index, vertex) in vertices.iter().enumerate() {
+                line(gizmos, *vertex, vertices[(index + 1) % vertices.len()]);
             }
+            vertices
         }
+    };
+
+    if config.draw_aabbs && !global_points.is_empty() {
+        let (min, max) = global_points
+            .iter()
+            .fold((Vec2::MAX, Vec2::MIN), |(min, max), point| {
+                (min.min(*point), max.max(*point))
+            });
+        gizmos.rect_2d((min + max) / 2., 0., max - min, config.aabb_color);
+    }
+
+    if debug_draw_fixtures.draw_pivot {
+        gizmos.circle_2d(body.position, debug_draw_fixtures.pivot_scale, Color::WHITE);
+    }
+
+    if debug_draw_fixtures.draw_up_vector {
+        gizmos.line_2d(
+            body.position,
+            body.position + transform.up().truncate() * debug_draw_fixtures.vector_scale,
+            Color::GREEN,
+        );
+    }
+
+    if debug_draw_fixtures.draw_right_vector {
+        gizmos.line_2d(
+            body.position,
+            body.position + transform.right().truncate() * debug_draw_fixtures.vector_scale,
+            Color::RED,
+        );
+    }
+}
 
-        if debug_draw_fixtures.draw_pivot {
-            gizmos.circle_2d(body.position, debug_draw_fixtures.pivot_scale, Color::WHITE);
+fn draw_dashed_line(gizmos: &mut Gizmos, start: Vec2, end: Vec2, dash_length: f32, color: Color) {
+    let length = start.distance(end);
+    if dash_length <= 0. || length <= dash_length {
+        gizmos.line_2d(start, end, color);
+        return;
+    }
+    let direction = (end - start) / length;
+    let mut distance = 0.;
+    while distance < length {
+        let dash_end = (distance + dash_length).min(length);
+        gizmos.line_2d(
+            start + direction * distance,
+            start + direction * dash_end,
+            color,
+        );
+        distance += dash_length * 2.;
+    }
+}
+
+fn draw_joints(
+    joints: Query<(Entity, &b2Joint)>,
+    bodies: Query<&b2Body>,
+    b2_world: NonSend<b2World>,
+    config: Res<DebugDrawConfig>,
+    mut gizmos: Gizmos,
+) {
+    if !config.draw_joints {
+        return;
+    }
+
+    for (entity, joint) in joints.iter() {
+        let Some((anchor_a, anchor_b)) = b2_world.get_joint_anchors(&entity) else {
+            continue;
+        };
+        if let Ok(body_a) = bodies.get(*joint.body_a()) {
+            gizmos.line_2d(body_a.position, anchor_a, config.joint_color);
+        }
+        if let Ok(body_b) = bodies.get(*joint.body_b()) {
+            gizmos.line_2d(body_b.position, anchor_b, config.joint_color);
         }
+        gizmos.line_2d(anchor_a, anchor_b, config.joint_color);
+        gizmos.circle_2d(anchor_a, 0.05, config.joint_color);
+        gizmos.circle_2d(anchor_b, 0.05, config.joint_color);
+    }
+}
 
-        if debug_draw_fixtures.draw_up_vector {
+fn draw_contacts(contacts: Res<b2Contacts>, config: Res<DebugDrawConfig>, mut gizmos: Gizmos) {
+    if !config.draw_contacts {
+        return;
+    }
+
+    for contact in contacts.contacts() {
+        let manifold = &contact.manifold;
+        for point in manifold.points() {
+            gizmos.circle_2d(*point, 0.03, config.contact_color);
             gizmos.line_2d(
-                body.position,
-                body.position + transform.up().truncate() * debug_draw_fixtures.vector_scale,
-                Color::GREEN,
+                *point,
+                *point + manifold.normal * config.contact_normal_scale,
+                config.contact_color,
             );
         }
+    }
+}
+
+fn draw_bodies(bodies: Query<&b2Body>, config: Res<DebugDrawConfig>, mut gizmos: Gizmos) {
+    if !config.draw_velocities && !config.draw_centers_of_mass {
+        return;
+    }
 
-        if debug_draw_fixtures.draw_right_vector {
+    for body in bodies.iter() {
+        if config.draw_velocities && body.linear_velocity != Vec2::ZERO {
             gizmos.line_2d(
                 body.position,
-                body.position + transform.right().truncate() * debug_draw_fixtures.vector_scale,
-                Color::RED,
+                body.position + body.linear_velocity,
+                config.velocity_color,
+            );
+        }
+        if config.draw_centers_of_mass {
+            let center = body.get_world_center();
+            let half_size = config.center_of_mass_size / 2.;
+            gizmos.line_2d(
+                center - Vec2::X * half_size,
+                center + Vec2::X * half_size,
+                config.center_of_mass_color,
+            );
+            gizmos.line_2d(
+                center - Vec2::Y * half_size,
+                center + Vec2::Y * half_size,
+                config.center_of_mass_color,
             );
         }
     }
@@ -378,12 +2230,434 @@ fn draw_fixtures(
 
 fn draw_particle_systems(
     particle_systems: Query<(&b2ParticleSystem, &DebugDrawParticleSystem)>,
+    cameras: Query<(&OrthographicProjection, &GlobalTransform), With<Camera>>,
+    config: Res<DebugDrawConfig>,
     mut gizmos: Gizmos,
 ) {
+    let visible_area = visible_area(&cameras, &config);
     for (particle_system, _debug_draw) in particle_systems.iter() {
         let radius = particle_system.get_definition().radius;
-        particle_system.get_positions().iter().for_each(|p| {
-            gizmos.circle_2d(*p, radius, Color::WHITE);
+        let positions = particle_system.get_positions();
+        let colors = particle_system.get_colors();
+        let stride = match config.max_particles_per_system {
+            Some(max_particles) if max_particles > 0 => positions.len().div_ceil(max_particles),
+            _ => 1,
+        };
+        let visible_area = visible_area.map(|visible_area| visible_area.inflate(radius));
+        for (position, color) in positions.iter().zip(colors).step_by(stride.max(1)) {
+            if visible_area.is_some_and(|visible_area| !visible_area.contains(*position)) {
+                continue;
+            }
+            let color = if color.is_zero() {
+                Color::WHITE
+            } else {
+                Color::from(*color)
+            };
+            gizmos.circle_2d(*position, radius, color);
+        }
+    }
+}
+
+/// Renders the particle systems with a [`RenderParticleSystem`] component. Every particle system
+/// is drawn with a single mesh, which scales to far more particles than the debug draw.
+pub struct LiquidFunParticleRenderPlugin;
+
+impl Plugin for LiquidFunParticleRenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<RenderParticleSystem>()
+            .add_systems(Startup, create_particle_texture)
+            .add_systems(
+                Last,
+                (setup_particle_meshes, update_particle_meshes).chain(),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct ParticleTexture(Handle<Image>);
+
+/// A white circle fading out towards the edge.
+fn create_particle_texture(mut images: ResMut<Assets<Image>>, mut commands: Commands) {
+    const SIZE: u32 = 64;
+    let mut data = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let offset = (Vec2::new(x as f32, y as f32) + 0.5) / SIZE as f32 * 2. - 1.;
+            let alpha = (1. - offset.length()).clamp(0., 1.).sqrt();
+            data.extend([255, 255, 255, (alpha * 255.) as u8]);
+        }
+    }
+    let image = Image::new(
+        Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    commands.insert_resource(ParticleTexture(images.add(image)));
+}
+
+fn setup_particle_meshes(
+    mut added: Query<(Entity, &mut RenderParticleSystem), Added<RenderParticleSystem>>,
+    particle_texture: Res<ParticleTexture>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut commands: Commands,
+) {
+    for (entity, mut render) in added.iter_mut() {
+        let mesh = meshes.add(Mesh::new(PrimitiveTopology::TriangleList));
+        let material = materials.add(ColorMaterial {
+            color: Color::WHITE,
+            texture: Some(
+                render
+                    .texture
+                    .clone()
+                    .unwrap_or_else(|| particle_texture.0.clone()),
+            ),
         });
+        render.mesh = Some(mesh.clone());
+        commands.entity(entity).insert((
+            MaterialMesh2dBundle {
+                mesh: mesh.into(),
+                material,
+                ..default()
+            },
+            // The bounds of the mesh change every frame
+            NoFrustumCulling,
+        ));
+    }
+}
+
+fn update_particle_meshes(
+    particle_systems: Query<(&b2ParticleSystem, &RenderParticleSystem)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    const CORNERS: [Vec2; 4] = [
+        Vec2::new(-1., -1.),
+        Vec2::new(1., -1.),
+        Vec2::new(1., 1.),
+        Vec2::new(-1., 1.),
+    ];
+
+    for (particle_system, render) in particle_systems.iter() {
+        let Some(mesh) = render.mesh.as_ref().and_then(|mesh| meshes.get_mut(mesh)) else {
+            continue;
+        };
+
+        let half_size = particle_system.get_definition().radius * render.size_scale;
+        let positions = particle_system.get_positions();
+        let colors = particle_system.get_colors();
+        let mut vertices = Vec::with_capacity(positions.len() * 4);
+        let mut uvs = Vec::with_capacity(positions.len() * 4);
+        let mut vertex_colors = Vec::with_capacity(positions.len() * 4);
+        let mut indices = Vec::with_capacity(positions.len() * 6);
+        for (index, (position, color)) in positions.iter().zip(colors).enumerate() {
+            let color = if color.is_zero() {
+                render.default_color
+            } else {
+                Color::from(*color)
+            };
+            let color = color.as_linear_rgba_f32();
+            for corner in CORNERS {
+                vertices.push((*position + corner * half_size).extend(0.).to_array());
+                uvs.push([(corner.x + 1.) / 2., (1. - corner.y) / 2.]);
+                vertex_colors.push(color);
+            }
+            let first = index as u32 * 4;
+            indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+        }
+
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vertex_colors);
+        mesh.set_indices(Some(Indices::U32(indices)));
+    }
+}
+
+/// Records the [`crate::dynamics::b2BroadPhaseStats`] of the physics world as Bevy diagnostics
+/// every frame, e.g. to print them with `LogDiagnosticsPlugin`.
+pub struct LiquidFunDiagnosticsPlugin;
+
+impl LiquidFunDiagnosticsPlugin {
+    pub const PROXY_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(0x88ed75b43fdb48efb193d7fcd053fa15);
+    pub const TREE_HEIGHT: DiagnosticId =
+        DiagnosticId::from_u128(0x42b19bf9632e4e4080d3a4cbbd710b6b);
+    pub const TREE_BALANCE: DiagnosticId =
+        DiagnosticId::from_u128(0xf04ab1c841f14f99a42276ab5b25ebb6);
+    pub const TREE_QUALITY: DiagnosticId =
+        DiagnosticId::from_u128(0x1874ca92e22b45f09d920de3b6dc1301);
+}
+
+impl Plugin for LiquidFunDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(
+            Self::PROXY_COUNT,
+            "physics_proxy_count",
+            20,
+        ))
+        .register_diagnostic(Diagnostic::new(
+            Self::TREE_HEIGHT,
+            "physics_tree_height",
+            20,
+        ))
+        .register_diagnostic(Diagnostic::new(
+            Self::TREE_BALANCE,
+            "physics_tree_balance",
+            20,
+        ))
+        .register_diagnostic(Diagnostic::new(
+            Self::TREE_QUALITY,
+            "physics_tree_quality",
+            20,
+        ))
+        .add_systems(
+            PostUpdate,
+            measure_broad_phase
+                .run_if(physics_world_exists)
+                .after(sync_bodies_from_world),
+        );
+    }
+}
+
+fn measure_broad_phase(b2_world: NonSend<b2World>, mut diagnostics: Diagnostics) {
+    let stats = b2_world.get_broad_phase_stats();
+    diagnostics.add_measurement(LiquidFunDiagnosticsPlugin::PROXY_COUNT, || {
+        stats.proxy_count as f64
+    });
+    diagnostics.add_measurement(LiquidFunDiagnosticsPlugin::TREE_HEIGHT, || {
+        stats.tree_height as f64
+    });
+    diagnostics.add_measurement(LiquidFunDiagnosticsPlugin::TREE_BALANCE, || {
+        stats.tree_balance as f64
+    });
+    diagnostics.add_measurement(LiquidFunDiagnosticsPlugin::TREE_QUALITY, || {
+        stats.tree_quality as f64
+    });
+}
+
+/// Moves the bodies with a [`CharacterController`] according to their [`MovementIntent`], and
+/// updates their [`Grounded`] state from their contacts and a shape cast below them.
+///
+/// The movement changes the velocity of the body once per frame, and an [`ExternalForce`] keeps
+/// it from sliding down slopes. Both are applied right before the physics systems of the
+/// [`LiquidFunPlugin`] in `PostUpdate`, so it doesn't support the `FixedUpdate` mode yet. The
+/// characters have to be in the default world.
+pub struct LiquidFunCharacterControllerPlugin;
+
+impl Plugin for LiquidFunCharacterControllerPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<CharacterController>()
+            .register_type::<MovementIntent>()
+            .add_systems(
+                PostUpdate,
+                (
+                    init_character_controllers,
+                    apply_deferred,
+                    update_grounded,
+                    apply_character_movement,
+                )
+                    .chain()
+                    .run_if(physics_world_exists)
+                    .after(reset_physics_world)
+                    .before(create_bodies),
+            );
+    }
+}
+
+fn init_character_controllers(
+    added: Query<(Entity, Has<MovementIntent>, Has<ExternalForce>), Added<CharacterController>>,
+    mut commands: Commands,
+) {
+    for (entity, has_intent, has_force) in added.iter() {
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert(Grounded::default());
+        if !has_intent {
+            entity_commands.insert(MovementIntent::default());
+        }
+        if !has_force {
+            entity_commands.insert(ExternalForce::default());
+        }
+    }
+}
+
+fn up_direction(gravity: Vec2) -> Vec2 {
+    let up = -gravity.normalize_or_zero();
+    if up == Vec2::ZERO {
+        Vec2::Y
+    } else {
+        up
+    }
+}
+
+fn update_grounded(
+    b2_world: NonSend<b2World>,
+    contacts: Res<b2Contacts>,
+    fixtures: Query<&b2Fixture>,
+    bodies: Query<&b2Body>,
+    mut characters: Query<(Entity, &CharacterController, &mut Grounded)>,
+) {
+    let up = up_direction(b2_world.gravity);
+    for (entity, controller, mut grounded) in characters.iter_mut() {
+        let Ok(body) = bodies.get(entity) else {
+            continue;
+        };
+
+        let mut ground: Option<(Vec2, Entity)> = None;
+        let mut wall_normal = None;
+        for contact in contacts.contacts_of(entity) {
+            if !contact.is_touching || !contact.is_enabled || contact.sensors().next().is_some() {
+                continue;
+            }
+            // The manifold normal points from A to B, we want it pointing at the character
+            let (normal, other_body) = if contact.body_a == entity {
+                (-contact.manifold.normal, contact.body_b)
+            } else {
+                (contact.manifold.normal, contact.body_a)
+            };
+            if controller.is_walkable(normal, up) {
+                let flatter = ground.map_or(true, |(ground_normal, _)| {
+                    normal.dot(up) > ground_normal.dot(up)
+                });
+                if flatter {
+                    ground = Some((normal, other_body));
+                }
+            } else if normal.dot(up) > -0.1 {
+                wall_normal = Some(normal);
+            }
+        }
+
+        // Jumping characters would be snapped right back to the ground
+        let moving_up = body.linear_velocity.dot(up) > 0.5 * controller.jump_speed;
+        if ground.is_none() && controller.ground_snap_distance > 0. && !moving_up {
+            let filter = b2RayCastFilter::excluding_bodies([entity]).without_sensors();
+            let fixture_entities = b2_world.get_fixtures_attached_to_entity(&entity);
+            for fixture in fixture_entities
+                .into_iter()
+                .flatten()
+                .filter_map(|fixture_entity| fixtures.get(*fixture_entity).ok())
+                .filter(|fixture| !fixture.is_sensor)
+            {
+                let hit = b2_world.shape_cast(
+                    fixture.get_shape(),
+                    (body.position, body.angle),
+                    -up * controller.ground_snap_distance,
+                    &filter,
+                );
+                if let Some(hit) = hit {
+                    if controller.is_walkable(hit.normal, up) {
+                        ground = Some((hit.normal, hit.body_entity));
+                        break;
+                    }
+                }
+            }
+        }
+
+        *grounded = match ground {
+            Some((normal, ground_body)) => Grounded {
+                is_grounded: true,
+                normal,
+                ground_body: Some(ground_body),
+                ground_velocity: bodies.get(ground_body).map_or(Vec2::ZERO, |ground| {
+                    ground.linear_velocity_at_world_point(body.position)
+                }),
+                wall_normal,
+            },
+            None => Grounded {
+                normal: up,
+                wall_normal,
+                ..default()
+            },
+        };
+    }
+}
+
+fn apply_character_movement(
+    b2_world: NonSend<b2World>,
+    settings: Res<b2WorldSettings>,
+    time: Res<Time>,
+    physics_time: Res<PhysicsTime>,
+    mut characters: Query<(
+        &CharacterController,
+        &Grounded,
+        &mut MovementIntent,
+        &mut b2Body,
+        &mut ExternalForce,
+        Option<&GravityScale>,
+    )>,
+) {
+    let up = up_direction(b2_world.gravity);
+    let right = Vec2::new(up.y, -up.x);
+    // The physics time the steps of this frame are going to simulate
+    let simulated_time = if physics_time.is_paused() {
+        0.
+    } else {
+        time.delta_seconds().min(settings.max_frame_delta) * physics_time.time_scale
+    };
+    for (controller, grounded, mut intent, mut body, mut external_force, gravity_scale) in
+        characters.iter_mut()
+    {
+        let mass = body.get_mass();
+        if mass == 0. {
+            continue;
+        }
+
+        let jump = intent.take_jump(
+            time.delta_seconds(),
+            controller.jump_buffer_time,
+            grounded.is_grounded,
+        );
+        if jump {
+            let velocity = body.linear_velocity;
+            body.linear_velocity = velocity - up * velocity.dot(up) + up * controller.jump_speed;
+        }
+
+        let target_speed = intent.desired_velocity.dot(right);
+        let (axis, max_acceleration) = if grounded.is_grounded && !jump {
+            let along_ground = Vec2::new(grounded.normal.y, -grounded.normal.x);
+            (along_ground, controller.max_acceleration)
+        } else {
+            (right, controller.max_air_acceleration)
+        };
+        // The velocity is changed once per frame rather than through a force, which would act
+        // in every step of the frame and overshoot the target speed when several steps run
+        let relative_velocity = body.linear_velocity - grounded.ground_velocity;
+        let max_speed_change = max_acceleration * simulated_time;
+        let mut velocity_change = axis
+            * (target_speed - relative_velocity.dot(axis))
+                .clamp(-max_speed_change, max_speed_change);
+
+        let mut force = Vec2::ZERO;
+        if grounded.is_grounded && !jump {
+            // Keeps the character from sliding down the slopes it can stand on. Gravity acts in
+            // every step, so this has to as well
+            let gravity_scale = gravity_scale.map_or(1., |gravity_scale| gravity_scale.0);
+            force -= axis * b2_world.gravity.dot(axis) * gravity_scale * mass;
+        }
+
+        // Pushing into a wall would press the character against it, and the friction would keep
+        // it from falling
+        if let Some(wall_normal) = grounded.wall_normal {
+            let into_wall = velocity_change.dot(wall_normal);
+            if into_wall < 0. {
+                velocity_change -= wall_normal * into_wall;
+            }
+            let into_wall = force.dot(wall_normal);
+            if into_wall < 0. {
+                force -= wall_normal * into_wall;
+            }
+        }
+
+        if velocity_change != Vec2::ZERO {
+            body.linear_velocity += velocity_change;
+            body.awake = true;
+        }
+        if force != Vec2::ZERO {
+            external_force.apply_force(force);
+            external_force.should_wake = true;
+        }
     }
 }
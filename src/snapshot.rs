@@ -0,0 +1,425 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::dynamics::{
+    b2Body, b2BodyBundle, b2BodyDef, b2BodyType, b2DistanceJoint, b2Fixture, b2FixtureDef,
+    b2Fixtures, b2Joint, b2JointReactions, b2JointType, b2MouseJoint, b2PrismaticJoint,
+    b2PulleyJoint, b2RevoluteJoint, b2WheelJoint, b2World, b2WorldSettings, GravityScale,
+    PhysicsWorldId,
+};
+use crate::particles::{
+    b2ParticleColor, b2ParticleDef, b2ParticleFlags, b2ParticleGroup, b2ParticleGroupDef,
+    b2ParticleSystem, b2ParticleSystemDef,
+};
+
+/// The state of the physics world, as far as it is needed to recreate it, e.g. for save games.
+///
+/// Entities are stored as they were when the snapshot was taken and are remapped by
+/// [`spawn_from_snapshot`]. The internal solver state, such as warm starting impulses, is not
+/// part of the snapshot. Particles keep their state and group, but not necessarily their index.
+///
+/// Only the default world is captured, the entities of the worlds in
+/// [`crate::dynamics::b2Worlds`] are left out.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct b2WorldSnapshot {
+    pub gravity: Vec2,
+    pub settings: b2WorldSettings,
+    pub bodies: Vec<b2BodySnapshot>,
+    pub fixtures: Vec<b2FixtureSnapshot>,
+    pub joints: Vec<b2JointSnapshot>,
+    pub particle_systems: Vec<b2ParticleSystemSnapshot>,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct b2BodySnapshot {
+    pub entity: Entity,
+    pub body_type: b2BodyType,
+    pub position: Vec2,
+    pub angle: f32,
+    pub linear_velocity: Vec2,
+    pub angular_velocity: f32,
+    pub awake: bool,
+    pub allow_sleep: bool,
+    pub fixed_rotation: bool,
+    pub bullet: bool,
+    pub enabled: bool,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    pub gravity_scale: f32,
+    /// The fixtures of a [`b2Fixtures`] component on the body entity.
+    pub indexed_fixtures: Option<Vec<b2FixtureDef>>,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct b2FixtureSnapshot {
+    pub entity: Entity,
+    pub body: Entity,
+    pub def: b2FixtureDef,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct b2JointSnapshot {
+    pub entity: Entity,
+    pub body_a: Entity,
+    pub body_b: Entity,
+    pub collide_connected: bool,
+    pub joint: b2JointStateSnapshot,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum b2JointStateSnapshot {
+    Revolute(b2RevoluteJoint),
+    Prismatic(b2PrismaticJoint),
+    Distance(b2DistanceJoint),
+    Pulley(b2PulleyJoint),
+    Mouse(b2MouseJoint),
+    Wheel(b2WheelJoint),
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct b2ParticleSystemSnapshot {
+    pub entity: Entity,
+    pub def: b2ParticleSystemDef,
+    pub positions: Vec<Vec2>,
+    pub velocities: Vec<Vec2>,
+    pub colors: Vec<b2ParticleColor>,
+    pub flags: Vec<b2ParticleFlags>,
+    /// The remaining lifetime of every particle in seconds, 0 for infinite.
+    pub lifetimes: Vec<f32>,
+    /// Only kept for particle systems created with [`b2ParticleSystem::with_user_data`].
+    pub user_data: Option<Vec<u32>>,
+    pub groups: Vec<b2ParticleGroupSnapshot>,
+}
+
+impl b2ParticleSystemSnapshot {
+    /// The state of a particle, with defaults for whatever is missing from the snapshot.
+    pub fn particle(&self, index: usize) -> b2ParticleDef {
+        b2ParticleDef {
+            flags: self
+                .flags
+                .get(index)
+                .copied()
+                .unwrap_or(b2ParticleFlags::WaterParticle),
+            position: self.positions.get(index).copied().unwrap_or_default(),
+            velocity: self.velocities.get(index).copied().unwrap_or_default(),
+            lifetime: self.lifetimes.get(index).copied().unwrap_or_default(),
+            color: self.colors.get(index).copied().unwrap_or_default(),
+            user_data: self
+                .user_data
+                .as_ref()
+                .and_then(|user_data| user_data.get(index))
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Box2D keeps the particles of a group next to each other, so they are stored as a range of the
+/// particles of the particle system.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct b2ParticleGroupSnapshot {
+    pub entity: Entity,
+    pub def: b2ParticleGroupDef,
+    pub first_index: usize,
+    pub particle_count: usize,
+}
+
+impl b2ParticleGroupSnapshot {
+    pub fn contains(&self, index: usize) -> bool {
+        (self.first_index..self.first_index + self.particle_count).contains(&index)
+    }
+}
+
+/// Takes a snapshot of all the bodies, fixtures, joints and particle systems in the default world.
+pub fn snapshot(world: &mut World) -> b2WorldSnapshot {
+    let gravity = world.non_send_resource::<b2World>().gravity;
+    let settings = world.resource::<b2WorldSettings>().clone();
+
+    let mut bodies: Vec<b2BodySnapshot> = world
+        .query::<(
+            Entity,
+            &b2Body,
+            Option<&GravityScale>,
+            Option<&b2Fixtures>,
+            Option<&PhysicsWorldId>,
+        )>()
+        .iter(world)
+        .filter(|(.., world_id)| in_default_world(*world_id))
+        .map(
+            |(entity, body, gravity_scale, fixtures, _)| b2BodySnapshot {
+                entity,
+                body_type: body.body_type,
+                position: body.position,
+                angle: body.angle,
+                linear_velocity: body.linear_velocity,
+                angular_velocity: body.angular_velocity,
+                awake: body.awake,
+                allow_sleep: body.allow_sleep,
+                fixed_rotation: body.fixed_rotation,
+                bullet: body.bullet,
+                enabled: body.enabled,
+                linear_damping: body.linear_damping,
+                angular_damping: body.angular_damping,
+                gravity_scale: gravity_scale.copied().unwrap_or_default().0,
+                indexed_fixtures: fixtures.map(|fixtures| fixtures.defs().to_vec()),
+            },
+        )
+        .collect();
+    bodies.sort_by_key(|body| body.entity);
+    let body_entities: HashSet<Entity> = bodies.iter().map(|body| body.entity).collect();
+
+    let mut fixtures: Vec<(Entity, b2FixtureSnapshot)> = world
+        .query::<(Entity, &b2Fixture)>()
+        .iter(world)
+        .filter(|(_, fixture)| body_entities.contains(&fixture.get_body_entity()))
+        .map(|(entity, fixture)| {
+            let snapshot = b2FixtureSnapshot {
+                entity,
+                body: fixture.get_body_entity(),
+                def: fixture.extract_fixture_def(),
+            };
+            (entity, snapshot)
+        })
+        .collect();
+    fixtures.sort_by_key(|(entity, _)| *entity);
+
+    let mut joints = Vec::new();
+    snapshot_joints(world, &mut joints, b2JointStateSnapshot::Revolute);
+    snapshot_joints(world, &mut joints, b2JointStateSnapshot::Prismatic);
+    snapshot_joints(world, &mut joints, b2JointStateSnapshot::Distance);
+    snapshot_joints(world, &mut joints, b2JointStateSnapshot::Pulley);
+    snapshot_joints(world, &mut joints, b2JointStateSnapshot::Mouse);
+    snapshot_joints(world, &mut joints, b2JointStateSnapshot::Wheel);
+    joints.retain(|(_, joint)| body_entities.contains(&joint.body_a));
+    joints.sort_by_key(|(entity, _)| *entity);
+
+    let mut particle_systems: Vec<b2ParticleSystemSnapshot> = world
+        .query::<(Entity, &b2ParticleSystem, Option<&PhysicsWorldId>)>()
+        .iter(world)
+        .filter(|(.., world_id)| in_default_world(*world_id))
+        .map(|(entity, particle_system, _)| {
+            let particle_count = particle_system.particle_count();
+            b2ParticleSystemSnapshot {
+                entity,
+                def: particle_system.get_definition().clone(),
+                positions: particle_system.get_positions().to_vec(),
+                velocities: particle_system.get_velocities().to_vec(),
+                colors: particle_system.get_colors().to_vec(),
+                flags: Vec::new(),
+                lifetimes: Vec::new(),
+                user_data: particle_system.has_user_data().then(|| {
+                    (0..particle_count)
+                        .map(|index| particle_system.get_user_data(index))
+                        .collect()
+                }),
+                groups: Vec::new(),
+            }
+        })
+        .collect();
+    particle_systems.sort_by_key(|particle_system| particle_system.entity);
+
+    let mut groups: Vec<(Entity, Entity, b2ParticleGroupDef)> = world
+        .query::<(Entity, &b2ParticleGroup)>()
+        .iter(world)
+        .map(|(entity, group)| {
+            let particle_system_entity = group.get_particle_system_entity();
+            (
+                entity,
+                particle_system_entity,
+                group.get_definition().clone(),
+            )
+        })
+        .collect();
+    groups.sort_by_key(|(entity, _, _)| *entity);
+
+    let mut b2_world = world.non_send_resource_mut::<b2World>();
+    let b2_world = b2_world.bypass_change_detection();
+    for particle_system in particle_systems.iter_mut() {
+        particle_system.flags = b2_world.get_particle_flags(&particle_system.entity);
+        particle_system.lifetimes = b2_world.get_particle_lifetimes(&particle_system.entity);
+    }
+    for (entity, particle_system_entity, def) in groups {
+        // Groups Box2D has destroyed or that were never created have no particles to restore
+        let Some(group_ptr) = b2_world.get_particle_group_ptr(&entity) else {
+            continue;
+        };
+        let Some(particle_system) = particle_systems
+            .iter_mut()
+            .find(|particle_system| particle_system.entity == particle_system_entity)
+        else {
+            continue;
+        };
+        particle_system.groups.push(b2ParticleGroupSnapshot {
+            entity,
+            def,
+            first_index: i32::from(group_ptr.as_ref().GetBufferIndex()) as usize,
+            particle_count: i32::from(group_ptr.as_ref().GetParticleCount()) as usize,
+        });
+    }
+
+    b2WorldSnapshot {
+        gravity,
+        settings,
+        bodies,
+        fixtures: fixtures.into_iter().map(|(_, fixture)| fixture).collect(),
+        joints: joints.into_iter().map(|(_, joint)| joint).collect(),
+        particle_systems,
+    }
+}
+
+fn in_default_world(world_id: Option<&PhysicsWorldId>) -> bool {
+    world_id.copied().unwrap_or_default() == PhysicsWorldId::DEFAULT
+}
+
+fn snapshot_joints<T: Component + Clone>(
+    world: &mut World,
+    joints: &mut Vec<(Entity, b2JointSnapshot)>,
+    to_snapshot: impl Fn(T) -> b2JointStateSnapshot,
+) {
+    let snapshots =
+        world
+            .query::<(Entity, &b2Joint, &T)>()
+            .iter(world)
+            .map(|(entity, joint, joint_state)| {
+                let snapshot = b2JointSnapshot {
+                    entity,
+                    body_a: *joint.body_a(),
+                    body_b: *joint.body_b(),
+                    collide_connected: joint.collide_connected(),
+                    joint: to_snapshot(joint_state.clone()),
+                };
+                (entity, snapshot)
+            });
+    joints.extend(snapshots);
+}
+
+/// Spawns the contents of a snapshot into the world, which is expected to be empty of physics
+/// entities. Returns the new entities of the bodies, fixtures, joints, particle systems and
+/// particle groups, keyed by the entities they had when the snapshot was taken.
+pub fn spawn_from_snapshot(
+    commands: &mut Commands,
+    snapshot: &b2WorldSnapshot,
+) -> HashMap<Entity, Entity> {
+    commands.insert_resource(snapshot.settings.clone());
+    let gravity = snapshot.gravity;
+    commands.add(move |world: &mut World| {
+        if let Some(mut b2_world) = world.get_non_send_resource_mut::<b2World>() {
+            b2_world.gravity = gravity;
+        }
+    });
+
+    let mut entity_map = HashMap::new();
+    for body_snapshot in &snapshot.bodies {
+        let body_def = b2BodyDef {
+            body_type: body_snapshot.body_type,
+            position: body_snapshot.position,
+            angle: body_snapshot.angle,
+            allow_sleep: body_snapshot.allow_sleep,
+            fixed_rotation: body_snapshot.fixed_rotation,
+            bullet: body_snapshot.bullet,
+            enabled: body_snapshot.enabled,
+            linear_damping: body_snapshot.linear_damping,
+            angular_damping: body_snapshot.angular_damping,
+            gravity_scale: body_snapshot.gravity_scale,
+        };
+        let mut body_bundle = b2BodyBundle::new(&body_def);
+        body_bundle.body.linear_velocity = body_snapshot.linear_velocity;
+        body_bundle.body.angular_velocity = body_snapshot.angular_velocity;
+        body_bundle.body.awake = body_snapshot.awake;
+
+        let mut body_entity = commands.spawn(body_bundle);
+        if let Some(fixture_defs) = &body_snapshot.indexed_fixtures {
+            body_entity.insert(b2Fixtures::new(fixture_defs.iter().cloned()));
+        }
+        entity_map.insert(body_snapshot.entity, body_entity.id());
+    }
+
+    for fixture_snapshot in &snapshot.fixtures {
+        let Some(body_entity) = entity_map.get(&fixture_snapshot.body) else {
+            continue;
+        };
+        let fixture_entity = commands
+            .spawn(b2Fixture::new(*body_entity, &fixture_snapshot.def))
+            .id();
+        entity_map.insert(fixture_snapshot.entity, fixture_entity);
+    }
+
+    for joint_snapshot in &snapshot.joints {
+        let (Some(body_a), Some(body_b)) = (
+            entity_map.get(&joint_snapshot.body_a),
+            entity_map.get(&joint_snapshot.body_b),
+        ) else {
+            continue;
+        };
+        let joint = |joint_type| {
+            (
+                b2Joint::new(
+                    joint_type,
+                    *body_a,
+                    *body_b,
+                    joint_snapshot.collide_connected,
+                ),
+                b2JointReactions::default(),
+            )
+        };
+        let joint_entity = match &joint_snapshot.joint {
+            b2JointStateSnapshot::Revolute(state) => {
+                commands.spawn((joint(b2JointType::Revolute), state.clone()))
+            }
+            b2JointStateSnapshot::Prismatic(state) => {
+                commands.spawn((joint(b2JointType::Prismatic), state.clone()))
+            }
+            b2JointStateSnapshot::Distance(state) => {
+                commands.spawn((joint(b2JointType::Distance), state.clone()))
+            }
+            b2JointStateSnapshot::Pulley(state) => {
+                commands.spawn((joint(b2JointType::Pulley), state.clone()))
+            }
+            b2JointStateSnapshot::Mouse(state) => {
+                commands.spawn((joint(b2JointType::Mouse), state.clone()))
+            }
+            b2JointStateSnapshot::Wheel(state) => {
+                commands.spawn((joint(b2JointType::Wheel), state.clone()))
+            }
+        }
+        .id();
+        entity_map.insert(joint_snapshot.entity, joint_entity);
+    }
+
+    for particle_system_snapshot in &snapshot.particle_systems {
+        let mut particle_system = b2ParticleSystem::new(&particle_system_snapshot.def);
+        if particle_system_snapshot.user_data.is_some() {
+            particle_system = particle_system.with_user_data();
+        }
+        let groups = &particle_system_snapshot.groups;
+        for index in 0..particle_system_snapshot.positions.len() {
+            if !groups.iter().any(|group| group.contains(index)) {
+                particle_system.queue_particle(particle_system_snapshot.particle(index));
+            }
+        }
+        let particle_system_entity = commands.spawn(particle_system).id();
+        entity_map.insert(particle_system_snapshot.entity, particle_system_entity);
+
+        for group_snapshot in groups {
+            let particles = (group_snapshot.first_index..)
+                .take(group_snapshot.particle_count)
+                .take_while(|index| *index < particle_system_snapshot.positions.len())
+                .map(|index| particle_system_snapshot.particle(index))
+                .collect();
+            let group = b2ParticleGroup::new(particle_system_entity, &group_snapshot.def)
+                .with_restored_particles(particles);
+            entity_map.insert(group_snapshot.entity, commands.spawn(group).id());
+        }
+    }
+
+    entity_map
+}
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
 pub struct DebugDrawFixtures {
     pub awake_color: Color,
     pub asleep_color: Color,
@@ -48,5 +49,104 @@ impl DebugDrawFixtures {
     }
 }
 
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
 pub struct DebugDrawParticleSystem {}
+
+/// Renders the particle system on the same entity as a single mesh with a textured quad per
+/// particle, see [`crate::plugins::LiquidFunParticleRenderPlugin`]. The mesh is rebuilt every
+/// frame in world space, so the entity should keep its identity transform.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct RenderParticleSystem {
+    /// The texture of every particle, a soft circle if not set.
+    pub texture: Option<Handle<Image>>,
+    /// The size of the quads relative to the particle diameter.
+    pub size_scale: f32,
+    /// The color of the particles without a color of their own.
+    pub default_color: Color,
+    #[reflect(ignore)]
+    pub(crate) mesh: Option<Handle<Mesh>>,
+}
+
+impl Default for RenderParticleSystem {
+    fn default() -> Self {
+        Self {
+            texture: None,
+            size_scale: 1.,
+            default_color: Color::WHITE,
+            mesh: None,
+        }
+    }
+}
+
+impl RenderParticleSystem {
+    /// The mesh the particles are rendered with, available once the plugin has picked up the
+    /// component. Its vertex buffer holds the corners of the particle quads, four per particle in
+    /// particle order, which custom materials can build on.
+    pub fn mesh(&self) -> Option<&Handle<Mesh>> {
+        self.mesh.as_ref()
+    }
+}
+
+/// The optional layers of [`crate::plugins::LiquidFunDebugDrawPlugin`], which can be switched
+/// at runtime. Unlike the fixtures and particle systems, these are drawn for the whole world.
+///
+/// The debug draw uses the default gizmos, so the line width is set with Bevy's `GizmoConfig`,
+/// which applies to all gizmos.
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct DebugDrawConfig {
+    /// The anchors of every joint and the line between them.
+    pub draw_joints: bool,
+    pub joint_color: Color,
+
+    /// The points and normals of the touching contacts in [`crate::dynamics::b2Contacts`].
+    pub draw_contacts: bool,
+    pub contact_color: Color,
+    pub contact_normal_scale: f32,
+
+    /// The bounding boxes of the fixtures with a [`DebugDrawFixtures`] component.
+    pub draw_aabbs: bool,
+    pub aabb_color: Color,
+
+    /// The linear velocity of every body, as the distance it moves in a second.
+    pub draw_velocities: bool,
+    pub velocity_color: Color,
+
+    pub draw_centers_of_mass: bool,
+    pub center_of_mass_color: Color,
+    pub center_of_mass_size: f32,
+
+    /// Sensors are drawn with dashed lines in this color instead of the fixture colors.
+    pub sensor_color: Color,
+    pub sensor_dash_length: f32,
+
+    /// Skips the fixtures and particles outside the view of the orthographic cameras.
+    pub cull_to_cameras: bool,
+    /// Draws only every n-th particle of the particle systems with more particles than this.
+    pub max_particles_per_system: Option<usize>,
+}
+
+impl Default for DebugDrawConfig {
+    fn default() -> Self {
+        Self {
+            draw_joints: false,
+            joint_color: Color::TEAL,
+            draw_contacts: false,
+            contact_color: Color::YELLOW,
+            contact_normal_scale: 0.3,
+            draw_aabbs: false,
+            aabb_color: Color::PINK,
+            draw_velocities: false,
+            velocity_color: Color::CYAN,
+            draw_centers_of_mass: false,
+            center_of_mass_color: Color::WHITE,
+            center_of_mass_size: 0.1,
+            sensor_color: Color::rgba(1., 1., 1., 0.5),
+            sensor_dash_length: 0.1,
+            cull_to_cameras: true,
+            max_particles_per_system: None,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app_with, spawn_box, update_frames};
+
+type Mutation = fn(&mut b2Body);
+type Check = fn(&b2Body) -> bool;
+
+/// Every field of [`b2Body`] that can be changed, with a change and how to tell it stuck.
+const FIELDS: [(&str, Mutation, Check); 12] = [
+    (
+        "body_type",
+        |body| body.body_type = b2BodyType::Kinematic,
+        |body| body.body_type == b2BodyType::Kinematic,
+    ),
+    (
+        "position",
+        |body| body.position = Vec2::new(3., 4.),
+        |body| body.position == Vec2::new(3., 4.),
+    ),
+    ("angle", |body| body.angle = 0.5, |body| body.angle == 0.5),
+    (
+        "linear_velocity",
+        |body| body.linear_velocity = Vec2::new(1., 2.),
+        |body| body.linear_velocity == Vec2::new(1., 2.),
+    ),
+    (
+        "angular_velocity",
+        |body| body.angular_velocity = 1.5,
+        |body| body.angular_velocity == 1.5,
+    ),
+    ("awake", |body| body.awake = false, |body| !body.awake),
+    (
+        "allow_sleep",
+        |body| body.allow_sleep = false,
+        |body| !body.allow_sleep,
+    ),
+    (
+        "fixed_rotation",
+        |body| body.fixed_rotation = true,
+        |body| body.fixed_rotation && body.get_inertia() == 0.,
+    ),
+    ("bullet", |body| body.bullet = true, |body| body.bullet),
+    ("enabled", |body| body.enabled = false, |body| !body.enabled),
+    (
+        "linear_damping",
+        |body| body.linear_damping = 0.25,
+        |body| body.linear_damping == 0.25,
+    ),
+    (
+        "angular_damping",
+        |body| body.angular_damping = 0.75,
+        |body| body.angular_damping == 0.75,
+    ),
+];
+
+#[test]
+fn every_body_field_reaches_the_physics_world() {
+    for (name, mutate, check) in FIELDS {
+        // Nothing moves the body by itself, so the changed state survives a step
+        let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+        let entity = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::ZERO);
+        update_frames(&mut app, 1);
+
+        mutate(&mut app.world.get_mut::<b2Body>(entity).unwrap());
+        update_frames(&mut app, 1);
+
+        let body = app.world.get::<b2Body>(entity).unwrap();
+        assert!(check(body), "{} didn't stick: {:?}", name, body);
+        // Reads back the physics world once more, in case the last sync skipped the body
+        let mut body = b2Body::default();
+        body.sync_with_world(entity, app.world.non_send_resource::<b2World>())
+            .unwrap();
+        assert!(
+            check(&body),
+            "{} isn't set in the physics world: {:?}",
+            name,
+            body
+        );
+    }
+}
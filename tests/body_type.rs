@@ -0,0 +1,30 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, spawn_box, update_frames};
+
+#[test]
+fn static_bodies_fall_once_they_are_made_dynamic() {
+    let mut app = headless_app();
+    let body = spawn_box(&mut app.world, b2BodyType::Static, Vec2::new(0., 5.));
+    update_frames(&mut app, 10);
+    let body_component = app.world.get::<b2Body>(body).unwrap();
+    assert_eq!(body_component.position, Vec2::new(0., 5.));
+    assert_eq!(body_component.get_mass(), 0.);
+
+    app.world.get_mut::<b2Body>(body).unwrap().body_type = b2BodyType::Dynamic;
+    update_frames(&mut app, 30);
+
+    let body_component = app.world.get::<b2Body>(body).unwrap();
+    assert!(
+        body_component.position.y < 4.,
+        "{:?}",
+        body_component.position
+    );
+    assert!(body_component.linear_velocity.y < 0.);
+    // Box2D recomputes the mass from the fixture when the type changes
+    assert!((body_component.get_mass() - 1.).abs() < 1e-5);
+}
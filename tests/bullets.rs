@@ -0,0 +1,60 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app_with, update_frames};
+
+/// Fires a small circle at a thin dynamic wall, far faster than the wall is thick per step.
+/// Box2D always sweeps dynamic bodies against static and kinematic ones, so the wall is dynamic
+/// to see what the bullet flag changes. Returns the final x of the circle and the wall.
+fn fire_at_wall(bullet: bool) -> (f32, f32) {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let wall_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        ..default()
+    };
+    let wall = app.world.spawn(b2BodyBundle::new(&wall_def)).id();
+    app.world.spawn(b2Fixture::new(
+        wall,
+        &b2FixtureDef::new(b2Shape::create_box(0.05, 2.), 100.),
+    ));
+
+    let circle_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position: Vec2::new(-5., 0.),
+        bullet,
+        ..default()
+    };
+    let circle = app.world.spawn(b2BodyBundle::new(&circle_def)).id();
+    let shape = b2Shape::Circle {
+        radius: 0.05,
+        position: Vec2::ZERO,
+    };
+    app.world
+        .spawn(b2Fixture::new(circle, &b2FixtureDef::new(shape, 1.)));
+    // Almost 2 units per step, Box2D's limit
+    app.world.get_mut::<b2Body>(circle).unwrap().linear_velocity = Vec2::new(110., 0.);
+    update_frames(&mut app, 10);
+
+    let x = |entity| app.world.get::<b2Body>(entity).unwrap().position.x;
+    (x(circle), x(wall))
+}
+
+#[test]
+fn bullets_hit_thin_dynamic_bodies() {
+    let (circle_x, wall_x) = fire_at_wall(true);
+    assert!(circle_x < wall_x, "{} went through {}", circle_x, wall_x);
+}
+
+#[test]
+fn non_bullets_tunnel_through_thin_dynamic_bodies() {
+    let (circle_x, wall_x) = fire_at_wall(false);
+    assert!(
+        circle_x > wall_x + 1.,
+        "{} didn't get past {}",
+        circle_x,
+        wall_x
+    );
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, spawn_box, update_frames};
+
+/// Above the body count the plugin syncs the bodies on several threads from, so both ways of
+/// syncing are covered.
+const BODY_COUNT: usize = 2_000;
+
+/// The number of bodies marked as changed during each frame.
+#[derive(Resource, Default)]
+struct ChangedBodies(Vec<usize>);
+
+fn count_changed_bodies(
+    bodies: Query<(), Changed<b2Body>>,
+    mut changed_bodies: ResMut<ChangedBodies>,
+) {
+    changed_bodies.0.push(bodies.iter().count());
+}
+
+#[test]
+fn sleeping_bodies_are_not_marked_as_changed() {
+    let mut app = headless_app();
+    app.init_resource::<ChangedBodies>()
+        .add_systems(Last, count_changed_bodies);
+
+    let ground = app.world.spawn(b2BodyBundle::default()).id();
+    let half_width = BODY_COUNT as f32;
+    let shape = b2Shape::EdgeTwoSided {
+        v1: Vec2::new(-half_width, 0.),
+        v2: Vec2::new(half_width, 0.),
+    };
+    app.world
+        .spawn(b2Fixture::new(ground, &b2FixtureDef::new(shape, 0.)));
+    let bodies: Vec<Entity> = (0..BODY_COUNT)
+        .map(|i| {
+            let position = Vec2::new(i as f32 * 2. - half_width, 0.5);
+            spawn_box(&mut app.world, b2BodyType::Dynamic, position)
+        })
+        .collect();
+
+    let mut frames = 0;
+    while bodies
+        .iter()
+        .any(|body| app.world.get::<b2Body>(*body).unwrap().awake)
+    {
+        assert!(frames < 300, "the bodies didn't fall asleep");
+        update_frames(&mut app, 1);
+        frames += 1;
+    }
+    app.world.resource_mut::<ChangedBodies>().0.clear();
+
+    update_frames(&mut app, 30);
+
+    assert_eq!(app.world.resource::<ChangedBodies>().0, vec![0; 30]);
+}
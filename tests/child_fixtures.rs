@@ -0,0 +1,51 @@
+mod common;
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, update_frames};
+
+fn ray_cast_down(app: &App, x: f32) -> Vec<b2RayCastHit> {
+    app.world
+        .non_send_resource::<b2World>()
+        .ray_cast_with_filter(
+            b2RayCastAll::new(),
+            &Vec2::new(x, 5.),
+            &Vec2::new(x, -5.),
+            &b2RayCastFilter::default(),
+        )
+}
+
+#[test]
+fn child_fixtures_are_offset_by_their_transform() {
+    let mut app = headless_app();
+    let body_def = b2BodyDef {
+        position: Vec2::new(1., 0.),
+        ..default()
+    };
+    let body = app.world.spawn(b2BodyBundle::new(&body_def)).id();
+    // A flat box, turned upright and moved to the right of the body
+    let fixture_def = b2FixtureDef::new(b2Shape::create_box(1., 0.25), 1.);
+    let transform = Transform::from_xyz(3., 0., 0.).with_rotation(Quat::from_rotation_z(FRAC_PI_2));
+    let fixture = app
+        .world
+        .spawn((
+            b2Fixture::new(body, &fixture_def),
+            TransformBundle::from_transform(transform),
+        ))
+        .set_parent(body)
+        .id();
+    update_frames(&mut app, 1);
+
+    assert!(ray_cast_down(&app, 1.).is_empty());
+    let hits = ray_cast_down(&app, 4.);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].entity, fixture);
+    assert!((hits[0].point.y - 1.).abs() < 1e-4, "{:?}", hits[0].point);
+    // Upright, so it is only half a unit wide
+    assert_eq!(ray_cast_down(&app, 4.2).len(), 1);
+    assert!(ray_cast_down(&app, 4.3).is_empty());
+}
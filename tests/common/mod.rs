@@ -0,0 +1,97 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use bevy_liquidfun::prelude::*;
+
+pub const FRAME_TIME: f32 = 1. / 60.;
+
+/// A headless app that advances the time by exactly one physics step per update.
+pub fn headless_app() -> App {
+    headless_app_with(LiquidFunPlugin::default())
+}
+
+pub fn headless_app_with(plugin: LiquidFunPlugin) -> App {
+    headless_app_with_frame_time(plugin, FRAME_TIME)
+}
+
+/// A headless app that advances the time by `frame_time` per update, which can be several
+/// physics steps or less than one.
+pub fn headless_app_with_frame_time(plugin: LiquidFunPlugin, frame_time: f32) -> App {
+    app_with_frame_time(plugin.headless(), frame_time)
+}
+
+/// Like [`headless_app_with_frame_time`], but the `Transform` of the bodies follows them.
+pub fn app_with_frame_time(plugin: LiquidFunPlugin, frame_time: f32) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, plugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            frame_time,
+        )));
+    // The first update only starts the clock
+    app.update();
+    app
+}
+
+pub fn update_frames(app: &mut App, frames: usize) {
+    for _ in 0..frames {
+        app.update();
+    }
+}
+
+pub fn step_count(app: &App) -> u64 {
+    app.world.resource::<PhysicsStepCount>().0
+}
+
+/// Runs the app until it has simulated `steps` steps, one frame at a time.
+pub fn run_steps(app: &mut App, steps: u64) {
+    while step_count(app) < steps {
+        update_frames(app, 1);
+    }
+    assert_eq!(step_count(app), steps);
+}
+
+pub fn spawn_box(world: &mut World, body_type: b2BodyType, position: Vec2) -> Entity {
+    let body_def = b2BodyDef {
+        body_type,
+        position,
+        ..default()
+    };
+    let body_entity = world.spawn(b2BodyBundle::new(&body_def)).id();
+    let fixture_def = b2FixtureDef::new(b2Shape::create_box(0.5, 0.5), 1.);
+    world.spawn(b2Fixture::new(body_entity, &fixture_def));
+    body_entity
+}
+
+pub fn spawn_particle_block(world: &mut World, center: Vec2, half_size: f32) -> (Entity, Entity) {
+    let particle_system_def = b2ParticleSystemDef {
+        radius: 0.1,
+        ..default()
+    };
+    let particle_system_entity = world
+        .spawn(b2ParticleSystem::new(&particle_system_def))
+        .id();
+    let particle_group_def = b2ParticleGroupDef {
+        flags: b2ParticleFlags::WaterParticle,
+        shape: b2Shape::Polygon {
+            vertices: vec![
+                center + Vec2::new(-half_size, -half_size),
+                center + Vec2::new(half_size, -half_size),
+                center + Vec2::new(half_size, half_size),
+                center + Vec2::new(-half_size, half_size),
+            ],
+        },
+        color: b2ParticleColor::ZERO,
+        lifetime: 0.,
+    };
+    let particle_group_entity = world
+        .spawn(b2ParticleGroup::new(
+            particle_system_entity,
+            &particle_group_def,
+        ))
+        .id();
+    (particle_system_entity, particle_group_entity)
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, update_frames};
+
+/// Below the body count the plugin spreads the body syncs over threads from, so the allocations of
+/// the task pool don't vary between runs.
+const BODY_COUNT: usize = 1_000;
+
+/// Counts the allocations made through the global allocator.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// A pile of boxes on a bumpy floor, so thousands of contacts begin, stay and end every frame.
+fn stress_app(filter: ContactEventFilter) -> App {
+    let mut app = headless_app();
+    app.insert_resource(filter);
+    // The multi-threaded executor allocates a varying amount for its tasks
+    for (_, schedule) in app.world.resource_mut::<Schedules>().iter_mut() {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    }
+
+    let ground = app.world.spawn(b2BodyBundle::default()).id();
+    for i in 0..40 {
+        let shape = b2Shape::Circle {
+            radius: 1.,
+            position: Vec2::new(i as f32 * 2. - 40., 0.),
+        };
+        app.world
+            .spawn(b2Fixture::new(ground, &b2FixtureDef::new(shape, 0.)));
+    }
+    let columns = 40;
+    let fixture_def = b2FixtureDef {
+        shape: b2Shape::create_box(0.4, 0.4),
+        density: 1.,
+        friction: 0.3,
+        restitution: 0.3,
+        ..default()
+    };
+    for i in 0..BODY_COUNT {
+        let body_def = b2BodyDef {
+            body_type: b2BodyType::Dynamic,
+            position: Vec2::new(
+                (i % columns) as f32 * 1.2 - 24.,
+                (i / columns) as f32 * 1.2 + 2.,
+            ),
+            allow_sleep: false,
+            ..default()
+        };
+        app.world.spawn((
+            b2BodyBundle::new(&body_def),
+            b2Fixtures::new([fixture_def.clone()]),
+        ));
+    }
+
+    // Lets the pile settle and the buffers grow to their working size
+    update_frames(&mut app, 240);
+    app
+}
+
+fn allocations_per_frame(app: &mut App, frames: usize) -> Vec<usize> {
+    (0..frames)
+        .map(|_| {
+            let before = ALLOCATIONS.load(Ordering::Relaxed);
+            app.update();
+            ALLOCATIONS.load(Ordering::Relaxed) - before
+        })
+        .collect()
+}
+
+#[test]
+fn contact_events_do_not_allocate_once_warm() {
+    // Bevy allocates every frame on its own, so the frames are compared against those of the
+    // same scene without contact events. Nothing is marked, so no contacts are recorded there
+    let mut app = stress_app(ContactEventFilter {
+        stay_events: true,
+        ..default()
+    });
+    let mut quiet_app = stress_app(ContactEventFilter {
+        require_marker: true,
+        ..default()
+    });
+    let contact_count = app.world.resource::<b2Contacts>().contacts().len();
+    assert!(
+        contact_count > BODY_COUNT,
+        "only {} contacts",
+        contact_count
+    );
+
+    let allocations = allocations_per_frame(&mut app, 120);
+    let quiet_allocations = allocations_per_frame(&mut quiet_app, 120);
+    let events = app.world.resource::<Events<b2ContactStayEvent>>();
+    assert!(events.len() > BODY_COUNT);
+    assert_eq!(
+        allocations, quiet_allocations,
+        "allocations per frame with contact events against without them"
+    );
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app_with, update_frames};
+
+type ContactKey = (Entity, Option<usize>, Entity, Option<usize>);
+
+fn key(contact: &b2Contact) -> ContactKey {
+    (
+        contact.fixture_a,
+        contact.fixture_index_a,
+        contact.fixture_b,
+        contact.fixture_index_b,
+    )
+}
+
+/// The contacts of an entity the way they were found before they were indexed, by going through
+/// all of them.
+fn scanned_contacts_of(contacts: &b2Contacts, entity: Entity) -> Vec<ContactKey> {
+    let mut keys: Vec<_> = contacts
+        .contacts()
+        .iter()
+        .filter(|contact| {
+            [
+                contact.fixture_a,
+                contact.fixture_b,
+                contact.body_a,
+                contact.body_b,
+            ]
+            .contains(&entity)
+        })
+        .map(key)
+        .collect();
+    keys.sort();
+    keys
+}
+
+fn indexed_contacts_of(contacts: &b2Contacts, entity: Entity) -> Vec<ContactKey> {
+    let mut keys: Vec<_> = contacts.contacts_of(entity).map(key).collect();
+    keys.sort();
+    keys
+}
+
+#[test]
+fn indexed_contacts_match_a_scan_of_every_contact() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let mut entities = Vec::new();
+
+    // A row of overlapping sensors, each on a body of its own
+    for i in 0..5 {
+        let sensor_body = app
+            .world
+            .spawn(b2BodyBundle::new(&b2BodyDef {
+                position: Vec2::new(i as f32 * 1.5, 0.),
+                ..default()
+            }))
+            .id();
+        let sensor_def = b2FixtureDef {
+            shape: b2Shape::create_box(1., 1.),
+            is_sensor: true,
+            ..default()
+        };
+        let sensor = app
+            .world
+            .spawn(b2Fixture::new(sensor_body, &sensor_def))
+            .id();
+        entities.extend([sensor_body, sensor]);
+    }
+    // Bodies with two fixtures each in and between the sensors, overlapping each other too
+    for i in 0..8 {
+        let body_def = b2BodyDef {
+            body_type: b2BodyType::Dynamic,
+            position: Vec2::new(i as f32 * 0.8, 0.5),
+            ..default()
+        };
+        let fixtures = b2Fixtures::new([
+            b2FixtureDef::new(b2Shape::create_box(0.3, 0.3), 1.),
+            b2FixtureDef::new(
+                b2Shape::Circle {
+                    radius: 0.3,
+                    position: Vec2::new(0., -0.6),
+                },
+                1.,
+            ),
+        ]);
+        let body = app
+            .world
+            .spawn((b2BodyBundle::new(&body_def), fixtures))
+            .id();
+        entities.push(body);
+    }
+    let lonely = app
+        .world
+        .spawn(b2BodyBundle::new(&b2BodyDef {
+            body_type: b2BodyType::Dynamic,
+            position: Vec2::new(-50., 0.),
+            ..default()
+        }))
+        .id();
+    entities.push(lonely);
+    update_frames(&mut app, 2);
+
+    let contacts = app.world.resource::<b2Contacts>();
+    let sensor_overlaps = contacts
+        .contacts()
+        .iter()
+        .filter(|contact| contact.is_touching)
+        .flat_map(|contact| contact.sensors())
+        .count();
+    assert!(sensor_overlaps > 10, "only {} overlaps", sensor_overlaps);
+    for entity in entities {
+        let indexed = indexed_contacts_of(contacts, entity);
+        assert_eq!(
+            indexed,
+            scanned_contacts_of(contacts, entity),
+            "{:?}",
+            entity
+        );
+        // Contacts between two fixtures of a single b2Fixtures are never listed twice
+        let mut deduplicated = indexed.clone();
+        deduplicated.dedup();
+        assert_eq!(deduplicated, indexed);
+    }
+    assert_eq!(contacts.contacts_of(lonely).count(), 0);
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use bevy::ecs::system::EntityCommand;
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, spawn_box, spawn_particle_block, update_frames};
+
+#[test]
+fn despawning_a_body_destroys_its_fixtures_and_joints() {
+    let mut app = headless_app();
+    let ground = spawn_box(&mut app.world, b2BodyType::Static, Vec2::ZERO);
+    let body = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::new(0., 2.));
+    let joint = app.world.spawn_empty().id();
+    CreateRevoluteJoint::new(ground, body, false, &b2RevoluteJointDef::default())
+        .apply(joint, &mut app.world);
+    update_frames(&mut app, 10);
+
+    // Only the body itself, the fixture and the joint are left behind for the plugin
+    app.world.despawn(body);
+    update_frames(&mut app, 10);
+
+    assert!(app.world.get_entity(joint).is_none());
+    let b2_world = app.world.non_send_resource::<b2World>();
+    assert!(b2_world.fixtures_of(body).next().is_none());
+    assert_eq!(b2_world.fixtures_of(ground).count(), 1);
+}
+
+#[test]
+fn despawning_a_body_in_the_frame_it_was_spawned() {
+    let mut app = headless_app();
+    app.add_systems(
+        Update,
+        |mut commands: Commands, mut spawned: Local<bool>| {
+            if *spawned {
+                return;
+            }
+            *spawned = true;
+            let body_def = b2BodyDef {
+                body_type: b2BodyType::Dynamic,
+                ..default()
+            };
+            let body = commands.spawn(b2BodyBundle::new(&body_def)).id();
+            let fixture_def = b2FixtureDef::new(b2Shape::create_box(0.5, 0.5), 1.);
+            commands.spawn(b2Fixture::new(body, &fixture_def));
+            commands.entity(body).despawn();
+        },
+    );
+    update_frames(&mut app, 10);
+
+    // The orphaned fixture is despawned instead of being created
+    let mut fixtures = app.world.query::<&b2Fixture>();
+    assert_eq!(fixtures.iter(&app.world).count(), 0);
+}
+
+#[test]
+fn despawning_bodies_while_others_keep_moving() {
+    let mut app = headless_app();
+    let bodies: Vec<Entity> = (0..10)
+        .map(|i| spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::new(i as f32, 5.)))
+        .collect();
+    update_frames(&mut app, 5);
+
+    for body in bodies.iter().step_by(2) {
+        app.world.entity_mut(*body).despawn_recursive();
+    }
+    let start = app.world.get::<b2Body>(bodies[1]).unwrap().position;
+    update_frames(&mut app, 10);
+
+    assert!(app.world.get::<b2Body>(bodies[1]).unwrap().position.y < start.y);
+}
+
+#[test]
+fn despawning_a_particle_system_with_groups() {
+    let mut app = headless_app();
+    let (particle_system, particle_group) =
+        spawn_particle_block(&mut app.world, Vec2::new(0., 5.), 1.);
+    update_frames(&mut app, 5);
+    assert!(
+        app.world
+            .get::<b2ParticleSystem>(particle_system)
+            .unwrap()
+            .particle_count()
+            > 0
+    );
+
+    app.world.despawn(particle_system);
+    update_frames(&mut app, 5);
+
+    // The group component stays around, but is left alone by the sync systems
+    assert!(app.world.get::<b2ParticleGroup>(particle_group).is_some());
+    let mut b2_world = app.world.non_send_resource_mut::<b2World>();
+    assert!(b2_world.particle_handle(particle_system, 0).is_none());
+}
+
+#[test]
+fn removing_only_the_particle_system_component() {
+    let mut app = headless_app();
+    let (particle_system, _) = spawn_particle_block(&mut app.world, Vec2::new(0., 5.), 1.);
+    update_frames(&mut app, 5);
+
+    app.world
+        .entity_mut(particle_system)
+        .remove::<b2ParticleSystem>();
+    update_frames(&mut app, 5);
+
+    assert!(app
+        .world
+        .non_send_resource_mut::<b2World>()
+        .particle_handle(particle_system, 0)
+        .is_none());
+}
+
+#[test]
+fn removing_a_particle_system_mid_simulation_clears_its_contacts() {
+    let mut app = headless_app();
+    let ground = spawn_box(&mut app.world, b2BodyType::Static, Vec2::ZERO);
+    let (particle_system, _) = spawn_particle_block(&mut app.world, Vec2::new(0., 1.5), 0.5);
+    app.world
+        .entity_mut(particle_system)
+        .insert(b2ParticleSystemContacts::default());
+    update_frames(&mut app, 30);
+    let contacts = app
+        .world
+        .get::<b2ParticleSystemContacts>(particle_system)
+        .unwrap();
+    assert!(contacts.body_contacts_of(ground).next().is_some());
+
+    app.world
+        .entity_mut(particle_system)
+        .remove::<b2ParticleSystem>();
+    update_frames(&mut app, 5);
+
+    let contacts = app
+        .world
+        .get::<b2ParticleSystemContacts>(particle_system)
+        .unwrap();
+    assert!(contacts.contacts().is_empty());
+    assert!(contacts.body_contacts().is_empty());
+}
+
+#[test]
+fn particle_systems_added_while_the_world_is_replaced() {
+    let mut app = headless_app();
+    let gravity = app.world.non_send_resource::<b2World>().gravity;
+    app.world.remove_non_send_resource::<b2World>();
+    let (particle_system, _) = spawn_particle_block(&mut app.world, Vec2::new(0., 5.), 0.5);
+    // Nothing is synced while there is no world
+    update_frames(&mut app, 5);
+
+    app.world.insert_non_send_resource(b2World::new(gravity));
+    update_frames(&mut app, 5);
+
+    assert!(
+        app.world
+            .get::<b2ParticleSystem>(particle_system)
+            .unwrap()
+            .particle_count()
+            > 0
+    );
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use bevy::ecs::system::EntityCommand;
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, spawn_box, spawn_particle_block, update_frames};
+
+const STEPS: usize = 600;
+/// The checksums of the scene after [`STEPS`] steps, one line per platform, as floating point
+/// results may differ between them. A platform without a line gets one on its first run, which
+/// is then meant to be committed. Set `UPDATE_DETERMINISM_CHECKSUM` to replace the line after a
+/// deliberate change to the simulation.
+const CHECKSUM_FILE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/determinism_checksums.txt"
+);
+
+/// Spawns a pile of boxes on the ground, a pendulum and a block of water next to it.
+fn spawn_scene(world: &mut World) -> Vec<Entity> {
+    let ground = world
+        .spawn(b2BodyBundle::new(&b2BodyDef {
+            body_type: b2BodyType::Static,
+            ..default()
+        }))
+        .id();
+    world.spawn(b2Fixture::new(
+        ground,
+        &b2FixtureDef::new(
+            b2Shape::EdgeTwoSided {
+                v1: Vec2::new(-40., 0.),
+                v2: Vec2::new(40., 0.),
+            },
+            0.,
+        ),
+    ));
+
+    let mut bodies = Vec::new();
+    for row in 0..10 {
+        for column in 0..(10 - row) {
+            let position = Vec2::new(
+                column as f32 * 1.05 + row as f32 * 0.525 - 5.,
+                row as f32 * 1.05 + 0.5,
+            );
+            bodies.push(spawn_box(world, b2BodyType::Dynamic, position));
+        }
+    }
+
+    let anchor = spawn_box(world, b2BodyType::Static, Vec2::new(12., 12.));
+    let bob = spawn_box(world, b2BodyType::Dynamic, Vec2::new(17., 12.));
+    let joint = world.spawn_empty().id();
+    CreateRevoluteJoint::new(
+        anchor,
+        bob,
+        false,
+        &b2RevoluteJointDef {
+            local_anchor_b: Vec2::new(-5., 0.),
+            ..default()
+        },
+    )
+    .apply(joint, world);
+    bodies.push(bob);
+
+    spawn_particle_block(world, Vec2::new(-12., 3.), 2.);
+    bodies
+}
+
+fn run_scene() -> Vec<(Vec2, f32)> {
+    let mut app = headless_app();
+    let bodies = spawn_scene(&mut app.world);
+    update_frames(&mut app, STEPS);
+    bodies
+        .iter()
+        .map(|body| {
+            let body = app.world.get::<b2Body>(*body).unwrap();
+            (body.position, body.angle)
+        })
+        .collect()
+}
+
+#[test]
+fn identical_apps_stay_bit_identical() {
+    let first = run_scene();
+    let second = run_scene();
+
+    assert_eq!(first.len(), second.len());
+    for (index, ((position_a, angle_a), (position_b, angle_b))) in
+        first.iter().zip(second.iter()).enumerate()
+    {
+        assert_eq!(
+            (
+                position_a.x.to_bits(),
+                position_a.y.to_bits(),
+                angle_a.to_bits()
+            ),
+            (
+                position_b.x.to_bits(),
+                position_b.y.to_bits(),
+                angle_b.to_bits()
+            ),
+            "body {} diverged: {:?} {} vs {:?} {}",
+            index,
+            position_a,
+            angle_a,
+            position_b,
+            angle_b,
+        );
+    }
+    // The pendulum actually swung, so the comparison covers solver results and not just the spawn
+    let (bob_position, _) = first.last().unwrap();
+    assert_ne!(*bob_position, Vec2::new(17., 12.));
+}
+
+/// FNV-1a over the bits of the positions and angles.
+fn checksum(state: &[(Vec2, f32)]) -> u64 {
+    state
+        .iter()
+        .flat_map(|(position, angle)| [position.x, position.y, *angle])
+        .flat_map(|value| value.to_bits().to_le_bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+#[test]
+fn results_match_the_recorded_checksum() {
+    let checksum = format!("{:016x}", checksum(&run_scene()));
+    let platform = format!(
+        "{}-{}-{}",
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        }
+    );
+
+    let recorded = std::fs::read_to_string(CHECKSUM_FILE).unwrap_or_default();
+    let mut lines: Vec<(String, String)> = recorded
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(platform, checksum)| (platform.to_string(), checksum.trim().to_string()))
+        .collect();
+    let update = std::env::var_os("UPDATE_DETERMINISM_CHECKSUM").is_some();
+    match lines
+        .iter()
+        .find(|(line_platform, _)| *line_platform == platform)
+    {
+        Some((_, recorded_checksum)) if !update => assert_eq!(
+            *recorded_checksum, checksum,
+            "the results on {} changed, see CHECKSUM_FILE",
+            platform
+        ),
+        _ => {
+            lines.retain(|(line_platform, _)| *line_platform != platform);
+            lines.push((platform, checksum));
+            lines.sort();
+            let contents: String = lines
+                .iter()
+                .map(|(platform, checksum)| format!("{} {}\n", platform, checksum))
+                .collect();
+            std::fs::create_dir_all(std::path::Path::new(CHECKSUM_FILE).parent().unwrap()).unwrap();
+            std::fs::write(CHECKSUM_FILE, contents).unwrap();
+            println!("Recorded the checksum in {}", CHECKSUM_FILE);
+        }
+    }
+}
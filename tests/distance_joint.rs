@@ -0,0 +1,77 @@
+mod common;
+
+use bevy::ecs::system::EntityCommand;
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app_with, spawn_box, update_frames};
+
+const MAX_LENGTH: f32 = 4.;
+
+/// A box on a slack rope from a static anchor, thrown away from it.
+fn thrown_box(def: &b2DistanceJointDef) -> (App, Entity) {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let anchor = spawn_box(&mut app.world, b2BodyType::Static, Vec2::ZERO);
+    let thrown = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::new(2., 0.));
+    app.world.get_mut::<b2Body>(thrown).unwrap().linear_velocity = Vec2::new(10., 0.);
+    let joint = app.world.spawn_empty().id();
+    CreateDistanceJoint::new(anchor, thrown, false, def).apply(joint, &mut app.world);
+    (app, joint)
+}
+
+fn current_length(app: &App, joint: Entity) -> f32 {
+    app.world
+        .get::<b2DistanceJoint>(joint)
+        .unwrap()
+        .current_length()
+}
+
+#[test]
+fn ropes_are_pulled_taut_up_to_their_max_length() {
+    let (mut app, joint) = thrown_box(&b2DistanceJointDef {
+        min_length: 1.,
+        length: 2.,
+        max_length: MAX_LENGTH,
+        ..default()
+    });
+    update_frames(&mut app, 1);
+    assert!((current_length(&app, joint) - 2.).abs() < 0.5);
+
+    let mut longest: f32 = 0.;
+    for _ in 0..60 {
+        update_frames(&mut app, 1);
+        longest = longest.max(current_length(&app, joint));
+    }
+
+    assert!(longest < MAX_LENGTH + 0.05, "stretched to {}", longest);
+    let length = current_length(&app, joint);
+    assert!(
+        (length - MAX_LENGTH).abs() < 0.05,
+        "{} instead of {}",
+        length,
+        MAX_LENGTH
+    );
+}
+
+#[test]
+fn invalid_lengths_are_clamped_instead_of_aborting() {
+    let (mut app, joint) = thrown_box(&b2DistanceJointDef {
+        min_length: 1.,
+        length: 10.,
+        max_length: -1.,
+        ..default()
+    });
+    update_frames(&mut app, 30);
+
+    // The maximum length can't be below the minimum one, which makes the joint rigid
+    let length = current_length(&app, joint);
+    assert!((length - 1.).abs() < 0.05, "{}", length);
+
+    app.world
+        .get_mut::<b2DistanceJoint>(joint)
+        .unwrap()
+        .max_length = f32::NAN;
+    update_frames(&mut app, 5);
+    assert!(current_length(&app, joint).is_finite());
+}
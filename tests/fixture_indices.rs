@@ -0,0 +1,42 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, spawn_box, update_frames};
+
+#[test]
+fn contacts_report_the_index_of_the_fixtures_of_a_body() {
+    let mut app = headless_app();
+    let ground = spawn_box(&mut app.world, b2BodyType::Static, Vec2::new(1.5, 0.));
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Dynamic,
+        position: Vec2::new(0., 1.5),
+        ..default()
+    };
+    // Only the second fixture lands on the ground
+    let fixture_defs = [-1.5, 1.5].map(|x| {
+        b2FixtureDef::new(
+            b2Shape::create_box_with_offset(0.5, 0.5, Vec2::new(x, 0.)),
+            1.,
+        )
+    });
+    let body = app
+        .world
+        .spawn((b2BodyBundle::new(&body_def), b2Fixtures::new(fixture_defs)))
+        .id();
+    update_frames(&mut app, 30);
+
+    let contacts = app.world.resource::<b2Contacts>().contacts();
+    assert_eq!(contacts.len(), 1);
+    let contact = contacts[0];
+    let (body_index, ground_index) = if contact.body_a == body {
+        (contact.fixture_index_a, contact.fixture_index_b)
+    } else {
+        (contact.fixture_index_b, contact.fixture_index_a)
+    };
+    assert_eq!(body_index, Some(1));
+    assert_eq!(ground_index, None);
+    assert!([contact.body_a, contact.body_b].contains(&ground));
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, spawn_box, update_frames};
+
+fn fixture_of(app: &App, body: Entity) -> Entity {
+    let b2_world = app.world.non_send_resource::<b2World>();
+    b2_world.fixtures_of(body).next().unwrap()
+}
+
+fn height(app: &App, body: Entity) -> f32 {
+    app.world.get::<b2Body>(body).unwrap().position.y
+}
+
+#[test]
+fn fixtures_keep_colliding_until_their_filter_changes() {
+    let mut app = headless_app();
+    spawn_box(&mut app.world, b2BodyType::Static, Vec2::ZERO);
+    let body = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::new(0., 1.5));
+    update_frames(&mut app, 30);
+    let fixture = fixture_of(&app, body);
+    assert!(height(&app, body) > 0.9);
+
+    // Other properties are synced without touching the filter
+    app.world.get_mut::<b2Fixture>(fixture).unwrap().friction = 0.9;
+    update_frames(&mut app, 30);
+    assert!(height(&app, body) > 0.9);
+    assert_eq!(app.world.resource::<b2Contacts>().contacts().len(), 1);
+
+    app.world.get_mut::<b2Fixture>(fixture).unwrap().filter = b2Filter::NONE;
+    update_frames(&mut app, 30);
+    assert!(height(&app, body) < 0.);
+    assert!(app.world.resource::<b2Contacts>().contacts().is_empty());
+}
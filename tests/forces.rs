@@ -0,0 +1,83 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app_with_frame_time, run_steps, spawn_box, step_count, update_frames};
+
+const TIME_STEP: f32 = 1. / 120.;
+
+fn app_at(frame_time: f32) -> App {
+    let settings = b2WorldSettings {
+        time_step: TIME_STEP,
+        ..default()
+    };
+    let plugin = LiquidFunPlugin::new(settings).with_gravity(Vec2::ZERO);
+    headless_app_with_frame_time(plugin, frame_time)
+}
+
+fn body(app: &App, entity: Entity) -> &b2Body {
+    app.world.get::<b2Body>(entity).unwrap()
+}
+
+#[test]
+fn persistent_forces_apply_to_every_step_of_a_frame() {
+    // Four steps per frame against one step per frame, over the same simulated time
+    let mut slow_app = app_at(1. / 30.);
+    let mut fast_app = app_at(TIME_STEP);
+    let slow_body = spawn_box(&mut slow_app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    let fast_body = spawn_box(&mut fast_app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    for (app, entity) in [(&mut slow_app, slow_body), (&mut fast_app, fast_body)] {
+        app.world
+            .entity_mut(entity)
+            .insert(ExternalForce::persistent(Vec2::new(10., 0.)));
+    }
+
+    update_frames(&mut slow_app, 30);
+    let steps = step_count(&slow_app);
+    assert!(steps >= 100, "only {} steps", steps);
+    run_steps(&mut fast_app, steps);
+
+    let slow_body = body(&slow_app, slow_body);
+    let fast_body = body(&fast_app, fast_body);
+    assert!(fast_body.position.x > 1.);
+    assert!(
+        (slow_body.position - fast_body.position).length() < 1e-4,
+        "{:?} vs {:?}",
+        slow_body.position,
+        fast_body.position
+    );
+    assert!((slow_body.linear_velocity - fast_body.linear_velocity).length() < 1e-4);
+}
+
+#[test]
+fn local_forces_follow_the_body_over_the_steps_of_a_frame() {
+    let mut slow_app = app_at(1. / 30.);
+    let mut fast_app = app_at(TIME_STEP);
+    let slow_body = spawn_box(&mut slow_app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    let fast_body = spawn_box(&mut fast_app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    for (app, entity) in [(&mut slow_app, slow_body), (&mut fast_app, fast_body)] {
+        let mut force = LocalExternalForce::new(Vec2::new(10., 0.));
+        force.persistent = true;
+        let mut body = app.world.get_mut::<b2Body>(entity).unwrap();
+        // A full turn every second, so the force points elsewhere at every step
+        body.angular_velocity = std::f32::consts::TAU;
+        app.world.entity_mut(entity).insert(force);
+    }
+
+    update_frames(&mut slow_app, 30);
+    let steps = step_count(&slow_app);
+    run_steps(&mut fast_app, steps);
+
+    let slow_body = body(&slow_app, slow_body);
+    let fast_body = body(&fast_app, fast_body);
+    assert!(fast_body.position.length() > 0.1);
+    assert!(
+        (slow_body.position - fast_body.position).length() < 1e-4,
+        "{:?} vs {:?}",
+        slow_body.position,
+        fast_body.position
+    );
+    assert!((slow_body.angle - fast_body.angle).abs() < 1e-4);
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, spawn_box, update_frames};
+
+#[test]
+fn bodies_fall_without_a_window() {
+    let mut app = headless_app();
+    let body = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::new(0., 10.));
+    update_frames(&mut app, 1);
+    let start = app.world.get::<b2Body>(body).unwrap().position;
+
+    let mut previous_y = start.y;
+    for _ in 0..6 {
+        update_frames(&mut app, 10);
+        let position = app.world.get::<b2Body>(body).unwrap().position;
+        assert!(position.y < previous_y);
+        assert_eq!(position.x, start.x);
+        previous_y = position.y;
+    }
+}
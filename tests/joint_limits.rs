@@ -0,0 +1,67 @@
+mod common;
+
+use bevy::ecs::system::EntityCommand;
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, spawn_box, update_frames};
+
+#[derive(Resource, Default)]
+struct JointLimitEvents(Vec<b2JointLimitEvent>);
+
+fn collect_joint_limit_events(
+    mut reader: EventReader<b2JointLimitEvent>,
+    mut events: ResMut<JointLimitEvents>,
+) {
+    events.0.extend(reader.read().copied());
+}
+
+fn take_events(app: &mut App) -> Vec<(JointLimit, bool)> {
+    app.world
+        .resource_mut::<JointLimitEvents>()
+        .0
+        .drain(..)
+        .map(|event| (event.limit, event.entering))
+        .collect()
+}
+
+#[test]
+fn prismatic_joint_reports_entering_and_leaving_its_limits_once() {
+    let mut app = headless_app();
+    app.init_resource::<JointLimitEvents>()
+        .add_systems(Last, collect_joint_limit_events);
+
+    let ground = spawn_box(&mut app.world, b2BodyType::Static, Vec2::ZERO);
+    let slider = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    let def = b2PrismaticJointDef {
+        local_axis_a: Vec2::Y,
+        enable_limit: true,
+        lower_translation: -1.,
+        upper_translation: 1.,
+        ..default()
+    };
+    let joint = app.world.spawn(EmitJointLimitEvents::default()).id();
+    CreatePrismaticJoint::new(ground, slider, false, &def).apply(joint, &mut app.world);
+
+    // Gravity pulls the slider down onto its lower limit, where it comes to rest
+    update_frames(&mut app, 120);
+    assert_eq!(take_events(&mut app), [(JointLimit::Lower, true)]);
+    assert_eq!(
+        app.world
+            .get::<EmitJointLimitEvents>(joint)
+            .unwrap()
+            .at_limit(),
+        Some(JointLimit::Lower)
+    );
+
+    // Flipping the gravity sends it up to the upper limit
+    app.world
+        .non_send_resource_mut::<b2World>()
+        .set_gravity(Vec2::new(0., 9.81), true);
+    update_frames(&mut app, 120);
+    assert_eq!(
+        take_events(&mut app),
+        [(JointLimit::Lower, false), (JointLimit::Upper, true)]
+    );
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use bevy::ecs::system::EntityCommand;
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app_with, spawn_box, update_frames};
+
+#[test]
+fn enabling_the_motor_wakes_a_sleeping_door() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let frame = spawn_box(&mut app.world, b2BodyType::Static, Vec2::ZERO);
+    let door = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::new(1., 0.));
+    let hinge = app.world.spawn_empty().id();
+    CreateRevoluteJoint::new(
+        frame,
+        door,
+        false,
+        &b2RevoluteJointDef {
+            local_anchor_a: Vec2::new(0.5, 0.),
+            local_anchor_b: Vec2::new(-0.5, 0.),
+            ..default()
+        },
+    )
+    .apply(hinge, &mut app.world);
+
+    let mut frames = 0;
+    while app.world.get::<b2Body>(door).unwrap().awake {
+        assert!(frames < 300, "the door didn't fall asleep");
+        update_frames(&mut app, 1);
+        frames += 1;
+    }
+    let closed_angle = app.world.get::<b2Body>(door).unwrap().angle;
+
+    let mut revolute_joint = app.world.get_mut::<b2RevoluteJoint>(hinge).unwrap();
+    revolute_joint.enable_motor = true;
+    revolute_joint.motor_speed = 2.;
+    revolute_joint.max_motor_torque = 100.;
+    update_frames(&mut app, 5);
+
+    let door = app.world.get::<b2Body>(door).unwrap();
+    assert!(door.awake);
+    assert!(
+        door.angle - closed_angle > 0.05,
+        "the door only turned from {} to {}",
+        closed_angle,
+        door.angle
+    );
+    let revolute_joint = app.world.get::<b2RevoluteJoint>(hinge).unwrap();
+    assert!(revolute_joint.joint_speed() > 1.);
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app_with_frame_time, spawn_box, update_frames, FRAME_TIME};
+
+#[test]
+fn kinematic_bodies_stop_at_their_target_within_a_frame() {
+    // Four steps per frame, the first of which already reaches the target
+    let mut app = headless_app_with_frame_time(LiquidFunPlugin::default(), 4. * FRAME_TIME);
+    let body = spawn_box(&mut app.world, b2BodyType::Kinematic, Vec2::ZERO);
+    app.world
+        .entity_mut(body)
+        .insert(KinematicTarget::new(Vec2::new(1., 0.), 0.5));
+    update_frames(&mut app, 1);
+    let steps = app.world.resource::<PhysicsStepCount>().0;
+    assert!(steps >= 3, "only {} steps", steps);
+
+    let body = app.world.get::<b2Body>(body).unwrap();
+    assert!(
+        (body.position - Vec2::new(1., 0.)).length() < 1e-4,
+        "{:?}",
+        body.position
+    );
+    assert!((body.angle - 0.5).abs() < 1e-4, "{}", body.angle);
+    assert!(body.linear_velocity.length() < 1e-4);
+    assert!(body.angular_velocity.abs() < 1e-4);
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, headless_app_with, spawn_box, update_frames};
+
+fn body(app: &App, entity: Entity) -> &b2Body {
+    app.world.get::<b2Body>(entity).unwrap()
+}
+
+/// A unit box next to the one [`spawn_box`] creates, on the same body.
+fn add_second_box(app: &mut App, body: Entity) -> Entity {
+    let fixture_def = b2FixtureDef::new(
+        b2Shape::create_box_with_offset(0.5, 0.5, Vec2::new(1., 0.)),
+        1.,
+    );
+    app.world.spawn(b2Fixture::new(body, &fixture_def)).id()
+}
+
+#[test]
+fn adding_a_second_fixture_doubles_the_mass() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let entity = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    update_frames(&mut app, 1);
+    assert!((body(&app, entity).get_mass() - 1.).abs() < 1e-5);
+
+    let second_box = add_second_box(&mut app, entity);
+    update_frames(&mut app, 1);
+    let doubled = body(&app, entity);
+    assert!(
+        (doubled.get_mass() - 2.).abs() < 1e-5,
+        "{}",
+        doubled.get_mass()
+    );
+    assert!((doubled.get_local_center() - Vec2::new(0.5, 0.)).length() < 1e-5);
+
+    app.world.despawn(second_box);
+    update_frames(&mut app, 1);
+    let halved = body(&app, entity);
+    assert!(
+        (halved.get_mass() - 1.).abs() < 1e-5,
+        "{}",
+        halved.get_mass()
+    );
+    assert!(halved.get_local_center().length() < 1e-5);
+}
+
+#[test]
+fn sleeping_bodies_pick_up_the_mass_of_new_fixtures() {
+    let mut app = headless_app();
+    let ground = app.world.spawn(b2BodyBundle::default()).id();
+    let ground_def = b2FixtureDef::new(b2Shape::create_box(10., 0.5), 0.);
+    app.world.spawn(b2Fixture::new(ground, &ground_def));
+    let entity = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::new(0., 1.));
+
+    let mut frames = 0;
+    while body(&app, entity).awake {
+        assert!(frames < 300, "the box didn't fall asleep");
+        update_frames(&mut app, 1);
+        frames += 1;
+    }
+    add_second_box(&mut app, entity);
+    update_frames(&mut app, 1);
+
+    assert!((body(&app, entity).get_mass() - 2.).abs() < 1e-5);
+}
+
+#[test]
+fn recompute_mass_replaces_an_overridden_mass() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let entity = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    app.world.entity_mut(entity).insert(b2MassData {
+        mass: 5.,
+        center: Vec2::ZERO,
+        inertia: 5.,
+    });
+    update_frames(&mut app, 1);
+    assert!((body(&app, entity).get_mass() - 5.).abs() < 1e-5);
+
+    app.world
+        .entity_mut(entity)
+        .remove::<b2MassData>()
+        .insert(RecomputeMass);
+    update_frames(&mut app, 1);
+
+    let recomputed = body(&app, entity);
+    assert!(
+        (recomputed.get_mass() - 1.).abs() < 1e-5,
+        "{}",
+        recomputed.get_mass()
+    );
+    // A unit box of density 1 around its center
+    assert!((recomputed.get_inertia() - 1. / 6.).abs() < 1e-5);
+    assert!(app.world.get::<RecomputeMass>(entity).is_none());
+}
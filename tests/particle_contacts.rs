@@ -0,0 +1,84 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, headless_app_with, spawn_particle_block, update_frames};
+
+#[test]
+fn contacts_between_particles_are_reported_once_from_both_sides() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let (particle_system, _) = spawn_particle_block(&mut app.world, Vec2::ZERO, 1.);
+    app.world
+        .entity_mut(particle_system)
+        .insert(b2ParticleSystemContacts::default());
+    update_frames(&mut app, 2);
+
+    let particle_count = app
+        .world
+        .get::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .particle_count();
+    let contacts = app
+        .world
+        .get::<b2ParticleSystemContacts>(particle_system)
+        .unwrap();
+    // Every particle of the blob touches its neighbours
+    assert!(contacts.contacts().len() > particle_count);
+
+    for contact in contacts.contacts() {
+        assert_ne!(contact.index_a, contact.index_b);
+        assert!(contact.index_a < particle_count && contact.index_b < particle_count);
+        assert!(contact.weight > 0.);
+        assert!((contact.normal.length() - 1.).abs() < 1e-3);
+
+        let from_a: Vec<_> = contacts
+            .contacts_of(contact.index_a)
+            .filter(|(other, _)| *other == contact.index_b)
+            .collect();
+        let from_b: Vec<_> = contacts
+            .contacts_of(contact.index_b)
+            .filter(|(other, _)| *other == contact.index_a)
+            .collect();
+        assert_eq!(from_a, vec![(contact.index_b, contact.normal)]);
+        assert_eq!(from_b, vec![(contact.index_a, -contact.normal)]);
+    }
+}
+
+#[test]
+fn contacts_with_bodies_point_from_the_particles_to_the_fixtures() {
+    let mut app = headless_app();
+    let ground = app.world.spawn(b2BodyBundle::default()).id();
+    let ground_def = b2FixtureDef::new(b2Shape::create_box(10., 0.5), 0.);
+    app.world.spawn(b2Fixture::new(ground, &ground_def));
+    let (particle_system, _) = spawn_particle_block(&mut app.world, Vec2::new(0., 1.), 0.5);
+    app.world
+        .entity_mut(particle_system)
+        .insert(b2ParticleSystemContacts::default());
+    update_frames(&mut app, 30);
+
+    let particle_count = app
+        .world
+        .get::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .particle_count();
+    let contacts = app
+        .world
+        .get::<b2ParticleSystemContacts>(particle_system)
+        .unwrap();
+    let b2_world = app.world.non_send_resource::<b2World>();
+    let ground_fixture = b2_world.fixtures_of(ground).next().unwrap();
+    let ground_contacts: Vec<_> = contacts.body_contacts_of(ground).collect();
+    assert!(!ground_contacts.is_empty());
+    assert_eq!(ground_contacts.len(), contacts.body_contacts().len());
+
+    for contact in ground_contacts {
+        assert_eq!(contact.body, ground);
+        assert_eq!(contact.fixture, ground_fixture);
+        assert!(contact.index < particle_count);
+        assert!(contact.weight > 0.);
+        // The ground is below the particles resting on it
+        assert!(contact.normal.y < -0.9, "{:?}", contact.normal);
+    }
+}
@@ -0,0 +1,212 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::headless_app_with;
+
+fn spawn_particle_system(world: &mut World, max_count: i32) -> Entity {
+    let particle_system_def = b2ParticleSystemDef {
+        radius: 0.1,
+        max_count,
+        ..default()
+    };
+    world
+        .spawn(b2ParticleSystem::new(&particle_system_def))
+        .id()
+}
+
+fn queue_particles(world: &mut World, particle_system: Entity, count: usize) {
+    let mut particle_system = world.get_mut::<b2ParticleSystem>(particle_system).unwrap();
+    for i in 0..count {
+        particle_system.queue_particle(b2ParticleDef {
+            position: Vec2::new((i % 100) as f32 * 0.3, (i / 100) as f32 * 0.3),
+            ..default()
+        });
+    }
+}
+
+fn particle_count(app: &App, particle_system: Entity) -> usize {
+    app.world
+        .get::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .particle_count()
+}
+
+#[test]
+fn particle_systems_without_a_maximum_grow_their_buffers() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let particle_system = spawn_particle_system(&mut app.world, 0);
+    let block = b2Shape::create_box(2., 2.);
+    app.world.spawn(b2ParticleGroup::new(
+        particle_system,
+        &b2ParticleGroupDef {
+            flags: b2ParticleFlags::WaterParticle,
+            shape: block,
+            color: b2ParticleColor::ZERO,
+            lifetime: 0.,
+        },
+    ));
+    app.update();
+
+    // Far more than the buffers start out with
+    let group_particles = particle_count(&app, particle_system);
+    assert!(group_particles > 500, "{}", group_particles);
+    let positions = app
+        .world
+        .get::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .get_positions()
+        .to_vec();
+    assert!(positions
+        .iter()
+        .all(|position| position.abs().cmple(Vec2::splat(2.5)).all()));
+
+    queue_particles(&mut app.world, particle_system, 1000);
+    app.update();
+    assert_eq!(
+        particle_count(&app, particle_system),
+        group_particles + 1000
+    );
+}
+
+#[test]
+fn lowering_the_maximum_takes_effect_once_enough_particles_are_gone() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let particle_system = spawn_particle_system(&mut app.world, 1000);
+    queue_particles(&mut app.world, particle_system, 100);
+    app.update();
+    assert_eq!(particle_count(&app, particle_system), 100);
+
+    // The existing particles are kept, but no new ones fit
+    app.world
+        .get_mut::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .set_max_count(50);
+    queue_particles(&mut app.world, particle_system, 10);
+    app.update();
+    assert_eq!(particle_count(&app, particle_system), 100);
+
+    let mut particle_system_component = app
+        .world
+        .get_mut::<b2ParticleSystem>(particle_system)
+        .unwrap();
+    for index in 0..60 {
+        particle_system_component.set_particle_flags(index, b2ParticleFlags::ZombieParticle);
+    }
+    app.update();
+    assert_eq!(particle_count(&app, particle_system), 40);
+
+    queue_particles(&mut app.world, particle_system, 20);
+    app.update();
+    assert_eq!(particle_count(&app, particle_system), 50);
+}
+
+#[test]
+fn particles_of_new_groups_past_a_lowered_maximum_are_destroyed() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let particle_system = spawn_particle_system(&mut app.world, 1000);
+    queue_particles(&mut app.world, particle_system, 100);
+    app.update();
+
+    app.world
+        .get_mut::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .set_max_count(150);
+    app.world.spawn(b2ParticleGroup::new(
+        particle_system,
+        &b2ParticleGroupDef {
+            flags: b2ParticleFlags::WaterParticle,
+            shape: b2Shape::create_box_with_offset(2., 2., Vec2::new(0., -10.)),
+            color: b2ParticleColor::ZERO,
+            lifetime: 0.,
+        },
+    ));
+    app.update();
+    assert_eq!(particle_count(&app, particle_system), 150);
+}
+
+#[test]
+fn raising_the_maximum_keeps_the_existing_particles() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let particle_system = spawn_particle_system(&mut app.world, 10);
+    queue_particles(&mut app.world, particle_system, 20);
+    app.update();
+    assert_eq!(particle_count(&app, particle_system), 10);
+    let positions = app
+        .world
+        .get::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .get_positions()
+        .to_vec();
+
+    app.world
+        .get_mut::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .set_max_count(100);
+    queue_particles(&mut app.world, particle_system, 20);
+    app.update();
+    let particle_system = app.world.get::<b2ParticleSystem>(particle_system).unwrap();
+    assert_eq!(particle_system.particle_count(), 30);
+    assert_eq!(&particle_system.get_positions()[..10], positions);
+}
+
+#[test]
+fn particle_systems_without_a_maximum_grow_to_fifty_thousand_particles() {
+    const PER_FRAME: usize = 500;
+    const FRAMES: usize = 100;
+    const COLUMNS: usize = 250;
+    const SPACING: f32 = 0.3;
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let particle_system = spawn_particle_system(&mut app.world, 0);
+
+    let upper_bound = Vec2::new(
+        COLUMNS as f32 * SPACING,
+        (PER_FRAME * FRAMES / COLUMNS) as f32 * SPACING,
+    );
+    for frame in 0..FRAMES {
+        let mut particle_system_component = app
+            .world
+            .get_mut::<b2ParticleSystem>(particle_system)
+            .unwrap();
+        // Apart from each other, so the particles don't push each other around
+        for index in frame * PER_FRAME..(frame + 1) * PER_FRAME {
+            particle_system_component.queue_particle(b2ParticleDef {
+                position: Vec2::new(
+                    (index % COLUMNS) as f32 * SPACING,
+                    (index / COLUMNS) as f32 * SPACING,
+                ),
+                ..default()
+            });
+        }
+        app.update();
+
+        let particle_system_component = app.world.get::<b2ParticleSystem>(particle_system).unwrap();
+        assert_eq!(
+            particle_system_component.particle_count(),
+            (frame + 1) * PER_FRAME
+        );
+        let positions = particle_system_component.get_positions();
+        assert_eq!(positions.len(), (frame + 1) * PER_FRAME);
+        assert!(positions.iter().all(|position| position.is_finite()
+            && position.cmpge(Vec2::splat(-SPACING)).all()
+            && position.cmple(upper_bound).all()));
+    }
+
+    // The particles created first survived every reallocation where they were put
+    let positions = app
+        .world
+        .get::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .get_positions();
+    for (index, position) in positions.iter().take(COLUMNS).enumerate() {
+        let expected = Vec2::new(index as f32 * SPACING, 0.);
+        assert!(
+            position.distance(expected) < 0.01,
+            "particle {} at {:?}",
+            index,
+            position
+        );
+    }
+}
@@ -0,0 +1,183 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::headless_app_with;
+
+/// Spawns a block of particles whose user data is their index at the time they were created.
+fn spawn_tagged_particles(app: &mut App) -> Entity {
+    let particle_system_def = b2ParticleSystemDef {
+        radius: 0.1,
+        ..default()
+    };
+    let particle_system = app
+        .world
+        .spawn(b2ParticleSystem::new(&particle_system_def).with_user_data())
+        .id();
+    let particle_group_def = b2ParticleGroupDef {
+        flags: b2ParticleFlags::WaterParticle,
+        shape: b2Shape::create_box(1., 1.),
+        color: b2ParticleColor::ZERO,
+        lifetime: 0.,
+    };
+    app.world
+        .spawn(b2ParticleGroup::new(particle_system, &particle_group_def));
+    app.update();
+
+    let mut particle_system_component = app
+        .world
+        .get_mut::<b2ParticleSystem>(particle_system)
+        .unwrap();
+    for index in 0..particle_system_component.particle_count() {
+        particle_system_component.set_particle_user_data(index, index as u32);
+    }
+    particle_system
+}
+
+#[test]
+fn handles_follow_their_particles_when_others_are_destroyed() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let particle_system = spawn_tagged_particles(&mut app);
+    let particle_count = app
+        .world
+        .get::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .particle_count();
+    assert!(particle_count > 10);
+
+    let handles: Vec<b2ParticleHandle> = {
+        let mut b2_world = app.world.non_send_resource_mut::<b2World>();
+        (0..particle_count as i32)
+            .map(|index| b2_world.particle_handle(particle_system, index).unwrap())
+            .collect()
+    };
+
+    // Destroys every other particle during the next step
+    let mut particle_system_component = app
+        .world
+        .get_mut::<b2ParticleSystem>(particle_system)
+        .unwrap();
+    for index in (0..particle_count).step_by(2) {
+        particle_system_component.set_particle_flags(index, b2ParticleFlags::ZombieParticle);
+    }
+    app.update();
+
+    let remaining = app
+        .world
+        .get::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .particle_count();
+    assert_eq!(remaining, particle_count / 2);
+
+    let mut indices = Vec::new();
+    {
+        let mut b2_world = app.world.non_send_resource_mut::<b2World>();
+        for (original_index, handle) in handles.iter().enumerate() {
+            let index = b2_world.particle_index(handle);
+            if original_index % 2 == 0 {
+                assert_eq!(index, None);
+            } else {
+                indices.push((original_index, index.unwrap()));
+            }
+        }
+    }
+    let particle_system_component = app.world.get::<b2ParticleSystem>(particle_system).unwrap();
+    for (original_index, index) in &indices {
+        assert_eq!(
+            particle_system_component.get_user_data(*index as usize),
+            *original_index as u32
+        );
+    }
+    // The surviving particles were moved down to fill the gaps
+    assert!(indices
+        .iter()
+        .any(|(original_index, index)| (*index as usize) < *original_index));
+}
+
+#[test]
+fn handles_of_destroyed_particles_stay_invalid_when_reused() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let particle_system = spawn_tagged_particles(&mut app);
+
+    let destroyed_handle = app
+        .world
+        .non_send_resource_mut::<b2World>()
+        .particle_handle(particle_system, 0)
+        .unwrap();
+    app.world
+        .get_mut::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .set_particle_flags(0, b2ParticleFlags::ZombieParticle);
+    app.update();
+
+    let mut b2_world = app.world.non_send_resource_mut::<b2World>();
+    // LiquidFun hands out the memory of the freed handle again
+    let new_handle = b2_world.particle_handle(particle_system, 0).unwrap();
+    assert_ne!(new_handle, destroyed_handle);
+    assert_eq!(b2_world.particle_index(&destroyed_handle), None);
+    assert_eq!(b2_world.particle_index(&new_handle), Some(0));
+}
+
+#[test]
+fn handles_stay_invalid_after_their_particle_system_is_destroyed() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let particle_system = spawn_tagged_particles(&mut app);
+    let handle = app
+        .world
+        .non_send_resource_mut::<b2World>()
+        .particle_handle(particle_system, 0)
+        .unwrap();
+
+    app.world.despawn(particle_system);
+    app.update();
+
+    assert_eq!(
+        app.world
+            .non_send_resource_mut::<b2World>()
+            .particle_index(&handle),
+        None
+    );
+}
+
+#[test]
+fn handles_of_expired_particles_are_dropped_without_destruction_events() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let particle_system = spawn_tagged_particles(&mut app);
+    let handles: Vec<b2ParticleHandle> = {
+        let mut b2_world = app.world.non_send_resource_mut::<b2World>();
+        (0..4)
+            .map(|index| b2_world.particle_handle(particle_system, index).unwrap())
+            .collect()
+    };
+
+    // Only the last one asked to be reported, the handles don't change that
+    let mut particle_system_component = app
+        .world
+        .get_mut::<b2ParticleSystem>(particle_system)
+        .unwrap();
+    particle_system_component.set_particle_flags(
+        3,
+        b2ParticleFlags::WaterParticle | b2ParticleFlags::DestructionListenerParticle,
+    );
+    for index in 0..4 {
+        particle_system_component.set_particle_lifetime(index, 0.05);
+    }
+    let mut reader = app
+        .world
+        .resource::<Events<b2ParticleDestroyedEvent>>()
+        .get_reader();
+    let mut destroyed = Vec::new();
+    for _ in 0..10 {
+        app.update();
+        let events = app.world.resource::<Events<b2ParticleDestroyedEvent>>();
+        destroyed.extend(reader.read(events).map(|event| event.index));
+    }
+
+    assert_eq!(destroyed, vec![3]);
+    let mut b2_world = app.world.non_send_resource_mut::<b2World>();
+    for handle in &handles {
+        assert_eq!(b2_world.particle_index(handle), None);
+    }
+}
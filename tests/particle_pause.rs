@@ -0,0 +1,61 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, spawn_particle_block, update_frames};
+
+fn positions(app: &App, particle_system: Entity) -> Vec<Vec2> {
+    app.world
+        .get::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .get_positions()
+        .to_vec()
+}
+
+#[test]
+fn paused_particle_systems_stay_where_they_are() {
+    let mut app = headless_app();
+    let (paused_system, _) = spawn_particle_block(&mut app.world, Vec2::new(-5., 0.), 0.5);
+    let (running_system, _) = spawn_particle_block(&mut app.world, Vec2::new(5., 0.), 0.5);
+    update_frames(&mut app, 1);
+
+    app.world
+        .get_mut::<b2ParticleSystem>(paused_system)
+        .unwrap()
+        .set_paused(true);
+    update_frames(&mut app, 1);
+    let paused_positions = positions(&app, paused_system);
+    let running_positions = positions(&app, running_system);
+    update_frames(&mut app, 30);
+
+    assert!(app
+        .world
+        .get::<b2ParticleSystem>(paused_system)
+        .unwrap()
+        .is_paused());
+    assert_eq!(positions(&app, paused_system), paused_positions);
+    // The rest of the world keeps falling
+    let fallen = positions(&app, running_system);
+    assert!(fallen
+        .iter()
+        .zip(running_positions.iter())
+        .all(|(position, before)| position.y < before.y - 0.5));
+
+    app.world
+        .get_mut::<b2ParticleSystem>(paused_system)
+        .unwrap()
+        .set_paused(false);
+    update_frames(&mut app, 30);
+
+    assert!(!app
+        .world
+        .get::<b2ParticleSystem>(paused_system)
+        .unwrap()
+        .is_paused());
+    assert!(positions(&app, paused_system)
+        .iter()
+        .zip(paused_positions.iter())
+        .all(|(position, before)| position.y < before.y - 0.5));
+}
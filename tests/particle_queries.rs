@@ -0,0 +1,81 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app_with, spawn_particle_block};
+
+#[test]
+fn query_particles_aabb_finds_the_particles_in_the_box() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let (particle_system, _) = spawn_particle_block(&mut app.world, Vec2::ZERO, 1.);
+    app.update();
+
+    let positions = app
+        .world
+        .get::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .get_positions()
+        .to_vec();
+    assert!(!positions.is_empty());
+
+    let aabb = b2AABB::new(Vec2::new(-2., -2.), Vec2::new(0., 2.));
+    let mut b2_world = app.world.non_send_resource_mut::<b2World>();
+    let mut indices = b2_world.query_particles_aabb(particle_system, &aabb);
+    indices.sort();
+
+    let mut expected: Vec<i32> = (0..positions.len() as i32)
+        .filter(|index| aabb.contains(positions[*index as usize]))
+        .collect();
+    expected.sort();
+    assert!(!expected.is_empty());
+    assert!(expected.len() < positions.len());
+    assert_eq!(indices, expected);
+}
+
+#[test]
+fn query_particles_shape_only_finds_the_particles_in_the_shape() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let (particle_system, _) = spawn_particle_block(&mut app.world, Vec2::ZERO, 1.);
+    app.update();
+
+    let positions = app
+        .world
+        .get::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .get_positions()
+        .to_vec();
+    let circle = b2Shape::Circle {
+        radius: 0.5,
+        position: Vec2::ZERO,
+    };
+    let center = Vec2::new(0.5, 0.5);
+    let mut b2_world = app.world.non_send_resource_mut::<b2World>();
+    let indices = b2_world.query_particles_shape(particle_system, &circle, center, 0.);
+
+    assert!(!indices.is_empty());
+    for index in indices {
+        assert!(positions[index as usize].distance(center) <= 0.5 + 1e-5);
+    }
+}
+
+#[test]
+fn querying_a_missing_particle_system_finds_nothing() {
+    let mut app = headless_app_with(LiquidFunPlugin::default().with_gravity(Vec2::ZERO));
+    let not_a_particle_system = app.world.spawn_empty().id();
+    app.update();
+
+    let aabb = b2AABB::new(Vec2::splat(-10.), Vec2::splat(10.));
+    let mut b2_world = app.world.non_send_resource_mut::<b2World>();
+    assert!(b2_world
+        .query_particles_aabb(not_a_particle_system, &aabb)
+        .is_empty());
+    let circle = b2Shape::Circle {
+        radius: 1.,
+        position: Vec2::ZERO,
+    };
+    assert!(b2_world
+        .query_particles_shape(not_a_particle_system, &circle, Vec2::ZERO, 0.)
+        .is_empty());
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, update_frames};
+
+const START: Vec2 = Vec2::new(0., 0.);
+const END: Vec2 = Vec2::new(10., 0.);
+const PLAYER_CATEGORY: u16 = 0x0002;
+
+struct Scene {
+    app: App,
+    sensor: Entity,
+    wall: Entity,
+}
+
+/// A trigger volume around x = 2 in front of a wall around x = 5, which doesn't block players.
+fn scene() -> Scene {
+    let mut app = headless_app();
+    let mut spawn_fixture = |position: Vec2, fixture_def: b2FixtureDef| {
+        let body = app
+            .world
+            .spawn(b2BodyBundle::new(&b2BodyDef {
+                position,
+                ..default()
+            }))
+            .id();
+        app.world.spawn(b2Fixture::new(body, &fixture_def)).id()
+    };
+    let sensor = spawn_fixture(
+        Vec2::new(2., 0.),
+        b2FixtureDef {
+            shape: b2Shape::create_box(0.5, 1.),
+            is_sensor: true,
+            ..default()
+        },
+    );
+    let wall = spawn_fixture(
+        Vec2::new(5., 0.),
+        b2FixtureDef {
+            shape: b2Shape::create_box(0.5, 1.),
+            filter: b2Filter {
+                mask_bits: !PLAYER_CATEGORY,
+                ..default()
+            },
+            ..default()
+        },
+    );
+    update_frames(&mut app, 1);
+    Scene { app, sensor, wall }
+}
+
+fn closest_hit(scene: &Scene, filter: &b2RayCastFilter) -> Option<b2RayCastHit> {
+    scene
+        .app
+        .world
+        .non_send_resource::<b2World>()
+        .ray_cast_with_filter(b2RayCastClosest::new(), &START, &END, filter)
+}
+
+#[test]
+fn sensors_in_front_of_a_wall_are_hit_first_unless_excluded() {
+    let scene = scene();
+
+    let hit = closest_hit(&scene, &b2RayCastFilter::default()).unwrap();
+    assert_eq!(hit.entity, scene.sensor);
+    assert!((hit.point.x - 1.5).abs() < 1e-4, "{:?}", hit.point);
+
+    let hit = closest_hit(&scene, &b2RayCastFilter::default().without_sensors()).unwrap();
+    assert_eq!(hit.entity, scene.wall);
+    assert!((hit.point.x - 4.5).abs() < 1e-4, "{:?}", hit.point);
+    assert_eq!(hit.normal, Vec2::new(-1., 0.));
+
+    let excluding_sensor = b2RayCastFilter {
+        excluded_fixtures: [scene.sensor].into_iter().collect(),
+        ..default()
+    };
+    let hit = closest_hit(&scene, &excluding_sensor).unwrap();
+    assert_eq!(hit.entity, scene.wall);
+}
+
+#[test]
+fn rays_only_hit_fixtures_whose_mask_accepts_their_category() {
+    let scene = scene();
+    let as_player = b2RayCastFilter {
+        ray_category_bits: Some(PLAYER_CATEGORY),
+        ..default()
+    }
+    .without_sensors();
+    assert!(closest_hit(&scene, &as_player).is_none());
+
+    let as_default = b2RayCastFilter {
+        ray_category_bits: Some(b2Filter::default().category_bits),
+        ..default()
+    }
+    .without_sensors();
+    assert_eq!(closest_hit(&scene, &as_default).unwrap().entity, scene.wall);
+}
+
+#[test]
+fn rays_clipped_by_max_fraction_stop_short_of_the_wall() {
+    let scene = scene();
+    let clipped = b2RayCastFilter::default()
+        .without_sensors()
+        .with_max_fraction(0.4);
+    assert!(closest_hit(&scene, &clipped).is_none());
+
+    // The hit point is still along the full ray
+    let long_enough = b2RayCastFilter::default()
+        .without_sensors()
+        .with_max_fraction(0.5);
+    let hit = closest_hit(&scene, &long_enough).unwrap();
+    assert_eq!(hit.entity, scene.wall);
+    assert!((hit.point.x - 4.5).abs() < 1e-4, "{:?}", hit.point);
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use bevy::ecs::system::EntityCommand;
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, update_frames};
+
+const SEGMENT_COUNT: usize = 10;
+const SEGMENT_LENGTH: f32 = 1.;
+/// How far apart the ends of neighboring segments may drift before the rope counts as unstable.
+const MAX_GAP: f32 = 0.1;
+
+/// A body without fixtures, so the rope can't collide with it.
+fn spawn_anchor(app: &mut App, body_type: b2BodyType) -> Entity {
+    let body_def = b2BodyDef {
+        body_type,
+        position: Vec2::new(0., 20.),
+        ..default()
+    };
+    app.world.spawn(b2BodyBundle::new(&body_def)).id()
+}
+
+/// A horizontal rope hanging from `anchor`, which it starts at.
+fn spawn_rope(app: &mut App, anchor: Entity, joint_type: RopeJointType) -> Rope {
+    let start = app.world.get::<b2Body>(anchor).unwrap().position;
+    let end = start + Vec2::new(SEGMENT_COUNT as f32 * SEGMENT_LENGTH, 0.);
+    let def = RopeDef {
+        segment_count: SEGMENT_COUNT,
+        joint_type,
+        ..default()
+    };
+    let rope = app.world.spawn_empty().id();
+    CreateRope::new(start, end, &def)
+        .with_start_body(anchor)
+        .apply(rope, &mut app.world);
+    app.world.get::<Rope>(rope).unwrap().clone()
+}
+
+/// The largest distance between the end of a segment and the start of the next one, with the
+/// anchor as the end of the segment before the first one.
+fn largest_gap(app: &App, anchor: Entity, rope: &Rope) -> f32 {
+    let body = |entity: Entity| app.world.get::<b2Body>(entity).unwrap();
+    let half_segment = Vec2::new(SEGMENT_LENGTH / 2., 0.);
+    let mut end = body(anchor).position;
+    let mut largest_gap: f32 = 0.;
+    for segment in &rope.segments {
+        let segment = body(*segment);
+        assert!(segment.position.is_finite() && segment.linear_velocity.is_finite());
+        assert!(
+            segment.linear_velocity.length() < 30.,
+            "a segment flies off at {:?}",
+            segment.linear_velocity
+        );
+        largest_gap = largest_gap.max(segment.world_point(-half_segment).distance(end));
+        end = segment.world_point(half_segment);
+    }
+    largest_gap
+}
+
+#[test]
+fn ropes_hang_stably_under_gravity() {
+    for joint_type in [
+        RopeJointType::default(),
+        RopeJointType::Distance {
+            stiffness: 0.,
+            damping: 0.,
+        },
+    ] {
+        let mut app = headless_app();
+        let anchor = spawn_anchor(&mut app, b2BodyType::Static);
+        let rope = spawn_rope(&mut app, anchor, joint_type.clone());
+        assert_eq!(rope.segments.len(), SEGMENT_COUNT);
+        assert_eq!(rope.joints.len(), SEGMENT_COUNT);
+
+        for frame in 0..300 {
+            update_frames(&mut app, 1);
+            let gap = largest_gap(&app, anchor, &rope);
+            assert!(
+                gap < MAX_GAP,
+                "{:?}: gap of {} in frame {}",
+                joint_type,
+                gap,
+                frame
+            );
+        }
+        // The rope swung down from the horizontal
+        let last_segment = app
+            .world
+            .get::<b2Body>(*rope.segments.last().unwrap())
+            .unwrap();
+        assert!(last_segment.position.y < 20. - SEGMENT_LENGTH);
+    }
+}
+
+#[test]
+fn ropes_follow_a_moving_body() {
+    let mut app = headless_app();
+    let anchor = spawn_anchor(&mut app, b2BodyType::Kinematic);
+    let rope = spawn_rope(&mut app, anchor, RopeJointType::default());
+
+    for frame in 0..240 {
+        // Back and forth, changing direction every second
+        let velocity = if (frame / 60) % 2 == 0 { 4. } else { -4. };
+        app.world.get_mut::<b2Body>(anchor).unwrap().linear_velocity = Vec2::new(velocity, 0.);
+        update_frames(&mut app, 1);
+        let gap = largest_gap(&app, anchor, &rope);
+        assert!(gap < MAX_GAP, "gap of {} in frame {}", gap, frame);
+    }
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use bevy::ecs::system::EntityCommand;
+use bevy::prelude::*;
+use bevy::scene::DynamicSceneBuilder;
+use bevy::utils::HashMap;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, spawn_box, update_frames};
+
+const ANCHOR_POSITION: Vec2 = Vec2::new(10., 5.);
+const PENDULUM_LENGTH: f32 = 3.;
+
+/// A box resting on the ground and a pendulum hanging from a static anchor.
+fn spawn_scene(world: &mut World) -> (Entity, Entity, Entity) {
+    spawn_box(world, b2BodyType::Static, Vec2::ZERO);
+    let resting = spawn_box(world, b2BodyType::Dynamic, Vec2::new(0., 2.));
+
+    let anchor = spawn_box(world, b2BodyType::Static, ANCHOR_POSITION);
+    let bob = spawn_box(
+        world,
+        b2BodyType::Dynamic,
+        ANCHOR_POSITION + Vec2::new(PENDULUM_LENGTH, 0.),
+    );
+    let joint = world.spawn_empty().id();
+    CreateRevoluteJoint::new(
+        anchor,
+        bob,
+        false,
+        &b2RevoluteJointDef {
+            local_anchor_b: Vec2::new(-PENDULUM_LENGTH, 0.),
+            ..default()
+        },
+    )
+    .apply(joint, world);
+    (resting, anchor, bob)
+}
+
+#[test]
+fn saved_scenes_load_into_a_new_app() {
+    let mut app = headless_app();
+    let (resting, anchor, bob) = spawn_scene(&mut app.world);
+    update_frames(&mut app, 10);
+
+    let physics_entities: Vec<Entity> = app
+        .world
+        .query_filtered::<Entity, Or<(With<b2Body>, With<b2Fixture>, With<b2Joint>)>>()
+        .iter(&app.world)
+        .collect();
+    let scene = DynamicSceneBuilder::from_world(&app.world)
+        .extract_entities(physics_entities.into_iter())
+        .build();
+
+    let mut loaded_app = headless_app();
+    // Takes up the first entity ids, so the loaded entities can't keep their old ones by chance
+    loaded_app.world.spawn_batch((0..10).map(|_| ()));
+    let mut entity_map = HashMap::default();
+    scene
+        .write_to_world(&mut loaded_app.world, &mut entity_map)
+        .unwrap();
+    let loaded_resting = entity_map[&resting];
+    let loaded_bob = entity_map[&bob];
+    assert_ne!(loaded_resting, resting);
+
+    let mut fixtures = loaded_app.world.query::<&b2Fixture>();
+    for fixture in fixtures.iter(&loaded_app.world) {
+        assert!(entity_map
+            .values()
+            .any(|entity| *entity == fixture.get_body_entity()));
+    }
+    let mut joints = loaded_app.world.query::<&b2Joint>();
+    let joint = joints.single(&loaded_app.world);
+    assert_eq!(*joint.body_a(), entity_map[&anchor]);
+    assert_eq!(*joint.body_b(), loaded_bob);
+
+    update_frames(&mut loaded_app, 60);
+
+    let b2_world = loaded_app.world.non_send_resource::<b2World>();
+    assert!(b2_world.fixtures_of(loaded_resting).next().is_some());
+    let resting_body = loaded_app.world.get::<b2Body>(loaded_resting).unwrap();
+    assert!(resting_body.position.y > 0.9);
+    assert!(resting_body.position.y < 1.1);
+    let bob_body = loaded_app.world.get::<b2Body>(loaded_bob).unwrap();
+    assert!(bob_body.position.y < ANCHOR_POSITION.y - 1.);
+    let length = (bob_body.position - ANCHOR_POSITION).length();
+    assert!(
+        (length - PENDULUM_LENGTH).abs() < 0.05,
+        "the pendulum is {} m long",
+        length
+    );
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, spawn_box};
+
+fn square_loop(center: Vec2, half_size: f32) -> b2Shape {
+    b2Shape::ChainLoop {
+        vertices: vec![
+            center + Vec2::new(-half_size, -half_size),
+            center + Vec2::new(half_size, -half_size),
+            center + Vec2::new(half_size, half_size),
+            center + Vec2::new(-half_size, half_size),
+        ],
+    }
+}
+
+fn spawn_static_shape(world: &mut World, shape: b2Shape) -> Entity {
+    let body_def = b2BodyDef {
+        body_type: b2BodyType::Static,
+        ..default()
+    };
+    let body_entity = world.spawn(b2BodyBundle::new(&body_def)).id();
+    world.spawn(b2Fixture::new(body_entity, &b2FixtureDef::new(shape, 0.)));
+    body_entity
+}
+
+#[test]
+fn overlap_finds_every_edge_of_a_chain_fixture() {
+    let mut app = headless_app();
+    let chain = spawn_static_shape(&mut app.world, square_loop(Vec2::ZERO, 5.));
+    app.update();
+
+    let b2_world = app.world.non_send_resource::<b2World>();
+    let probe = b2Shape::create_box(0.25, 0.25);
+    let overlapping_bodies = |position: Vec2| -> Vec<Entity> {
+        b2_world
+            .overlap_shape(&probe, position, 0., &b2QueryFilter::default())
+            .into_iter()
+            .map(|hit| hit.body_entity)
+            .collect()
+    };
+    for edge_midpoint in [
+        Vec2::new(0., -5.),
+        Vec2::new(5., 0.),
+        Vec2::new(0., 5.),
+        Vec2::new(-5., 0.),
+    ] {
+        assert_eq!(
+            overlapping_bodies(edge_midpoint),
+            [chain],
+            "{}",
+            edge_midpoint
+        );
+    }
+    // Chains are hollow
+    assert!(overlapping_bodies(Vec2::ZERO).is_empty());
+}
+
+#[test]
+fn overlap_with_a_chain_shape_covers_all_of_its_edges() {
+    let mut app = headless_app();
+    // Only touches the top edge, far away from the first one
+    let body = spawn_box(&mut app.world, b2BodyType::Static, Vec2::new(15., 19.8));
+    app.update();
+
+    let b2_world = app.world.non_send_resource::<b2World>();
+    let hits = b2_world.overlap_shape(
+        &square_loop(Vec2::new(15., 15.), 5.),
+        Vec2::ZERO,
+        0.,
+        &b2QueryFilter::default(),
+    );
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].body_entity, body);
+}
+
+#[test]
+fn shape_cast_hits_the_closest_edge_of_a_chain() {
+    let mut app = headless_app();
+    let chain = spawn_static_shape(&mut app.world, square_loop(Vec2::ZERO, 5.));
+    app.update();
+
+    let b2_world = app.world.non_send_resource::<b2World>();
+    let circle = b2Shape::Circle {
+        radius: 0.25,
+        position: Vec2::ZERO,
+    };
+    let hit = b2_world
+        .shape_cast(
+            &circle,
+            (Vec2::new(0., -3.), 0.),
+            Vec2::new(0., 10.),
+            &b2RayCastFilter::default(),
+        )
+        .unwrap();
+    assert_eq!(hit.body_entity, chain);
+    assert!((hit.point.y - 5.).abs() < 0.01, "{:?}", hit);
+    assert!((hit.fraction - 0.775).abs() < 0.01, "{:?}", hit);
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use std::collections::HashMap;
+
+use bevy::ecs::system::{CommandQueue, EntityCommand};
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+use bevy_liquidfun::snapshot::{
+    b2ParticleSystemSnapshot, b2WorldSnapshot, snapshot, spawn_from_snapshot,
+};
+
+use common::{headless_app, spawn_box, update_frames};
+
+fn spawn_scene(app: &mut App) {
+    let ground = spawn_box(&mut app.world, b2BodyType::Static, Vec2::ZERO);
+    let body = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::new(1., 3.));
+    let joint = app.world.spawn_empty().id();
+    CreateRevoluteJoint::new(ground, body, false, &b2RevoluteJointDef::default())
+        .apply(joint, &mut app.world);
+
+    let particle_system_def = b2ParticleSystemDef {
+        radius: 0.1,
+        ..default()
+    };
+    let mut particle_system = b2ParticleSystem::new(&particle_system_def).with_user_data();
+    particle_system.queue_particle(b2ParticleDef {
+        position: Vec2::new(-3., 2.),
+        lifetime: 5.,
+        color: b2ParticleColor::new(255, 0, 0, 255),
+        user_data: 7,
+        ..default()
+    });
+    let particle_system = app.world.spawn(particle_system).id();
+    app.world.spawn(b2ParticleGroup::new(
+        particle_system,
+        &b2ParticleGroupDef {
+            flags: b2ParticleFlags::WaterParticle,
+            shape: b2Shape::create_box_with_offset(0.5, 0.5, Vec2::new(3., 2.)),
+            color: b2ParticleColor::new(0, 0, 255, 255),
+            lifetime: 0.,
+        },
+    ));
+    app.update();
+
+    // Tags every particle, so they can be told apart regardless of their index
+    let mut particle_system = app
+        .world
+        .get_mut::<b2ParticleSystem>(particle_system)
+        .unwrap();
+    for index in 0..particle_system.particle_count() {
+        if particle_system.get_user_data(index) == 0 {
+            particle_system.set_particle_user_data(index, 1000 + index as u32);
+        }
+    }
+}
+
+fn restore(snapshot: &b2WorldSnapshot) -> (App, HashMap<Entity, Entity>) {
+    let mut app = headless_app();
+    // Without a step, the restored state can be compared exactly
+    app.world.resource_mut::<PhysicsTime>().pause();
+    let mut queue = CommandQueue::default();
+    let entity_map = spawn_from_snapshot(&mut Commands::new(&mut queue, &app.world), snapshot);
+    queue.apply(&mut app.world);
+    app.update();
+    (app, entity_map)
+}
+
+#[test]
+fn restoring_a_snapshot_recreates_the_same_world() {
+    let mut app = headless_app();
+    spawn_scene(&mut app);
+    update_frames(&mut app, 30);
+    let original = snapshot(&mut app.world);
+
+    let (mut restored_app, entity_map) = restore(&original);
+    let restored = snapshot(&mut restored_app.world);
+
+    assert_eq!(restored.gravity, original.gravity);
+    assert_eq!(restored.bodies.len(), original.bodies.len());
+    for body in &original.bodies {
+        let restored_body = restored
+            .bodies
+            .iter()
+            .find(|restored_body| restored_body.entity == entity_map[&body.entity])
+            .unwrap();
+        assert_eq!(restored_body.position, body.position);
+        assert_eq!(restored_body.angle, body.angle);
+        assert_eq!(restored_body.linear_velocity, body.linear_velocity);
+        assert_eq!(restored_body.angular_velocity, body.angular_velocity);
+    }
+
+    assert_eq!(restored.fixtures.len(), original.fixtures.len());
+    for fixture in &original.fixtures {
+        let restored_fixture = restored
+            .fixtures
+            .iter()
+            .find(|restored_fixture| restored_fixture.entity == entity_map[&fixture.entity])
+            .unwrap();
+        assert_eq!(restored_fixture.body, entity_map[&fixture.body]);
+    }
+
+    assert_eq!(restored.joints.len(), 1);
+    let (joint, restored_joint) = (&original.joints[0], &restored.joints[0]);
+    assert_eq!(restored_joint.entity, entity_map[&joint.entity]);
+    assert_eq!(restored_joint.body_a, entity_map[&joint.body_a]);
+    assert_eq!(restored_joint.body_b, entity_map[&joint.body_b]);
+
+    assert_eq!(restored.particle_systems.len(), 1);
+    let (particle_system, restored_particle_system) =
+        (&original.particle_systems[0], &restored.particle_systems[0]);
+    assert_eq!(
+        restored_particle_system.entity,
+        entity_map[&particle_system.entity]
+    );
+    assert_eq!(particle_system.groups.len(), 1);
+    assert_eq!(restored_particle_system.groups.len(), 1);
+    let (group, restored_group) = (
+        &particle_system.groups[0],
+        &restored_particle_system.groups[0],
+    );
+    assert_eq!(restored_group.entity, entity_map[&group.entity]);
+    assert_eq!(restored_group.particle_count, group.particle_count);
+
+    let particles_by_user_data = |particle_system: &b2ParticleSystemSnapshot| {
+        let mut particles: Vec<b2ParticleDef> = (0..particle_system.positions.len())
+            .map(|index| particle_system.particle(index))
+            .collect();
+        particles.sort_by_key(|particle| particle.user_data);
+        particles
+    };
+    let particles = particles_by_user_data(particle_system);
+    let restored_particles = particles_by_user_data(restored_particle_system);
+    assert_eq!(restored_particles.len(), particles.len());
+    assert!(particles.len() > group.particle_count);
+    for (particle, restored_particle) in particles.iter().zip(&restored_particles) {
+        assert_eq!(restored_particle.user_data, particle.user_data);
+        assert_eq!(restored_particle.position, particle.position);
+        assert_eq!(restored_particle.velocity, particle.velocity);
+        assert_eq!(restored_particle.color, particle.color);
+        assert_eq!(restored_particle.flags, particle.flags);
+        assert!((restored_particle.lifetime - particle.lifetime).abs() < 0.05);
+    }
+    // The loose particle is about to expire, the others live forever
+    assert!(particles[0].user_data == 7 && particles[0].lifetime > 4.);
+    assert!(particles[1..]
+        .iter()
+        .all(|particle| particle.lifetime == 0.));
+}
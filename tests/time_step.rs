@@ -0,0 +1,53 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, spawn_box, update_frames};
+
+fn simulated_time(app: &App) -> f64 {
+    app.world.non_send_resource::<b2World>().get_elapsed_time()
+}
+
+fn real_time(app: &App) -> f64 {
+    app.world.resource::<Time>().elapsed_seconds_f64()
+}
+
+#[test]
+fn changing_the_time_step_keeps_the_simulated_time_monotonic() {
+    let mut app = headless_app();
+    let body = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    let mut last_simulated_time = 0.;
+    let mut last_y = 0.;
+    for time_step in [1. / 60., 1. / 30., 1. / 60.] {
+        app.world.resource_mut::<b2WorldSettings>().time_step = time_step;
+        for frame in 0..45 {
+            update_frames(&mut app, 1);
+            let simulated_time = simulated_time(&app);
+            assert!(
+                simulated_time >= last_simulated_time,
+                "the simulated time went back from {} to {}",
+                last_simulated_time,
+                simulated_time
+            );
+            // The leftover time is rescaled along with the time step, which can leave the
+            // simulation up to another step behind
+            let lag = real_time(&app) - simulated_time;
+            let time_step = time_step as f64;
+            assert!(
+                (-1e-4..2. * time_step).contains(&lag),
+                "{} s behind with a time step of {} in frame {}",
+                lag,
+                time_step,
+                frame
+            );
+            last_simulated_time = simulated_time;
+
+            let y = app.world.get::<b2Body>(body).unwrap().position.y;
+            assert!(y <= last_y);
+            last_y = y;
+        }
+    }
+    assert!(last_simulated_time > 2.);
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{app_with_frame_time, run_steps, spawn_box, step_count, update_frames};
+
+/// Exactly representable, so that the frames end exactly on a step and nothing is left to
+/// interpolate.
+const TIME_STEP: f32 = 1. / 64.;
+
+fn app_at(frame_time: f32, transform_sync: TransformSyncMode) -> App {
+    let settings = b2WorldSettings {
+        time_step: TIME_STEP,
+        transform_sync,
+        ..default()
+    };
+    app_with_frame_time(LiquidFunPlugin::new(settings), frame_time)
+}
+
+#[test]
+fn interpolation_starts_from_the_state_before_the_last_step() {
+    let mut app = app_at(3. * TIME_STEP, TransformSyncMode::Interpolate);
+    let body = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    update_frames(&mut app, 10);
+    let steps = step_count(&app);
+    assert_eq!(steps, 30);
+
+    // The same fall, one step per frame, up to the step before the last one
+    let mut reference_app = app_at(TIME_STEP, TransformSyncMode::Snap);
+    let reference_body = spawn_box(&mut reference_app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    run_steps(&mut reference_app, steps - 1);
+    let before_last_step = reference_app
+        .world
+        .get::<b2Body>(reference_body)
+        .unwrap()
+        .position;
+
+    // Nothing is left over after the steps, so the interpolation is at the start of the last step
+    let translation = app.world.get::<Transform>(body).unwrap().translation;
+    assert!(
+        (translation.truncate() - before_last_step).length() < 1e-5,
+        "{:?} vs {:?}",
+        translation,
+        before_last_step
+    );
+    let position = app.world.get::<b2Body>(body).unwrap().position;
+    assert!((position - before_last_step).length() > 1e-3);
+}
+
+fn fixed_update_app_at(frame_time: f32, transform_sync: TransformSyncMode) -> App {
+    let settings = b2WorldSettings {
+        transform_sync,
+        ..default()
+    };
+    let mut app = app_with_frame_time(LiquidFunPlugin::new_in_fixed_update(settings), frame_time);
+    // The time step of the settings follows it
+    app.world
+        .resource_mut::<Time<Fixed>>()
+        .set_timestep_seconds(TIME_STEP as f64);
+    app
+}
+
+fn translation(app: &App, body: Entity) -> Vec2 {
+    app.world
+        .get::<Transform>(body)
+        .unwrap()
+        .translation
+        .truncate()
+}
+
+#[test]
+fn both_plugin_modes_simulate_the_same_fall() {
+    let mut app = app_at(TIME_STEP, TransformSyncMode::Snap);
+    let mut fixed_update_app = fixed_update_app_at(TIME_STEP, TransformSyncMode::Snap);
+    let body = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    let fixed_update_body = spawn_box(&mut fixed_update_app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    run_steps(&mut app, 60);
+    run_steps(&mut fixed_update_app, 60);
+
+    assert_eq!(
+        fixed_update_app
+            .world
+            .resource::<b2WorldSettings>()
+            .time_step,
+        TIME_STEP
+    );
+    let position = app.world.get::<b2Body>(body).unwrap().position;
+    let fixed_update_position = fixed_update_app
+        .world
+        .get::<b2Body>(fixed_update_body)
+        .unwrap()
+        .position;
+    assert!(position.y < -1.);
+    assert_eq!(position, fixed_update_position);
+    assert!((translation(&app, body) - position).length() < 1e-5);
+    assert!((translation(&fixed_update_app, fixed_update_body) - position).length() < 1e-5);
+}
+
+#[test]
+fn both_plugin_modes_extrapolate_by_the_time_left_over() {
+    // Every other frame leaves half a step over
+    let frame_time = 1.5 * TIME_STEP;
+    let mut app = app_at(frame_time, TransformSyncMode::Extrapolate);
+    let mut fixed_update_app = fixed_update_app_at(frame_time, TransformSyncMode::Extrapolate);
+    let body = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    let fixed_update_body = spawn_box(&mut fixed_update_app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    update_frames(&mut app, 11);
+    update_frames(&mut fixed_update_app, 11);
+    assert_eq!(step_count(&app), 16);
+    assert_eq!(step_count(&fixed_update_app), 16);
+    let overstep = fixed_update_app
+        .world
+        .resource::<Time<Fixed>>()
+        .overstep_percentage();
+    assert!((overstep - 0.5).abs() < 1e-5, "{}", overstep);
+
+    for (app, body) in [(&app, body), (&fixed_update_app, fixed_update_body)] {
+        let b2_body = app.world.get::<b2Body>(body).unwrap();
+        let expected = b2_body.position + b2_body.linear_velocity * 0.5 * TIME_STEP;
+        assert!(b2_body.linear_velocity.y < -1.);
+        assert!(
+            (translation(app, body) - expected).length() < 1e-5,
+            "{:?} vs {:?}",
+            translation(app, body),
+            expected
+        );
+    }
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, spawn_box, update_frames};
+
+fn fixture_of(app: &App, body: Entity) -> Entity {
+    let b2_world = app.world.non_send_resource::<b2World>();
+    b2_world.fixtures_of(body).next().unwrap()
+}
+
+#[test]
+fn ray_casts_and_contacts_report_recycled_entities() {
+    let mut app = headless_app();
+    let ground = spawn_box(&mut app.world, b2BodyType::Static, Vec2::ZERO);
+    let old_body = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::new(0., 1.5));
+    update_frames(&mut app, 1);
+    let old_fixture = fixture_of(&app, old_body);
+    app.world.despawn(old_fixture);
+    app.world.despawn(old_body);
+    update_frames(&mut app, 1);
+
+    // Bevy hands out the freed indices again, with a new generation
+    let body = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::new(0., 1.5));
+    update_frames(&mut app, 30);
+    let fixture = fixture_of(&app, body);
+    let old_indices = [old_body.index(), old_fixture.index()];
+    assert!(old_indices.contains(&body.index()));
+    assert!(old_indices.contains(&fixture.index()));
+    assert!(![old_body, old_fixture].contains(&body));
+    assert!(![old_body, old_fixture].contains(&fixture));
+
+    let hits = app
+        .world
+        .non_send_resource::<b2World>()
+        .ray_cast_with_filter(
+            b2RayCastAll::new(),
+            &Vec2::new(0., 5.),
+            &Vec2::new(0., -5.),
+            &b2RayCastFilter::default(),
+        );
+    let mut hit_entities: Vec<Entity> = hits.iter().map(|hit| hit.entity).collect();
+    hit_entities.sort();
+    let mut expected = vec![fixture, fixture_of(&app, ground)];
+    expected.sort();
+    assert_eq!(hit_entities, expected);
+
+    let contacts = app.world.resource::<b2Contacts>().contacts();
+    assert_eq!(contacts.len(), 1);
+    let contact = contacts[0];
+    let mut bodies = [contact.body_a, contact.body_b];
+    bodies.sort();
+    let mut expected_bodies = [body, ground];
+    expected_bodies.sort();
+    assert_eq!(bodies, expected_bodies);
+    assert!([contact.fixture_a, contact.fixture_b].contains(&fixture));
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use bevy::ecs::system::EntityCommand;
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{headless_app, spawn_box, spawn_particle_block, update_frames};
+
+const ANCHOR_POSITION: Vec2 = Vec2::new(10., 10.);
+
+fn body(app: &App, entity: Entity) -> &b2Body {
+    app.world.get::<b2Body>(entity).unwrap()
+}
+
+fn particle_count(app: &App, particle_system: Entity) -> usize {
+    app.world
+        .get::<b2ParticleSystem>(particle_system)
+        .unwrap()
+        .particle_count()
+}
+
+#[test]
+fn bodies_fall_again_in_a_reinserted_world() {
+    let mut app = headless_app();
+    let falling = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::new(0., 10.));
+    let anchor = spawn_box(&mut app.world, b2BodyType::Static, ANCHOR_POSITION);
+    let bob = spawn_box(
+        &mut app.world,
+        b2BodyType::Dynamic,
+        ANCHOR_POSITION + Vec2::new(3., 0.),
+    );
+    let joint = app.world.spawn_empty().id();
+    CreateRevoluteJoint::new(
+        anchor,
+        bob,
+        false,
+        &b2RevoluteJointDef {
+            local_anchor_b: Vec2::new(-3., 0.),
+            ..default()
+        },
+    )
+    .apply(joint, &mut app.world);
+    let (particle_system, _) = spawn_particle_block(&mut app.world, Vec2::new(-10., 10.), 0.5);
+    update_frames(&mut app, 10);
+    let particles = particle_count(&app, particle_system);
+    assert!(particles > 0);
+
+    // The physics pauses while there is no world
+    app.world.remove_non_send_resource::<b2World>();
+    let paused_position = body(&app, falling).position;
+    update_frames(&mut app, 10);
+    assert_eq!(body(&app, falling).position, paused_position);
+
+    app.world
+        .insert_non_send_resource(b2World::new(Vec2::new(0., -9.81)));
+    update_frames(&mut app, 30);
+
+    let b2_world = app.world.non_send_resource::<b2World>();
+    for entity in [falling, anchor, bob] {
+        assert!(b2_world.fixtures_of(entity).next().is_some());
+    }
+    let falling_body = body(&app, falling);
+    assert!(falling_body.position.y < paused_position.y - 1.);
+    assert!(falling_body.linear_velocity.y < -1.);
+    // The pendulum is still attached to its anchor
+    let length = (body(&app, bob).position - ANCHOR_POSITION).length();
+    assert!(
+        (length - 3.).abs() < 0.05,
+        "the pendulum is {} m long",
+        length
+    );
+    assert!(body(&app, bob).position.y < ANCHOR_POSITION.y - 0.5);
+    assert_eq!(particle_count(&app, particle_system), particles);
+}
@@ -0,0 +1,190 @@
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_liquidfun::prelude::*;
+
+use common::{app_with_frame_time, headless_app, spawn_box, update_frames};
+
+const MIRROR_WORLD: PhysicsWorldId = PhysicsWorldId(1);
+
+fn app_with_mirror_world(gravity: Vec2) -> App {
+    let mut app = headless_app();
+    app.world
+        .non_send_resource_mut::<b2Worlds>()
+        .insert(MIRROR_WORLD, b2World::new(gravity));
+    app
+}
+
+fn spawn_box_in(
+    world: &mut World,
+    body_type: b2BodyType,
+    position: Vec2,
+    id: PhysicsWorldId,
+) -> Entity {
+    let body = spawn_box(world, body_type, position);
+    world.entity_mut(body).insert(id);
+    body
+}
+
+fn position(app: &App, body: Entity) -> Vec2 {
+    app.world.get::<b2Body>(body).unwrap().position
+}
+
+#[test]
+fn bodies_fall_with_the_gravity_of_their_world() {
+    let mut app = app_with_mirror_world(Vec2::new(0., 9.81));
+    let falling = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    let rising = spawn_box_in(
+        &mut app.world,
+        b2BodyType::Dynamic,
+        Vec2::ZERO,
+        MIRROR_WORLD,
+    );
+    update_frames(&mut app, 30);
+
+    assert!(position(&app, falling).y < -1.);
+    assert!(position(&app, rising).y > 1.);
+    let worlds = app.world.non_send_resource::<b2Worlds>();
+    assert_eq!(worlds.step_count(MIRROR_WORLD), Some(30));
+    assert_eq!(app.world.resource::<PhysicsStepCount>().0, 30);
+}
+
+#[test]
+fn bodies_only_collide_within_their_world() {
+    let mut app = app_with_mirror_world(Vec2::new(0., -9.81));
+    spawn_box(&mut app.world, b2BodyType::Static, Vec2::ZERO);
+    let resting = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::new(0., 2.));
+    let falling = spawn_box_in(
+        &mut app.world,
+        b2BodyType::Dynamic,
+        Vec2::new(0., 2.),
+        MIRROR_WORLD,
+    );
+    update_frames(&mut app, 60);
+
+    assert!(position(&app, resting).y > 0.9);
+    assert!(position(&app, falling).y < -2.);
+    let worlds = app.world.non_send_resource::<b2Worlds>();
+    assert!(worlds.contacts(MIRROR_WORLD).unwrap().contacts().is_empty());
+    assert!(!app.world.resource::<b2Contacts>().contacts().is_empty());
+}
+
+#[test]
+fn ray_casts_only_hit_the_bodies_of_their_world() {
+    let mut app = app_with_mirror_world(Vec2::ZERO);
+    let default_body = spawn_box(&mut app.world, b2BodyType::Static, Vec2::new(-2., 0.));
+    let mirror_body = spawn_box_in(
+        &mut app.world,
+        b2BodyType::Static,
+        Vec2::new(2., 0.),
+        MIRROR_WORLD,
+    );
+    update_frames(&mut app, 1);
+
+    let start = Vec2::new(-5., 0.);
+    let end = Vec2::new(5., 0.);
+    let filter = b2RayCastFilter::default();
+    let default_hits = app
+        .world
+        .non_send_resource::<b2World>()
+        .ray_cast_with_filter(b2RayCastAll::new(), &start, &end, &filter);
+    let worlds = app.world.non_send_resource::<b2Worlds>();
+    let mirror_hits = worlds.get(MIRROR_WORLD).unwrap().ray_cast_with_filter(
+        b2RayCastAll::new(),
+        &start,
+        &end,
+        &filter,
+    );
+
+    let b2_world = app.world.non_send_resource::<b2World>();
+    assert_eq!(default_hits.len(), 1);
+    assert_eq!(b2_world.body_of(default_hits[0].entity), Some(default_body));
+    assert_eq!(mirror_hits.len(), 1);
+    assert_eq!(
+        worlds
+            .get(MIRROR_WORLD)
+            .unwrap()
+            .body_of(mirror_hits[0].entity),
+        Some(mirror_body)
+    );
+}
+
+#[test]
+fn resetting_empties_every_world() {
+    let mut app = app_with_mirror_world(Vec2::new(0., 9.81));
+    let body = spawn_box_in(
+        &mut app.world,
+        b2BodyType::Dynamic,
+        Vec2::ZERO,
+        MIRROR_WORLD,
+    );
+    update_frames(&mut app, 5);
+
+    app.world.send_event(ResetPhysicsWorld);
+    update_frames(&mut app, 1);
+
+    assert!(app.world.get_entity(body).is_none());
+    let worlds = app.world.non_send_resource::<b2Worlds>();
+    let mirror_world = worlds.get(MIRROR_WORLD).unwrap();
+    assert_eq!(mirror_world.gravity, Vec2::new(0., 9.81));
+    assert!(mirror_world.fixtures_of(body).next().is_none());
+}
+
+#[test]
+fn transforms_move_the_bodies_of_their_world() {
+    let mut app = app_with_mirror_world(Vec2::ZERO);
+    let body = spawn_box_in(
+        &mut app.world,
+        b2BodyType::Kinematic,
+        Vec2::ZERO,
+        MIRROR_WORLD,
+    );
+    app.world
+        .entity_mut(body)
+        .insert(SyncTransformToBody::default());
+    update_frames(&mut app, 1);
+
+    app.world.get_mut::<Transform>(body).unwrap().translation = Vec3::new(3., 0., 0.);
+    update_frames(&mut app, 2);
+
+    assert_eq!(position(&app, body), Vec2::new(3., 0.));
+    let worlds = app.world.non_send_resource::<b2Worlds>();
+    let hits = worlds.get(MIRROR_WORLD).unwrap().ray_cast_with_filter(
+        b2RayCastAll::new(),
+        &Vec2::new(3., 5.),
+        &Vec2::new(3., -5.),
+        &b2RayCastFilter::default(),
+    );
+    assert_eq!(hits.len(), 1);
+}
+
+#[test]
+fn every_world_interpolates_by_its_leftover_time_in_fixed_update() {
+    let settings = b2WorldSettings {
+        transform_sync: TransformSyncMode::Interpolate,
+        ..default()
+    };
+    // Leaves a different part of a step over every frame
+    let mut app = app_with_frame_time(LiquidFunPlugin::new_in_fixed_update(settings), 1. / 45.);
+    app.world
+        .non_send_resource_mut::<b2Worlds>()
+        .insert(MIRROR_WORLD, b2World::new(Vec2::new(0., -9.81)));
+    let default_body = spawn_box(&mut app.world, b2BodyType::Dynamic, Vec2::ZERO);
+    let mirror_body = spawn_box_in(
+        &mut app.world,
+        b2BodyType::Dynamic,
+        Vec2::ZERO,
+        MIRROR_WORLD,
+    );
+
+    for _ in 0..20 {
+        update_frames(&mut app, 1);
+        let default_transform = app.world.get::<Transform>(default_body).unwrap();
+        let mirror_transform = app.world.get::<Transform>(mirror_body).unwrap();
+        assert_eq!(default_transform.translation, mirror_transform.translation);
+    }
+    assert!(position(&app, mirror_body).y < -0.1);
+    let mirror_transform = app.world.get::<Transform>(mirror_body).unwrap();
+    assert!(mirror_transform.translation.y < -0.1);
+}